@@ -0,0 +1,224 @@
+//! Compares the auction backends enabled by the crate's features on sets of
+//! random auctions, printing how long each backend takes and whether it finds
+//! the highest value within the time limit. Run it in release mode, with the
+//! features of the backends to compare:
+//!
+//! ```sh
+//! cargo run --release --example compare_backends --features lp,rayon -- \
+//!     [auctions per set] [seed]
+//! ```
+//!
+//! To compare backends on a particular workload, change the shapes in
+//! `instance_sets` to match its auctions.
+
+use std::env;
+use std::time::{Duration, Instant};
+
+use rand::{distributions::Distribution, rngs::StdRng, SeedableRng};
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_assignment, vcg_auction_dp,
+    vcg_auction_with_options, AuctionError, AuctionInstance, AuctionOutcome,
+    InstanceShape, OnTimeout, SearchOptions, SearchStrategy,
+};
+
+type Outcome<'a> = Result<AuctionOutcome<'a, SimpleBid>, AuctionError>;
+
+/// Runs an auction with a backend, breaking ties with the first outcome.
+type Backend =
+    for<'a> fn(&'a AuctionInstance, &SearchOptions<u64>) -> Outcome<'a>;
+
+/// Longest a backend may take on one auction before it's given up on.
+const TIME_LIMIT: Duration = Duration::from_secs(10);
+
+fn branch_and_bound<'a>(
+    auction: &'a AuctionInstance,
+    options: &SearchOptions<u64>,
+) -> Outcome<'a> {
+    vcg_auction_with_options(&auction.items, &auction.bid_sets, |_| 0, options)
+}
+
+fn best_first<'a>(
+    auction: &'a AuctionInstance,
+    options: &SearchOptions<u64>,
+) -> Outcome<'a> {
+    let options = SearchOptions {
+        strategy: SearchStrategy::BestFirst,
+        ..options.clone()
+    };
+    vcg_auction_with_options(&auction.items, &auction.bid_sets, |_| 0, &options)
+}
+
+fn dynamic_programming<'a>(
+    auction: &'a AuctionInstance,
+    options: &SearchOptions<u64>,
+) -> Outcome<'a> {
+    vcg_auction_dp(&auction.items, &auction.bid_sets, |_| 0, options)
+}
+
+fn assignment<'a>(
+    auction: &'a AuctionInstance,
+    options: &SearchOptions<u64>,
+) -> Outcome<'a> {
+    vcg_auction_assignment(&auction.items, &auction.bid_sets, |_| 0, options)
+}
+
+#[cfg(feature = "lp")]
+fn linear_relaxation<'a>(
+    auction: &'a AuctionInstance,
+    options: &SearchOptions<u64>,
+) -> Outcome<'a> {
+    vcg_auction::vcg_auction_lp(
+        &auction.items,
+        &auction.bid_sets,
+        |_| 0,
+        options,
+    )
+}
+
+#[cfg(feature = "rayon")]
+fn parallel<'a>(
+    auction: &'a AuctionInstance,
+    options: &SearchOptions<u64>,
+) -> Outcome<'a> {
+    vcg_auction::vcg_auction_parallel_with_options(
+        &auction.items,
+        &auction.bid_sets,
+        |_| 0,
+        options,
+    )
+}
+
+/// The backends enabled by the crate's features. The first is the
+/// reference the others are checked against.
+fn backends() -> Vec<(&'static str, Backend)> {
+    vec![
+        ("branch and bound", branch_and_bound as Backend),
+        ("best first", best_first),
+        ("dynamic programming", dynamic_programming),
+        ("assignment", assignment),
+        #[cfg(feature = "lp")]
+        ("linear relaxation", linear_relaxation),
+        #[cfg(feature = "rayon")]
+        ("parallel", parallel),
+    ]
+}
+
+/// Named shapes of the auctions to compare the backends on.
+fn instance_sets() -> Vec<(&'static str, InstanceShape)> {
+    vec![
+        ("small", InstanceShape::default()),
+        (
+            "single units",
+            InstanceShape {
+                max_items: 12,
+                max_stock: 1,
+                max_bidders: 8,
+                max_bid_sets: 12,
+                max_bids_per_set: 4,
+                max_items_per_bid: 1,
+                max_quantity: 1,
+                max_value: 1000,
+            },
+        ),
+        (
+            "contended bundles",
+            InstanceShape {
+                max_items: 8,
+                max_stock: 3,
+                max_bidders: 10,
+                max_bid_sets: 16,
+                max_bids_per_set: 4,
+                max_items_per_bid: 4,
+                max_quantity: 2,
+                max_value: 1000,
+            },
+        ),
+    ]
+}
+
+/// Totals of one backend over an instance set.
+#[derive(Default)]
+struct Totals {
+    completed: usize,
+    highest_value: usize,
+    time: Duration,
+    slowest: Duration,
+}
+
+fn welfare(bids: &[&SimpleBid]) -> u64 {
+    bids.iter().map(|bid| bid.value).sum()
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let count = args.next().map_or(100, |count| {
+        count.parse().expect("auctions per set is a number")
+    });
+    let seed = args
+        .next()
+        .map_or(0, |seed| seed.parse().expect("seed is a number"));
+    let options = SearchOptions {
+        max_duration: Some(TIME_LIMIT),
+        on_timeout: OnTimeout::BestSoFar,
+        ..SearchOptions::default()
+    };
+    let backends = backends();
+    for (name, shape) in instance_sets() {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let auctions =
+            shape.sample_iter(&mut rng).take(count).collect::<Vec<_>>();
+        let mut totals = backends
+            .iter()
+            .map(|_| Totals::default())
+            .collect::<Vec<_>>();
+        for auction in &auctions {
+            let mut highest = None;
+            for (i, ((_, backend), totals)) in
+                backends.iter().zip(&mut totals).enumerate()
+            {
+                let started = Instant::now();
+                let outcome = backend(auction, &options);
+                let elapsed = started.elapsed();
+                totals.time += elapsed;
+                totals.slowest = totals.slowest.max(elapsed);
+                let (complete, value) = match &outcome {
+                    Ok(AuctionOutcome::Complete(result)) => {
+                        (true, Some(welfare(&result.winning_bids)))
+                    }
+                    Ok(AuctionOutcome::TimedOut { best_so_far, .. }) => {
+                        (false, Some(welfare(best_so_far)))
+                    }
+                    _ => (false, None),
+                };
+                if complete {
+                    totals.completed += 1;
+                }
+                // the highest value is the reference backend's, if it
+                // finished
+                if i == 0 {
+                    highest = value.filter(|_| complete);
+                }
+                if value.is_some() && value == highest {
+                    totals.highest_value += 1;
+                }
+            }
+        }
+        println!("{name}: {count} auctions");
+        println!(
+            "{:<20} {:>9} {:>9} {:>11} {:>11}",
+            "backend", "complete", "highest", "mean ms", "slowest ms"
+        );
+        for ((backend, _), totals) in backends.iter().zip(&totals) {
+            let mean = totals.time / count.max(1) as u32;
+            println!(
+                "{:<20} {:>9} {:>9} {:>11.3} {:>11.3}",
+                backend,
+                totals.completed,
+                totals.highest_value,
+                mean.as_secs_f64() * 1e3,
+                totals.slowest.as_secs_f64() * 1e3,
+            );
+        }
+        println!();
+    }
+}
@@ -0,0 +1,121 @@
+//! Times the branch-and-bound search on square auctions, of as many bidders
+//! as items, each bidding on every item alone, as in the `square_complexity`
+//! test. Prints the time per node of each search strategy: a depth-first
+//! search updates one path of selections in place, while a best-first search
+//! keeps a copy of them with every node waiting to be explored.
+//!
+//! Then times the depth-first search on wide auctions of many item types,
+//! with bids on a few of them each, where checking the selected quantities
+//! against the stock at every node takes most of the time. Run it in release
+//! mode:
+//!
+//! ```sh
+//! cargo run --release --example search_speed --features rng -- \
+//!     [largest size] [seed]
+//! ```
+
+use std::env;
+use std::time::Duration;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use vcg_auction::{
+    run_with_stats, types::SimpleBid, AuctionConfig, SearchStrategy, SolveStats,
+};
+
+/// Items and bid sets of a square auction of `size` items and bidders, with
+/// random values.
+fn square_auction(
+    size: usize,
+    rng: &mut impl Rng,
+) -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = (0..size).map(|i| (i.to_string(), 1)).collect();
+    let bid_sets = (0..size)
+        .map(|bidder| {
+            (0..size)
+                .map(|i| {
+                    let value = rng.gen_range(0..100);
+                    SimpleBid::new(
+                        bidder.to_string(),
+                        value,
+                        [(i.to_string(), 1)],
+                    )
+                })
+                .collect()
+        })
+        .collect();
+    (items, bid_sets)
+}
+
+/// Items and bid sets of a wide auction of `n_items` item types, with 12
+/// bidders making 3 bids each, with random values. Every bid is on one of 4
+/// units of the first item, so no bids can be searched apart, and on 2
+/// random others.
+fn wide_auction(
+    n_items: usize,
+    rng: &mut impl Rng,
+) -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = (0..n_items)
+        .map(|i| (i.to_string(), if i == 0 { 4 } else { 1 }))
+        .collect();
+    let bid_sets = (0..12)
+        .map(|bidder: usize| {
+            (0..3)
+                .map(|_| {
+                    let value = rng.gen_range(0..100);
+                    let bundle = [
+                        0,
+                        rng.gen_range(1..n_items),
+                        rng.gen_range(1..n_items),
+                    ]
+                    .map(|i| (i.to_string(), 1));
+                    SimpleBid::new(bidder.to_string(), value, bundle)
+                })
+                .collect()
+        })
+        .collect();
+    (items, bid_sets)
+}
+
+/// Print the nodes a search explored and the time it took per node.
+fn print_speed(name: &str, stats: &SolveStats) {
+    let time =
+        stats.winner_determination.search_time + stats.payments.search_time;
+    let per_node = time.as_nanos() / u128::from(stats.nodes().max(1));
+    println!(
+        "{name}: {:>10} nodes in {:>12?}, {:>5?} per node",
+        stats.nodes(),
+        time,
+        Duration::from_nanos(per_node as u64),
+    );
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let largest = args
+        .next()
+        .map_or(7, |size| size.parse().expect("largest size is a number"));
+    let seed = args
+        .next()
+        .map_or(0, |seed| seed.parse().expect("seed is a number"));
+    let mut rng = StdRng::seed_from_u64(seed);
+    for size in 4..=largest {
+        let (items, bid_sets) = square_auction(size, &mut rng);
+        for (name, strategy) in [
+            ("depth first", SearchStrategy::DepthFirst),
+            ("best first", SearchStrategy::BestFirst),
+        ] {
+            let mut config = AuctionConfig::deterministic();
+            config.search.strategy = strategy;
+            let (outcome, stats) = run_with_stats(&items, &bid_sets, &config);
+            outcome.expect("square auctions are valid");
+            print_speed(&format!("{size}x{size} {name}"), &stats);
+        }
+    }
+    for n_items in [8, 64, 512] {
+        let (items, bid_sets) = wide_auction(n_items, &mut rng);
+        let config = AuctionConfig::deterministic();
+        let (outcome, stats) = run_with_stats(&items, &bid_sets, &config);
+        outcome.expect("wide auctions are valid");
+        print_speed(&format!("{n_items} items"), &stats);
+    }
+}
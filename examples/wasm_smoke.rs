@@ -0,0 +1,27 @@
+//! Smoke test for targets without an operating system source of randomness,
+//! such as `wasm32-unknown-unknown`. Build it with only the `rng` feature:
+//!
+//! ```sh
+//! cargo build --example wasm_smoke --target wasm32-unknown-unknown \
+//!     --no-default-features --features rng
+//! ```
+
+use rand::{rngs::StdRng, SeedableRng};
+use vcg_auction::{types::SimpleBid, vcg_auction_with_rng};
+
+fn main() {
+    let items = vec![("chair".to_string(), 2)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 4, [("chair", 1)])],
+    ];
+    let mut rng = StdRng::seed_from_u64(0);
+    let result = vcg_auction_with_rng(&items, &bids, &mut rng)
+        .expect("auctions with a random tiebreak don't fail");
+    assert_eq!(result.winning_bids.len(), 2);
+    assert_eq!(result.payment_for(&"Alice".to_string()), Some(&4));
+}
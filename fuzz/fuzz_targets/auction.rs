@@ -0,0 +1,19 @@
+//! Runs arbitrary auctions, checking that the search doesn't panic, that no
+//! winner pays more than their winning bids are worth, and that the result
+//! passes verification.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vcg_auction::{vcg_auction_with_tiebreaker, verify_result};
+use vcg_auction_fuzz::{value_of, Instance};
+
+fuzz_target!(|instance: Instance| {
+    let Instance { items, bid_sets } = instance;
+    let result = vcg_auction_with_tiebreaker(&items, &bid_sets, |_| 0)
+        .expect("the first tied outcome is valid");
+    for (bidder, payment) in &result.payments {
+        assert!(*payment <= value_of(&result.winning_bids, bidder));
+    }
+    verify_result(&items, &bid_sets, &result).unwrap();
+});
@@ -0,0 +1,25 @@
+//! Prices arbitrary allocations under each pricing rule, checking that VCG
+//! payments are at most first-price payments, and that both pass
+//! verification.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vcg_auction::{vcg_allocation_with_tiebreaker, verify_result, PricingRule};
+use vcg_auction_fuzz::{value_of, Instance};
+
+fuzz_target!(|instance: Instance| {
+    let Instance { items, bid_sets } = instance;
+    let allocation = vcg_allocation_with_tiebreaker(&items, &bid_sets, |_| 0)
+        .expect("the first tied outcome is valid");
+    let vcg = allocation.price(PricingRule::Vcg);
+    let first_price = allocation.price(PricingRule::FirstPrice);
+    for ((bidder, payment), (_, value)) in
+        vcg.payments.iter().zip(&first_price.payments)
+    {
+        assert_eq!(*value, value_of(&vcg.winning_bids, bidder));
+        assert!(payment <= value);
+    }
+    verify_result(&items, &bid_sets, &vcg).unwrap();
+    verify_result(&items, &bid_sets, &first_price).unwrap();
+});
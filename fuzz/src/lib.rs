@@ -0,0 +1,64 @@
+//! Arbitrary auctions for the fuzz targets, which are run with
+//! [cargo-fuzz](https://github.com/rust-fuzz/cargo-fuzz) from the root of the
+//! repository, such as with `cargo +nightly fuzz run auction`.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use vcg_auction::types::SimpleBid;
+
+const MAX_ITEMS: usize = 4;
+const MAX_BID_SETS: usize = 6;
+const MAX_BIDS_PER_SET: usize = 3;
+const MAX_BIDDERS: u8 = 4;
+
+/// A small auction of [`SimpleBid`]s. Sizes are bounded so every instance
+/// solves quickly. Values fit in a `u32`, so the total value of the bids
+/// can't overflow, and quantities can be zero or more than the stock.
+#[derive(Debug)]
+pub struct Instance {
+    pub items: Vec<(String, u64)>,
+    pub bid_sets: Vec<Vec<SimpleBid>>,
+}
+
+impl<'a> Arbitrary<'a> for Instance {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let item_count = u.int_in_range(0..=MAX_ITEMS)?;
+        let items = (0..item_count)
+            .map(|i| Ok((format!("item{i}"), u.int_in_range(0..=3)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let bid_set_count = u.int_in_range(0..=MAX_BID_SETS)?;
+        let mut bid_sets = vec![];
+        for _ in 0..bid_set_count {
+            let bidder = u.int_in_range(0..=MAX_BIDDERS - 1)?;
+            let bid_count = u.int_in_range(0..=MAX_BIDS_PER_SET)?;
+            let bid_set = (0..bid_count)
+                .map(|_| bid(u, bidder, item_count))
+                .collect::<Result<_>>()?;
+            bid_sets.push(bid_set);
+        }
+        Ok(Self { items, bid_sets })
+    }
+}
+
+fn bid(
+    u: &mut Unstructured,
+    bidder: u8,
+    item_count: usize,
+) -> Result<SimpleBid> {
+    let value = u64::from(u.arbitrary::<u32>()?);
+    let mut items = vec![];
+    // one more than the stock, for bids of unknown items
+    for i in 0..=item_count {
+        if u.arbitrary()? {
+            items.push((format!("item{i}"), u.int_in_range(0..=4)?));
+        }
+    }
+    Ok(SimpleBid::new(format!("bidder{bidder}"), value, items))
+}
+
+/// Total value of a bidder's bids in `bids`.
+pub fn value_of(bids: &[&SimpleBid], bidder: &str) -> u64 {
+    bids.iter()
+        .filter(|b| b.name == bidder)
+        .map(|b| b.value)
+        .sum()
+}
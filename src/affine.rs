@@ -0,0 +1,188 @@
+//! Affine maximizers: auctions choosing the bids with the highest weighted
+//! value, so policies like favouring new entrants can be expressed while
+//! bidding truthfully stays each bidder's best strategy.
+
+use std::ops::{Div, Mul};
+
+use num_traits::{One, Zero};
+
+use crate::{
+    input::bid_set_refs, vcg::copy_value, vcg_auction_with_tiebreaker,
+    AddSubSelf, AuctionResult, Bid, StockEntry,
+};
+
+/// Weight of a bidder in an [`AffineMaximizer`].
+pub type WeightFn<'f, N, V> = dyn Fn(&N) -> V + 'f;
+
+/// Offset of a bid in an [`AffineMaximizer`].
+pub type OffsetFn<'f, B, V> = dyn Fn(&B) -> V + 'f;
+
+/// The objective of an affine maximizer: each bidder's bid values are
+/// multiplied by the bidder's weight, and each winning bid adds an offset
+/// that doesn't depend on its value. Without weights or offsets it's a VCG
+/// auction.
+pub struct AffineMaximizer<'f, B: Bid> {
+    weight: Option<&'f WeightFn<'f, B::Name, B::Value>>,
+    offset: Option<&'f OffsetFn<'f, B, B::Value>>,
+}
+
+impl<'f, B: Bid> AffineMaximizer<'f, B> {
+    /// The objective of a VCG auction, with every weight one and no offsets.
+    pub fn new() -> Self {
+        Self {
+            weight: None,
+            offset: None,
+        }
+    }
+
+    /// Weigh each bidder's values. Weights must be positive.
+    pub fn weight(
+        mut self,
+        weight: &'f WeightFn<'f, B::Name, B::Value>,
+    ) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Add an offset to the objective for each winning bid.
+    pub fn offset(mut self, offset: &'f OffsetFn<'f, B, B::Value>) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+}
+
+impl<'f, B: Bid> Default for AffineMaximizer<'f, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A bid valued at its weighted value plus its offset.
+pub(crate) struct AffineBid<'a, B: Bid> {
+    pub(crate) bid: &'a B,
+    pub(crate) value: B::Value,
+    pub(crate) offset: B::Value,
+}
+
+impl<'a, B: Bid> Bid for AffineBid<'a, B> {
+    type Name = B::Name;
+    type Value = B::Value;
+    type Item = B::Item;
+    type Quantity = B::Quantity;
+
+    fn bidder_name(&self) -> &Self::Name {
+        self.bid.bidder_name()
+    }
+    fn bid_value(&self) -> &Self::Value {
+        &self.value
+    }
+    fn bid_items(&self) -> &[(Self::Item, Self::Quantity)] {
+        self.bid.bid_items()
+    }
+}
+
+/// Calculate an affine maximizer auction with a tiebreaker. Items and bid
+/// sets are given as for
+/// [`vcg_auction_with_tiebreaker`](crate::vcg_auction_with_tiebreaker), and
+/// `None` is returned if the tiebreaker returns an invalid index.
+///
+/// The winning bids are those with the highest total of weighted values and
+/// offsets. A winner with weight `w` pays `1/w` of the weighted VCG payment:
+/// the highest objective without their bids, less the objective of the
+/// winning bids without their weighted values. Their own bids' offsets are
+/// taken off their payment, so offsets can make a payment negative, a
+/// subsidy, and need a signed value type when they can exceed a winner's
+/// payment. Payments of integer values are rounded down by the division.
+///
+/// ```
+/// use vcg_auction::{
+///     affine_maximizer_auction, types::SimpleBid, AffineMaximizer,
+/// };
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Newcomer", 8, [("chair", 1)])],
+/// ];
+/// // the newcomer's values count double
+/// let weight = |name: &String| if name == "Newcomer" { 2 } else { 1 };
+/// let maximizer = AffineMaximizer::new().weight(&weight);
+/// let result =
+///     affine_maximizer_auction(&items, &bids, |_| 0, &maximizer).unwrap();
+/// assert_eq!(result.winning_bids, [&bids[1][0]]);
+/// // the newcomer would still win bidding 5
+/// assert_eq!(result.payments, [(&"Newcomer".to_string(), 5)]);
+/// ```
+pub fn affine_maximizer_auction<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
+    maximizer: &AffineMaximizer<B>,
+) -> Option<AuctionResult<'a, B>>
+where
+    B: Bid + 'a + 'i,
+    B::Value: One + Mul<Output = B::Value> + Div<Output = B::Value>,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let items = items
+        .into_iter()
+        .map(StockEntry::into_entry)
+        .collect::<Vec<_>>();
+    let weight = |bidder_name: &B::Name| {
+        maximizer
+            .weight
+            .map_or_else(B::Value::one, |weight| weight(bidder_name))
+    };
+    let affine_bid_sets = bid_set_refs(exclusive_bid_sets)
+        .into_iter()
+        .map(|bid_set| {
+            bid_set
+                .into_iter()
+                .map(|bid| {
+                    let offset = maximizer
+                        .offset
+                        .map_or_else(B::Value::zero, |offset| offset(bid));
+                    let weighted = weight(bid.bidder_name())
+                        * copy_value::<B>(bid.bid_value());
+                    AffineBid {
+                        bid,
+                        value: weighted.add(&offset),
+                        offset,
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let result = vcg_auction_with_tiebreaker(
+        items.iter().copied(),
+        &affine_bid_sets,
+        |tied| {
+            let tied = tied
+                .iter()
+                .map(|bids| bids.iter().map(|b| b.bid).collect())
+                .collect::<Vec<_>>();
+            tiebreaker(&tied)
+        },
+    )?;
+    let payments = result
+        .payments
+        .into_iter()
+        .map(|(bidder_name, payment)| {
+            let bids = result
+                .winning_bids
+                .iter()
+                .filter(|b| b.bidder_name() == bidder_name)
+                .collect::<Vec<_>>();
+            let offsets = bids
+                .iter()
+                .fold(B::Value::zero(), |sum, b| sum.add(&b.offset));
+            // the name borrowed from the bids passed in
+            let bidder_name = bids[0].bid.bidder_name();
+            (bidder_name, payment.sub(&offsets) / weight(bidder_name))
+        })
+        .collect();
+    Some(AuctionResult {
+        winning_bids: result.winning_bids.iter().map(|b| b.bid).collect(),
+        payments,
+    })
+}
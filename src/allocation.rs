@@ -0,0 +1,214 @@
+//! Winner determination with payments computed only when asked for.
+//!
+//! Each winner's payment takes a search of the whole auction without them, so
+//! computing every payment can cost much more than finding the winners. An
+//! [`Allocation`] holds the winning bids, and searches for a winner's payment
+//! the first time it's requested. The same allocation can be priced under
+//! several [`PricingRule`]s without searching for the winners again.
+
+use std::sync::OnceLock;
+
+use crate::{
+    control::SearchControl,
+    input::{bid_set_refs, ItemStock},
+    pricing::{first_price_payments, first_rejected_bid_payments},
+    vcg::{
+        break_tie, find_counterfactual_bid_sets, find_highest_value_bid_sets,
+        other_winning_bids, payment_for_bidder, winning_bidders,
+    },
+    AuctionResult, Bid, PricingRule, SearchOptions, SearchPhase, StockEntry,
+};
+
+/// The winning bids of an auction, with payments calculated on demand and
+/// cached.
+///
+/// ```
+/// use vcg_auction::{types::SimpleBid, vcg_allocation_with_tiebreaker};
+///
+/// let items = vec![("chair".to_string(), 2)];
+/// let bids = vec![
+///     vec![
+///         SimpleBid::new("Alice", 5, [("chair", 1)]),
+///         SimpleBid::new("Alice", 7, [("chair", 2)]),
+///     ],
+///     vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+/// ];
+/// let allocation =
+///     vcg_allocation_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+/// assert_eq!(allocation.winning_bids(), [&bids[0][0], &bids[1][0]]);
+/// // only Bob's payment is searched for
+/// assert_eq!(allocation.payment_for(&"Bob".to_string()), Some(&2));
+/// ```
+pub struct Allocation<'a, B: Bid> {
+    items: ItemStock<'a, B>,
+    exclusive_bid_sets: Vec<Vec<&'a B>>,
+    winning_bids: Vec<&'a B>,
+    payments: Vec<(&'a B::Name, OnceLock<B::Value>)>,
+}
+
+/// Find the winning bids of a VCG auction, without calculating payments.
+/// Tied outcomes are broken at random like [`vcg_auction`](crate::vcg_auction).
+#[cfg(feature = "rand")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+pub fn vcg_allocation<'a, B: Bid, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'a, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+) -> Option<Allocation<'a, B>>
+where
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    vcg_allocation_with_tiebreaker(
+        items,
+        exclusive_bid_sets,
+        crate::vcg::random_tiebreaker,
+    )
+}
+
+/// Find the winning bids of a VCG auction with a tiebreaker, without
+/// calculating payments. Returns `None` if the tiebreaker returns an invalid
+/// index, like
+/// [`vcg_auction_with_tiebreaker`](crate::vcg_auction_with_tiebreaker).
+pub fn vcg_allocation_with_tiebreaker<'a, B: Bid, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'a, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
+) -> Option<Allocation<'a, B>>
+where
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    Allocation::find(
+        ItemStock::new(items),
+        bid_set_refs(exclusive_bid_sets),
+        tiebreaker,
+    )
+}
+
+impl<'a, B: Bid> Allocation<'a, B> {
+    /// Find the winning bids of the bid sets.
+    pub(crate) fn find(
+        items: ItemStock<'a, B>,
+        exclusive_bid_sets: Vec<Vec<&'a B>>,
+        tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
+    ) -> Option<Self> {
+        let (highest_bid_sets, _highest_value) = find_highest_value_bid_sets(
+            &items,
+            &exclusive_bid_sets,
+            &SearchControl::new(SearchOptions::default()),
+            SearchPhase::WinnerDetermination,
+        )
+        .ok()?;
+        let winning_bids = break_tie(&highest_bid_sets, tiebreaker)?.clone();
+        let payments = winning_bidders(&winning_bids)
+            .into_iter()
+            .map(|bidder_name| (bidder_name, OnceLock::new()))
+            .collect();
+        Some(Allocation {
+            items,
+            exclusive_bid_sets,
+            winning_bids,
+            payments,
+        })
+    }
+
+    /// The winning bids.
+    pub fn winning_bids(&self) -> &[&'a B] {
+        &self.winning_bids
+    }
+
+    /// The payment of a winning bidder, calculated the first time it's
+    /// requested. Returns `None` if the bidder didn't win anything.
+    pub fn payment_for(&self, bidder_name: &B::Name) -> Option<&B::Value> {
+        let (bidder_name, payment) = self
+            .payments
+            .iter()
+            .find(|(name, _)| *name == bidder_name)?;
+        Some(payment.get_or_init(|| self.calculate_payment(bidder_name)))
+    }
+
+    /// The winning bids with payments under a pricing rule. VCG payments are
+    /// calculated as needed and cached, like with
+    /// [`payment_for`](Self::payment_for).
+    ///
+    /// ```
+    /// use vcg_auction::{
+    ///     types::SimpleBid, vcg_allocation_with_tiebreaker, PricingRule,
+    /// };
+    ///
+    /// let items = vec![("chair".to_string(), 1)];
+    /// let bids = vec![
+    ///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+    ///     vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+    /// ];
+    /// let allocation =
+    ///     vcg_allocation_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    /// let vcg = allocation.price(PricingRule::Vcg);
+    /// let first_price = allocation.price(PricingRule::FirstPrice);
+    /// assert_eq!(vcg.payments, [(&"Alice".to_string(), 7)]);
+    /// assert_eq!(first_price.payments, [(&"Alice".to_string(), 10)]);
+    /// ```
+    pub fn price(&self, rule: PricingRule) -> AuctionResult<'a, B>
+    where
+        B::Value: Clone,
+    {
+        let payments = match rule {
+            PricingRule::Vcg => self
+                .payments
+                .iter()
+                .map(|(bidder_name, payment)| {
+                    let payment = payment
+                        .get_or_init(|| self.calculate_payment(bidder_name));
+                    (*bidder_name, payment.clone())
+                })
+                .collect(),
+            PricingRule::FirstPrice => first_price_payments(&self.winning_bids),
+            PricingRule::FirstRejectedBid => first_rejected_bid_payments(
+                &self.winning_bids,
+                &self.exclusive_bid_sets,
+            ),
+        };
+        AuctionResult {
+            winning_bids: self.winning_bids.clone(),
+            payments,
+        }
+    }
+
+    /// Calculate any payments not yet requested, and convert to an
+    /// [`AuctionResult`].
+    pub fn into_result(self) -> AuctionResult<'a, B> {
+        for (bidder_name, payment) in &self.payments {
+            payment.get_or_init(|| self.calculate_payment(bidder_name));
+        }
+        AuctionResult {
+            winning_bids: self.winning_bids,
+            payments: self
+                .payments
+                .into_iter()
+                .map(|(bidder_name, payment)| {
+                    let payment =
+                        payment.into_inner().expect("payment was calculated");
+                    (bidder_name, payment)
+                })
+                .collect(),
+        }
+    }
+
+    fn calculate_payment(&self, bidder_name: &B::Name) -> B::Value {
+        let control = SearchControl::new(SearchOptions::default());
+        payment_for_bidder(
+            &self.winning_bids,
+            bidder_name,
+            &self.exclusive_bid_sets,
+            |bid_sets| {
+                find_counterfactual_bid_sets(
+                    &self.items,
+                    bid_sets,
+                    &control,
+                    &other_winning_bids(&self.winning_bids, bidder_name),
+                )
+                .map(|(_, value)| value)
+                .map_err(|(interrupt, _)| interrupt)
+            },
+        )
+        .expect("searches without a deadline or cancellation finish")
+    }
+}
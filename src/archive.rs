@@ -0,0 +1,115 @@
+//! Versioned serialization of auction results, for archiving them in a format
+//! that later versions of the crate can still read.
+
+use std::fmt;
+
+use crate::{Bid, OwnedAuctionResult};
+
+/// Schema version written by [`ArchivedResult::new`]. Archives with this or
+/// an earlier version can be read back with [`ArchivedResult::into_result`].
+pub const RESULT_SCHEMA: u32 = 1;
+
+/// An [`OwnedAuctionResult`] tagged with the version of its schema, which
+/// serializes as an object with a `schema` field beside the result's fields.
+///
+/// The schema only changes by a new version. Fields a later version adds are
+/// read with defaults from archives of earlier versions, and
+/// [`into_result`](Self::into_result) upgrades those to the current result,
+/// so archives stay readable across versions of the crate.
+///
+/// ```
+/// use vcg_auction::{
+///     types::SimpleBid, vcg_auction_with_tiebreaker, ArchivedResult,
+/// };
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+/// ];
+/// let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+/// let archived = ArchivedResult::new(result.to_owned());
+/// let json = serde_json::to_string(&archived).unwrap();
+///
+/// let read: ArchivedResult<SimpleBid> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(read.into_result()?, result.to_owned());
+/// # Ok::<(), vcg_auction::ArchiveError>(())
+/// ```
+#[derive(
+    Clone, Eq, PartialEq, Hash, Debug, serde::Serialize, serde::Deserialize,
+)]
+#[serde(bound(
+    serialize = "B: serde::Serialize, \
+                 B::Name: serde::Serialize, \
+                 B::Value: serde::Serialize",
+    deserialize = "B: serde::Deserialize<'de>, \
+                   B::Name: serde::Deserialize<'de>, \
+                   B::Value: serde::Deserialize<'de>"
+))]
+#[cfg_attr(
+    feature = "schemars",
+    derive(schemars::JsonSchema),
+    schemars(bound = "B: schemars::JsonSchema, \
+                      B::Name: schemars::JsonSchema, \
+                      B::Value: schemars::JsonSchema")
+)]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub struct ArchivedResult<B: Bid> {
+    /// Version of the schema the result was written with.
+    pub schema: u32,
+    pub winning_bids: Vec<B>,
+    pub payments: Vec<(B::Name, B::Value)>,
+}
+
+impl<B: Bid> ArchivedResult<B> {
+    /// Tag a result with the current [`RESULT_SCHEMA`].
+    pub fn new(result: OwnedAuctionResult<B>) -> Self {
+        Self {
+            schema: RESULT_SCHEMA,
+            winning_bids: result.winning_bids,
+            payments: result.payments,
+        }
+    }
+
+    /// The archived result, upgraded from the schema it was written with.
+    /// Fails if its schema isn't one this version of the crate reads.
+    pub fn into_result(self) -> Result<OwnedAuctionResult<B>, ArchiveError> {
+        match self.schema {
+            1 => Ok(OwnedAuctionResult {
+                winning_bids: self.winning_bids,
+                payments: self.payments,
+            }),
+            schema => Err(ArchiveError::UnsupportedSchema(schema)),
+        }
+    }
+}
+
+impl<B: Bid> From<OwnedAuctionResult<B>> for ArchivedResult<B> {
+    fn from(result: OwnedAuctionResult<B>) -> Self {
+        Self::new(result)
+    }
+}
+
+/// Error reading an [`ArchivedResult`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub enum ArchiveError {
+    /// The result was written with a schema version this version of the
+    /// crate doesn't know, such as by a newer version of the crate.
+    UnsupportedSchema(u32),
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveError::UnsupportedSchema(schema) => write!(
+                f,
+                "unknown result schema {schema}, expected at most \
+                 {RESULT_SCHEMA}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
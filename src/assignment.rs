@@ -0,0 +1,313 @@
+//! Auctions of single units, where every bid is for one unit of one item.
+//!
+//! When no bid can win more than one unit, choosing the winning bids assigns
+//! bid sets to units of stock, and the highest value assignment is found in
+//! polynomial time by the Hungarian algorithm. The winner determination
+//! search starts from the assignment's value, so it only explores
+//! combinations that can reach it while still listing every tied outcome for
+//! the tiebreaker. Each payment is the value of the assignment without the
+//! bidder, with no search at all.
+
+use std::time::Instant;
+
+use num_traits::Zero;
+
+use crate::{
+    control::{Interrupt, SearchControl},
+    input::{bid_set_refs, ItemStock},
+    unit_dp::units,
+    vcg::{
+        copy_value, find_counterfactual_bid_sets,
+        find_highest_value_bid_sets_from, other_winning_bids,
+        payment_for_bidder, run_auction_with, winning_bidders,
+    },
+    AddSubSelf, AuctionError, AuctionOutcome, Bid, PhaseStats, SearchOptions,
+    SearchPhase, StockEntry,
+};
+
+/// Version of [`vcg_auction_with_options`](crate::vcg_auction_with_options)
+/// that solves auctions of single units as assignment problems.
+///
+/// Applies when every bid is for one unit of one item in stock, with values
+/// converted with `TryInto<i64>` and quantities with `TryInto<u64>`. Winner
+/// determination still searches, so the tiebreaker is given every tied
+/// outcome, but only explores combinations reaching the highest value, and
+/// payments need no search. Otherwise, or if a value or quantity can't be
+/// converted, the auction is searched as usual. Either way the result is
+/// identical to [`vcg_auction_with_options`](crate::vcg_auction_with_options).
+///
+/// ```
+/// use vcg_auction::{types::SimpleBid, vcg_auction_assignment, SearchOptions};
+///
+/// let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+/// let bids = vec![
+///     vec![
+///         SimpleBid::new("Alice", 5, [("chair", 1)]),
+///         SimpleBid::new("Alice", 13, [("table", 1)]),
+///     ],
+///     vec![SimpleBid::new("Bob", 7, [("table", 1)])],
+///     vec![SimpleBid::new("Carol", 3, [("chair", 1)])],
+/// ];
+/// let outcome =
+///     vcg_auction_assignment(&items, &bids, |_| 0, &SearchOptions::default())?;
+/// let result = outcome.complete().unwrap();
+/// assert_eq!(result.winning_bids, [&bids[0][1], &bids[2][0]]);
+/// // without Alice, Bob would have won the table
+/// assert_eq!(result.payments[0], (&"Alice".to_string(), 7));
+/// # Ok::<(), vcg_auction::AuctionError>(())
+/// ```
+pub fn vcg_auction_assignment<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
+    options: &SearchOptions<B::Value>,
+) -> Result<AuctionOutcome<'a, B>, AuctionError>
+where
+    B: Bid + 'i,
+    B::Value: TryInto<i64>,
+    B::Quantity: TryInto<u64>,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let items = ItemStock::new(items);
+    let control = SearchControl::new(options.clone());
+    run_auction_with(
+        &items,
+        bid_set_refs(exclusive_bid_sets),
+        tiebreaker,
+        &control,
+        |bid_sets, phase| {
+            let assigned = Assignment::new(&items, bid_sets)
+                .map(|assignment| assignment.solve())
+                .unwrap_or_default();
+            find_highest_value_bid_sets_from(
+                &items, bid_sets, &control, phase, &assigned,
+            )
+        },
+        |winning_bid_set, bid_sets| {
+            winning_bidders(winning_bid_set)
+                .into_iter()
+                .map(|bidder_name| {
+                    let payment = payment_for_bidder(
+                        winning_bid_set,
+                        bidder_name,
+                        bid_sets,
+                        |bid_sets| {
+                            counterfactual_value(
+                                &items,
+                                bid_sets,
+                                &control,
+                                &other_winning_bids(
+                                    winning_bid_set,
+                                    bidder_name,
+                                ),
+                            )
+                        },
+                    )?;
+                    Ok((bidder_name, payment))
+                })
+                .collect()
+        },
+    )
+}
+
+/// Highest value of the bid sets without a winning bidder, from their
+/// assignment if every bid is for a single unit, otherwise by search.
+fn counterfactual_value<'a, B>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>], // bid sets without the bidder
+    control: &SearchControl<B::Value>,
+    other_winning_bids: &[&'a B],
+) -> Result<B::Value, Interrupt>
+where
+    B: Bid,
+    B::Value: TryInto<i64>,
+    B::Quantity: TryInto<u64>,
+{
+    let Some(assignment) = Assignment::new(items, exclusive_bid_sets) else {
+        return find_counterfactual_bid_sets(
+            items,
+            exclusive_bid_sets,
+            control,
+            other_winning_bids,
+        )
+        .map(|(_, value)| value)
+        .map_err(|(interrupt, _)| interrupt);
+    };
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("assignment").entered();
+    let started = Instant::now();
+    let value = assignment
+        .solve()
+        .iter()
+        .fold(B::Value::zero(), |sum, bid| sum.add(bid.bid_value()));
+    let stats = PhaseStats {
+        searches: 1,
+        search_time: started.elapsed(),
+        ..PhaseStats::default()
+    };
+    control.record(SearchPhase::Payments, &stats);
+    Ok(value)
+}
+
+/// The non-empty bid sets of an auction of single units, with the item and
+/// value of each bid.
+struct Assignment<'a, B: Bid> {
+    stock: Vec<u64>,
+    bid_sets: Vec<Vec<(&'a B, usize, i64)>>,
+}
+
+impl<'a, B> Assignment<'a, B>
+where
+    B: Bid,
+    B::Value: TryInto<i64>,
+    B::Quantity: TryInto<u64>,
+{
+    /// The assignment problem of the bid sets, or `None` if a bid isn't for
+    /// one unit of one item in stock, or a value or quantity can't be
+    /// converted.
+    fn new(
+        items: &ItemStock<B>,
+        exclusive_bid_sets: &[Vec<&'a B>],
+    ) -> Option<Self> {
+        let stock = units(items.quantities())?;
+        let no_items = items.no_items_selected();
+        let bid_sets = exclusive_bid_sets
+            .iter()
+            .filter(|bid_set| !bid_set.is_empty())
+            .map(|bid_set| {
+                bid_set
+                    .iter()
+                    .map(|bid| {
+                        let selected =
+                            units(&items.select_bid_items(&no_items, bid))?;
+                        let item = selected.iter().position(|&u| u != 0)?;
+                        let single = selected[item] == 1
+                            && selected[item + 1..].iter().all(|&u| u == 0);
+                        let value =
+                            copy_value::<B>(bid.bid_value()).try_into().ok()?;
+                        single.then_some((*bid, item, value))
+                    })
+                    .collect::<Option<Vec<_>>>()
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { stock, bid_sets })
+    }
+
+    /// The winning bids of a highest value assignment, in the order of their
+    /// bid sets.
+    fn solve(&self) -> Vec<&'a B> {
+        let rows = self.bid_sets.len();
+        // the highest positive bid of each bid set on each item
+        let best = self
+            .bid_sets
+            .iter()
+            .map(|bid_set| {
+                let mut best: Vec<Option<(&'a B, i64)>> =
+                    vec![None; self.stock.len()];
+                for &(bid, item, value) in bid_set {
+                    if value > 0 && best[item].map_or(true, |(_, v)| value > v)
+                    {
+                        best[item] = Some((bid, value));
+                    }
+                }
+                best
+            })
+            .collect::<Vec<_>>();
+        // a column for each unit that could be won, then one per bid set for
+        // winning nothing
+        let columns = self
+            .stock
+            .iter()
+            .enumerate()
+            .flat_map(|(item, &units)| {
+                let units = usize::try_from(units).unwrap_or(usize::MAX);
+                (0..units.min(rows)).map(move |_| Some(item))
+            })
+            .chain((0..rows).map(|_| None))
+            .collect::<Vec<_>>();
+        let cost = |row: usize, column: usize| -> i128 {
+            columns[column]
+                .and_then(|item| best[row][item])
+                .map_or(0, |(_, value)| -i128::from(value))
+        };
+        hungarian(rows, columns.len(), cost)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(row, column)| {
+                let (bid, _) = best[row][columns[column]?]?;
+                Some(bid)
+            })
+            .collect()
+    }
+}
+
+/// The column assigned to each row of a lowest cost assignment, with at least
+/// as many columns as rows. Runs in `O(rows² × columns)` time, keeping a
+/// potential for each row and column so that each row is added by one
+/// shortest augmenting path.
+fn hungarian(
+    rows: usize,
+    columns: usize,
+    cost: impl Fn(usize, usize) -> i128,
+) -> Vec<usize> {
+    debug_assert!(rows <= columns);
+    const INFINITY: i128 = i128::MAX / 4;
+    // 1-based, with column 0 holding the row being added
+    let mut row_potential = vec![0; rows + 1];
+    let mut column_potential = vec![0; columns + 1];
+    let mut row_of = vec![0; columns + 1];
+    let mut previous = vec![0; columns + 1];
+    for row in 1..=rows {
+        row_of[0] = row;
+        let mut column = 0;
+        let mut slack = vec![INFINITY; columns + 1];
+        let mut visited = vec![false; columns + 1];
+        loop {
+            visited[column] = true;
+            let current = row_of[column];
+            let mut delta = INFINITY;
+            let mut next = 0;
+            for j in 1..=columns {
+                if visited[j] {
+                    continue;
+                }
+                let reduced = cost(current - 1, j - 1)
+                    - row_potential[current]
+                    - column_potential[j];
+                if reduced < slack[j] {
+                    slack[j] = reduced;
+                    previous[j] = column;
+                }
+                if slack[j] < delta {
+                    delta = slack[j];
+                    next = j;
+                }
+            }
+            for j in 0..=columns {
+                if visited[j] {
+                    row_potential[row_of[j]] += delta;
+                    column_potential[j] -= delta;
+                } else {
+                    slack[j] -= delta;
+                }
+            }
+            column = next;
+            if row_of[column] == 0 {
+                break;
+            }
+        }
+        // flip the augmenting path
+        while column != 0 {
+            let before = previous[column];
+            row_of[column] = row_of[before];
+            column = before;
+        }
+    }
+    let mut column_of = vec![0; rows];
+    for (column, &row) in row_of.iter().enumerate().skip(1) {
+        if row != 0 {
+            column_of[row - 1] = column - 1;
+        }
+    }
+    column_of
+}
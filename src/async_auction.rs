@@ -0,0 +1,98 @@
+//! Async wrappers that run the auction on tokio's blocking thread pool.
+//!
+//! Large auctions can take a long time to compute, which would stall an async
+//! executor if run directly on it. These functions move the computation onto
+//! [`tokio::task::spawn_blocking`] and return a future for the result. If the
+//! future is dropped before the auction finishes, the search is cancelled and
+//! the blocking thread is released at the next search step.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::{
+    control::SearchControl,
+    input::{bid_set_refs, ItemStock},
+    vcg::run_auction,
+    AuctionOutcome, Bid, OwnedAuctionResult, SearchOptions,
+};
+
+/// Async version of [`vcg_auction`](crate::vcg_auction). Takes ownership of
+/// the items and bids so they can be moved to a blocking thread, and returns
+/// an owned result.
+///
+/// Must be awaited from within a tokio runtime.
+#[cfg(feature = "rand")]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "tokio", feature = "rand"))))]
+pub async fn vcg_auction_async<B>(
+    items: Vec<(B::Item, B::Quantity)>,
+    exclusive_bid_sets: Vec<Vec<B>>,
+) -> Option<OwnedAuctionResult<B>>
+where
+    B: Bid + Clone + Send + 'static,
+    B::Name: Clone + Send,
+    B::Value: Send,
+    B::Item: Send,
+    B::Quantity: Send,
+{
+    vcg_auction_with_tiebreaker_async(
+        items,
+        exclusive_bid_sets,
+        crate::vcg::random_tiebreaker,
+    )
+    .await
+}
+
+/// Async version of
+/// [`vcg_auction_with_tiebreaker`](crate::vcg_auction_with_tiebreaker).
+///
+/// Must be awaited from within a tokio runtime.
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub async fn vcg_auction_with_tiebreaker_async<B>(
+    items: Vec<(B::Item, B::Quantity)>,
+    exclusive_bid_sets: Vec<Vec<B>>,
+    tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize + Send + 'static,
+) -> Option<OwnedAuctionResult<B>>
+where
+    B: Bid + Clone + Send + 'static,
+    B::Name: Clone + Send,
+    B::Value: Send,
+    B::Item: Send,
+    B::Quantity: Send,
+{
+    let cancel = CancelOnDrop(Arc::new(AtomicBool::new(false)));
+    let flag = Arc::clone(&cancel.0);
+    let handle = tokio::task::spawn_blocking(move || {
+        let control =
+            SearchControl::new(SearchOptions::default()).with_cancel(&flag);
+        run_auction(
+            &ItemStock::new(&items),
+            bid_set_refs(&exclusive_bid_sets),
+            tiebreaker,
+            &control,
+        )
+        .ok()
+        .and_then(AuctionOutcome::complete)
+        .map(|result| result.into_owned())
+    });
+    match handle.await {
+        Ok(result) => result,
+        // propagate panics from the auction to the awaiting task
+        Err(err) if err.is_panic() => {
+            std::panic::resume_unwind(err.into_panic())
+        }
+        // the runtime is shutting down
+        Err(_) => None,
+    }
+}
+
+/// Signals cancellation to the blocking search when the future owning it is
+/// dropped. Setting the flag after a completed search has no effect.
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
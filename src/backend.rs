@@ -0,0 +1,175 @@
+//! Pluggable backends for winner determination.
+//!
+//! An auction needs the highest value combinations of bids once for the
+//! winners and once more for each winner's payment. A
+//! [`WinnerDeterminationSolver`] answers those searches, so other solvers,
+//! such as integer programming or an external service, can price an auction
+//! with [`vcg_auction_with_solver`], and be tested against the
+//! [`BranchAndBound`] search used by the other auction functions.
+
+use std::fmt;
+
+use crate::{
+    control::SearchControl,
+    input::{bid_set_refs, ItemStock},
+    vcg::{
+        bid_sets_without_bidder, break_tie, find_highest_value_bid_sets,
+        payment_from_value, winning_bidders,
+    },
+    AuctionError, AuctionResult, Bid, SearchOptions, SearchPhase, SolveStats,
+    StockEntry,
+};
+
+/// The combinations of bids tied for the highest value of an auction.
+pub struct HighestValueBids<'a, B: Bid> {
+    /// Every combination tied for the highest value, with one bid from each
+    /// bid set at most. Tiebreakers are given them in this order.
+    pub tied: Vec<Vec<&'a B>>,
+    /// Value of each tied combination.
+    pub value: B::Value,
+}
+
+impl<'a, B: Bid> fmt::Debug for HighestValueBids<'a, B>
+where
+    B: fmt::Debug,
+    B::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HighestValueBids")
+            .field("tied", &self.tied)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+/// A backend finding the highest value combinations of bids, for
+/// [`vcg_auction_with_solver`].
+///
+/// Implementations should find the same combinations, in the same order, as
+/// [`BranchAndBound`] for auctions run with a tiebreaker to give the same
+/// results. Only the value matters for payments.
+pub trait WinnerDeterminationSolver<B: Bid> {
+    /// The combinations of bids tied for the highest value, choosing at most
+    /// one bid from each bid set, within the stock of each item. Items bid on
+    /// that aren't in stock are unlimited.
+    fn highest_value_bids<'a>(
+        &self,
+        items: &[(&B::Item, &B::Quantity)],
+        exclusive_bid_sets: &[Vec<&'a B>],
+        phase: SearchPhase,
+    ) -> Result<HighestValueBids<'a, B>, AuctionError>;
+}
+
+/// The branch-and-bound search used by
+/// [`vcg_auction_with_options`](crate::vcg_auction_with_options), as a
+/// [`WinnerDeterminationSolver`].
+///
+/// The limits of its [`SearchOptions`] apply across every search it runs, so
+/// create one for each auction. Timeouts are returned as
+/// [`AuctionError::TimedOut`] whatever the
+/// [`on_timeout`](SearchOptions::on_timeout).
+pub struct BranchAndBound<'o, V> {
+    control: SearchControl<'o, V>,
+}
+
+impl<'o, V> BranchAndBound<'o, V> {
+    /// A search with the given options. The
+    /// [`max_duration`](SearchOptions::max_duration) starts now.
+    pub fn new(options: &SearchOptions<'o, V>) -> Self {
+        Self {
+            control: SearchControl::new(options.clone()),
+        }
+    }
+
+    /// Statistics of the searches run so far.
+    pub fn stats(&self) -> SolveStats {
+        self.control.stats()
+    }
+}
+
+impl<V> Default for BranchAndBound<'_, V> {
+    fn default() -> Self {
+        Self::new(&SearchOptions::default())
+    }
+}
+
+impl<B: Bid> WinnerDeterminationSolver<B> for BranchAndBound<'_, B::Value> {
+    fn highest_value_bids<'a>(
+        &self,
+        items: &[(&B::Item, &B::Quantity)],
+        exclusive_bid_sets: &[Vec<&'a B>],
+        phase: SearchPhase,
+    ) -> Result<HighestValueBids<'a, B>, AuctionError> {
+        self.control.options().check_size(exclusive_bid_sets)?;
+        let items = ItemStock::new(items.iter().copied());
+        let (tied, value) = find_highest_value_bid_sets(
+            &items,
+            exclusive_bid_sets,
+            &self.control,
+            phase,
+        )
+        .map_err(|(interrupt, _)| interrupt.into_error())?;
+        Ok(HighestValueBids { tied, value })
+    }
+}
+
+/// Calculate a VCG auction with a tiebreaker, finding the highest value
+/// combinations of bids with `solver`.
+///
+/// ```
+/// use vcg_auction::{
+///     types::SimpleBid, vcg_auction_with_solver, BranchAndBound,
+/// };
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+/// ];
+/// let solver = BranchAndBound::default();
+/// let result = vcg_auction_with_solver(&items, &bids, |_| 0, &solver)?;
+/// assert_eq!(result.winning_bids, [&bids[0][0]]);
+/// assert_eq!(result.payments, [(&"Alice".to_string(), 7)]);
+/// # Ok::<(), vcg_auction::AuctionError>(())
+/// ```
+pub fn vcg_auction_with_solver<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
+    solver: &impl WinnerDeterminationSolver<B>,
+) -> Result<AuctionResult<'a, B>, AuctionError>
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    // the solver is given items listed more than once as one entry
+    let stock = ItemStock::<B>::new(items);
+    let items = stock.entries();
+    let exclusive_bid_sets = bid_set_refs(exclusive_bid_sets);
+    let highest = solver.highest_value_bids(
+        &items,
+        &exclusive_bid_sets,
+        SearchPhase::WinnerDetermination,
+    )?;
+    let winning_bid_set = break_tie(&highest.tied, tiebreaker)
+        .ok_or(AuctionError::InvalidTiebreak)?;
+    let payments = winning_bidders(winning_bid_set)
+        .into_iter()
+        .map(|bidder_name| {
+            let without_bidder =
+                bid_sets_without_bidder(&exclusive_bid_sets, bidder_name);
+            let highest = solver.highest_value_bids(
+                &items,
+                &without_bidder,
+                SearchPhase::Payments,
+            )?;
+            let payment =
+                payment_from_value(winning_bid_set, bidder_name, highest.value);
+            Ok((bidder_name, payment))
+        })
+        .collect::<Result<_, AuctionError>>()?;
+    Ok(AuctionResult {
+        winning_bids: winning_bid_set.clone(),
+        payments,
+    })
+}
@@ -0,0 +1,106 @@
+//! A reference solver that tries every combination of bids, for testing other
+//! solvers against.
+
+use num_traits::Zero;
+
+use crate::{
+    AddSubSelf, AuctionError, Bid, HighestValueBids, SearchPhase,
+    WinnerDeterminationSolver,
+};
+
+/// A [`WinnerDeterminationSolver`] trying every combination of bids, without
+/// pruning or limits. It's kept simple to be obviously correct, so it's only
+/// practical for small auctions: the number of combinations is the product of
+/// one more than the size of each bid set.
+///
+/// Combinations are tried in the order of the branch-and-bound search, each
+/// bid of the first bid set and then none, with the combinations of the
+/// remaining bid sets tried for each. Tied combinations are found in the same
+/// order as [`BranchAndBound`](crate::BranchAndBound), so auctions give the
+/// same result with either.
+///
+/// ```
+/// use vcg_auction::{
+///     types::SimpleBid, vcg_auction_with_solver, vcg_auction_with_tiebreaker,
+///     BruteForce,
+/// };
+///
+/// let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 5, [("chair", 1), ("table", 1)])],
+///     vec![SimpleBid::new("Bob", 4, [("chair", 2)])],
+///     vec![SimpleBid::new("Carol", 3, [("table", 1)])],
+/// ];
+/// assert_eq!(
+///     vcg_auction_with_solver(&items, &bids, |_| 0, &BruteForce)?,
+///     vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap(),
+/// );
+/// # Ok::<(), vcg_auction::AuctionError>(())
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "test-utils")))]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct BruteForce;
+
+impl<B: Bid> WinnerDeterminationSolver<B> for BruteForce {
+    fn highest_value_bids<'a>(
+        &self,
+        items: &[(&B::Item, &B::Quantity)],
+        exclusive_bid_sets: &[Vec<&'a B>],
+        _phase: SearchPhase,
+    ) -> Result<HighestValueBids<'a, B>, AuctionError> {
+        let mut highest = HighestValueBids {
+            tied: vec![],
+            value: B::Value::zero(),
+        };
+        try_combinations(items, exclusive_bid_sets, &mut vec![], &mut highest);
+        Ok(highest)
+    }
+}
+
+/// Try each combination of the remaining bid sets with the bids chosen so
+/// far, keeping the combinations of the highest value.
+fn try_combinations<'a, B: Bid>(
+    items: &[(&B::Item, &B::Quantity)],
+    remaining: &[Vec<&'a B>],
+    chosen: &mut Vec<&'a B>,
+    highest: &mut HighestValueBids<'a, B>,
+) {
+    let Some((bid_set, rest)) = remaining.split_first() else {
+        if !fits(items, chosen) {
+            return;
+        }
+        let value = chosen
+            .iter()
+            .fold(B::Value::zero(), |sum, bid| sum.add(bid.bid_value()));
+        if highest.tied.is_empty() || value > highest.value {
+            highest.tied = vec![chosen.clone()];
+            highest.value = value;
+        } else if value == highest.value {
+            highest.tied.push(chosen.clone());
+        }
+        return;
+    };
+    for bid in bid_set {
+        chosen.push(bid);
+        try_combinations(items, rest, chosen, highest);
+        chosen.pop();
+    }
+    try_combinations(items, rest, chosen, highest);
+}
+
+/// Whether the bids use no more of each item than is in stock. Like the
+/// search, an item listed twice in a bid counts its first quantity.
+fn fits<B: Bid>(items: &[(&B::Item, &B::Quantity)], bids: &[&B]) -> bool {
+    items.iter().all(|(item, stock)| {
+        let used = bids
+            .iter()
+            .filter_map(|bid| {
+                let mut bid_items = bid.bid_items().iter();
+                bid_items.find(|(bid_item, _)| bid_item == *item)
+            })
+            .fold(B::Quantity::zero(), |used, (_, quantity)| {
+                used.add(quantity)
+            });
+        used <= **stock
+    })
+}
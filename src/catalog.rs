@@ -0,0 +1,349 @@
+//! Preset for auctions of items tagged with attributes, such as their color,
+//! size or location, where bidders ask for any items with some attributes
+//! instead of naming them.
+//!
+//! A [`Pattern`] asks for a quantity of any items with all of its attributes,
+//! like "any 2 chairs that are blue". [`Catalog::bid_set`] grounds the
+//! patterns of a bid against the stock: every way of taking their quantities
+//! from the matching items becomes a concrete bid, and the bids are returned
+//! as one bid set, so at most one of them wins. Bidders don't have to list
+//! every combination of items they'd be as happy with.
+//!
+//! ```
+//! use vcg_auction::catalog::{Catalog, Pattern};
+//!
+//! let mut catalog = Catalog::new();
+//! catalog
+//!     .item("chair-1", 1, [("type", "chair"), ("color", "blue")])
+//!     .item("chair-2", 1, [("type", "chair"), ("color", "blue")])
+//!     .item("chair-3", 1, [("type", "chair"), ("color", "red")]);
+//! let blue_chair = Pattern::new(1, [("type", "chair"), ("color", "blue")]);
+//! let bids = vec![
+//!     catalog.bid_set("Alice", 10, [blue_chair.clone()])?,
+//!     catalog.bid_set("Bob", 7, [blue_chair])?,
+//! ];
+//! // either blue chair will do for each of them
+//! assert_eq!(bids[0].len(), 2);
+//!
+//! let items = catalog.items();
+//! let result =
+//!     vcg_auction::vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+//! assert_eq!(result.winning_bids.len(), 2);
+//! assert_eq!(
+//!     result.payments,
+//!     [(&"Alice".to_string(), 0), (&"Bob".to_string(), 0)]
+//! );
+//! # Ok::<(), vcg_auction::catalog::CatalogError>(())
+//! ```
+
+use std::fmt;
+
+use crate::Bid;
+
+/// An item of a [`Catalog`], with its attributes.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CatalogItem {
+    pub id: String,
+    pub quantity: u64,
+    /// Name and value of each attribute.
+    pub attributes: Vec<(String, String)>,
+}
+
+impl CatalogItem {
+    /// Whether the item has every attribute of the pattern.
+    pub fn matches(&self, pattern: &Pattern) -> bool {
+        pattern
+            .attributes
+            .iter()
+            .all(|attribute| self.attributes.contains(attribute))
+    }
+}
+
+/// A quantity of any items with all of some attributes. A pattern without
+/// attributes matches every item.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Pattern {
+    pub quantity: u64,
+    /// Name and value of each attribute the items must have.
+    pub attributes: Vec<(String, String)>,
+}
+
+impl Pattern {
+    /// A pattern for `quantity` of any items with the attributes.
+    pub fn new<N: Into<String>, V: Into<String>>(
+        quantity: u64,
+        attributes: impl IntoIterator<Item = (N, V)>,
+    ) -> Self {
+        Self {
+            quantity,
+            attributes: attributes
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into()))
+                .collect(),
+        }
+    }
+}
+
+/// A concrete bid grounded from patterns by [`Catalog::bid_set`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CatalogBid {
+    pub name: String,
+    pub value: u64,
+    /// Quantity of each item, in the catalog's order.
+    pub items: Vec<(String, u64)>,
+}
+
+impl Bid for CatalogBid {
+    type Name = String;
+    type Value = u64;
+    type Item = String;
+    type Quantity = u64;
+
+    fn bidder_name(&self) -> &Self::Name {
+        &self.name
+    }
+    fn bid_value(&self) -> &Self::Value {
+        &self.value
+    }
+    fn bid_items(&self) -> &[(Self::Item, Self::Quantity)] {
+        &self.items
+    }
+}
+
+/// Reasons a bid's patterns can't be grounded in a [`Catalog`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum CatalogError {
+    /// Fewer items match the pattern at `pattern` than it asks for.
+    NotEnoughStock {
+        pattern: usize,
+        requested: u64,
+        available: u64,
+    },
+    /// The patterns each match enough items, but not all at once, since
+    /// they ask for more of the items they share than are in stock.
+    NoAlternatives,
+    /// There are more ways of taking the patterns from the stock than the
+    /// catalog's [`max_alternatives`](Catalog::max_alternatives).
+    TooManyAlternatives { limit: usize },
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogError::NotEnoughStock {
+                pattern,
+                requested,
+                available,
+            } => write!(
+                f,
+                "pattern {pattern} asks for {requested} items, but only \
+                 {available} match"
+            ),
+            CatalogError::NoAlternatives => {
+                write!(f, "the patterns can't all be taken from the stock")
+            }
+            CatalogError::TooManyAlternatives { limit } => write!(
+                f,
+                "the patterns can be taken from the stock in more than \
+                 {limit} ways"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CatalogError {}
+
+/// The items of an auction, tagged with attributes, to ground bids' patterns
+/// against.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Catalog {
+    items: Vec<CatalogItem>,
+    max_alternatives: usize,
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Self {
+            items: vec![],
+            max_alternatives: 1000,
+        }
+    }
+}
+
+impl Catalog {
+    /// An empty catalog, grounding each bid in at most 1000 alternatives.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an item with its quantity and attributes. An item added more than
+    /// once keeps what it was first added with.
+    pub fn item<N: Into<String>, V: Into<String>>(
+        &mut self,
+        id: impl Into<String>,
+        quantity: u64,
+        attributes: impl IntoIterator<Item = (N, V)>,
+    ) -> &mut Self {
+        let id = id.into();
+        if !self.items.iter().any(|item| item.id == id) {
+            let attributes = Pattern::new(0, attributes).attributes;
+            self.items.push(CatalogItem {
+                id,
+                quantity,
+                attributes,
+            });
+        }
+        self
+    }
+
+    /// Limit the concrete bids one call to [`bid_set`](Self::bid_set) may
+    /// ground its patterns in. Each bid is another bid the auction searches,
+    /// so patterns matching many items should be narrowed instead.
+    pub fn max_alternatives(&mut self, max: usize) -> &mut Self {
+        self.max_alternatives = max;
+        self
+    }
+
+    /// The items of the catalog, in the order they were added.
+    pub fn entries(&self) -> &[CatalogItem] {
+        &self.items
+    }
+
+    /// The items matching a pattern, in the catalog's order.
+    pub fn matching<'c>(
+        &'c self,
+        pattern: &'c Pattern,
+    ) -> impl Iterator<Item = &'c CatalogItem> + 'c {
+        self.items.iter().filter(move |item| item.matches(pattern))
+    }
+
+    /// Items of the auction: the quantity of each item of the catalog.
+    pub fn items(&self) -> Vec<(String, u64)> {
+        self.items
+            .iter()
+            .map(|item| (item.id.clone(), item.quantity))
+            .collect()
+    }
+
+    /// A bidder's bid of `value` for all the patterns, grounded in a bid set
+    /// with a concrete bid for every way of taking the patterns' quantities
+    /// from the stock. Items matching several patterns are shared between
+    /// them, so no bid takes more of an item than is in stock.
+    ///
+    /// Bids are in a fixed order, taking as much as possible of the earlier
+    /// items of the catalog first.
+    pub fn bid_set(
+        &self,
+        bidder: impl Into<String>,
+        value: u64,
+        patterns: impl IntoIterator<Item = Pattern>,
+    ) -> Result<Vec<CatalogBid>, CatalogError> {
+        let limit = self.max_alternatives;
+        let too_many = CatalogError::TooManyAlternatives { limit };
+        // quantities taken of each item, by position in the catalog
+        let mut alternatives = vec![vec![0; self.items.len()]];
+        for (i, pattern) in patterns.into_iter().enumerate() {
+            let matching = (0..self.items.len())
+                .filter(|&item| self.items[item].matches(&pattern))
+                .collect::<Vec<_>>();
+            let available = matching.iter().fold(0u64, |sum, &item| {
+                sum.saturating_add(self.items[item].quantity)
+            });
+            if available < pattern.quantity {
+                return Err(CatalogError::NotEnoughStock {
+                    pattern: i,
+                    requested: pattern.quantity,
+                    available,
+                });
+            }
+            let mut ways = vec![];
+            let mut taken = vec![0; matching.len()];
+            let quantity = pattern.quantity;
+            if !self.ways(&matching, 0, quantity, &mut taken, &mut ways) {
+                return Err(too_many);
+            }
+            let mut grounded: Vec<Vec<u64>> = vec![];
+            for alternative in &alternatives {
+                for way in &ways {
+                    let mut quantities = alternative.clone();
+                    for (&item, &quantity) in matching.iter().zip(way) {
+                        quantities[item] += quantity;
+                    }
+                    let in_stock = quantities
+                        .iter()
+                        .zip(&self.items)
+                        .all(|(&quantity, item)| quantity <= item.quantity);
+                    if in_stock && !grounded.contains(&quantities) {
+                        if grounded.len() == limit {
+                            return Err(too_many);
+                        }
+                        grounded.push(quantities);
+                    }
+                }
+            }
+            if grounded.is_empty() {
+                return Err(CatalogError::NoAlternatives);
+            }
+            alternatives = grounded;
+        }
+
+        let name = bidder.into();
+        Ok(alternatives
+            .into_iter()
+            .map(|quantities| CatalogBid {
+                name: name.clone(),
+                value,
+                items: quantities
+                    .into_iter()
+                    .zip(&self.items)
+                    .filter(|(quantity, _)| *quantity > 0)
+                    .map(|(quantity, item)| (item.id.clone(), quantity))
+                    .collect(),
+            })
+            .collect())
+    }
+
+    /// Push every way of taking `remaining` from the matching items from
+    /// `at` on, after those already `taken`, largest quantities of earlier
+    /// items first. Returns `false` if there are more than
+    /// `max_alternatives`.
+    fn ways(
+        &self,
+        matching: &[usize],
+        at: usize,
+        remaining: u64,
+        taken: &mut Vec<u64>,
+        ways: &mut Vec<Vec<u64>>,
+    ) -> bool {
+        if remaining == 0 {
+            if ways.len() == self.max_alternatives {
+                return false;
+            }
+            ways.push(taken.clone());
+            return true;
+        }
+        let Some(&item) = matching.get(at) else {
+            return true;
+        };
+        let later = matching[at + 1..].iter().fold(0u64, |sum, &item| {
+            sum.saturating_add(self.items[item].quantity)
+        });
+        let most = remaining.min(self.items[item].quantity);
+        let least = remaining.saturating_sub(later);
+        for quantity in (least..=most).rev() {
+            taken[at] = quantity;
+            let done =
+                self.ways(matching, at + 1, remaining - quantity, taken, ways);
+            taken[at] = 0;
+            if !done {
+                return false;
+            }
+        }
+        true
+    }
+}
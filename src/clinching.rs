@@ -0,0 +1,294 @@
+//! An ascending clinching auction for identical units of one good, which
+//! reaches the VCG outcome in open rounds rather than from sealed bids.
+//!
+//! The auctioneer calls out a rising price, and each bidder answers with how
+//! many units they want at that price. Once the other bidders together want
+//! fewer units than are left, a bidder is sure to win the difference, and
+//! clinches those units at the current price. The auction closes when the
+//! total demand fits in the supply, and every bidder wins the units they
+//! still want. Bidders whose demand falls as the price rises do best by
+//! answering truthfully, and each pays what they'd pay in a VCG auction of
+//! the same units, up to the price increment.
+
+use std::fmt;
+use std::ops::Mul;
+
+use num_traits::{FromPrimitive, Zero};
+
+/// A bidder of a [`ClinchingAuction`] and what they've won so far.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ClinchingBidder<N, V> {
+    pub name: N,
+    /// Units wanted at the current price, or `None` before the first round.
+    pub demand: Option<u64>,
+    /// Units won so far.
+    pub clinched: u64,
+    /// Total price of the units won so far.
+    pub payment: V,
+}
+
+/// Units clinched by a bidder in a round of a [`ClinchingAuction`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Clinch<V> {
+    /// Index of the bidder, in the order they were given.
+    pub bidder: usize,
+    pub units: u64,
+    /// Price of each unit.
+    pub price: V,
+}
+
+/// Reasons a round of a [`ClinchingAuction`] can't go ahead.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum ClinchingError {
+    /// The auction has closed.
+    Closed,
+    /// The price wasn't raised since the last round.
+    PriceNotRaised,
+    /// Demands weren't given for exactly the bidders of the auction.
+    WrongBidderCount { expected: usize, found: usize },
+    /// A bidder wants more units than in the last round.
+    DemandIncreased { bidder: usize },
+}
+
+impl fmt::Display for ClinchingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClinchingError::Closed => write!(f, "auction has closed"),
+            ClinchingError::PriceNotRaised => {
+                write!(f, "price wasn't raised since the last round")
+            }
+            ClinchingError::WrongBidderCount { expected, found } => write!(
+                f,
+                "{found} demands given for an auction of {expected} bidders"
+            ),
+            ClinchingError::DemandIncreased { bidder } => {
+                write!(f, "demand of bidder {bidder} increased")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClinchingError {}
+
+/// An ascending clinching auction of identical units, run a round at a time.
+///
+/// Each round takes every bidder's demand at the current price with
+/// [`round`](Self::round), and the price is then raised with
+/// [`raise_price`](Self::raise_price) until the auction closes. Demands may
+/// only fall as the price rises. Clinched units are kept to the end, even if
+/// a bidder's demand falls below them.
+///
+/// ```
+/// use vcg_auction::ClinchingAuction;
+///
+/// let mut auction = ClinchingAuction::new(2, 0, ["Alice", "Bob"]);
+/// // Bob wants one unit, so Alice is sure of one of hers
+/// let clinches = auction.round(&[2, 1])?;
+/// assert_eq!((clinches[0].bidder, clinches[0].units), (0, 1));
+/// auction.raise_price(6)?;
+/// // now the demand fits, and Bob wins his unit at 6
+/// auction.round(&[1, 1])?;
+/// assert!(!auction.is_open());
+/// let bidders = auction.bidders();
+/// assert_eq!((bidders[0].clinched, bidders[0].payment), (1, 0));
+/// assert_eq!((bidders[1].clinched, bidders[1].payment), (1, 6));
+/// # Ok::<(), vcg_auction::ClinchingError>(())
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct ClinchingAuction<N, V> {
+    supply: u64,
+    price: V,
+    priced: bool,
+    open: bool,
+    bidders: Vec<ClinchingBidder<N, V>>,
+    clinches: Vec<Clinch<V>>,
+}
+
+impl<N, V> ClinchingAuction<N, V>
+where
+    V: Ord + Clone + Zero + FromPrimitive + Mul<Output = V>,
+{
+    /// An auction of `supply` units, starting at a price.
+    pub fn new(
+        supply: u64,
+        start_price: V,
+        bidders: impl IntoIterator<Item = N>,
+    ) -> Self {
+        Self {
+            supply,
+            price: start_price,
+            priced: true,
+            open: true,
+            bidders: bidders
+                .into_iter()
+                .map(|name| ClinchingBidder {
+                    name,
+                    demand: None,
+                    clinched: 0,
+                    payment: V::zero(),
+                })
+                .collect(),
+            clinches: vec![],
+        }
+    }
+
+    /// Take each bidder's demand at the current price, in the order the
+    /// bidders were given, and return the units clinched in the round. The
+    /// auction closes if the total demand fits in the supply.
+    pub fn round(
+        &mut self,
+        demands: &[u64],
+    ) -> Result<&[Clinch<V>], ClinchingError> {
+        if !self.open {
+            return Err(ClinchingError::Closed);
+        }
+        if !self.priced {
+            return Err(ClinchingError::PriceNotRaised);
+        }
+        if demands.len() != self.bidders.len() {
+            return Err(ClinchingError::WrongBidderCount {
+                expected: self.bidders.len(),
+                found: demands.len(),
+            });
+        }
+        for (bidder, (b, &demand)) in
+            self.bidders.iter().zip(demands).enumerate()
+        {
+            if b.demand.is_some_and(|last| demand > last) {
+                return Err(ClinchingError::DemandIncreased { bidder });
+            }
+        }
+
+        let total = demands.iter().fold(0u64, |sum, d| sum.saturating_add(*d));
+        self.open = total > self.supply;
+        self.priced = false;
+        let first_clinch = self.clinches.len();
+        for (bidder, (b, &demand)) in
+            self.bidders.iter_mut().zip(demands).enumerate()
+        {
+            b.demand = Some(demand);
+            // a bidder is sure of the units the others can't take, and
+            // keeps what they clinched even if their demand fell below it
+            let sure = if self.open {
+                self.supply.saturating_sub(total - demand)
+            } else {
+                demand
+            }
+            .max(b.clinched);
+            let units = sure - b.clinched;
+            if units == 0 {
+                continue;
+            }
+            let cost = V::from_u64(units).expect("units convert to a value")
+                * self.price.clone();
+            b.clinched = sure;
+            b.payment = b.payment.clone() + cost;
+            self.clinches.push(Clinch {
+                bidder,
+                units,
+                price: self.price.clone(),
+            });
+        }
+        Ok(&self.clinches[first_clinch..])
+    }
+
+    /// Raise the price for the next round.
+    pub fn raise_price(&mut self, price: V) -> Result<(), ClinchingError> {
+        if !self.open {
+            return Err(ClinchingError::Closed);
+        }
+        if price <= self.price {
+            return Err(ClinchingError::PriceNotRaised);
+        }
+        self.price = price;
+        self.priced = true;
+        Ok(())
+    }
+
+    /// Whether the total demand is still more than the supply.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// The current price.
+    pub fn price(&self) -> &V {
+        &self.price
+    }
+
+    /// Units up for auction.
+    pub fn supply(&self) -> u64 {
+        self.supply
+    }
+
+    /// Units no bidder has clinched.
+    pub fn unsold(&self) -> u64 {
+        self.supply - self.bidders.iter().map(|b| b.clinched).sum::<u64>()
+    }
+
+    /// The bidders, in the order they were given.
+    pub fn bidders(&self) -> &[ClinchingBidder<N, V>] {
+        &self.bidders
+    }
+
+    /// Every clinch so far, in the order they were made.
+    pub fn clinches(&self) -> &[Clinch<V>] {
+        &self.clinches
+    }
+}
+
+/// Run a clinching auction of `supply` units to the end for bidders who
+/// answer with their true demand, from the value of each extra unit they
+/// win. The price starts at zero and rises by `increment` each round, and a
+/// bidder wants every unit whose value is more than the price.
+///
+/// With diminishing values for each extra unit, and values that are
+/// multiples of the increment, the units won and payments are those of a VCG
+/// auction of the same bids. Returns
+/// [`ClinchingError::PriceNotRaised`] if the increment isn't positive.
+///
+/// ```
+/// use vcg_auction::clinching_auction;
+///
+/// let bidders = [("Alice", vec![10, 6]), ("Bob", vec![8])];
+/// let auction = clinching_auction(2, &bidders, 1)?;
+/// let won = auction
+///     .bidders()
+///     .iter()
+///     .map(|b| (*b.name, b.clinched, b.payment))
+///     .collect::<Vec<_>>();
+/// assert_eq!(won, [("Alice", 1, 0), ("Bob", 1, 6)]);
+/// # Ok::<(), vcg_auction::ClinchingError>(())
+/// ```
+pub fn clinching_auction<N, V>(
+    supply: u64,
+    bidders: &[(N, Vec<V>)],
+    increment: V,
+) -> Result<ClinchingAuction<&N, V>, ClinchingError>
+where
+    V: Ord + Clone + Zero + FromPrimitive + Mul<Output = V>,
+{
+    if increment <= V::zero() {
+        return Err(ClinchingError::PriceNotRaised);
+    }
+    let mut auction =
+        ClinchingAuction::new(supply, V::zero(), bidders.iter().map(|b| &b.0));
+    loop {
+        let demands = bidders
+            .iter()
+            .map(|(_, values)| {
+                values
+                    .iter()
+                    .filter(|value| **value > auction.price)
+                    .count() as u64
+            })
+            .collect::<Vec<_>>();
+        auction.round(&demands)?;
+        if !auction.is_open() {
+            return Ok(auction);
+        }
+        auction.raise_price(auction.price.clone() + increment.clone())?;
+    }
+}
@@ -0,0 +1,223 @@
+//! Canonical encoding of auction inputs, and commitments to them.
+//!
+//! Sealed-bid schemes publish a commitment to the inputs of an auction before
+//! they're revealed, so that anyone can later check that the auction solved
+//! was the one committed to. The encoding is independent of the machine and
+//! of the order of the items, which doesn't affect the result. Bid sets and
+//! the bids inside them are kept in order, since they decide ties broken with
+//! [`Tiebreak::First`].
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    AuctionConfig, Bid, DuplicateItems, Objective, PricingRule, StockEntry,
+    Tiebreak,
+};
+
+/// Prefix of every encoded instance, versioning the encoding.
+const INSTANCE_TAG: &[u8] = b"vcg-auction/instance/v1";
+
+/// Types with a canonical byte encoding, used for bidder names, bid values,
+/// items and quantities in [`encode_instance`].
+///
+/// Encodings must be injective: different values must never encode to the
+/// same bytes, including when concatenated with other encodings.
+/// Implementations for compound types can length-prefix variable-length
+/// parts, as the provided implementations do.
+pub trait CanonicalEncode {
+    /// Append the encoding of the value to `out`.
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+/// Big-endian bytes of the integer.
+macro_rules! impl_canonical_encode_int {
+    ($($int:ty),*) => {
+        $(
+            impl CanonicalEncode for $int {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_be_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_canonical_encode_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+/// Length as a `u64`, then the UTF-8 bytes.
+impl CanonicalEncode for str {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u64).encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl CanonicalEncode for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_str().encode(out);
+    }
+}
+
+impl<T: CanonicalEncode + ?Sized> CanonicalEncode for &T {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (**self).encode(out);
+    }
+}
+
+/// Encode an auction instance: its items, bid sets, and the parts of its
+/// config that decide the result.
+///
+/// Items are sorted by their encoding, as is each bid's list of items. The
+/// config is encoded as its pricing rule, tiebreak, search limits,
+/// treatment of duplicate items and objectives. A
+/// [`Tiebreak::Custom`] tiebreaker is encoded only as being custom, so the
+/// function itself has to be agreed on separately.
+pub fn encode_instance<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    config: &AuctionConfig<B>,
+) -> Vec<u8>
+where
+    B: Bid + 'i,
+    B::Name: CanonicalEncode,
+    B::Value: CanonicalEncode,
+    B::Item: CanonicalEncode,
+    B::Quantity: CanonicalEncode,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let mut out = INSTANCE_TAG.to_vec();
+    let items = items.into_iter().map(StockEntry::into_entry);
+    encode_sorted(items, &mut out);
+
+    let bid_sets = exclusive_bid_sets.into_iter().collect::<Vec<_>>();
+    (bid_sets.len() as u64).encode(&mut out);
+    for bid_set in bid_sets {
+        let bid_set = bid_set.as_ref();
+        (bid_set.len() as u64).encode(&mut out);
+        for bid in bid_set {
+            encode_bid(bid, &mut out);
+        }
+    }
+
+    encode_config(config, &mut out);
+    out
+}
+
+/// SHA-256 of the [`encode_instance`] encoding of an auction, to commit to
+/// its inputs before they're revealed.
+///
+/// ```
+/// use vcg_auction::{commitment_hash, types::SimpleBid, AuctionConfig};
+///
+/// let items = vec![("chair".to_string(), 1), ("table".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 7, [("table", 1)])],
+/// ];
+/// let config = AuctionConfig::deterministic();
+/// let commitment = commitment_hash(&items, &bids, &config);
+///
+/// // the order of the items doesn't change the commitment
+/// let reordered = vec![("table".to_string(), 1), ("chair".to_string(), 1)];
+/// assert_eq!(commitment_hash(&reordered, &bids, &config), commitment);
+/// ```
+pub fn commitment_hash<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    config: &AuctionConfig<B>,
+) -> [u8; 32]
+where
+    B: Bid + 'i,
+    B::Name: CanonicalEncode,
+    B::Value: CanonicalEncode,
+    B::Item: CanonicalEncode,
+    B::Quantity: CanonicalEncode,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    Sha256::digest(encode_instance(items, exclusive_bid_sets, config)).into()
+}
+
+/// Encode a bid's name, value, and items sorted by their encodings.
+pub(crate) fn encode_bid<B>(bid: &B, out: &mut Vec<u8>)
+where
+    B: Bid,
+    B::Name: CanonicalEncode,
+    B::Value: CanonicalEncode,
+    B::Item: CanonicalEncode,
+    B::Quantity: CanonicalEncode,
+{
+    bid.bidder_name().encode(out);
+    bid.bid_value().encode(out);
+    let items = bid.bid_items().iter().map(|(item, qty)| (item, qty));
+    encode_sorted(items, out);
+}
+
+/// Encode a count, then item and quantity pairs in the order of their
+/// encodings.
+fn encode_sorted<'e, I, Q>(
+    entries: impl IntoIterator<Item = (&'e I, &'e Q)>,
+    out: &mut Vec<u8>,
+) where
+    I: CanonicalEncode + 'e,
+    Q: CanonicalEncode + 'e,
+{
+    let mut encoded = entries
+        .into_iter()
+        .map(|(item, quantity)| {
+            let mut entry = vec![];
+            item.encode(&mut entry);
+            quantity.encode(&mut entry);
+            entry
+        })
+        .collect::<Vec<_>>();
+    encoded.sort_unstable();
+    (encoded.len() as u64).encode(out);
+    for entry in encoded {
+        out.extend_from_slice(&entry);
+    }
+}
+
+fn encode_config<B: Bid>(config: &AuctionConfig<B>, out: &mut Vec<u8>) {
+    match config.pricing {
+        PricingRule::Vcg => out.push(0),
+        PricingRule::FirstPrice => out.push(1),
+        PricingRule::FirstRejectedBid => out.push(2),
+    }
+    match config.tiebreak {
+        #[cfg(feature = "rand")]
+        Tiebreak::Random => out.push(0),
+        #[cfg(feature = "rng")]
+        Tiebreak::Seeded(seed) => {
+            out.push(1);
+            seed.encode(out);
+        }
+        Tiebreak::First => out.push(2),
+        Tiebreak::Custom(_) => out.push(3),
+    }
+    encode_limit(config.search.max_nodes, out);
+    encode_limit(config.search.max_tied_outcomes.map(|max| max as u64), out);
+    encode_limit(config.search.max_bid_sets.map(|max| max as u64), out);
+    encode_limit(config.search.max_bids_per_set.map(|max| max as u64), out);
+    match config.duplicate_items {
+        DuplicateItems::Merge => out.push(0),
+        DuplicateItems::Reject => out.push(1),
+    }
+    (config.objectives.len() as u64).encode(out);
+    for objective in config.objectives {
+        match objective {
+            Objective::MinPayments => out.push(0),
+            Objective::MinConcentration => out.push(1),
+            Objective::MaxUtilization => out.push(2),
+        }
+    }
+}
+
+fn encode_limit(limit: Option<u64>, out: &mut Vec<u8>) {
+    match limit {
+        None => out.push(0),
+        Some(limit) => {
+            out.push(1);
+            limit.encode(out);
+        }
+    }
+}
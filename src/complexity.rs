@@ -0,0 +1,209 @@
+//! Estimates of how long an auction takes to solve, for scheduling auctions
+//! onto workers before running them.
+
+use std::time::Duration;
+
+use crate::{input::bid_set_refs, Bid, SolveStats, StockEntry};
+
+/// Size of an auction's search, returned by [`estimate_complexity`].
+///
+/// Search sizes are worst cases, for a search that prunes nothing. They're
+/// kept as floats since they grow exponentially with the number of bid sets,
+/// and are infinite if they overflow. A [`Calibration`] from earlier runs
+/// turns them into estimates.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ComplexityReport {
+    /// Number of bid sets with at least one bid.
+    pub bid_sets: usize,
+    /// Total number of bids.
+    pub bids: usize,
+    /// Number of bids in the largest bid set.
+    pub largest_bid_set: usize,
+    /// Most bid sets bidding on any one item. The more bid sets compete for
+    /// an item, the more combinations of bids over-allocate it.
+    pub max_contention: usize,
+    /// Average number of bid sets bidding on each item.
+    pub mean_contention: f64,
+    /// Number of ways to choose at most one bid from each bid set, whether or
+    /// not the items are in stock.
+    pub search_space: f64,
+    /// Most search nodes the auction can explore, across the search for the
+    /// winners and the searches for each payment.
+    pub worst_case_nodes: f64,
+}
+
+impl ComplexityReport {
+    /// Number of search nodes the auction is expected to explore.
+    pub fn estimated_nodes(&self, calibration: &Calibration) -> f64 {
+        self.worst_case_nodes * calibration.explored_fraction
+    }
+
+    /// Time the auction is expected to take to solve.
+    pub fn estimated_duration(&self, calibration: &Calibration) -> Duration {
+        let seconds =
+            self.estimated_nodes(calibration) / calibration.nodes_per_second;
+        Duration::try_from_secs_f64(seconds).unwrap_or(Duration::MAX)
+    }
+}
+
+/// Speed of a worker and effectiveness of pruning, measured on earlier
+/// auctions.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Calibration {
+    /// Search nodes explored per second.
+    pub nodes_per_second: f64,
+    /// Fraction of the worst-case nodes that auctions explore.
+    pub explored_fraction: f64,
+}
+
+impl Calibration {
+    /// Calibrate from the reports and statistics of earlier runs, such as
+    /// those of [`run_with_stats`](crate::run_with_stats). Returns `None` if
+    /// the runs explored no nodes or took no time.
+    ///
+    /// ```
+    /// use vcg_auction::{
+    ///     estimate_complexity, run_with_stats, types::SimpleBid,
+    ///     AuctionConfig, Calibration,
+    /// };
+    ///
+    /// let items = vec![("chair".to_string(), 1)];
+    /// let bids = vec![
+    ///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+    ///     vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+    /// ];
+    /// let report = estimate_complexity(&items, &bids);
+    /// let (_, stats) =
+    ///     run_with_stats(&items, &bids, &AuctionConfig::deterministic());
+    /// if let Some(calibration) = Calibration::from_runs([(&report, &stats)]) {
+    ///     println!("{:?}", report.estimated_duration(&calibration));
+    /// }
+    /// ```
+    pub fn from_runs<'r>(
+        runs: impl IntoIterator<Item = (&'r ComplexityReport, &'r SolveStats)>,
+    ) -> Option<Self> {
+        let mut worst_case_nodes = 0.0;
+        let mut nodes = 0.0;
+        let mut seconds = 0.0;
+        for (report, stats) in runs {
+            worst_case_nodes += report.worst_case_nodes;
+            nodes += stats.nodes() as f64;
+            seconds += (stats.winner_determination.search_time
+                + stats.payments.search_time)
+                .as_secs_f64();
+        }
+        if nodes == 0.0 || seconds == 0.0 || !worst_case_nodes.is_finite() {
+            return None;
+        }
+        Some(Self {
+            nodes_per_second: nodes / seconds,
+            explored_fraction: nodes / worst_case_nodes,
+        })
+    }
+}
+
+/// Estimate how large an auction's search is from the sizes of its bid sets
+/// and how many compete for each item, without searching. Items and bid sets
+/// are given as for [`vcg_auction`](crate::vcg_auction).
+///
+/// ```
+/// use vcg_auction::{estimate_complexity, types::SimpleBid};
+///
+/// let items = vec![("chair".to_string(), 1), ("table".to_string(), 1)];
+/// let bids = vec![
+///     vec![
+///         SimpleBid::new("Alice", 10, [("chair", 1)]),
+///         SimpleBid::new("Alice", 12, [("chair", 1), ("table", 1)]),
+///     ],
+///     vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+/// ];
+/// let report = estimate_complexity(&items, &bids);
+/// assert_eq!(report.search_space, 6.0);
+/// assert_eq!(report.max_contention, 2);
+/// assert_eq!(report.mean_contention, 1.5);
+/// ```
+pub fn estimate_complexity<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+) -> ComplexityReport
+where
+    B: Bid + 'a + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let exclusive_bid_sets = bid_set_refs(exclusive_bid_sets);
+    let bid_sets = exclusive_bid_sets
+        .iter()
+        .filter(|bid_set| !bid_set.is_empty())
+        .collect::<Vec<_>>();
+    let size = SearchSize::of(&bid_sets);
+
+    // number of bid sets with a bid on each item
+    let contention = items
+        .into_iter()
+        .map(|entry| {
+            let (item, _) = entry.into_entry();
+            bid_sets
+                .iter()
+                .filter(|bid_set| {
+                    bid_set.iter().any(|bid| {
+                        bid.bid_items().iter().any(|(id, _)| id == item)
+                    })
+                })
+                .count()
+        })
+        .collect::<Vec<_>>();
+    let mean_contention = if contention.is_empty() {
+        0.0
+    } else {
+        contention.iter().sum::<usize>() as f64 / contention.len() as f64
+    };
+
+    ComplexityReport {
+        bid_sets: bid_sets.len(),
+        bids: bid_sets.iter().map(|bid_set| bid_set.len()).sum(),
+        largest_bid_set: bid_sets
+            .iter()
+            .map(|bid_set| bid_set.len())
+            .max()
+            .unwrap_or(0),
+        max_contention: contention.iter().copied().max().unwrap_or(0),
+        mean_contention,
+        search_space: size.search_space,
+        worst_case_nodes: size.winner_determination_nodes + size.payment_nodes,
+    }
+}
+
+/// Worst-case size of the searches of an auction.
+pub(crate) struct SearchSize {
+    pub(crate) search_space: f64,
+    pub(crate) winner_determination_nodes: f64,
+    pub(crate) payment_nodes: f64,
+}
+
+impl SearchSize {
+    /// Size of the searches over the given non-empty bid sets.
+    pub(crate) fn of<B: Bid>(bid_sets: &[&Vec<&B>]) -> Self {
+        // nodes at each depth of the search are the combinations of bids of
+        // the bid sets before it, every one of which is explored without
+        // pruning
+        let mut search_space = 1.0;
+        let mut winner_determination_nodes = 1.0;
+        for bid_set in bid_sets {
+            search_space *= (bid_set.len() + 1) as f64;
+            winner_determination_nodes += search_space;
+        }
+        let mut bidders: Vec<&B::Name> = vec![];
+        for bid in bid_sets.iter().flat_map(|bid_set| bid_set.iter()) {
+            if !bidders.contains(&bid.bidder_name()) {
+                bidders.push(bid.bidder_name());
+            }
+        }
+        // each winner's search leaves out their bids, so is no larger
+        let winners = bidders.len().min(bid_sets.len());
+        Self {
+            search_space,
+            winner_determination_nodes,
+            payment_nodes: winners as f64 * winner_determination_nodes,
+        }
+    }
+}
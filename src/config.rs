@@ -0,0 +1,893 @@
+//! Configuration of every option of an auction, for the [`run`] entry point.
+
+use std::cell::Cell;
+use std::fmt;
+use std::time::Duration;
+
+use num_traits::Zero;
+
+#[cfg(feature = "rng")]
+use crate::AuctionRng;
+use crate::{
+    control::SearchControl,
+    dropped::dropped_bids,
+    dry_run::input_problems,
+    input::{bid_set_refs, ItemStock},
+    objectives::preferred,
+    pricing::{first_price_payments, first_rejected_bid_payments},
+    pruning::PruningRecorder,
+    report::{position, summarize},
+    vcg::{
+        calculate_payments, derive_payments, find_highest_value_bid_sets,
+        run_auction, run_auction_with,
+    },
+    AddSubSelf, AuctionError, AuctionOutcome, Bid, BidSetOrder, ConfigSummary,
+    InputError, Objective, OnTimeout, PaymentDerivation, PricingRule,
+    ProgressCallback, PruningLog, ReportedResult, RunReport, SearchOptions,
+    SearchStrategy, SearchTree, SolveStats, StockEntry, TieReport,
+};
+
+/// How an auction chooses between tied outcomes.
+#[non_exhaustive]
+pub enum Tiebreak<'c, B: Bid> {
+    /// Choose uniformly at random, like [`vcg_auction`](crate::vcg_auction),
+    /// or with the config's [`rng`](AuctionConfig::rng) if it has one.
+    #[cfg(feature = "rand")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+    Random,
+    /// Choose uniformly at random with rand's `StdRng` seeded from this
+    /// value, so the choice can be reproduced. `StdRng`'s algorithm may change
+    /// between versions of rand.
+    #[cfg(feature = "rng")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rng")))]
+    Seeded(u64),
+    /// Choose the first tied outcome found by the search.
+    First,
+    /// Choose with a tiebreaker, like
+    /// [`vcg_auction_with_tiebreaker`](crate::vcg_auction_with_tiebreaker).
+    Custom(&'c TiebreakerFn<'c, B>),
+}
+
+/// Tiebreaker of a [`Tiebreak::Custom`], returning the index of the winning
+/// outcome among those tied.
+pub type TiebreakerFn<'c, B> = dyn Fn(&[Vec<&B>]) -> usize + 'c;
+
+/// How an auction treats an item listed more than once in its stock.
+///
+/// Auction functions that don't take a config, and
+/// [`verify_result`](crate::verify_result), always merge the entries.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum DuplicateItems {
+    /// Treat the entries as one, with their quantities added up.
+    #[default]
+    Merge,
+    /// Fail with [`InputError::DuplicateStockItem`] for the first repeated
+    /// entry.
+    Reject,
+}
+
+/// The order of the winning bids and payments of an auction's result.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum ResultOrder {
+    /// The order the search found them in, which isn't guaranteed and may
+    /// change with the search's options or between versions.
+    #[default]
+    Search,
+    /// Winning bids in the order of the bid sets and of the bids in them,
+    /// and payments in the order of each bidder's first winning bid, so the
+    /// result doesn't depend on how the search went. For an order that
+    /// doesn't depend on the order of the bid sets either, use
+    /// [`AuctionResult::sorted`](crate::AuctionResult::sorted).
+    Input,
+}
+
+/// Random with the `rand` feature, otherwise first.
+impl<B: Bid> Default for Tiebreak<'_, B> {
+    fn default() -> Self {
+        #[cfg(feature = "rand")]
+        return Tiebreak::Random;
+        #[cfg(not(feature = "rand"))]
+        return Tiebreak::First;
+    }
+}
+
+// manual impls, since derives would require `B` to implement them
+impl<B: Bid> Clone for Tiebreak<'_, B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B: Bid> Copy for Tiebreak<'_, B> {}
+
+impl<B: Bid> fmt::Debug for Tiebreak<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "rand")]
+            Tiebreak::Random => write!(f, "Random"),
+            #[cfg(feature = "rng")]
+            Tiebreak::Seeded(seed) => write!(f, "Seeded({seed})"),
+            Tiebreak::First => write!(f, "First"),
+            Tiebreak::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// All the options of an auction run with [`run`]. Created with
+/// [`AuctionConfig::builder`], or [`Default`] for a VCG auction with a random
+/// tiebreak and no time limit.
+pub struct AuctionConfig<'c, B: Bid> {
+    /// How to choose between tied outcomes.
+    pub tiebreak: Tiebreak<'c, B>,
+    /// How winners pay for their bids.
+    pub pricing: PricingRule,
+    /// How items listed more than once in the stock are treated.
+    pub duplicate_items: DuplicateItems,
+    /// The order of the winning bids and payments.
+    pub result_order: ResultOrder,
+    /// Goals to choose between outcomes tied for the highest welfare by, in
+    /// order, before the tiebreak chooses between those left.
+    pub objectives: &'c [Objective],
+    /// Progress reporting and time limits of the search.
+    pub search: SearchOptions<'c, B::Value>,
+    /// Generator every random choice of a run is drawn from, instead of
+    /// `thread_rng`, so runs can be reproduced from a seed.
+    #[cfg(feature = "rng")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rng")))]
+    pub rng: Option<&'c AuctionRng>,
+}
+
+impl<B: Bid> Default for AuctionConfig<'_, B> {
+    fn default() -> Self {
+        Self {
+            tiebreak: Tiebreak::default(),
+            pricing: PricingRule::default(),
+            duplicate_items: DuplicateItems::default(),
+            result_order: ResultOrder::default(),
+            objectives: &[],
+            search: SearchOptions::default(),
+            #[cfg(feature = "rng")]
+            rng: None,
+        }
+    }
+}
+
+impl<B: Bid> Clone for AuctionConfig<'_, B> {
+    fn clone(&self) -> Self {
+        Self {
+            tiebreak: self.tiebreak,
+            pricing: self.pricing,
+            duplicate_items: self.duplicate_items,
+            result_order: self.result_order,
+            objectives: self.objectives,
+            search: self.search.clone(),
+            #[cfg(feature = "rng")]
+            rng: self.rng,
+        }
+    }
+}
+
+impl<'c, B: Bid> AuctionConfig<'c, B> {
+    /// A config whose result depends only on the items and bid sets, for
+    /// replicated systems where every node must compute identical results.
+    /// Ties are broken with [`Tiebreak::First`], payments use VCG pricing,
+    /// results are in [`ResultOrder::Input`], and there is no time limit. See
+    /// the [crate documentation](crate#determinism) for what's covered.
+    ///
+    /// ```
+    /// use vcg_auction::{types::SimpleBid, AuctionConfig};
+    ///
+    /// let items = vec![("chair".to_string(), 1)];
+    /// let bids = vec![
+    ///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+    ///     vec![SimpleBid::new("Bob", 10, [("chair", 1)])],
+    /// ];
+    /// let config = AuctionConfig::deterministic();
+    /// let result = vcg_auction::run(&items, &bids, &config)
+    ///     .unwrap()
+    ///     .complete()
+    ///     .unwrap();
+    /// // Alice's bid set comes first
+    /// assert_eq!(result.winning_bids, [&bids[0][0]]);
+    /// ```
+    pub fn deterministic() -> Self {
+        Self {
+            tiebreak: Tiebreak::First,
+            pricing: PricingRule::Vcg,
+            duplicate_items: DuplicateItems::Merge,
+            result_order: ResultOrder::Input,
+            objectives: &[],
+            search: SearchOptions::default(),
+            #[cfg(feature = "rng")]
+            rng: None,
+        }
+    }
+
+    /// The index of the winning outcome among those tied, with the config's
+    /// tiebreak.
+    fn choose(&self, tied: &[Vec<&B>]) -> usize {
+        match self.tiebreak {
+            #[cfg(feature = "rand")]
+            Tiebreak::Random => match self.rng {
+                Some(rng) => rng.choose(tied),
+                None => crate::vcg::random_tiebreaker(tied),
+            },
+            #[cfg(feature = "rng")]
+            Tiebreak::Seeded(seed) => {
+                AuctionRng::seed_from_u64(seed).choose(tied)
+            }
+            Tiebreak::First => 0,
+            Tiebreak::Custom(tiebreaker) => tiebreaker(tied),
+        }
+    }
+
+    /// The index of the winning outcome among those tied, chosen by the
+    /// config's objectives and then its tiebreak. `total_payment` is the
+    /// total paid in an outcome, for [`Objective::MinPayments`].
+    fn break_tie(
+        &self,
+        tied: &[Vec<&B>],
+        total_payment: impl Fn(&[&B]) -> Option<B::Value>,
+    ) -> usize {
+        if self.objectives.is_empty() {
+            return self.choose(tied);
+        }
+        let candidates = preferred(tied, self.objectives, total_payment);
+        let left = candidates
+            .iter()
+            .map(|&i| tied[i].clone())
+            .collect::<Vec<_>>();
+        // an invalid choice stays invalid
+        candidates
+            .get(self.choose(&left))
+            .copied()
+            .unwrap_or(tied.len())
+    }
+
+    /// Start building a config from the defaults.
+    pub fn builder() -> AuctionConfigBuilder<'c, B> {
+        AuctionConfigBuilder {
+            config: Self::default(),
+        }
+    }
+}
+
+/// Builder for an [`AuctionConfig`], created with
+/// [`AuctionConfig::builder`].
+///
+/// ```
+/// use std::time::Duration;
+/// use vcg_auction::{
+///     types::SimpleBid, AuctionConfig, AuctionOutcome, PricingRule, Tiebreak,
+/// };
+///
+/// let config = AuctionConfig::<SimpleBid>::builder()
+///     .tiebreak(Tiebreak::First)
+///     .pricing(PricingRule::FirstPrice)
+///     .max_duration(Duration::from_secs(10))
+///     .build();
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 10, [("chair", 1)])],
+/// ];
+/// let outcome = vcg_auction::run(&items, &bids, &config).unwrap();
+/// let result = outcome.complete().unwrap();
+/// assert_eq!(result.winning_bids, [&bids[0][0]]);
+/// assert_eq!(result.payments, [(&"Alice".to_string(), 10)]);
+/// ```
+pub struct AuctionConfigBuilder<'c, B: Bid> {
+    config: AuctionConfig<'c, B>,
+}
+
+impl<'c, B: Bid> AuctionConfigBuilder<'c, B> {
+    /// Set how to choose between tied outcomes.
+    pub fn tiebreak(mut self, tiebreak: Tiebreak<'c, B>) -> Self {
+        self.config.tiebreak = tiebreak;
+        self
+    }
+
+    /// Set how winners pay for their bids.
+    pub fn pricing(mut self, pricing: PricingRule) -> Self {
+        self.config.pricing = pricing;
+        self
+    }
+
+    /// Set how items listed more than once in the stock are treated.
+    pub fn duplicate_items(mut self, duplicate_items: DuplicateItems) -> Self {
+        self.config.duplicate_items = duplicate_items;
+        self
+    }
+
+    /// Set the order of the winning bids and payments.
+    pub fn result_order(mut self, result_order: ResultOrder) -> Self {
+        self.config.result_order = result_order;
+        self
+    }
+
+    /// Set the goals to choose between outcomes tied for the highest welfare
+    /// by.
+    pub fn objectives(mut self, objectives: &'c [Objective]) -> Self {
+        self.config.objectives = objectives;
+        self
+    }
+
+    /// Set the generator random choices are drawn from.
+    #[cfg(feature = "rng")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rng")))]
+    pub fn rng(mut self, rng: &'c AuctionRng) -> Self {
+        self.config.rng = Some(rng);
+        self
+    }
+
+    /// Set all the search options at once.
+    pub fn search(mut self, search: SearchOptions<'c, B::Value>) -> Self {
+        self.config.search = search;
+        self
+    }
+
+    /// Set [`SearchOptions::progress`] and
+    /// [`SearchOptions::progress_interval`].
+    pub fn progress(
+        mut self,
+        progress: &'c ProgressCallback<'c, B::Value>,
+        interval: Duration,
+    ) -> Self {
+        self.config.search.progress = Some(progress);
+        self.config.search.progress_interval = interval;
+        self
+    }
+
+    /// Set [`SearchOptions::max_duration`].
+    pub fn max_duration(mut self, max_duration: Duration) -> Self {
+        self.config.search.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Set [`SearchOptions::on_timeout`].
+    pub fn on_timeout(mut self, on_timeout: OnTimeout) -> Self {
+        self.config.search.on_timeout = on_timeout;
+        self
+    }
+
+    /// Set [`SearchOptions::max_nodes`].
+    pub fn max_nodes(mut self, max_nodes: u64) -> Self {
+        self.config.search.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Set [`SearchOptions::max_tied_outcomes`].
+    pub fn max_tied_outcomes(mut self, max_tied_outcomes: usize) -> Self {
+        self.config.search.max_tied_outcomes = Some(max_tied_outcomes);
+        self
+    }
+
+    /// Set [`SearchOptions::max_bid_sets`].
+    pub fn max_bid_sets(mut self, max_bid_sets: usize) -> Self {
+        self.config.search.max_bid_sets = Some(max_bid_sets);
+        self
+    }
+
+    /// Set [`SearchOptions::max_bids_per_set`].
+    pub fn max_bids_per_set(mut self, max_bids_per_set: usize) -> Self {
+        self.config.search.max_bids_per_set = Some(max_bids_per_set);
+        self
+    }
+
+    /// Set [`SearchOptions::strategy`].
+    pub fn strategy(mut self, strategy: SearchStrategy) -> Self {
+        self.config.search.strategy = strategy;
+        self
+    }
+
+    /// Set [`SearchOptions::bid_set_order`].
+    pub fn bid_set_order(mut self, order: BidSetOrder) -> Self {
+        self.config.search.bid_set_order = order;
+        self
+    }
+
+    /// Set [`SearchOptions::check_invariants`].
+    pub fn check_invariants(mut self) -> Self {
+        self.config.search.check_invariants = true;
+        self
+    }
+
+    /// Set [`SearchOptions::verify_payments`].
+    pub fn verify_payments(mut self) -> Self {
+        self.config.search.verify_payments = true;
+        self
+    }
+
+    /// Finish building the config.
+    pub fn build(self) -> AuctionConfig<'c, B> {
+        self.config
+    }
+}
+
+/// Run an auction with every option taken from an [`AuctionConfig`]. Items
+/// and bid sets are given as for [`vcg_auction`](crate::vcg_auction).
+///
+/// Parallel searches are run with
+/// [`vcg_auction_parallel_with_options`](crate::vcg_auction_parallel_with_options)
+/// instead, since they need the bids to be shareable between threads.
+pub fn run<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    config: &AuctionConfig<B>,
+) -> Result<AuctionOutcome<'a, B>, AuctionError>
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let control = SearchControl::new(config.search.clone());
+    run_with_control(items, exclusive_bid_sets, config, &control)
+}
+
+/// Run an auction like [`run`], also returning statistics of its searches
+/// whether or not it finished.
+///
+/// ```
+/// use vcg_auction::{types::SimpleBid, AuctionConfig};
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+/// ];
+/// let config = AuctionConfig::deterministic();
+/// let (outcome, stats) = vcg_auction::run_with_stats(&items, &bids, &config);
+/// assert!(outcome.is_ok());
+/// // one search for the winners, and one for Alice's payment
+/// assert_eq!(stats.winner_determination.searches, 1);
+/// assert_eq!(stats.payments.searches, 1);
+/// println!("cleared in {:?}", stats.winner_determination.search_time);
+/// ```
+pub fn run_with_stats<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    config: &AuctionConfig<B>,
+) -> (Result<AuctionOutcome<'a, B>, AuctionError>, SolveStats)
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let control = SearchControl::new(config.search.clone());
+    let outcome = run_with_control(items, exclusive_bid_sets, config, &control);
+    (outcome, control.stats())
+}
+
+/// Run an auction like [`run`], recording every search node explored, for
+/// debugging instances that take unexpectedly long. The tree can be exported
+/// with [`SearchTree::to_json`] or [`SearchTree::to_dot`].
+///
+/// Recording keeps every explored node in memory, so set
+/// [`max_nodes`](SearchOptions::max_nodes) when the instance may be large.
+///
+/// The recorded searches are the ones [`run`] makes: bids that can't win are
+/// dropped first, and parts of the auction that share no items are searched
+/// apart, each with its own root. So the tree doesn't cover the bids
+/// dropped, which [`run_with_pruning_log`] lists with why.
+///
+/// ```
+/// use vcg_auction::{types::SimpleBid, AuctionConfig, NodeOutcome};
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 5, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+///     vec![SimpleBid::new("Carol", 3, [("chair", 1)])],
+/// ];
+/// let config = AuctionConfig::deterministic();
+/// let (outcome, tree) =
+///     vcg_auction::run_with_search_tree(&items, &bids, &config);
+/// assert!(outcome.is_ok());
+/// // choosing Alice's and Bob's bids needs two chairs
+/// assert!(tree
+///     .nodes
+///     .iter()
+///     .any(|node| node.outcome == NodeOutcome::OverAllocated));
+/// println!("{}", tree.to_dot());
+/// ```
+pub fn run_with_search_tree<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    config: &AuctionConfig<B>,
+) -> (
+    Result<AuctionOutcome<'a, B>, AuctionError>,
+    SearchTree<B::Value>,
+)
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let control = SearchControl::new(config.search.clone()).with_search_tree();
+    let outcome = run_with_control(items, exclusive_bid_sets, config, &control);
+    (outcome, control.take_search_tree())
+}
+
+/// Run an auction like [`run`], logging the bid sets and bids dropped before
+/// searching and every branch the searches cut by their bound, so auditors
+/// can check that no allocation was wrongly discarded.
+///
+/// The logged searches are the ones [`run`] makes: bids that can't win are
+/// dropped first, each listed with its [`DropReason`](crate::DropReason), and
+/// parts of the auction that share no items are searched apart, so each cut
+/// lists the remaining bid sets of its own part.
+///
+/// ```
+/// use vcg_auction::{types::SimpleBid, AuctionConfig};
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![],
+///     vec![SimpleBid::new("Bob", 1, [("chair", 1)])],
+/// ];
+/// let config = AuctionConfig::deterministic();
+/// let (outcome, log) =
+///     vcg_auction::run_with_pruning_log(&items, &bids, &config);
+/// assert!(outcome.is_ok());
+/// assert_eq!(log.empty_bid_sets, [1]);
+/// for cut in &log.bound_cuts {
+///     assert!(cut.bound < cut.best);
+/// }
+/// ```
+pub fn run_with_pruning_log<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    config: &AuctionConfig<B>,
+) -> (
+    Result<AuctionOutcome<'a, B>, AuctionError>,
+    PruningLog<B::Value>,
+)
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let items = match item_stock(items, config) {
+        Ok(items) => items,
+        Err(error) => return (Err(error), PruningLog::default()),
+    };
+    let exclusive_bid_sets = bid_set_refs(exclusive_bid_sets);
+    let control = SearchControl::new(config.search.clone())
+        .with_pruning_log(PruningRecorder::new(&exclusive_bid_sets));
+    let outcome = solve(&items, exclusive_bid_sets, config, &control);
+    let log = control.pruning().map(PruningRecorder::take_log);
+    (outcome, log.unwrap_or_default())
+}
+
+/// Run an auction like [`run`], also returning how each winner's payment
+/// was derived, for showing to a bidder who disputes their payment.
+///
+/// Derivations are returned once every payment has been calculated, so
+/// they're empty if the auction stopped early. They're also empty with
+/// pricing rules other than [`PricingRule::Vcg`], whose payments don't come
+/// from counterfactual auctions.
+///
+/// ```
+/// use vcg_auction::{types::SimpleBid, AuctionConfig};
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+/// ];
+/// let config = AuctionConfig::deterministic();
+/// let (outcome, derivations) =
+///     vcg_auction::run_with_payment_derivations(&items, &bids, &config);
+/// assert!(outcome.is_ok());
+/// // without Alice, Bob would have won the chair
+/// assert_eq!(derivations[0].counterfactual_bids, [&bids[1][0]]);
+/// // and Bob's bid was displaced by Alice's
+/// assert_eq!(derivations[0].displaced_bids, [&bids[1][0]]);
+/// assert_eq!(derivations[0].counterfactual_value, 7);
+/// assert_eq!(derivations[0].others_value, 0);
+/// assert_eq!(derivations[0].payment, 7);
+/// ```
+pub fn run_with_payment_derivations<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    config: &AuctionConfig<B>,
+) -> (
+    Result<AuctionOutcome<'a, B>, AuctionError>,
+    Vec<PaymentDerivation<'a, B>>,
+)
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let PricingRule::Vcg = config.pricing else {
+        return (run(items, exclusive_bid_sets, config), vec![]);
+    };
+    let items = match item_stock(items, config) {
+        Ok(items) => items,
+        Err(error) => return (Err(error), vec![]),
+    };
+    let exclusive_bid_sets = bid_set_refs(exclusive_bid_sets);
+    let control = SearchControl::new(config.search.clone());
+    let mut derivations = vec![];
+    let outcome = run_auction_with(
+        &items,
+        exclusive_bid_sets.clone(),
+        |tied| {
+            config.break_tie(tied, |outcome| {
+                total_payment(
+                    outcome,
+                    &items,
+                    &exclusive_bid_sets,
+                    config,
+                    &control,
+                )
+            })
+        },
+        &control,
+        |bid_sets, phase| {
+            find_highest_value_bid_sets(&items, bid_sets, &control, phase)
+        },
+        |winning_bid_set, bid_sets| {
+            derivations =
+                derive_payments(winning_bid_set, &items, bid_sets, &control)?;
+            Ok(derivations
+                .iter()
+                .map(|derivation| {
+                    let payment = derivation
+                        .counterfactual_value
+                        .sub(&derivation.others_value);
+                    (derivation.bidder_name, payment)
+                })
+                .collect())
+        },
+    );
+    let outcome =
+        outcome.map(|outcome| in_order(outcome, config, &exclusive_bid_sets));
+    (outcome, derivations)
+}
+
+/// Run an auction like [`run`], also returning a report of the run with its
+/// inputs, config, result, statistics and tiebreak, to archive so the run
+/// can later be reproduced or audited. With the `serde` feature the report
+/// can be serialized, such as to JSON.
+///
+/// Problems that [`vcg_auction_dry_run`](crate::vcg_auction_dry_run) would
+/// reject the inputs for are listed in the report's warnings, but don't stop
+/// the auction. The bids the search for the winners left out because they
+/// can't win are listed with why, so their bidders can be told.
+///
+/// ```
+/// use vcg_auction::{types::SimpleBid, AuctionConfig, ReportedResult};
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+/// ];
+/// let config = AuctionConfig::deterministic();
+/// let (outcome, report) =
+///     vcg_auction::run_with_report(&items, &bids, &config);
+/// assert!(outcome.is_ok());
+/// assert_eq!(report.summary.bids, 2);
+/// let ReportedResult::Complete { winning_bids, .. } = report.result else {
+///     panic!("auction didn't finish");
+/// };
+/// assert_eq!(winning_bids, [(0, 0)]);
+/// ```
+pub fn run_with_report<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    config: &AuctionConfig<B>,
+) -> (
+    Result<AuctionOutcome<'a, B>, AuctionError>,
+    RunReport<'a, 'i, B>,
+)
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let items = items
+        .into_iter()
+        .map(StockEntry::into_entry)
+        .collect::<Vec<_>>();
+    let bid_sets = bid_set_refs(exclusive_bid_sets);
+
+    // record the tiebreak as the configured one makes it
+    let tie = Cell::new(None);
+    let record_tie = |tied: &[Vec<&B>]| {
+        let chosen = config.choose(tied);
+        tie.set(Some(TieReport::new(tied, chosen, &bid_sets)));
+        chosen
+    };
+    let recording = AuctionConfig {
+        tiebreak: Tiebreak::Custom(&record_tie),
+        ..config.clone()
+    };
+    let control = SearchControl::new(config.search.clone());
+    let mut dropped = vec![];
+    let outcome = item_stock(items.iter().copied(), config).and_then(|stock| {
+        dropped = dropped_bids(&stock, &bid_sets);
+        solve(&stock, bid_sets.clone(), &recording, &control)
+    });
+
+    let item_names = items.iter().map(|(item, _)| *item).collect::<Vec<_>>();
+    let report = RunReport {
+        summary: summarize(items.len(), &bid_sets),
+        config: ConfigSummary::new(config),
+        result: ReportedResult::new(
+            outcome.as_ref().map_err(|e| *e),
+            &bid_sets,
+        ),
+        stats: control.stats(),
+        warnings: input_problems(&item_names, &bid_sets),
+        dropped_bids: dropped,
+        tie: tie.take(),
+        items,
+        bid_sets,
+    };
+    (outcome, report)
+}
+
+fn run_with_control<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    config: &AuctionConfig<B>,
+    control: &SearchControl<B::Value>,
+) -> Result<AuctionOutcome<'a, B>, AuctionError>
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let items = item_stock(items, config)?;
+    let exclusive_bid_sets = bid_set_refs(exclusive_bid_sets);
+    solve(&items, exclusive_bid_sets, config, control)
+}
+
+/// Run an auction of a stock already built with [`item_stock`].
+pub(crate) fn run_with_stock<'a, B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: Vec<Vec<&'a B>>,
+    config: &AuctionConfig<B>,
+) -> Result<AuctionOutcome<'a, B>, AuctionError> {
+    let control = SearchControl::new(config.search.clone());
+    solve(items, exclusive_bid_sets, config, &control)
+}
+
+/// The stock of an auction, with items listed more than once treated as the
+/// config says.
+pub(crate) fn item_stock<'i, B: Bid + 'i>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    config: &AuctionConfig<B>,
+) -> Result<ItemStock<'i, B>, AuctionError> {
+    let stock = ItemStock::new(items);
+    match (config.duplicate_items, stock.first_duplicate()) {
+        (DuplicateItems::Reject, Some(index)) => {
+            let error = InputError::DuplicateStockItem(index);
+            Err(AuctionError::Input(error))
+        }
+        _ => Ok(stock),
+    }
+}
+
+fn solve<'a, B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: Vec<Vec<&'a B>>,
+    config: &AuctionConfig<B>,
+    control: &SearchControl<B::Value>,
+) -> Result<AuctionOutcome<'a, B>, AuctionError> {
+    let bid_sets = match config.result_order {
+        ResultOrder::Search => vec![],
+        ResultOrder::Input => exclusive_bid_sets.clone(),
+    };
+    let outcome = solve_priced(items, exclusive_bid_sets, config, control)?;
+    Ok(in_order(outcome, config, &bid_sets))
+}
+
+/// The outcome with its winning bids and payments in the config's
+/// [`ResultOrder`]. `exclusive_bid_sets` are the bid sets the outcome was
+/// found from, which aren't needed for [`ResultOrder::Search`].
+fn in_order<'a, B: Bid>(
+    outcome: AuctionOutcome<'a, B>,
+    config: &AuctionConfig<B>,
+    exclusive_bid_sets: &[Vec<&'a B>],
+) -> AuctionOutcome<'a, B> {
+    if config.result_order == ResultOrder::Search {
+        return outcome;
+    }
+    let sort = |bids: &mut Vec<&B>| {
+        bids.sort_by_cached_key(|bid| position(exclusive_bid_sets, bid));
+    };
+    match outcome {
+        AuctionOutcome::Complete(mut result) => {
+            sort(&mut result.winning_bids);
+            let winning_bids = &result.winning_bids;
+            result.payments.sort_by_cached_key(|(name, _)| {
+                winning_bids
+                    .iter()
+                    .position(|bid| bid.bidder_name() == *name)
+            });
+            AuctionOutcome::Complete(result)
+        }
+        AuctionOutcome::TimedOut {
+            mut best_so_far,
+            gap,
+        } => {
+            sort(&mut best_so_far);
+            AuctionOutcome::TimedOut { best_so_far, gap }
+        }
+    }
+}
+
+fn solve_priced<'a, B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: Vec<Vec<&'a B>>,
+    config: &AuctionConfig<B>,
+    control: &SearchControl<B::Value>,
+) -> Result<AuctionOutcome<'a, B>, AuctionError> {
+    // the bid sets are only needed again to price tied outcomes
+    let bid_sets = match config.objectives.contains(&Objective::MinPayments) {
+        true => exclusive_bid_sets.clone(),
+        false => vec![],
+    };
+    let tiebreaker = |tied: &[Vec<&B>]| {
+        config.break_tie(tied, |outcome| {
+            total_payment(outcome, items, &bid_sets, config, control)
+        })
+    };
+    match config.pricing {
+        PricingRule::Vcg => {
+            run_auction(items, exclusive_bid_sets, tiebreaker, control)
+        }
+        PricingRule::FirstPrice => run_auction_with(
+            items,
+            exclusive_bid_sets,
+            tiebreaker,
+            control,
+            |bid_sets, phase| {
+                find_highest_value_bid_sets(items, bid_sets, control, phase)
+            },
+            |winning_bid_set, _| Ok(first_price_payments(winning_bid_set)),
+        ),
+        PricingRule::FirstRejectedBid => run_auction_with(
+            items,
+            exclusive_bid_sets,
+            tiebreaker,
+            control,
+            |bid_sets, phase| {
+                find_highest_value_bid_sets(items, bid_sets, control, phase)
+            },
+            |winning_bid_set, bid_sets| {
+                Ok(first_rejected_bid_payments(winning_bid_set, bid_sets))
+            },
+        ),
+    }
+}
+
+/// The total paid in an outcome with the config's pricing rule, or `None` if
+/// the auction stopped before the payments were found.
+fn total_payment<'a, B: Bid>(
+    outcome: &[&'a B],
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>],
+    config: &AuctionConfig<B>,
+    control: &SearchControl<B::Value>,
+) -> Option<B::Value> {
+    let payments = match config.pricing {
+        PricingRule::Vcg => {
+            calculate_payments(outcome, items, exclusive_bid_sets, control)
+                .ok()?
+        }
+        PricingRule::FirstPrice => first_price_payments(outcome),
+        PricingRule::FirstRejectedBid => {
+            first_rejected_bid_payments(outcome, exclusive_bid_sets)
+        }
+    };
+    Some(
+        payments
+            .iter()
+            .fold(B::Value::zero(), |sum, (_, payment)| sum.add(payment)),
+    )
+}
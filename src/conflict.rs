@@ -0,0 +1,83 @@
+//! The conflict graph between bids, for tighter bounds on the value the bid
+//! sets left in a search can add.
+//!
+//! Two bids conflict if they can't both win: they're in the same bid set, or
+//! together they need more of an item than is in stock. Bid sets whose bids
+//! all conflict with each other's form a clique, of which at most one bid can
+//! win, so the clique adds at most its highest bid rather than the sum of
+//! each bid set's highest bid. The bid sets are covered with cliques greedily,
+//! in the order they're searched.
+
+use crate::{input::ItemStock, vcg::AnnotatedBidSet, AddSubSelf, Bid};
+
+/// Most bids to build the conflict graph of. Building it compares every pair
+/// of bids, so larger searches are bounded by each bid set alone.
+const MAX_CONFLICT_BIDS: usize = 1024;
+
+/// The clique of each bid set, numbered in the order of their first bid set.
+/// Without any conflicts, each bid set is its own clique.
+pub(crate) fn clique_cover<B: Bid>(
+    items: &ItemStock<B>,
+    bid_sets: &[AnnotatedBidSet<B>],
+) -> Vec<usize> {
+    let alone = (0..bid_sets.len()).collect();
+    let bids = bid_sets
+        .iter()
+        .map(|(bid_set, _)| bid_set.len())
+        .sum::<usize>();
+    if bids > MAX_CONFLICT_BIDS {
+        return alone;
+    }
+    let stock = items.quantities();
+    let no_items = items.no_items_selected();
+    let demands = bid_sets
+        .iter()
+        .map(|(bid_set, _)| {
+            bid_set
+                .iter()
+                .map(|bid| items.select_bid_items(&no_items, bid))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    // a negative quantity makes room for other bids, so nothing conflicts
+    let zero = &no_items;
+    let negative = demands.iter().flatten().any(|quantities| {
+        quantities
+            .iter()
+            .zip(zero)
+            .any(|(quantity, zero)| quantity < zero)
+    });
+    if negative {
+        return alone;
+    }
+
+    let conflict = |a: &[B::Quantity], b: &[B::Quantity]| {
+        a.iter()
+            .zip(b)
+            .zip(stock)
+            .any(|((a, b), stock)| a.add(b) > *stock)
+    };
+    let all_conflict = |i: usize, j: usize| {
+        demands[i]
+            .iter()
+            .all(|a| demands[j].iter().all(|b| conflict(a, b)))
+    };
+    let mut cliques: Vec<Vec<usize>> = vec![];
+    let mut clique_of = Vec::with_capacity(bid_sets.len());
+    for i in 0..bid_sets.len() {
+        let joined = cliques
+            .iter()
+            .position(|clique| clique.iter().all(|&j| all_conflict(i, j)));
+        match joined {
+            Some(c) => {
+                cliques[c].push(i);
+                clique_of.push(c);
+            }
+            None => {
+                clique_of.push(cliques.len());
+                cliques.push(vec![i]);
+            }
+        }
+    }
+    clique_of
+}
@@ -0,0 +1,234 @@
+//! Hooks that let a caller steer a running search, shared by all the searches
+//! of one auction.
+
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Mutex,
+};
+use std::time::Instant;
+
+use crate::{
+    pruning::PruningRecorder, AuctionError, OnTimeout, PhaseStats, Progress,
+    ResourceLimit, SearchOptions, SearchPhase, SearchTree, SearchTreeNode,
+    SolveStats,
+};
+
+/// Number of search nodes between checks of the clock and updates of shared
+/// counters.
+pub(crate) const CHECK_INTERVAL: u64 = 1024;
+
+/// Why a search stopped before exploring every combination of bids.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum Interrupt {
+    Cancelled,
+    TimedOut,
+    ResourceLimit(ResourceLimit),
+}
+
+impl Interrupt {
+    pub(crate) fn into_error(self) -> AuctionError {
+        match self {
+            Interrupt::Cancelled => AuctionError::Cancelled,
+            Interrupt::TimedOut => AuctionError::TimedOut,
+            Interrupt::ResourceLimit(limit) => {
+                AuctionError::ResourceLimit(limit)
+            }
+        }
+    }
+}
+
+pub(crate) struct SearchControl<'c, V> {
+    options: SearchOptions<'c, V>,
+    /// Checked at every search node. Once set, the search unwinds.
+    cancel: Option<&'c AtomicBool>,
+    started: Instant,
+    deadline: Option<Instant>,
+    timed_out: AtomicBool,
+    /// Set once `max_nodes` is reached, stopping every search of the auction.
+    out_of_nodes: AtomicBool,
+    nodes: AtomicU64,
+    last_report: Mutex<Instant>,
+    stats: Mutex<SolveStats>,
+    /// Explored nodes, if the search tree is being recorded.
+    tree: Option<Mutex<SearchTree<V>>>,
+    /// Bound cuts, if they're being logged.
+    pruning: Option<PruningRecorder<V>>,
+}
+
+impl<'c, V> SearchControl<'c, V> {
+    pub(crate) fn new(options: SearchOptions<'c, V>) -> Self {
+        let started = Instant::now();
+        Self {
+            deadline: options
+                .max_duration
+                .and_then(|max_duration| started.checked_add(max_duration)),
+            options,
+            cancel: None,
+            started,
+            timed_out: AtomicBool::new(false),
+            out_of_nodes: AtomicBool::new(false),
+            nodes: AtomicU64::new(0),
+            last_report: Mutex::new(started),
+            stats: Mutex::new(SolveStats::default()),
+            tree: None,
+            pruning: None,
+        }
+    }
+
+    /// Log the branches cut by the bound.
+    pub(crate) fn with_pruning_log(self, pruning: PruningRecorder<V>) -> Self {
+        Self {
+            pruning: Some(pruning),
+            ..self
+        }
+    }
+
+    /// The log of bound cuts, if they're being logged.
+    pub(crate) fn pruning(&self) -> Option<&PruningRecorder<V>> {
+        self.pruning.as_ref()
+    }
+
+    /// Record the explored search tree.
+    pub(crate) fn with_search_tree(self) -> Self {
+        Self {
+            tree: Some(Mutex::new(SearchTree::default())),
+            ..self
+        }
+    }
+
+    /// Whether searches should record the nodes they explore.
+    pub(crate) fn records_tree(&self) -> bool {
+        self.tree.is_some()
+    }
+
+    /// Add the nodes recorded by a finished search.
+    pub(crate) fn add_tree_nodes(&self, nodes: Vec<SearchTreeNode<V>>) {
+        if let Some(tree) = &self.tree {
+            tree.lock()
+                .unwrap_or_else(|poison| poison.into_inner())
+                .extend(nodes);
+        }
+    }
+
+    /// Take the search tree recorded so far.
+    pub(crate) fn take_search_tree(&self) -> SearchTree<V> {
+        self.tree.as_ref().map_or_else(SearchTree::default, |tree| {
+            std::mem::take(
+                &mut *tree.lock().unwrap_or_else(|poison| poison.into_inner()),
+            )
+        })
+    }
+
+    /// Cancel the search once `cancel` is set.
+    pub(crate) fn with_cancel(self, cancel: &'c AtomicBool) -> Self {
+        Self {
+            cancel: Some(cancel),
+            ..self
+        }
+    }
+
+    pub(crate) fn options(&self) -> &SearchOptions<'c, V> {
+        &self.options
+    }
+
+    pub(crate) fn on_timeout(&self) -> OnTimeout {
+        self.options.on_timeout
+    }
+
+    /// Returns why the search should stop, if it should.
+    pub(crate) fn interrupt(&self) -> Option<Interrupt> {
+        if self
+            .cancel
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+        {
+            Some(Interrupt::Cancelled)
+        } else if self.timed_out.load(Ordering::Relaxed) {
+            Some(Interrupt::TimedOut)
+        } else if self.out_of_nodes.load(Ordering::Relaxed) {
+            Some(Interrupt::ResourceLimit(ResourceLimit::Nodes))
+        } else {
+            None
+        }
+    }
+
+    /// Whether exploring another node would exceed `max_nodes`, given the
+    /// nodes a search has explored but not yet added to the count.
+    pub(crate) fn out_of_nodes(&self, pending: u64) -> bool {
+        let Some(max_nodes) = self.options.max_nodes else {
+            return false;
+        };
+        let out_of_nodes =
+            self.nodes.load(Ordering::Relaxed) + pending >= max_nodes;
+        if out_of_nodes {
+            self.out_of_nodes.store(true, Ordering::Relaxed);
+        }
+        out_of_nodes
+    }
+
+    /// Whether a search may hold `tied` combinations of bids tied for the
+    /// highest value.
+    pub(crate) fn allows_ties(&self, tied: usize) -> bool {
+        self.options
+            .max_tied_outcomes
+            .map_or(true, |max_tied_outcomes| tied <= max_tied_outcomes)
+    }
+
+    /// Add to the count of explored nodes.
+    pub(crate) fn add_nodes(&self, nodes: u64) -> u64 {
+        self.nodes.fetch_add(nodes, Ordering::Relaxed) + nodes
+    }
+
+    /// Add the statistics of a finished search.
+    pub(crate) fn record(&self, phase: SearchPhase, stats: &PhaseStats) {
+        let mut solve_stats = self
+            .stats
+            .lock()
+            .unwrap_or_else(|poison| poison.into_inner());
+        solve_stats.phase_mut(phase).merge(stats);
+    }
+
+    /// Statistics of the searches recorded so far.
+    pub(crate) fn stats(&self) -> SolveStats {
+        self.stats
+            .lock()
+            .unwrap_or_else(|poison| poison.into_inner())
+            .clone()
+    }
+
+    /// Add to the count of explored nodes, check the deadline, and report
+    /// progress if it's due.
+    pub(crate) fn checkpoint(
+        &self,
+        nodes: u64,
+        phase: SearchPhase,
+        best_value: &V,
+        upper_bound: &V,
+    ) {
+        let nodes = self.add_nodes(nodes);
+        if self.deadline.is_none() && self.options.progress.is_none() {
+            return;
+        }
+        let now = Instant::now();
+        if self.deadline.is_some_and(|deadline| now >= deadline) {
+            self.timed_out.store(true, Ordering::Relaxed);
+        }
+        let Some(progress) = self.options.progress else {
+            return;
+        };
+        // skip the report if another thread is reporting
+        let Ok(mut last_report) = self.last_report.try_lock() else {
+            return;
+        };
+        if now.duration_since(*last_report) < self.options.progress_interval {
+            return;
+        }
+        *last_report = now;
+        progress(&Progress {
+            phase,
+            best_value,
+            upper_bound,
+            nodes,
+            elapsed: now.duration_since(self.started),
+        });
+    }
+}
@@ -0,0 +1,483 @@
+//! Minimum-revenue core prices, for when VCG payments are too low.
+//!
+//! VCG payments can be far below what losing bidders offered for the same
+//! items, when winners complement each other. Prices are in the core when no
+//! coalition of losing bidders and winners could offer the seller more than
+//! the winners pay. Core prices are found by constraint generation: starting
+//! from the VCG payments, the most blocking coalition is found by solving
+//! winner determination with each winner's bids reduced by what they gain
+//! over their payment. While it offers more than the winners pay, its
+//! constraint on the payments is added, and the lowest total payments
+//! meeting every constraint so far are solved for by linear programming.
+
+use microlp::{ComparisonOp, OptimizationDirection, Problem};
+use num_traits::{FromPrimitive, ToPrimitive, Zero};
+
+use crate::{
+    affine::AffineBid,
+    control::SearchControl,
+    input::{bid_set_refs, ItemStock},
+    vcg::{copy_value, run_auction, total_value},
+    AddSubSelf, Allocation, AuctionOutcome, AuctionResult, Bid, SearchOptions,
+    StockEntry,
+};
+
+/// Which of the payments with the lowest total in the core an auction
+/// charges, given to [`core_auction`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(docsrs, doc(cfg(feature = "lp")))]
+#[non_exhaustive]
+pub enum CoreSelection {
+    /// The payments closest to the VCG payments in Euclidean distance, which
+    /// are unique and spread the raise over the winners.
+    #[default]
+    NearestVcg,
+    /// Any of the payments, as chosen by the linear programming solver.
+    MinimumRevenue,
+}
+
+/// Result of [`core_auction`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(docsrs, doc(cfg(feature = "lp")))]
+pub struct CoreResult<'a, B: Bid> {
+    /// The winning bids, with each winner's core payment.
+    pub result: AuctionResult<'a, B>,
+    /// The VCG payment of each winner, in the same order.
+    pub vcg_payments: Vec<(&'a B::Name, B::Value)>,
+    /// Number of blocking coalitions found, each adding a constraint.
+    pub coalitions: usize,
+}
+
+/// Calculate an auction with minimum-revenue core payments, breaking ties
+/// between outcomes with a tiebreaker. Items and bid sets are given as for
+/// [`vcg_auction_with_tiebreaker`](crate::vcg_auction_with_tiebreaker), and
+/// `None` is returned if the tiebreaker returns an invalid index, or if the
+/// payments still haven't settled after 1,000 blocking coalitions.
+///
+/// The winners are those of the VCG auction. Each pays at least their VCG
+/// payment and at most the value of their winning bids, and together they
+/// pay the least that no coalition could beat. When several payments total
+/// the least, the selection rule chooses between them. Constraints are
+/// generated for the payments the rule selects, so they're in the core
+/// whichever is chosen.
+///
+/// A coalition's winners are assumed to each give up what they gain over
+/// their payment once, so each bidder's bids should be in one bid set.
+/// Payments are solved for as `f64`, converted with [`ToPrimitive`] and
+/// [`FromPrimitive`], and rounded up to the next value the type can hold.
+/// Payments nearest the VCG payments are found by alternating projections,
+/// to within a small fraction of the values, and solved payments are then
+/// raised until every constraint found holds exactly. A coalition that blocks
+/// by less than that fraction, as float values can after rounding, is taken
+/// to be met, as is one no stronger than a constraint already added.
+///
+/// ```
+/// use vcg_auction::{core_auction, types::SimpleBid, CoreSelection};
+///
+/// let items = vec![("left".to_string(), 1), ("right".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 6, [("left", 1)])],
+///     vec![SimpleBid::new("Bob", 6, [("right", 1)])],
+///     vec![SimpleBid::new("Carol", 8, [("left", 1), ("right", 1)])],
+/// ];
+/// let core =
+///     core_auction(&items, &bids, |_| 0, CoreSelection::NearestVcg).unwrap();
+/// // either winner alone beats Carol by 4, so VCG charges each only 2
+/// assert_eq!(core.vcg_payments, [(&"Alice".into(), 2), (&"Bob".into(), 2)]);
+/// // but Carol offered 8 for both, which they split evenly
+/// let payments = core.result.payments.iter().map(|(_, p)| *p);
+/// assert_eq!(payments.collect::<Vec<_>>(), [4, 4]);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "lp")))]
+pub fn core_auction<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
+    selection: CoreSelection,
+) -> Option<CoreResult<'a, B>>
+where
+    B: Bid + 'a + 'i,
+    B::Value: ToPrimitive + FromPrimitive,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let items = items
+        .into_iter()
+        .map(StockEntry::into_entry)
+        .collect::<Vec<_>>();
+    let exclusive_bid_sets = bid_set_refs(exclusive_bid_sets);
+    let vcg = run_auction(
+        &ItemStock::new(items.iter().copied()),
+        exclusive_bid_sets.clone(),
+        tiebreaker,
+        &SearchControl::new(SearchOptions::default()),
+    )
+    .ok()
+    .and_then(AuctionOutcome::complete)?;
+    let winners = vcg
+        .payments
+        .iter()
+        .map(|(bidder_name, payment)| {
+            let bids = vcg
+                .winning_bids
+                .iter()
+                .filter(|b| b.bidder_name() == *bidder_name)
+                .copied();
+            Winner {
+                name: *bidder_name,
+                bid: total_value(bids),
+                vcg: copy_value::<B>(payment),
+                payment: copy_value::<B>(payment),
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut winners = winners;
+    let mut core = CorePrices::new(&winners)?;
+    while let Some(constraint) =
+        blocking_coalition(&items, &exclusive_bid_sets, &winners)
+    {
+        if core.settles(&constraint, &winners)? {
+            break;
+        }
+        if core.constraints.len() == MAX_COALITIONS {
+            return None;
+        }
+        core.constraints.push(constraint);
+        let payments = core.solve(&winners, selection)?;
+        for (winner, payment) in winners.iter_mut().zip(payments) {
+            winner.payment = payment;
+        }
+    }
+    Some(CoreResult {
+        result: AuctionResult {
+            winning_bids: vcg.winning_bids,
+            payments: winners.iter().map(|w| (w.name, w.payment())).collect(),
+        },
+        vcg_payments: vcg.payments,
+        coalitions: core.constraints.len(),
+    })
+}
+
+/// A winner of the auction, with the value of their winning bids and their
+/// payments.
+struct Winner<'a, B: Bid> {
+    name: &'a B::Name,
+    bid: B::Value,
+    vcg: B::Value,
+    payment: B::Value,
+}
+
+impl<'a, B: Bid> Winner<'a, B> {
+    fn payment(&self) -> B::Value {
+        copy_value::<B>(&self.payment)
+    }
+}
+
+/// A core constraint: the winners outside a blocking coalition must pay at
+/// least `at_least` together.
+struct Constraint {
+    outside: Vec<usize>,
+    at_least: f64,
+}
+
+/// The bounds of each winner's payment, and the constraints found so far.
+struct CorePrices {
+    bounds: Vec<(f64, f64)>,
+    constraints: Vec<Constraint>,
+}
+
+impl CorePrices {
+    fn new<B: Bid>(winners: &[Winner<B>]) -> Option<Self>
+    where
+        B::Value: ToPrimitive,
+    {
+        let bounds = winners
+            .iter()
+            .map(|w| Some((w.vcg.to_f64()?, w.bid.to_f64()?)))
+            .collect::<Option<_>>()?;
+        Some(Self {
+            bounds,
+            constraints: vec![],
+        })
+    }
+
+    /// Whether a blocking coalition's constraint needn't be added, because
+    /// the payments meet it within the tolerance of solving, or one already
+    /// added for the same coalition asks as much.
+    fn settles<B: Bid>(
+        &self,
+        constraint: &Constraint,
+        winners: &[Winner<B>],
+    ) -> Option<bool>
+    where
+        B::Value: ToPrimitive,
+    {
+        let tolerance = TOLERANCE * constraint.at_least.abs().max(1.0);
+        let paid = constraint
+            .outside
+            .iter()
+            .map(|&i| winners[i].payment.to_f64())
+            .sum::<Option<f64>>()?;
+        let repeated = self.constraints.iter().any(|added| {
+            added.outside == constraint.outside
+                && added.at_least + tolerance >= constraint.at_least
+        });
+        Some(repeated || paid + tolerance >= constraint.at_least)
+    }
+
+    /// The payments the rule selects of those with the lowest total meeting
+    /// every constraint, or `None` if the solver fails or a payment can't be
+    /// converted back.
+    fn solve<B: Bid>(
+        &self,
+        winners: &[Winner<B>],
+        selection: CoreSelection,
+    ) -> Option<Vec<B::Value>>
+    where
+        B::Value: ToPrimitive + FromPrimitive,
+    {
+        let mut problem = Problem::new(OptimizationDirection::Minimize);
+        let vars = self
+            .bounds
+            .iter()
+            .map(|&bounds| problem.add_var(1.0, bounds))
+            .collect::<Vec<_>>();
+        for constraint in &self.constraints {
+            let terms = constraint
+                .outside
+                .iter()
+                .map(|&winner| (vars[winner], 1.0))
+                .collect::<Vec<_>>();
+            problem.add_constraint(
+                terms,
+                ComparisonOp::Ge,
+                constraint.at_least,
+            );
+        }
+        let solution = problem.solve().ok()?;
+        let mut payments =
+            vars.iter().map(|&var| solution[var]).collect::<Vec<_>>();
+        if selection == CoreSelection::NearestVcg {
+            let vcg = self.bounds.iter().map(|&(vcg, _)| vcg).collect();
+            payments = self.nearest(vcg, solution.objective());
+        }
+        self.meet(&mut payments);
+        payments
+            .into_iter()
+            .zip(winners)
+            .map(|(payment, winner)| round_up::<B>(payment, &winner.bid))
+            .collect()
+    }
+
+    /// The payments totalling `revenue` and meeting every constraint that
+    /// are nearest to `point`, by Dykstra's alternating projections onto the
+    /// bounds, the total and each constraint.
+    fn nearest(&self, point: Vec<f64>, revenue: f64) -> Vec<f64> {
+        let tolerance = TOLERANCE * revenue.abs().max(1.0);
+        let sets = self.constraints.len() + 2;
+        let mut corrections = vec![vec![0.0; point.len()]; sets];
+        let mut x = point;
+        for _ in 0..MAX_PROJECTION_ROUNDS {
+            let mut moved = 0.0f64;
+            for (set, correction) in corrections.iter_mut().enumerate() {
+                let z = x
+                    .iter()
+                    .zip(correction.iter())
+                    .map(|(x, c)| x + c)
+                    .collect::<Vec<_>>();
+                let projected = self.project(set, &z, revenue);
+                for (((c, z), p), x) in
+                    correction.iter_mut().zip(&z).zip(&projected).zip(&x)
+                {
+                    *c = z - p;
+                    moved = moved.max((p - x).abs());
+                }
+                x = projected;
+            }
+            if moved < tolerance {
+                break;
+            }
+        }
+        x
+    }
+
+    /// Raise solved payments until every constraint holds exactly, as the
+    /// solver and the projections only meet them to within a tolerance.
+    /// Payments are first kept within their bounds, then each constraint's
+    /// shortfall is spread over the winners outside its coalition, up to
+    /// their bounds. Raising a payment can't break a constraint, so each one
+    /// only needs meeting once.
+    fn meet(&self, payments: &mut [f64]) {
+        for (payment, &(low, high)) in payments.iter_mut().zip(&self.bounds) {
+            *payment = payment.min(high).max(low);
+        }
+        for constraint in &self.constraints {
+            loop {
+                let paid = constraint
+                    .outside
+                    .iter()
+                    .map(|&i| payments[i])
+                    .sum::<f64>();
+                let raisable = constraint
+                    .outside
+                    .iter()
+                    .copied()
+                    .filter(|&i| payments[i] < self.bounds[i].1)
+                    .collect::<Vec<_>>();
+                if paid >= constraint.at_least || raisable.is_empty() {
+                    break;
+                }
+                let raise =
+                    (constraint.at_least - paid) / raisable.len() as f64;
+                for i in raisable {
+                    // by at least the gap to the next float, so rounding
+                    // can't stop the payments from rising
+                    let raise = raise.max(payments[i].abs() * f64::EPSILON);
+                    payments[i] = (payments[i] + raise).min(self.bounds[i].1);
+                }
+            }
+        }
+    }
+
+    /// Project a point onto one of the sets of [`nearest`](Self::nearest):
+    /// the bounds, the payments totalling `revenue`, or a constraint.
+    fn project(&self, set: usize, z: &[f64], revenue: f64) -> Vec<f64> {
+        match set {
+            0 => z
+                .iter()
+                .zip(&self.bounds)
+                .map(|(z, &(low, high))| z.clamp(low, high))
+                .collect(),
+            1 => {
+                let shift = (revenue - z.iter().sum::<f64>()) / z.len() as f64;
+                z.iter().map(|z| z + shift).collect()
+            }
+            _ => {
+                let constraint = &self.constraints[set - 2];
+                let paid =
+                    constraint.outside.iter().map(|&i| z[i]).sum::<f64>();
+                let mut z = z.to_vec();
+                if paid < constraint.at_least {
+                    let shift = (constraint.at_least - paid)
+                        / constraint.outside.len() as f64;
+                    for &i in &constraint.outside {
+                        z[i] += shift;
+                    }
+                }
+                z
+            }
+        }
+    }
+}
+
+/// Most rounds of projections when finding the payments nearest the VCG
+/// payments.
+const MAX_PROJECTION_ROUNDS: usize = 100_000;
+
+/// Fraction of the values within which payments are solved for.
+const TOLERANCE: f64 = 1e-9;
+
+/// Most blocking coalitions added as constraints before giving up on the
+/// payments settling.
+const MAX_COALITIONS: usize = 1_000;
+
+/// The constraint of the most blocking coalition, if it offers the seller
+/// more than the winners pay. Each winner's bids are reduced by what they
+/// gain over their payment, and bids left worth nothing are dropped.
+fn blocking_coalition<B: Bid>(
+    items: &[(&B::Item, &B::Quantity)],
+    exclusive_bid_sets: &[Vec<&B>],
+    winners: &[Winner<B>],
+) -> Option<Constraint>
+where
+    B::Value: ToPrimitive,
+{
+    let reduced_bid_sets = exclusive_bid_sets
+        .iter()
+        .map(|bid_set| {
+            bid_set
+                .iter()
+                .filter_map(|&bid| {
+                    let value = match winners
+                        .iter()
+                        .find(|w| w.name == bid.bidder_name())
+                    {
+                        Some(w) => {
+                            let gain = w.bid.sub(&w.payment);
+                            if *bid.bid_value() <= gain {
+                                return None;
+                            }
+                            bid.bid_value().sub(&gain)
+                        }
+                        None => copy_value::<B>(bid.bid_value()),
+                    };
+                    Some(AffineBid {
+                        bid,
+                        value,
+                        offset: B::Value::zero(),
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let allocation = Allocation::find(
+        ItemStock::new(items.iter().copied()),
+        reduced_bid_sets
+            .iter()
+            .map(|bid_set| bid_set.iter().collect())
+            .collect(),
+        |_| 0,
+    )
+    .expect("the first tied outcome is valid");
+    let offered = total_value(allocation.winning_bids().iter().copied());
+    let paid = winners
+        .iter()
+        .fold(B::Value::zero(), |sum, w| sum.add(&w.payment));
+    if offered <= paid {
+        return None;
+    }
+    let in_coalition = |w: &Winner<B>| {
+        allocation
+            .winning_bids()
+            .iter()
+            .any(|b| b.bidder_name() == w.name)
+    };
+    let coalition_paid = winners
+        .iter()
+        .filter(|w| in_coalition(w))
+        .map(|w| w.payment.to_f64())
+        .sum::<Option<f64>>()?;
+    Some(Constraint {
+        outside: (0..winners.len())
+            .filter(|&i| !in_coalition(&winners[i]))
+            .collect(),
+        at_least: offered.to_f64()? - coalition_paid,
+    })
+}
+
+/// A solved payment as a value, kept at most the value of the winner's bids.
+/// Payments the type holds exactly are kept, so they still meet the
+/// constraints. Others are snapped to a nearby whole number to undo the
+/// solver's rounding, and otherwise rounded up.
+fn round_up<B: Bid>(payment: f64, bid: &B::Value) -> Option<B::Value>
+where
+    B::Value: ToPrimitive + FromPrimitive,
+{
+    let mut value = B::Value::from_f64(payment)?;
+    if value.to_f64()? != payment {
+        let tolerance = 1e-6 * payment.abs().max(1.0);
+        let payment = if (payment - payment.round()).abs() < tolerance {
+            payment.round()
+        } else {
+            payment
+        };
+        value = B::Value::from_f64(payment)?;
+        if value.to_f64()? < payment {
+            value = B::Value::from_f64(payment.ceil())?;
+        }
+    }
+    Some(if value > *bid {
+        copy_value::<B>(bid)
+    } else {
+        value
+    })
+}
@@ -0,0 +1,312 @@
+//! Preset for course bidding, where students spend a budget of points on the
+//! sections of courses they want a seat in.
+//!
+//! Every section is an item, with a stock of its seats. A student can't take
+//! two sections that meet at the same time, or two sections of the same
+//! course, so a [`CourseMarket`] works out the conflicts from the timetable:
+//! sections connected by conflicts are bid on together, with one bid for each
+//! combination of them the student could attend, worth the points of its
+//! sections. Sections without conflicts are bid on alone.
+//!
+//! ```
+//! use vcg_auction::{
+//!     courses::{CourseMarket, Meeting, Section},
+//!     vcg_auction_with_tiebreaker,
+//! };
+//!
+//! let section = |id: &str, course: &str, start| Section {
+//!     id: id.into(),
+//!     course: course.into(),
+//!     seats: 1,
+//!     meetings: vec![Meeting { day: 0, start, end: start + 90 }],
+//! };
+//! let mut market = CourseMarket::new([
+//!     section("algebra-1", "algebra", 540),
+//!     section("physics-1", "physics", 600),
+//! ]);
+//! market.student("Alice", 100).student("Bob", 100);
+//! // algebra and physics overlap, so Alice can only get one of them
+//! let bids = [
+//!     market.bid_sets("Alice", [("algebra-1", 60), ("physics-1", 40)])?,
+//!     market.bid_sets("Bob", [("physics-1", 30)])?,
+//! ]
+//! .concat();
+//! let result =
+//!     vcg_auction_with_tiebreaker(market.items(), &bids, |_| 0).unwrap();
+//! assert_eq!(result.winning_bids, [&bids[0][0], &bids[1][0]]);
+//! # Ok::<(), vcg_auction::courses::CourseError>(())
+//! ```
+
+use std::fmt;
+
+use crate::types::SimpleBid;
+
+/// Most sections connected by conflicts that one student can bid on. Each
+/// combination of them the student could attend is a bid.
+pub const MAX_CONFLICTING_SECTIONS: usize = 12;
+
+/// A weekly meeting of a section, in minutes from midnight.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Meeting {
+    /// Day of the week, from 0.
+    pub day: u8,
+    pub start: u16,
+    pub end: u16,
+}
+
+impl Meeting {
+    /// Whether two meetings are on the same day at overlapping times.
+    pub fn overlaps(&self, other: &Meeting) -> bool {
+        self.day == other.day
+            && self.start < other.end
+            && other.start < self.end
+    }
+}
+
+/// A section of a course, with its seats and meetings.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Section {
+    pub id: String,
+    pub course: String,
+    pub seats: u64,
+    pub meetings: Vec<Meeting>,
+}
+
+impl Section {
+    /// Whether a student can't take both sections: they're of the same
+    /// course, or meet at the same time.
+    pub fn conflicts_with(&self, other: &Section) -> bool {
+        self.course == other.course
+            || self.meetings.iter().any(|meeting| {
+                other.meetings.iter().any(|other| meeting.overlaps(other))
+            })
+    }
+}
+
+/// Reasons a student's points can't be turned into bids.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum CourseError {
+    /// The student wasn't added to the market.
+    UnknownStudent(String),
+    /// The section isn't in the market.
+    UnknownSection(String),
+    /// The points spent are over the student's budget.
+    OverBudget { points: u64, budget: u64 },
+    /// More sections connected by conflicts than
+    /// [`MAX_CONFLICTING_SECTIONS`].
+    TooManyConflicts(usize),
+}
+
+impl fmt::Display for CourseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CourseError::UnknownStudent(student) => {
+                write!(f, "student {student:?} isn't in the market")
+            }
+            CourseError::UnknownSection(section) => {
+                write!(f, "section {section:?} isn't in the market")
+            }
+            CourseError::OverBudget { points, budget } => {
+                write!(f, "{points} points spent of a budget of {budget}")
+            }
+            CourseError::TooManyConflicts(sections) => write!(
+                f,
+                "{sections} sections connected by conflicts, more than \
+                 {MAX_CONFLICTING_SECTIONS}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CourseError {}
+
+/// The sections of a course bidding round and the point budget of each
+/// student.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CourseMarket {
+    sections: Vec<Section>,
+    items: Vec<(String, u64)>,
+    budgets: Vec<(String, u64)>,
+}
+
+impl CourseMarket {
+    /// A market of the sections. A section listed more than once keeps its
+    /// first listing.
+    pub fn new(sections: impl IntoIterator<Item = Section>) -> Self {
+        let mut unique: Vec<Section> = vec![];
+        for section in sections {
+            if !unique.iter().any(|s| s.id == section.id) {
+                unique.push(section);
+            }
+        }
+        let items = unique
+            .iter()
+            .map(|section| (section.id.clone(), section.seats))
+            .collect();
+        Self {
+            sections: unique,
+            items,
+            budgets: vec![],
+        }
+    }
+
+    /// Add a student with a budget of points, replacing the budget of a
+    /// student already added.
+    pub fn student(
+        &mut self,
+        name: impl Into<String>,
+        budget: u64,
+    ) -> &mut Self {
+        let name = name.into();
+        match self
+            .budgets
+            .iter_mut()
+            .find(|(student, _)| *student == name)
+        {
+            Some((_, b)) => *b = budget,
+            None => self.budgets.push((name, budget)),
+        }
+        self
+    }
+
+    /// The sections of the market.
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// The budget of a student, if they were added.
+    pub fn budget(&self, student: &str) -> Option<u64> {
+        self.budgets
+            .iter()
+            .find(|(name, _)| name == student)
+            .map(|(_, budget)| *budget)
+    }
+
+    /// Items of the auction, with the seats of every section.
+    pub fn items(&self) -> &[(String, u64)] {
+        &self.items
+    }
+
+    /// Bid sets of a student spending points on sections. Sections connected
+    /// by conflicts share a bid set, with a bid for each combination of them
+    /// without conflicts, and every other section is a bid set of its own.
+    /// Bid sets are in the order of each one's first section, and the bids of
+    /// a bid set from the most sections to the fewest. Points on the same
+    /// section are added together.
+    pub fn bid_sets<S: AsRef<str>>(
+        &self,
+        student: &str,
+        points: impl IntoIterator<Item = (S, u64)>,
+    ) -> Result<Vec<Vec<SimpleBid>>, CourseError> {
+        let budget = self
+            .budget(student)
+            .ok_or_else(|| CourseError::UnknownStudent(student.into()))?;
+        let mut spent: Vec<(&Section, u64)> = vec![];
+        for (id, section_points) in points {
+            let id = id.as_ref();
+            let section = self
+                .sections
+                .iter()
+                .find(|section| section.id == id)
+                .ok_or_else(|| CourseError::UnknownSection(id.into()))?;
+            match spent.iter_mut().find(|(s, _)| s.id == id) {
+                Some((_, total)) => {
+                    *total = total.saturating_add(section_points)
+                }
+                None => spent.push((section, section_points)),
+            }
+        }
+        let total = spent
+            .iter()
+            .fold(0u64, |sum, (_, points)| sum.saturating_add(*points));
+        if total > budget {
+            return Err(CourseError::OverBudget {
+                points: total,
+                budget,
+            });
+        }
+
+        let mut bid_sets = vec![];
+        for component in conflict_components(&spent) {
+            if component.len() > MAX_CONFLICTING_SECTIONS {
+                return Err(CourseError::TooManyConflicts(component.len()));
+            }
+            // every combination of the component's sections without conflicts,
+            // by bitmask over the component
+            let mut combinations = (1u32..1 << component.len())
+                .filter(|mask| {
+                    let chosen = || {
+                        component
+                            .iter()
+                            .enumerate()
+                            .filter(move |(i, _)| mask & (1 << i) != 0)
+                            .map(|(_, &s)| spent[s].0)
+                    };
+                    chosen().enumerate().all(|(i, section)| {
+                        chosen()
+                            .take(i)
+                            .all(|other| !section.conflicts_with(other))
+                    })
+                })
+                .collect::<Vec<_>>();
+            combinations
+                .sort_by_key(|mask| std::cmp::Reverse(mask.count_ones()));
+            let bids = combinations
+                .into_iter()
+                .map(|mask| {
+                    let chosen = component
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| mask & (1 << i) != 0)
+                        .map(|(_, &s)| spent[s]);
+                    let value =
+                        chosen.clone().fold(0u64, |sum, (_, points)| {
+                            sum.saturating_add(points)
+                        });
+                    SimpleBid::new(
+                        student,
+                        value,
+                        chosen.map(|(section, _)| (section.id.as_str(), 1)),
+                    )
+                })
+                .collect();
+            bid_sets.push(bids);
+        }
+        Ok(bid_sets)
+    }
+}
+
+/// Groups of sections connected by conflicts, as indices of `spent`, in the
+/// order of each group's first section.
+fn conflict_components(spent: &[(&Section, u64)]) -> Vec<Vec<usize>> {
+    let mut component_of = vec![None; spent.len()];
+    let mut components: Vec<Vec<usize>> = vec![];
+    for start in 0..spent.len() {
+        if component_of[start].is_some() {
+            continue;
+        }
+        let c = components.len();
+        component_of[start] = Some(c);
+        let mut component = vec![start];
+        let mut next = 0;
+        while next < component.len() {
+            let s = component[next];
+            next += 1;
+            for other in 0..spent.len() {
+                if component_of[other].is_none()
+                    && spent[s].0.conflicts_with(spent[other].0)
+                {
+                    component_of[other] = Some(c);
+                    component.push(other);
+                }
+            }
+        }
+        component.sort_unstable();
+        components.push(component);
+    }
+    components
+}
@@ -0,0 +1,442 @@
+//! Splitting an auction into independent sub-auctions.
+//!
+//! Two bid sets are connected if bids of each ask for the same item, and
+//! connected bid sets form components. Bids of different components never
+//! compete for stock, so each component's highest-value outcomes can be
+//! searched for alone, in a search whose size is exponential in the
+//! component's bid sets rather than the auction's. The highest value of the
+//! auction is the sum of the components', and its tied outcomes are every
+//! combination of one tied outcome of each component.
+
+use std::ptr;
+
+use num_traits::Zero;
+
+use crate::{
+    control::{Interrupt, SearchControl},
+    input::ItemStock,
+    local_search::{improve_timed_out, improved_value},
+    ordering::{order_bid_sets, restore_order},
+    propagation::{propagate, propagate_logged},
+    vcg::{start_search, Highest, Search, SearchResult},
+    AddSubSelf, Bid, BidSetOrder, ResourceLimit, SearchBuffers, SearchPhase,
+};
+
+/// The bid sets of each component, as positions among the bid sets in
+/// order. Components are in the order of their first bid set. Bid sets
+/// asking for no item in stock are components of their own, and empty bid
+/// sets, which add nothing to a search, are left out.
+pub(crate) fn components<B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&B>],
+) -> Vec<Vec<usize>> {
+    // union-find over the bid sets, joined through the first bid set
+    // asking for each item
+    let mut parents = (0..exclusive_bid_sets.len()).collect::<Vec<_>>();
+    let mut first = vec![None; items.quantities().len()];
+    for (bid_set, bids) in exclusive_bid_sets.iter().enumerate() {
+        for bid in bids {
+            for (item, _) in items.index_bid_items(*bid) {
+                let Some(other) = first[item] else {
+                    first[item] = Some(bid_set);
+                    continue;
+                };
+                let (a, b) =
+                    (root(&mut parents, other), root(&mut parents, bid_set));
+                // the root of a component is its first bid set
+                parents[a.max(b)] = a.min(b);
+            }
+        }
+    }
+    let mut components: Vec<Vec<usize>> = vec![];
+    let mut component_of = vec![0; exclusive_bid_sets.len()];
+    for bid_set in 0..exclusive_bid_sets.len() {
+        if exclusive_bid_sets[bid_set].is_empty() {
+            continue;
+        }
+        let root = root(&mut parents, bid_set);
+        if root == bid_set {
+            component_of[bid_set] = components.len();
+            components.push(vec![bid_set]);
+        } else {
+            components[component_of[root]].push(bid_set);
+        }
+    }
+    components
+}
+
+/// The root of a bid set's component, halving the path to it on the way.
+fn root(parents: &mut [usize], mut bid_set: usize) -> usize {
+    while parents[bid_set] != bid_set {
+        parents[bid_set] = parents[parents[bid_set]];
+        bid_set = parents[bid_set];
+    }
+    bid_set
+}
+
+/// The search of a phase, a component at a time, which can be run a bounded
+/// number of nodes at a time. Bid sets that form a single component are
+/// searched whole.
+pub(crate) struct ComponentSearches<'a, B: Bid> {
+    phase: SearchPhase,
+    // bid sets left after propagation, which combined outcomes follow
+    propagated: Vec<Vec<&'a B>>,
+    // components left to search, the next one last
+    components: Vec<Vec<Vec<&'a B>>>,
+    known_bids: Vec<&'a B>,
+    // the search of the current component, none once all are finished
+    current: Option<ComponentSearch<'a, B>>,
+    combined: Combined<'a, B>,
+}
+
+/// The search of one component, over its bid sets in the order searched.
+struct ComponentSearch<'a, B: Bid> {
+    bid_sets: Vec<Vec<&'a B>>, // in the order given
+    ordered: bool,
+    search: Search<'a, B>,
+}
+
+impl<'a, B: Bid> ComponentSearches<'a, B> {
+    /// Propagate the bid sets and start searching their first component,
+    /// needing at least the value of the combination of `known_bids` raised
+    /// by a local search. Bids dropped by propagation are logged if pruning
+    /// is.
+    pub(crate) fn new(
+        items: &ItemStock<B>,
+        exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
+        control: &SearchControl<B::Value>,
+        phase: SearchPhase,
+        known_bids: &[&'a B],
+    ) -> Self {
+        let propagated = match control.pruning() {
+            None => propagate(items, exclusive_bid_sets),
+            Some(pruning) => {
+                let mut dropped = vec![];
+                let propagated = propagate_logged(
+                    items,
+                    exclusive_bid_sets,
+                    |bid, reason| dropped.push((bid, reason)),
+                );
+                pruning.add_dropped(phase, dropped);
+                propagated
+            }
+        };
+        let components = components(items, &propagated);
+        if components.len() <= 1 {
+            let known_value =
+                known_value(items, exclusive_bid_sets, known_bids);
+            let current = ComponentSearch::start(
+                items,
+                propagated.clone(),
+                control,
+                phase,
+                known_value,
+            );
+            return Self {
+                phase,
+                propagated,
+                components: vec![],
+                known_bids: vec![],
+                current: Some(current),
+                combined: Combined::whole(),
+            };
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(components = components.len(), "decomposed search");
+        let mut components = components
+            .into_iter()
+            .rev()
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|bid_set| propagated[bid_set].clone())
+                    .collect()
+            })
+            .collect::<Vec<_>>();
+        let mut searches = Self {
+            phase,
+            propagated,
+            components: vec![],
+            known_bids: known_bids.to_vec(),
+            current: None,
+            combined: Combined::decomposed(),
+        };
+        let first = components.pop().expect("there are components");
+        searches.components = components;
+        searches.current = Some(searches.start(items, control, first));
+        searches
+    }
+
+    /// A payment search set up by the caller, searched whole. Payment
+    /// searches timing out aren't improved, so its bid sets aren't needed.
+    pub(crate) fn single(search: Search<'a, B>) -> Self {
+        Self {
+            phase: SearchPhase::Payments,
+            propagated: vec![],
+            components: vec![],
+            known_bids: vec![],
+            current: Some(ComponentSearch {
+                bid_sets: vec![],
+                ordered: false,
+                search,
+            }),
+            combined: Combined::whole(),
+        }
+    }
+
+    /// Start searching a component of the bid sets.
+    fn start(
+        &self,
+        items: &ItemStock<B>,
+        control: &SearchControl<B::Value>,
+        bid_sets: Vec<Vec<&'a B>>,
+    ) -> ComponentSearch<'a, B> {
+        // the known bids of the component still fit without the others
+        let known_bids = self
+            .known_bids
+            .iter()
+            .filter(|bid| bid_sets.iter().flatten().any(|b| ptr::eq(*b, **bid)))
+            .copied()
+            .collect::<Vec<_>>();
+        let known_value = known_value(items, &bid_sets, &known_bids);
+        ComponentSearch::start(
+            items,
+            bid_sets,
+            control,
+            self.phase,
+            known_value,
+        )
+    }
+
+    /// Explore up to `max_nodes` nodes of the current component's search,
+    /// returning the number explored.
+    pub(crate) fn run(
+        &mut self,
+        items: &ItemStock<B>,
+        control: &SearchControl<B::Value>,
+        max_nodes: u64,
+    ) -> u64 {
+        match &mut self.current {
+            Some(current) => current.search.run(items, control, max_nodes),
+            None => 0,
+        }
+    }
+
+    /// Whether the current component's search is finished.
+    pub(crate) fn component_finished(&self) -> bool {
+        self.current
+            .as_ref()
+            .map_or(true, |current| current.search.is_finished())
+    }
+
+    /// Move on from the finished search of a component, returning whether
+    /// the search of another was started.
+    pub(crate) fn advance(
+        &mut self,
+        items: &ItemStock<B>,
+        control: &SearchControl<B::Value>,
+    ) -> bool {
+        let Some(current) = self.current.take() else {
+            return false;
+        };
+        let result = current.finish(items, control, self.phase);
+        if !self.combined.add(control, result) {
+            return false;
+        }
+        let Some(next) = self.components.pop() else {
+            return false;
+        };
+        self.current = Some(self.start(items, control, next));
+        true
+    }
+
+    /// Run every component's search to the end.
+    pub(crate) fn run_to_end(
+        mut self,
+        items: &ItemStock<B>,
+        control: &SearchControl<B::Value>,
+    ) -> SearchResult<'a, B> {
+        loop {
+            self.run(items, control, u64::MAX);
+            if !self.advance(items, control) {
+                return self.into_highest();
+            }
+        }
+    }
+
+    /// The highest value bid sets of the finished searches, in the order of
+    /// the bid sets given.
+    pub(crate) fn into_highest(self) -> SearchResult<'a, B> {
+        self.combined.into_result(&self.propagated)
+    }
+
+    /// Continue the current search in the memory of `buffers`.
+    pub(crate) fn use_buffers(&mut self, buffers: &mut SearchBuffers<B>) {
+        if let Some(current) = &mut self.current {
+            current.search.use_buffers(buffers);
+        }
+    }
+
+    /// Give back the buffers of [`use_buffers`](Self::use_buffers).
+    pub(crate) fn return_buffers(&mut self, buffers: &mut SearchBuffers<B>) {
+        if let Some(current) = &mut self.current {
+            current.search.return_buffers(buffers);
+        }
+    }
+}
+
+impl<'a, B: Bid> ComponentSearch<'a, B> {
+    /// Start searching the bid sets in the order of the search options,
+    /// needing at least `known_value` if any.
+    fn start(
+        items: &ItemStock<B>,
+        bid_sets: Vec<Vec<&'a B>>,
+        control: &SearchControl<B::Value>,
+        phase: SearchPhase,
+        known_value: Option<B::Value>,
+    ) -> Self {
+        let order = control.options().bid_set_order;
+        let ordered = (order != BidSetOrder::Input)
+            .then(|| order_bid_sets(items, bid_sets.clone(), order));
+        let mut search = start_search(
+            items,
+            ordered.as_deref().unwrap_or(&bid_sets),
+            phase,
+            control,
+        );
+        if let Some(value) = known_value {
+            search.need_at_least(value);
+        }
+        Self {
+            bid_sets,
+            ordered: ordered.is_some(),
+            search,
+        }
+    }
+
+    /// The highest value bid sets of the finished search, in the order of
+    /// the bid sets given.
+    fn finish(
+        self,
+        items: &ItemStock<B>,
+        control: &SearchControl<B::Value>,
+        phase: SearchPhase,
+    ) -> SearchResult<'a, B> {
+        let result = self.search.into_highest(control);
+        let mut result =
+            improve_timed_out(items, &self.bid_sets, control, phase, result);
+        if self.ordered {
+            let (Ok((outcomes, _)) | Err((_, (outcomes, _)))) = &mut result;
+            restore_order(&self.bid_sets, outcomes);
+        }
+        result
+    }
+}
+
+/// The value of `known_bids` raised by a local search, if there are any and
+/// they're a combination of the bid sets.
+fn known_value<B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&B>],
+    known_bids: &[&B],
+) -> Option<B::Value> {
+    (!known_bids.is_empty())
+        .then(|| improved_value(items, exclusive_bid_sets, known_bids))
+        .flatten()
+}
+
+/// The highest value bid sets of an auction, combined from the searches of
+/// its components as each finishes.
+pub(crate) struct Combined<'a, B: Bid> {
+    highest: Highest<'a, B>,
+    interrupted: Option<Interrupt>,
+    decomposed: bool,
+}
+
+impl<'a, B: Bid> Combined<'a, B> {
+    /// The result of searching the bid sets whole, as a single component.
+    pub(crate) fn whole() -> Self {
+        Self {
+            highest: (vec![], B::Value::zero()),
+            interrupted: None,
+            decomposed: false,
+        }
+    }
+
+    /// The result of searching the bid sets a component at a time, starting
+    /// with no bids chosen.
+    pub(crate) fn decomposed() -> Self {
+        Self {
+            highest: (vec![vec![]], B::Value::zero()),
+            interrupted: None,
+            decomposed: true,
+        }
+    }
+
+    /// Add the result of a component's search, returning whether the
+    /// search of the other components should go on.
+    pub(crate) fn add(
+        &mut self,
+        control: &SearchControl<B::Value>,
+        result: SearchResult<'a, B>,
+    ) -> bool {
+        let (component_highest, interrupt) = match result {
+            Ok(component_highest) => (component_highest, None),
+            Err((interrupt, component_highest)) => {
+                (component_highest, Some(interrupt))
+            }
+        };
+        if !self.decomposed {
+            self.highest = component_highest;
+            self.interrupted = interrupt;
+            return false;
+        }
+        let (outcomes, value) = component_highest;
+        // a search interrupted before finding any outcome has chosen no bids
+        let outcomes = if outcomes.is_empty() {
+            vec![vec![]]
+        } else {
+            outcomes
+        };
+        let highest = &mut self.highest;
+        let tied = highest.0.len().saturating_mul(outcomes.len());
+        if !control.allows_ties(tied) {
+            let limit = Interrupt::ResourceLimit(ResourceLimit::TiedOutcomes);
+            highest.0.truncate(1);
+            highest.0[0].extend(&outcomes[0]);
+            highest.1 = highest.1.add(&value);
+            self.interrupted = Some(limit);
+            return false;
+        }
+        highest.0 = highest
+            .0
+            .iter()
+            .flat_map(|bids| {
+                outcomes
+                    .iter()
+                    .map(move |others| [&bids[..], others].concat())
+            })
+            .collect();
+        highest.1 = highest.1.add(&value);
+        // timed out components go on, so the best outcome of each of the
+        // others is found too
+        self.interrupted = self.interrupted.or(interrupt);
+        interrupt.map_or(true, |interrupt| interrupt == Interrupt::TimedOut)
+    }
+
+    /// The combined highest value bid sets, in the order of `bid_sets`, the
+    /// bid sets the components were taken from.
+    pub(crate) fn into_result(
+        mut self,
+        bid_sets: &[Vec<&'a B>],
+    ) -> SearchResult<'a, B> {
+        if self.decomposed {
+            restore_order(bid_sets, &mut self.highest.0);
+        }
+        match self.interrupted {
+            None => Ok(self.highest),
+            Some(interrupt) => Err((interrupt, self.highest)),
+        }
+    }
+}
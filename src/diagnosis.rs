@@ -0,0 +1,175 @@
+//! Diagnosing the outcome of an auction, so that an outcome where no bid wins
+//! can be told apart from one where no bid could, and each losing bid can be
+//! explained.
+
+use std::fmt;
+
+use num_traits::Zero;
+
+use crate::{
+    config::{item_stock, run_with_stock},
+    input::bid_set_refs,
+    quantities::within_stock,
+    AddSubSelf, AuctionConfig, AuctionError, AuctionOutcome, Bid, StockEntry,
+};
+
+/// Why a bid didn't win, found by [`run_with_diagnosis`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum Exclusion {
+    /// The bid's value is negative, so leaving it out is worth more.
+    NegativeValue,
+    /// The bid needs more of an item than is in stock.
+    OverStock,
+    /// Another bid of its bid set won.
+    BidSetWon,
+    /// The bid fits in stock, but other bids were chosen over it, being worth
+    /// more together or tied with it.
+    Outbid,
+}
+
+impl fmt::Display for Exclusion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Exclusion::NegativeValue => write!(f, "value is negative"),
+            Exclusion::OverStock => {
+                write!(f, "needs more of an item than is in stock")
+            }
+            Exclusion::BidSetWon => {
+                write!(f, "another bid of its bid set won")
+            }
+            Exclusion::Outbid => write!(f, "other bids were chosen over it"),
+        }
+    }
+}
+
+/// A bid that didn't win, identified by its bid set and its position in it.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ExcludedBid {
+    pub bid_set: usize,
+    pub bid: usize,
+    pub reason: Exclusion,
+}
+
+/// The outcome of an auction with what it found out along the way, returned
+/// by [`run_with_diagnosis`].
+pub struct Diagnosed<'a, B: Bid> {
+    pub outcome: AuctionOutcome<'a, B>,
+    /// Total value of the winning bids, the highest welfare any outcome has,
+    /// or of the best combination found if the search timed out. Zero if no
+    /// bid won.
+    pub welfare: B::Value,
+    /// Whether any bid fits in stock without a negative value, so an outcome
+    /// with a winning bid was possible.
+    pub feasible: bool,
+    /// Every bid that didn't win, in the order of the bid sets.
+    pub excluded: Vec<ExcludedBid>,
+}
+
+// manual impl, since the derive can't bound on the fields' associated types
+impl<'a, B: Bid> fmt::Debug for Diagnosed<'a, B>
+where
+    AuctionOutcome<'a, B>: fmt::Debug,
+    B::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Diagnosed")
+            .field("outcome", &self.outcome)
+            .field("welfare", &self.welfare)
+            .field("feasible", &self.feasible)
+            .field("excluded", &self.excluded)
+            .finish()
+    }
+}
+
+/// Run an auction like [`run`](crate::run), also reporting the welfare of
+/// its outcome, whether any bid could have won, and why each losing bid
+/// lost.
+///
+/// ```
+/// use vcg_auction::{
+///     run_with_diagnosis, types::SimpleBid, AuctionConfig, ExcludedBid,
+///     Exclusion,
+/// };
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 2)])],
+///     vec![SimpleBid::new("Bob", 7, [("chair", 2)])],
+/// ];
+/// let config = AuctionConfig::deterministic();
+/// let diagnosed = run_with_diagnosis(&items, &bids, &config)?;
+/// // nobody wins, since there's only one chair
+/// assert_eq!(diagnosed.welfare, 0);
+/// assert!(!diagnosed.feasible);
+/// assert_eq!(
+///     diagnosed.excluded[0],
+///     ExcludedBid {
+///         bid_set: 0,
+///         bid: 0,
+///         reason: Exclusion::OverStock,
+///     }
+/// );
+/// # Ok::<(), vcg_auction::AuctionError>(())
+/// ```
+pub fn run_with_diagnosis<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    config: &AuctionConfig<B>,
+) -> Result<Diagnosed<'a, B>, AuctionError>
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let stock = item_stock(items, config)?;
+    let bid_sets = bid_set_refs(exclusive_bid_sets);
+    let outcome = run_with_stock(&stock, bid_sets.clone(), config)?;
+    let winning_bids = match &outcome {
+        AuctionOutcome::Complete(result) => &result.winning_bids,
+        AuctionOutcome::TimedOut { best_so_far, .. } => best_so_far,
+    };
+    let won = |bid: &B| winning_bids.iter().any(|w| std::ptr::eq(*w, bid));
+    let welfare = winning_bids
+        .iter()
+        .fold(B::Value::zero(), |sum, bid| sum.add(bid.bid_value()));
+
+    let no_items = stock.no_items_selected();
+    let mut feasible = false;
+    let mut excluded = vec![];
+    for (i, bid_set) in bid_sets.iter().enumerate() {
+        let bid_set_won = bid_set.iter().any(|bid| won(bid));
+        for (j, bid) in bid_set.iter().enumerate() {
+            let negative = *bid.bid_value() < B::Value::zero();
+            let fits = within_stock(
+                &stock.select_bid_items(&no_items, bid),
+                stock.quantities(),
+            );
+            feasible |= fits && !negative;
+            if won(bid) {
+                continue;
+            }
+            let reason = if negative {
+                Exclusion::NegativeValue
+            } else if !fits {
+                Exclusion::OverStock
+            } else if bid_set_won {
+                Exclusion::BidSetWon
+            } else {
+                Exclusion::Outbid
+            };
+            excluded.push(ExcludedBid {
+                bid_set: i,
+                bid: j,
+                reason,
+            });
+        }
+    }
+    Ok(Diagnosed {
+        outcome,
+        welfare,
+        feasible,
+        excluded,
+    })
+}
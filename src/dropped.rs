@@ -0,0 +1,100 @@
+//! Bids an auction leaves out before searching, with why they can't win.
+
+use std::fmt;
+
+use crate::{
+    input::ItemStock, propagation::propagate_logged, report::position, Bid,
+};
+
+/// A bid left out of an auction's search, so its bidder can be told why it
+/// couldn't win. Bids are identified by their bid set and their position in
+/// it.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DroppedBid {
+    pub bid_set: usize,
+    pub bid: usize,
+    pub reason: DropReason,
+}
+
+/// Why a bid was left out of an auction's search, or out of the auction
+/// itself by an adapter, such as the `openrtb` module's.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum DropReason {
+    /// The bid's value is negative, so leaving it out is worth more.
+    NegativeValue,
+    /// The bid needs more of an item than is in stock.
+    OverStock,
+    /// Another bid of its bid set is worth more and uses no more of any item.
+    Dominated,
+    /// The bid doesn't fit beside the bids that are in every highest-value
+    /// outcome.
+    Excluded,
+    /// The bid is on an item that isn't for sale.
+    UnknownItem,
+    /// The bid's value isn't one the auction can use, such as a price that
+    /// isn't a finite positive number.
+    InvalidValue,
+    /// The bid's value is below the reserve price of its item.
+    BelowReserve,
+    /// The bid is on the same item as another part of the combined bid it
+    /// belongs to.
+    RepeatedItem,
+    /// Another part of the combined bid it belongs to was dropped, taking the
+    /// whole combined bid with it.
+    PartDropped,
+    /// The values of the combined bid it belongs to add up to more than a
+    /// bid's value can be.
+    ValueOverflow,
+}
+
+impl fmt::Display for DropReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DropReason::NegativeValue => write!(f, "value is negative"),
+            DropReason::OverStock => {
+                write!(f, "needs more of an item than is in stock")
+            }
+            DropReason::Dominated => {
+                write!(f, "another bid of the bid set is worth more")
+            }
+            DropReason::Excluded => {
+                write!(f, "doesn't fit beside bids that must win")
+            }
+            DropReason::UnknownItem => write!(f, "item isn't for sale"),
+            DropReason::InvalidValue => write!(f, "value isn't valid"),
+            DropReason::BelowReserve => {
+                write!(f, "value is below the reserve price")
+            }
+            DropReason::RepeatedItem => {
+                write!(f, "combined bid is on the item more than once")
+            }
+            DropReason::PartDropped => {
+                write!(f, "another part of the combined bid was dropped")
+            }
+            DropReason::ValueOverflow => {
+                write!(f, "combined bid's total value is too high")
+            }
+        }
+    }
+}
+
+/// The bids the search for the winners drops before searching, in the order
+/// they're dropped.
+pub(crate) fn dropped_bids<B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&B>],
+) -> Vec<DroppedBid> {
+    let mut dropped = vec![];
+    propagate_logged(items, exclusive_bid_sets, |bid, reason| {
+        let (bid_set, bid) = position(exclusive_bid_sets, bid);
+        dropped.push(DroppedBid {
+            bid_set,
+            bid,
+            reason,
+        });
+    });
+    dropped
+}
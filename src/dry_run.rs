@@ -0,0 +1,172 @@
+//! Checking an auction before running it, so services can turn away
+//! instances that would take too long to solve.
+
+use crate::{
+    complexity::SearchSize, input::bid_set_refs, AuctionConfig, AuctionError,
+    Bid, DuplicateItems, InputError, ResourceLimit, StockEntry,
+};
+
+/// What an auction would search, estimated by
+/// [`vcg_auction_dry_run`].
+///
+/// Counts are worst cases, for a search that prunes nothing. They're kept as
+/// floats since they grow exponentially with the number of bid sets, and
+/// are infinite if they overflow.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DryRun {
+    /// Number of bid sets with at least one bid.
+    pub bid_sets: usize,
+    /// Total number of bids.
+    pub bids: usize,
+    /// Number of ways to choose at most one bid from each bid set, whether or
+    /// not the items are in stock.
+    pub search_space: f64,
+    /// Most search nodes the search for the winners can explore.
+    pub winner_determination_nodes: f64,
+    /// Most search nodes the searches for the payments can explore, with one
+    /// search for each bidder that could win.
+    pub payment_nodes: f64,
+    /// Limits of the config the auction may run into.
+    pub may_exceed: Vec<ResourceLimit>,
+}
+
+impl DryRun {
+    /// Most search nodes the auction can explore.
+    pub fn worst_case_nodes(&self) -> f64 {
+        self.winner_determination_nodes + self.payment_nodes
+    }
+}
+
+/// Validate the inputs of an auction and estimate how large its search is,
+/// without searching. Items and bid sets are given as for
+/// [`vcg_auction`](crate::vcg_auction).
+///
+/// Nothing is searched, so this is cheap even for instances that would take
+/// far too long to solve, and can be used to reject them up front.
+///
+/// ```
+/// use vcg_auction::{
+///     types::SimpleBid, vcg_auction_dry_run, AuctionConfig, InputError,
+///     ResourceLimit,
+/// };
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+/// ];
+/// let config = AuctionConfig::builder().max_nodes(5).build();
+/// let dry_run = vcg_auction_dry_run(&items, &bids, &config)?;
+/// // no bids, either bid, or both
+/// assert_eq!(dry_run.search_space, 4.0);
+/// assert_eq!(dry_run.may_exceed, [ResourceLimit::Nodes]);
+///
+/// let bids = vec![vec![SimpleBid::new("Alice", 10, [("table", 1)])]];
+/// assert_eq!(
+///     vcg_auction_dry_run(&items, &bids, &config),
+///     Err(InputError::UnknownItem { bid_set: 0, bid: 0 })
+/// );
+/// # Ok::<(), InputError>(())
+/// ```
+pub fn vcg_auction_dry_run<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    config: &AuctionConfig<B>,
+) -> Result<DryRun, InputError>
+where
+    B: Bid + 'a + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let items = items
+        .into_iter()
+        .map(|entry| entry.into_entry().0)
+        .collect::<Vec<_>>();
+    let exclusive_bid_sets = bid_set_refs(exclusive_bid_sets);
+    validate::<B>(&items, &exclusive_bid_sets, config.duplicate_items)?;
+
+    let bid_sets = exclusive_bid_sets
+        .iter()
+        .filter(|bid_set| !bid_set.is_empty())
+        .collect::<Vec<_>>();
+    let size = SearchSize::of(&bid_sets);
+
+    let mut dry_run = DryRun {
+        bid_sets: bid_sets.len(),
+        bids: bid_sets.iter().map(|bid_set| bid_set.len()).sum(),
+        search_space: size.search_space,
+        winner_determination_nodes: size.winner_determination_nodes,
+        payment_nodes: size.payment_nodes,
+        may_exceed: vec![],
+    };
+    if let Some(max_nodes) = config.search.max_nodes {
+        if dry_run.worst_case_nodes() > max_nodes as f64 {
+            dry_run.may_exceed.push(ResourceLimit::Nodes);
+        }
+    }
+    if let Err(AuctionError::ResourceLimit(limit)) =
+        config.search.check_size(&exclusive_bid_sets)
+    {
+        dry_run.may_exceed.push(limit);
+    }
+    // ties are only bounded by the number of combinations
+    if let Some(max_tied_outcomes) = config.search.max_tied_outcomes {
+        if size.search_space > max_tied_outcomes as f64 {
+            dry_run.may_exceed.push(ResourceLimit::TiedOutcomes);
+        }
+    }
+    Ok(dry_run)
+}
+
+/// Check that stock items are distinct, unless they're merged, and that bids
+/// only list distinct items of the stock.
+fn validate<B: Bid>(
+    items: &[&B::Item],
+    exclusive_bid_sets: &[Vec<&B>],
+    duplicate_items: DuplicateItems,
+) -> Result<(), InputError> {
+    let merged = |problem: &InputError| {
+        duplicate_items == DuplicateItems::Merge
+            && matches!(problem, InputError::DuplicateStockItem(_))
+    };
+    match input_problems::<B>(items, exclusive_bid_sets)
+        .into_iter()
+        .find(|problem| !merged(problem))
+    {
+        Some(problem) => Err(problem),
+        None => Ok(()),
+    }
+}
+
+/// Every problem with the items and bids, in the order of the stock and then
+/// of the bids. Each problem is listed once, even if a bid has several
+/// unknown or repeated items.
+pub(crate) fn input_problems<B: Bid>(
+    items: &[&B::Item],
+    exclusive_bid_sets: &[Vec<&B>],
+) -> Vec<InputError> {
+    let mut problems = vec![];
+    for (i, item) in items.iter().enumerate() {
+        if items[..i].contains(item) {
+            problems.push(InputError::DuplicateStockItem(i));
+        }
+    }
+    for (bid_set, bids) in exclusive_bid_sets.iter().enumerate() {
+        for (bid, b) in bids.iter().enumerate() {
+            let bid_items = b.bid_items();
+            for (i, (item, _)) in bid_items.iter().enumerate() {
+                let problem = if !items.contains(&item) {
+                    InputError::UnknownItem { bid_set, bid }
+                } else if bid_items[..i].iter().any(|(other, _)| other == item)
+                {
+                    InputError::DuplicateBidItem { bid_set, bid }
+                } else {
+                    continue;
+                };
+                if !problems.contains(&problem) {
+                    problems.push(problem);
+                }
+            }
+        }
+    }
+    problems
+}
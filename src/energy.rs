@@ -0,0 +1,490 @@
+//! Preset for day-ahead energy markets, where a resource such as power or
+//! reserve capacity is traded in each time slot of a day.
+//!
+//! Every resource and time slot is an item. Block bids ask for quantities in
+//! consecutive slots, all or nothing, like a plant that must run for several
+//! hours once started. An [`EnergyMarket`] can have a fixed supply, sold to
+//! the buyers like any other auction, or block offers from sellers, cleared
+//! as a double auction with [`EnergyMarket::clear`].
+//!
+//! In the double auction each offer is an all-or-nothing block, whose seller
+//! incurs the ask if the block is sold. Offers are turned into bids by the
+//! seller to keep their block, worth the ask, so the auction sells a block
+//! only if buyers value it more than its seller. Buyers pay their VCG payment
+//! as usual, and each seller receives the welfare the market would lose
+//! without them, plus the ask of each block sold. Receipts can add up to more
+//! than the payments: VCG double auctions are efficient and truthful, but not
+//! budget balanced. Markets that can't run a deficit can instead be cleared
+//! with McAfee's mechanism by [`EnergyMarket::clear_mcafee`], which never
+//! pays sellers more than buyers pay, at the cost of sometimes dropping a
+//! trade.
+//!
+//! ```
+//! use vcg_auction::energy::EnergyMarket;
+//!
+//! let mut market = EnergyMarket::new(["power"], 24);
+//! // a plant offering 10 MWh in each of hours 8 to 11 for 400
+//! market.offer("plant", 400, "power", 8, &[10; 4])?;
+//! let bids = vec![
+//!     vec![market.block_bid("factory", 600, "power", 8, &[10; 4])?],
+//!     vec![market.block_bid("office", 200, "power", 9, &[10; 2])?],
+//! ];
+//! let cleared = market.clear(&bids, |_| 0).unwrap();
+//! assert_eq!(cleared.winning_bids, [&bids[0][0]]);
+//! // the factory pays the most the office or the plant would give up
+//! assert_eq!(cleared.payments, [(&"factory".to_string(), 400)]);
+//! // and the plant receives the most the factory would pay for its block
+//! assert_eq!(cleared.receipts, [(&"plant".to_string(), 600)]);
+//! # Ok::<(), vcg_auction::energy::EnergyError>(())
+//! ```
+
+use std::fmt;
+
+use crate::{
+    control::SearchControl,
+    input::ItemStock,
+    vcg::{find_highest_value_bid_sets, run_auction, winning_bidders},
+    Bid, SearchOptions, SearchPhase,
+};
+
+/// Item of an energy market: a resource in one time slot.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SlotItem {
+    pub resource: String,
+    pub slot: usize,
+}
+
+/// A block bid or offer, for quantities of a resource in consecutive slots.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EnergyBid {
+    pub name: String,
+    pub value: u64,
+    items: Vec<(SlotItem, u64)>,
+}
+
+impl Bid for EnergyBid {
+    type Name = String;
+    type Value = u64;
+    type Item = SlotItem;
+    type Quantity = u64;
+
+    fn bidder_name(&self) -> &Self::Name {
+        &self.name
+    }
+    fn bid_value(&self) -> &Self::Value {
+        &self.value
+    }
+    fn bid_items(&self) -> &[(Self::Item, Self::Quantity)] {
+        &self.items
+    }
+}
+
+/// Reasons a block can't be bid or offered in an [`EnergyMarket`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum EnergyError {
+    /// The resource isn't traded in the market.
+    UnknownResource(String),
+    /// The block runs past the market's last slot.
+    PastLastSlot { last_slot: usize, slots: usize },
+    /// The block has no quantities.
+    EmptyBlock,
+    /// Buyers can't also be sellers, since a bidder's payment accounts for
+    /// all of their bids.
+    SellerBidding(String),
+    /// McAfee's mechanism needs every bid and offer to be for the same block,
+    /// without a fixed supply.
+    UnlikeBlocks,
+}
+
+impl fmt::Display for EnergyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnergyError::UnknownResource(resource) => {
+                write!(f, "resource {resource:?} isn't traded in the market")
+            }
+            EnergyError::PastLastSlot { last_slot, slots } => write!(
+                f,
+                "block runs to slot {last_slot}, past the market's {slots} \
+                 slots"
+            ),
+            EnergyError::EmptyBlock => write!(f, "block has no quantities"),
+            EnergyError::SellerBidding(name) => {
+                write!(f, "seller {name:?} can't also bid")
+            }
+            EnergyError::UnlikeBlocks => {
+                write!(f, "bids and offers aren't all for the same block")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnergyError {}
+
+/// The result of clearing an [`EnergyMarket`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct EnergyClearing<'a> {
+    /// Buyers' winning bids, in the order of their bid sets.
+    pub winning_bids: Vec<&'a EnergyBid>,
+    /// Payment of each winning buyer.
+    pub payments: Vec<(&'a String, u64)>,
+    /// Offers sold, in the order they were made.
+    pub sold: Vec<&'a EnergyBid>,
+    /// Receipts of each seller with an offer sold.
+    pub receipts: Vec<(&'a String, u64)>,
+    /// Welfare of the trades left out to keep the market budget balanced,
+    /// which is zero when cleared by VCG.
+    pub efficiency_loss: u64,
+}
+
+/// The resources and time slots of an energy market, with its supply.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct EnergyMarket {
+    resources: Vec<String>,
+    slots: usize,
+    supply: Vec<(SlotItem, u64)>,
+    offers: Vec<EnergyBid>,
+}
+
+impl EnergyMarket {
+    /// A market for the resources in slots numbered from 0, without any
+    /// supply.
+    pub fn new<R: Into<String>>(
+        resources: impl IntoIterator<Item = R>,
+        slots: usize,
+    ) -> Self {
+        let resources = resources.into_iter().map(Into::into).collect();
+        Self {
+            resources,
+            slots,
+            supply: vec![],
+            offers: vec![],
+        }
+    }
+
+    /// Add a fixed supply of a resource in consecutive slots from
+    /// `first_slot`, sold without a seller.
+    pub fn supply(
+        &mut self,
+        resource: &str,
+        first_slot: usize,
+        quantities: &[u64],
+    ) -> Result<&mut Self, EnergyError> {
+        let block = self.block(resource, first_slot, quantities)?;
+        self.supply.extend(block);
+        Ok(self)
+    }
+
+    /// Add a seller's offer of a resource in consecutive slots from
+    /// `first_slot`, which is sold all together if buyers value it more than
+    /// `ask`.
+    pub fn offer(
+        &mut self,
+        seller: impl Into<String>,
+        ask: u64,
+        resource: &str,
+        first_slot: usize,
+        quantities: &[u64],
+    ) -> Result<&mut Self, EnergyError> {
+        let items = self.block(resource, first_slot, quantities)?;
+        self.offers.push(EnergyBid {
+            name: seller.into(),
+            value: ask,
+            items,
+        });
+        Ok(self)
+    }
+
+    /// A buyer's bid for a resource in consecutive slots from `first_slot`,
+    /// which wins all of the slots or none.
+    pub fn block_bid(
+        &self,
+        buyer: impl Into<String>,
+        value: u64,
+        resource: &str,
+        first_slot: usize,
+        quantities: &[u64],
+    ) -> Result<EnergyBid, EnergyError> {
+        let name = buyer.into();
+        if self.offers.iter().any(|offer| offer.name == name) {
+            return Err(EnergyError::SellerBidding(name));
+        }
+        Ok(EnergyBid {
+            name,
+            value,
+            items: self.block(resource, first_slot, quantities)?,
+        })
+    }
+
+    /// The sellers' offers, in the order they were made.
+    pub fn offers(&self) -> &[EnergyBid] {
+        &self.offers
+    }
+
+    /// Items of the market: each resource in each slot, with the supply and
+    /// offers of it added up.
+    pub fn items(&self) -> Vec<(SlotItem, u64)> {
+        let mut items = self
+            .resources
+            .iter()
+            .flat_map(|resource| {
+                (0..self.slots).map(|slot| {
+                    let item = SlotItem {
+                        resource: resource.clone(),
+                        slot,
+                    };
+                    (item, 0u64)
+                })
+            })
+            .collect::<Vec<_>>();
+        let offered = self.offers.iter().flat_map(|offer| &offer.items);
+        for (item, quantity) in self.supply.iter().chain(offered) {
+            if let Some((_, total)) = items.iter_mut().find(|(i, _)| i == item)
+            {
+                *total = total.saturating_add(*quantity);
+            }
+        }
+        items
+    }
+
+    /// Clear the market with the buyers' bid sets, breaking ties between
+    /// outcomes with `tiebreaker`, which is given the outcomes with the
+    /// sellers' bids to keep their offers. Returns `None` if the tiebreaker
+    /// returns an invalid index. Without offers this is an auction of the
+    /// supply.
+    pub fn clear<'a>(
+        &'a self,
+        bid_sets: &'a [Vec<EnergyBid>],
+        tiebreaker: impl FnOnce(&[Vec<&EnergyBid>]) -> usize,
+    ) -> Option<EnergyClearing<'a>> {
+        let items = self.items();
+        let stock = ItemStock::new(&items);
+        let control = SearchControl::new(SearchOptions::default());
+        let buyer_bid_sets = bid_sets.iter().map(|bid_set| bid_set.iter());
+        // each offer is a bid set of the seller's bid to keep it
+        let kept = self.offers.iter().map(std::slice::from_ref);
+        let all_bid_sets = buyer_bid_sets
+            .chain(kept.map(|offer| offer.iter()))
+            .map(Iterator::collect)
+            .collect::<Vec<Vec<&EnergyBid>>>();
+        let result =
+            run_auction(&stock, all_bid_sets.clone(), tiebreaker, &control)
+                .ok()?
+                .complete()?;
+        let is_offer = |bid: &EnergyBid| {
+            self.offers.iter().any(|offer| std::ptr::eq(offer, bid))
+        };
+        let is_seller =
+            |name: &String| self.offers.iter().any(|offer| offer.name == *name);
+
+        let welfare = result
+            .winning_bids
+            .iter()
+            .fold(0u64, |sum, bid| sum.saturating_add(bid.value));
+        let sold = self
+            .offers
+            .iter()
+            .filter(|offer| {
+                !result
+                    .winning_bids
+                    .iter()
+                    .any(|bid| std::ptr::eq(*bid, *offer))
+            })
+            .collect::<Vec<_>>();
+        let mut receipts: Vec<(&String, u64)> = vec![];
+        for offer in &sold {
+            let seller = &offer.name;
+            if receipts.iter().any(|(name, _)| *name == seller) {
+                continue;
+            }
+            // the market without the seller's offers
+            let mut without_items = items.clone();
+            for offer in self.offers.iter().filter(|o| o.name == *seller) {
+                for (item, quantity) in &offer.items {
+                    if let Some((_, total)) =
+                        without_items.iter_mut().find(|(i, _)| i == item)
+                    {
+                        *total = total.saturating_sub(*quantity);
+                    }
+                }
+            }
+            let without_bid_sets = all_bid_sets
+                .iter()
+                .filter(|bid_set| bid_set.iter().all(|bid| bid.name != *seller))
+                .cloned()
+                .collect::<Vec<_>>();
+            let (_, without_welfare) = find_highest_value_bid_sets(
+                &ItemStock::new(&without_items),
+                &without_bid_sets,
+                &control,
+                SearchPhase::Payments,
+            )
+            .ok()?;
+            let kept = result
+                .winning_bids
+                .iter()
+                .filter(|bid| is_offer(bid) && bid.name == *seller)
+                .fold(0u64, |sum, bid| sum.saturating_add(bid.value));
+            let receipt =
+                welfare.saturating_sub(without_welfare).saturating_sub(kept);
+            receipts.push((seller, receipt));
+        }
+        Some(EnergyClearing {
+            winning_bids: result
+                .winning_bids
+                .into_iter()
+                .filter(|bid| !is_offer(bid))
+                .collect(),
+            payments: result
+                .payments
+                .into_iter()
+                .filter(|(name, _)| !is_seller(name))
+                .collect(),
+            sold,
+            receipts,
+            efficiency_loss: 0,
+        })
+    }
+
+    /// Clear a market of identical blocks with McAfee's mechanism, a budget
+    /// balanced alternative to [`clear`](Self::clear). Every offer and every
+    /// buyer's bid must be for the same quantities in the same slots, and the
+    /// market can't have a fixed supply.
+    ///
+    /// Each bid set is a buyer's bid for one block, at its highest value.
+    /// With bids sorted from the highest and asks from the lowest, `k` trades
+    /// are worth making while the `k`th bid is more than the `k`th ask. If the
+    /// average of the next bid and ask lies between those two, all `k` trades
+    /// go ahead at that price. Otherwise the `k`th trade is dropped: the other
+    /// buyers pay the `k`th bid, the other sellers receive the `k`th ask, and
+    /// the market keeps the difference. Bidding their values is the best
+    /// strategy of buyers and sellers trading a single block, and the welfare
+    /// of a dropped trade is reported as the efficiency loss. Ties between
+    /// equal bids or asks go to the earliest.
+    ///
+    /// ```
+    /// use vcg_auction::energy::EnergyMarket;
+    ///
+    /// let mut market = EnergyMarket::new(["power"], 24);
+    /// market
+    ///     .offer("plant", 300, "power", 8, &[10; 4])?
+    ///     .offer("turbine", 500, "power", 8, &[10; 4])?;
+    /// let bids = vec![
+    ///     vec![market.block_bid("factory", 600, "power", 8, &[10; 4])?],
+    ///     vec![market.block_bid("office", 400, "power", 8, &[10; 4])?],
+    /// ];
+    /// let cleared = market.clear_mcafee(&bids)?;
+    /// // the factory buys the plant's block at 450, the average of the
+    /// // office's bid and the turbine's ask
+    /// assert_eq!(cleared.payments, [(&"factory".to_string(), 450)]);
+    /// assert_eq!(cleared.receipts, [(&"plant".to_string(), 450)]);
+    /// assert_eq!(cleared.efficiency_loss, 0);
+    /// # Ok::<(), vcg_auction::energy::EnergyError>(())
+    /// ```
+    pub fn clear_mcafee<'a>(
+        &'a self,
+        bid_sets: &'a [Vec<EnergyBid>],
+    ) -> Result<EnergyClearing<'a>, EnergyError> {
+        let blocks = || {
+            let bids = bid_sets.iter().flatten();
+            self.offers.iter().chain(bids).map(|bid| &bid.items)
+        };
+        let first = blocks().next();
+        if !self.supply.is_empty() || blocks().any(|b| Some(b) != first) {
+            return Err(EnergyError::UnlikeBlocks);
+        }
+        let mut bids = bid_sets
+            .iter()
+            .filter_map(|bid_set| bid_set.iter().max_by_key(|bid| bid.value))
+            .collect::<Vec<_>>();
+        bids.sort_by_key(|bid| std::cmp::Reverse(bid.value));
+        let mut asks = self.offers.iter().collect::<Vec<_>>();
+        asks.sort_by_key(|offer| offer.value);
+        let k = bids
+            .iter()
+            .zip(&asks)
+            .take_while(|(bid, ask)| bid.value > ask.value)
+            .count();
+
+        let (trades, buyer_price, seller_price, efficiency_loss) = if k == 0 {
+            (0, 0, 0, 0)
+        } else {
+            let (last_bid, last_ask) = (bids[k - 1].value, asks[k - 1].value);
+            let average = bids.get(k).zip(asks.get(k)).map(|(bid, ask)| {
+                let (a, b) = (bid.value, ask.value);
+                a / 2 + b / 2 + (a % 2 + b % 2) / 2
+            });
+            match average {
+                Some(price) if (last_ask..=last_bid).contains(&price) => {
+                    (k, price, price, 0)
+                }
+                _ => (k - 1, last_bid, last_ask, last_bid - last_ask),
+            }
+        };
+        let traded = |bids: &[&EnergyBid], bid: &EnergyBid| {
+            bids[..trades].iter().any(|b| std::ptr::eq(*b, bid))
+        };
+        let winning_bids = bid_sets
+            .iter()
+            .flatten()
+            .filter(|bid| traded(&bids, bid))
+            .collect::<Vec<_>>();
+        let sold = self
+            .offers
+            .iter()
+            .filter(|offer| traded(&asks, offer))
+            .collect::<Vec<_>>();
+        let total = |bids: &[&'a EnergyBid], price: u64| {
+            winning_bidders(bids)
+                .into_iter()
+                .map(|name| {
+                    let count = bids.iter().filter(|b| b.name == *name).count();
+                    (name, price.saturating_mul(count as u64))
+                })
+                .collect::<Vec<_>>()
+        };
+        Ok(EnergyClearing {
+            payments: total(&winning_bids, buyer_price),
+            receipts: total(&sold, seller_price),
+            winning_bids,
+            sold,
+            efficiency_loss,
+        })
+    }
+
+    /// Items of a block of a resource in consecutive slots.
+    fn block(
+        &self,
+        resource: &str,
+        first_slot: usize,
+        quantities: &[u64],
+    ) -> Result<Vec<(SlotItem, u64)>, EnergyError> {
+        if !self.resources.iter().any(|r| r == resource) {
+            return Err(EnergyError::UnknownResource(resource.into()));
+        }
+        if quantities.is_empty() {
+            return Err(EnergyError::EmptyBlock);
+        }
+        let last_slot = first_slot.saturating_add(quantities.len() - 1);
+        if last_slot >= self.slots {
+            return Err(EnergyError::PastLastSlot {
+                last_slot,
+                slots: self.slots,
+            });
+        }
+        Ok(quantities
+            .iter()
+            .enumerate()
+            .filter(|(_, quantity)| **quantity > 0)
+            .map(|(i, quantity)| {
+                let item = SlotItem {
+                    resource: resource.into(),
+                    slot: first_slot + i,
+                };
+                (item, *quantity)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,172 @@
+//! Errors returned by auctions.
+
+use std::fmt;
+
+/// Reasons an auction can fail to produce a result.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum AuctionError {
+    /// The tiebreaker returned an index outside of the tied outcomes.
+    InvalidTiebreak,
+    /// The search took longer than
+    /// [`max_duration`](crate::SearchOptions::max_duration).
+    TimedOut,
+    /// The search was cancelled before finishing.
+    Cancelled,
+    /// The thread pool for a parallel search couldn't be created.
+    ThreadPool,
+    /// The search reached one of the resource limits of its
+    /// [`SearchOptions`](crate::SearchOptions).
+    ResourceLimit(ResourceLimit),
+    /// A payment differed from the one found by searching again, with
+    /// [`verify_payments`](crate::SearchOptions::verify_payments).
+    PaymentMismatch,
+    /// The items were rejected, with
+    /// [`DuplicateItems::Reject`](crate::DuplicateItems::Reject).
+    Input(InputError),
+    /// A search option can't be honoured by the way the auction is run, like
+    /// [`OnTimeout::BestSoFar`](crate::OnTimeout::BestSoFar) for a
+    /// [`Solver`](crate::Solver).
+    UnsupportedOption,
+}
+
+/// Resource limits of a search, set in
+/// [`SearchOptions`](crate::SearchOptions).
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum ResourceLimit {
+    /// [`max_nodes`](crate::SearchOptions::max_nodes)
+    Nodes,
+    /// [`max_tied_outcomes`](crate::SearchOptions::max_tied_outcomes)
+    TiedOutcomes,
+    /// [`max_bid_sets`](crate::SearchOptions::max_bid_sets)
+    BidSets,
+    /// [`max_bids_per_set`](crate::SearchOptions::max_bids_per_set)
+    BidsPerSet,
+}
+
+impl fmt::Display for AuctionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuctionError::InvalidTiebreak => {
+                write!(f, "tiebreaker returned an invalid index")
+            }
+            AuctionError::TimedOut => {
+                write!(f, "auction exceeded its maximum duration")
+            }
+            AuctionError::Cancelled => write!(f, "auction was cancelled"),
+            AuctionError::ThreadPool => {
+                write!(f, "failed to create a thread pool for the auction")
+            }
+            AuctionError::ResourceLimit(ResourceLimit::Nodes) => {
+                write!(f, "auction exceeded its limit on search nodes")
+            }
+            AuctionError::ResourceLimit(ResourceLimit::TiedOutcomes) => {
+                write!(f, "auction exceeded its limit on tied outcomes")
+            }
+            AuctionError::ResourceLimit(ResourceLimit::BidSets) => {
+                write!(f, "auction exceeded its limit on bid sets")
+            }
+            AuctionError::ResourceLimit(ResourceLimit::BidsPerSet) => {
+                write!(f, "auction exceeded its limit on bids per bid set")
+            }
+            AuctionError::PaymentMismatch => {
+                write!(f, "payment differed when searched again")
+            }
+            AuctionError::Input(error) => error.fmt(f),
+            AuctionError::UnsupportedOption => {
+                write!(f, "auction can't honour one of its search options")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AuctionError {}
+
+/// Problems with the items or bids of an auction, found by
+/// [`vcg_auction_dry_run`](crate::vcg_auction_dry_run). Bids are identified
+/// by their bid set and their position in it.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum InputError {
+    /// The item at this position of the stock was already listed.
+    DuplicateStockItem(usize),
+    /// A bid includes an item that isn't in the stock. The search ignores
+    /// the item, so the bid could win without it.
+    UnknownItem { bid_set: usize, bid: usize },
+    /// A bid lists the same item twice. The search counts only the first
+    /// quantity.
+    DuplicateBidItem { bid_set: usize, bid: usize },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::DuplicateStockItem(index) => {
+                write!(f, "stock item {index} is listed more than once")
+            }
+            InputError::UnknownItem { bid_set, bid } => write!(
+                f,
+                "bid {bid} of bid set {bid_set} includes an item not in stock"
+            ),
+            InputError::DuplicateBidItem { bid_set, bid } => write!(
+                f,
+                "bid {bid} of bid set {bid_set} lists an item more than once"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Reasons an auction can't be run by
+/// [`vcg_auction_unique`](crate::vcg_auction_unique). Bids are identified by
+/// their bid set and their position in it.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum UniqueItemError {
+    /// A problem any auction would have with its items or bids.
+    Input(InputError),
+    /// The item at this position of the stock has a quantity other than one.
+    NotOneInStock(usize),
+    /// A bid isn't for exactly one unit of one item.
+    NotOneUnit { bid_set: usize, bid: usize },
+    /// A bid is by a bidder with bids in an earlier bid set, so the bidder
+    /// could win more than one item.
+    SeveralBidSets { bid_set: usize, bid: usize },
+    /// The tiebreaker returned an invalid index.
+    InvalidTiebreak,
+}
+
+impl fmt::Display for UniqueItemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UniqueItemError::Input(error) => error.fmt(f),
+            UniqueItemError::NotOneInStock(index) => {
+                write!(f, "stock item {index} doesn't have a quantity of one")
+            }
+            UniqueItemError::NotOneUnit { bid_set, bid } => write!(
+                f,
+                "bid {bid} of bid set {bid_set} isn't for one unit of one item"
+            ),
+            UniqueItemError::SeveralBidSets { bid_set, bid } => write!(
+                f,
+                "bid {bid} of bid set {bid_set} is by a bidder of an earlier \
+                 bid set"
+            ),
+            UniqueItemError::InvalidTiebreak => {
+                write!(f, "tiebreaker returned an invalid index")
+            }
+        }
+    }
+}
+
+impl std::error::Error for UniqueItemError {}
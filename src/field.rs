@@ -0,0 +1,125 @@
+//! Bid values represented as prime field elements.
+//!
+//! Zero-knowledge proof systems work with elements of a prime field, which
+//! have no order of their own. Winner determination compares sums of bids, so
+//! [`FieldValue`] orders field elements by their canonical representatives,
+//! the integers in `0..modulus`. The order matches that of the bid values as
+//! long as no sum wraps around the modulus, which is checked on every
+//! addition and subtraction.
+
+use std::cmp::Ordering;
+use std::ops::Add;
+
+use num_traits::Zero;
+
+use crate::AddSubSelf;
+
+/// Element of a prime field, to be wrapped in a [`FieldValue`].
+/// Implementations are thin forwards to the field library in use.
+pub trait FieldElement: Sized {
+    /// Canonical representative of an element, ordered like the integer in
+    /// `0..modulus` it stands for. For example the element as big-endian bytes
+    /// of a fixed length.
+    type Repr: Ord;
+
+    /// The additive identity.
+    fn zero() -> Self;
+    /// Field addition.
+    fn add(&self, other: &Self) -> Self;
+    /// Field subtraction.
+    fn sub(&self, other: &Self) -> Self;
+    /// The canonical representative of the element.
+    fn to_repr(&self) -> Self::Repr;
+}
+
+/// A field element used as a bid value, ordered by its canonical
+/// representative.
+///
+/// # Panics
+///
+/// Arithmetic panics if a sum wraps around the modulus, or a difference goes
+/// below zero, since the order of the values would no longer match the order
+/// of the bids. The sum of the highest bid of every bid set must be below the
+/// modulus, as described for [`Bid::Value`](crate::Bid::Value).
+///
+/// ```
+/// use vcg_auction::{FieldElement, FieldValue};
+///
+/// /// Integers modulo the prime 2^31 - 1.
+/// #[derive(Clone, Debug)]
+/// struct Fp(u32);
+///
+/// const P: u32 = (1 << 31) - 1;
+///
+/// impl FieldElement for Fp {
+///     type Repr = u32;
+///     fn zero() -> Self {
+///         Fp(0)
+///     }
+///     fn add(&self, other: &Self) -> Self {
+///         Fp(((u64::from(self.0) + u64::from(other.0)) % u64::from(P)) as u32)
+///     }
+///     fn sub(&self, other: &Self) -> Self {
+///         Fp((self.0 + P - other.0) % P)
+///     }
+///     fn to_repr(&self) -> u32 {
+///         self.0
+///     }
+/// }
+///
+/// assert!(FieldValue(Fp(7)) > FieldValue(Fp(5)));
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FieldValue<F>(pub F);
+
+impl<F: FieldElement> PartialEq for FieldValue<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_repr() == other.0.to_repr()
+    }
+}
+
+impl<F: FieldElement> Eq for FieldValue<F> {}
+
+impl<F: FieldElement> Ord for FieldValue<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.to_repr().cmp(&other.0.to_repr())
+    }
+}
+
+impl<F: FieldElement> PartialOrd for FieldValue<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: FieldElement> AddSubSelf for FieldValue<F> {
+    fn add(&self, other: &Self) -> Self {
+        let sum = Self(self.0.add(&other.0));
+        assert!(
+            sum >= *self,
+            "sum of field values wrapped around the modulus"
+        );
+        sum
+    }
+    fn sub(&self, other: &Self) -> Self {
+        assert!(other <= self, "difference of field values is negative");
+        Self(self.0.sub(&other.0))
+    }
+}
+
+// Required by Zero
+impl<F: FieldElement> Add for FieldValue<F> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        AddSubSelf::add(&self, &other)
+    }
+}
+
+impl<F: FieldElement> Zero for FieldValue<F> {
+    fn zero() -> Self {
+        Self(F::zero())
+    }
+    fn is_zero(&self) -> bool {
+        self.0.to_repr() == F::zero().to_repr()
+    }
+}
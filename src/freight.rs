@@ -0,0 +1,360 @@
+//! Preset for freight procurement, a reverse auction where a shipper buys
+//! transport on lanes from carriers.
+//!
+//! Each lane has a volume to move and a reserve rate for each unit of it,
+//! such as the spot market rate the shipper would otherwise pay. Carriers bid
+//! the cost of packages of volume on several lanes, and can limit the total
+//! volume they're awarded with a capacity.
+//!
+//! Procurement minimizes cost rather than maximizing value, so each package
+//! is bid at its savings: the reserve of its volume, less its cost. Volume
+//! not awarded goes at the reserve rate, so the highest savings are the
+//! lowest total cost. Each carrier is compensated the reserve of the volume
+//! they win less their VCG payment, which comes to their cost plus what the
+//! shipper would lose without them. Packages costing the reserve or more
+//! save nothing, and are rejected.
+//!
+//! ```
+//! use vcg_auction::freight::{Lane, LaneMarket};
+//!
+//! let mut market = LaneMarket::new([
+//!     Lane { id: "CHI-NYC".into(), volume: 10, reserve_rate: 100 },
+//!     Lane { id: "NYC-CHI".into(), volume: 10, reserve_rate: 80 },
+//! ]);
+//! // a round trip is cheaper than two one-way lanes
+//! let round_trip = [("CHI-NYC", 10), ("NYC-CHI", 10)];
+//! let bids = vec![
+//!     vec![market.package("Acme", 1_400, round_trip)?],
+//!     vec![market.package("Bolt", 900, [("CHI-NYC", 10)])?],
+//! ];
+//! let awards = market.award(&bids, |_| 0).unwrap();
+//! assert_eq!(awards.carriers[0].carrier, "Acme");
+//! // Acme's cost, plus the 300 more the shipper would pay without Acme:
+//! // Bolt's 900, and 800 for NYC-CHI at the reserve rate
+//! assert_eq!(awards.carriers[0].compensation, 1_700);
+//! assert_eq!(awards.total_cost, 1_700);
+//! # Ok::<(), vcg_auction::freight::LaneError>(())
+//! ```
+
+use std::fmt;
+
+use crate::{vcg_auction_with_tiebreaker, Bid};
+
+/// A lane, with the volume to move and the reserve rate of each unit.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Lane {
+    pub id: String,
+    pub volume: u64,
+    /// Cost of each unit of volume that isn't awarded to a carrier.
+    pub reserve_rate: u64,
+}
+
+/// Item of a freight auction.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum FreightItem {
+    /// Volume of a lane.
+    Lane(String),
+    /// Volume a carrier can still be awarded.
+    Capacity(String),
+}
+
+/// A carrier's bid for a package of volume on lanes, created with
+/// [`LaneMarket::package`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PackageBid {
+    pub carrier: String,
+    /// Cost the carrier asks to move the package.
+    pub cost: u64,
+    /// Reserve of the package's volume, less its cost.
+    pub savings: u64,
+    items: Vec<(FreightItem, u64)>,
+}
+
+impl PackageBid {
+    /// Volume of each lane of the package.
+    pub fn lanes(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.items.iter().filter_map(|(item, volume)| match item {
+            FreightItem::Lane(lane) => Some((lane.as_str(), *volume)),
+            FreightItem::Capacity(_) => None,
+        })
+    }
+}
+
+impl Bid for PackageBid {
+    type Name = String;
+    type Value = u64;
+    type Item = FreightItem;
+    type Quantity = u64;
+
+    fn bidder_name(&self) -> &Self::Name {
+        &self.carrier
+    }
+    fn bid_value(&self) -> &Self::Value {
+        &self.savings
+    }
+    fn bid_items(&self) -> &[(Self::Item, Self::Quantity)] {
+        &self.items
+    }
+}
+
+/// Reasons a package can't be bid in a [`LaneMarket`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum LaneError {
+    /// The lane isn't in the market.
+    UnknownLane(String),
+    /// The package has no volume.
+    EmptyPackage,
+    /// The package asks for more volume of a lane than it has.
+    OverVolume {
+        lane: String,
+        requested: u64,
+        volume: u64,
+    },
+    /// The package costs at least the reserve of its volume, so it can't
+    /// save anything.
+    AboveReserve { cost: u64, reserve: u64 },
+}
+
+impl fmt::Display for LaneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LaneError::UnknownLane(lane) => {
+                write!(f, "lane {lane:?} isn't in the market")
+            }
+            LaneError::EmptyPackage => write!(f, "package has no volume"),
+            LaneError::OverVolume {
+                lane,
+                requested,
+                volume,
+            } => write!(
+                f,
+                "{requested} units requested of lane {lane:?}, which has \
+                 {volume}"
+            ),
+            LaneError::AboveReserve { cost, reserve } => write!(
+                f,
+                "package costs {cost}, at least the reserve of {reserve}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LaneError {}
+
+/// What a carrier is awarded.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct CarrierAward<'a> {
+    pub carrier: &'a String,
+    /// Packages won, in the order of their bid sets.
+    pub packages: Vec<&'a PackageBid>,
+    /// Total cost of the packages won.
+    pub cost: u64,
+    /// What the shipper pays the carrier, at least their cost.
+    pub compensation: u64,
+}
+
+/// The result of a freight auction.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct LaneAwards<'a> {
+    /// Awards of each carrier winning a package, in the order of their first
+    /// package.
+    pub carriers: Vec<CarrierAward<'a>>,
+    /// Volume of each lane left at the reserve rate, for lanes with any left.
+    pub unawarded: Vec<(&'a str, u64)>,
+    /// Compensation of the carriers, plus the reserve of the volume left.
+    pub total_cost: u64,
+}
+
+/// The lanes of a freight auction and the capacity of each carrier.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct LaneMarket {
+    lanes: Vec<Lane>,
+    capacities: Vec<(String, u64)>,
+}
+
+impl LaneMarket {
+    /// A market for the lanes. A lane listed more than once keeps its first
+    /// listing.
+    pub fn new(lanes: impl IntoIterator<Item = Lane>) -> Self {
+        let mut unique: Vec<Lane> = vec![];
+        for lane in lanes {
+            if !unique.iter().any(|l| l.id == lane.id) {
+                unique.push(lane);
+            }
+        }
+        Self {
+            lanes: unique,
+            capacities: vec![],
+        }
+    }
+
+    /// Limit the total volume a carrier is awarded across their packages,
+    /// replacing any limit set before. Carriers without a capacity can be
+    /// awarded any of their packages together. Capacities apply to packages
+    /// bid after they're set.
+    pub fn capacity(
+        &mut self,
+        carrier: impl Into<String>,
+        volume: u64,
+    ) -> &mut Self {
+        let carrier = carrier.into();
+        match self.capacities.iter_mut().find(|(c, _)| *c == carrier) {
+            Some((_, capacity)) => *capacity = volume,
+            None => self.capacities.push((carrier, volume)),
+        }
+        self
+    }
+
+    /// The lanes of the market.
+    pub fn lanes(&self) -> &[Lane] {
+        &self.lanes
+    }
+
+    /// A carrier's bid to move volume on lanes at a cost. Volumes of the
+    /// same lane are added together.
+    pub fn package<L: AsRef<str>>(
+        &self,
+        carrier: impl Into<String>,
+        cost: u64,
+        volumes: impl IntoIterator<Item = (L, u64)>,
+    ) -> Result<PackageBid, LaneError> {
+        let carrier = carrier.into();
+        let mut items: Vec<(FreightItem, u64)> = vec![];
+        let mut reserve = 0u64;
+        for (id, volume) in volumes {
+            let id = id.as_ref();
+            let lane = self
+                .lanes
+                .iter()
+                .find(|lane| lane.id == id)
+                .ok_or_else(|| LaneError::UnknownLane(id.into()))?;
+            if volume == 0 {
+                continue;
+            }
+            let item = FreightItem::Lane(lane.id.clone());
+            let total = match items.iter_mut().find(|(i, _)| *i == item) {
+                Some((_, total)) => {
+                    *total = total.saturating_add(volume);
+                    *total
+                }
+                None => {
+                    items.push((item, volume));
+                    volume
+                }
+            };
+            if total > lane.volume {
+                return Err(LaneError::OverVolume {
+                    lane: lane.id.clone(),
+                    requested: total,
+                    volume: lane.volume,
+                });
+            }
+            reserve = reserve
+                .saturating_add(volume.saturating_mul(lane.reserve_rate));
+        }
+        let volume = items
+            .iter()
+            .fold(0u64, |sum, (_, volume)| sum.saturating_add(*volume));
+        if volume == 0 {
+            return Err(LaneError::EmptyPackage);
+        }
+        if cost >= reserve {
+            return Err(LaneError::AboveReserve { cost, reserve });
+        }
+        if self.capacities.iter().any(|(c, _)| *c == carrier) {
+            items.push((FreightItem::Capacity(carrier.clone()), volume));
+        }
+        Ok(PackageBid {
+            carrier,
+            cost,
+            savings: reserve - cost,
+            items,
+        })
+    }
+
+    /// Items of the auction: the volume of each lane, then the capacity of
+    /// each carrier.
+    pub fn items(&self) -> Vec<(FreightItem, u64)> {
+        let lanes = self
+            .lanes
+            .iter()
+            .map(|lane| (FreightItem::Lane(lane.id.clone()), lane.volume));
+        let capacities = self.capacities.iter().map(|(carrier, volume)| {
+            (FreightItem::Capacity(carrier.clone()), *volume)
+        });
+        lanes.chain(capacities).collect()
+    }
+
+    /// Award packages to carriers, breaking ties between the lowest cost
+    /// outcomes with `tiebreaker`. Returns `None` if the tiebreaker returns
+    /// an invalid index.
+    pub fn award<'a>(
+        &'a self,
+        bid_sets: &'a [Vec<PackageBid>],
+        tiebreaker: impl FnOnce(&[Vec<&PackageBid>]) -> usize,
+    ) -> Option<LaneAwards<'a>> {
+        let items = self.items();
+        let result = vcg_auction_with_tiebreaker(&items, bid_sets, tiebreaker)?;
+        let mut carriers: Vec<CarrierAward> = vec![];
+        for package in &result.winning_bids {
+            let award = match carriers
+                .iter_mut()
+                .find(|a| *a.carrier == package.carrier)
+            {
+                Some(award) => award,
+                None => {
+                    carriers.push(CarrierAward {
+                        carrier: &package.carrier,
+                        packages: vec![],
+                        cost: 0,
+                        compensation: 0,
+                    });
+                    carriers.last_mut().expect("award was just added")
+                }
+            };
+            award.packages.push(package);
+            award.cost += package.cost;
+            // the reserve of the package, less the payment below
+            award.compensation += package.cost + package.savings;
+        }
+        for award in &mut carriers {
+            let payment = result
+                .payment_for(award.carrier)
+                .copied()
+                .unwrap_or_default();
+            award.compensation -= payment;
+        }
+        let unawarded = self
+            .lanes
+            .iter()
+            .filter_map(|lane| {
+                let awarded = result
+                    .winning_bids
+                    .iter()
+                    .flat_map(|package| package.lanes())
+                    .filter(|(id, _)| *id == lane.id)
+                    .map(|(_, volume)| volume)
+                    .sum::<u64>();
+                let left = lane.volume - awarded;
+                (left > 0).then_some((lane.id.as_str(), left))
+            })
+            .collect::<Vec<_>>();
+        let total_cost = carriers
+            .iter()
+            .map(|award| award.compensation)
+            .chain(unawarded.iter().map(|(id, left)| {
+                let lane = self.lanes.iter().find(|l| l.id == *id);
+                left * lane.map_or(0, |lane| lane.reserve_rate)
+            }))
+            .sum();
+        Some(LaneAwards {
+            carriers,
+            unawarded,
+            total_cost,
+        })
+    }
+}
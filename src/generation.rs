@@ -0,0 +1,230 @@
+//! Generation of bids on demand, for bidders whose bids are too many to list.
+//!
+//! Each bidder answers demand queries: given a price for each item, which of
+//! their bids is worth the most less the price of its items. The linear
+//! programming relaxation of the auction is solved over the bids generated so
+//! far, and its dual gives the item prices at which no bid generated so far is
+//! worth more than its bidder's share of the relaxation's value. A bid worth
+//! more at those prices would raise the relaxation's value, so it's added,
+//! and the relaxation is solved again until no bidder has such a bid. Only the
+//! bids the relaxation needs are ever listed.
+
+use microlp::{ComparisonOp, OptimizationDirection, Problem};
+use num_traits::ToPrimitive;
+
+use crate::{input::ItemStock, Bid, StockEntry};
+
+/// Most rounds of demand queries in each generation, which ends early once
+/// no bidder has a bid to add.
+const MAX_ROUNDS: usize = 1000;
+
+/// A bidder that generates their bids on demand, for
+/// [`generate_bid_sets`].
+pub trait DemandOracle<B: Bid> {
+    /// The bid worth the most less the price of its items, with each item's
+    /// price given in the order of the stock, or `None` if the bidder
+    /// wouldn't bid at these prices. Items not in stock have no price.
+    fn demand(&self, prices: &[(&B::Item, f64)]) -> Option<B>;
+}
+
+impl<B, F> DemandOracle<B> for F
+where
+    B: Bid,
+    F: Fn(&[(&B::Item, f64)]) -> Option<B>,
+{
+    fn demand(&self, prices: &[(&B::Item, f64)]) -> Option<B> {
+        self(prices)
+    }
+}
+
+/// Generate the bids of each bidder that the auction's linear programming
+/// relaxation needs, returning one bid set per bidder in the order given.
+///
+/// Bids are generated for the auction, then again with each bidder left out,
+/// so the searches for each payment have the bids they need too. The bid sets
+/// can be passed to any of the auction functions, which are exact over the
+/// bids generated. That's the exact result over every bid whenever the
+/// relaxation's best allocation chooses whole bids, such as when every bidder
+/// wants at most one item, and usually close to it otherwise.
+///
+/// Values and quantities are converted to `f64` with [`ToPrimitive`]. Bids
+/// with a value or quantity that can't be converted are ignored.
+///
+/// ```
+/// use vcg_auction::{generate_bid_sets, types::SimpleBid, vcg_auction};
+///
+/// let items = vec![("chair".to_string(), 1), ("table".to_string(), 1)];
+/// // each bidder wants the item they value most less its price
+/// let bidder = |name: &'static str, chair: u64, table: u64| {
+///     move |prices: &[(&String, f64)]| {
+///         let chair_surplus = chair as f64 - prices[0].1;
+///         let table_surplus = table as f64 - prices[1].1;
+///         Some(if chair_surplus >= table_surplus {
+///             SimpleBid::new(name, chair, [("chair", 1)])
+///         } else {
+///             SimpleBid::new(name, table, [("table", 1)])
+///         })
+///     }
+/// };
+/// let bidders = [bidder("Alice", 10, 4), bidder("Bob", 8, 6)];
+/// let bid_sets = generate_bid_sets(&items, &bidders);
+///
+/// let result = vcg_auction(&items, &bid_sets).unwrap();
+/// assert_eq!(result.winning_bids[0].items, [("chair".to_string(), 1)]);
+/// assert_eq!(result.winning_bids[1].items, [("table".to_string(), 1)]);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "lp")))]
+pub fn generate_bid_sets<'i, B, O>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    bidders: &[O],
+) -> Vec<Vec<B>>
+where
+    B: Bid + 'i,
+    B::Value: ToPrimitive,
+    B::Quantity: ToPrimitive,
+    O: DemandOracle<B>,
+{
+    let items = ItemStock::new(items);
+    let Some(stock) = items
+        .quantities()
+        .iter()
+        .map(ToPrimitive::to_f64)
+        .collect::<Option<Vec<_>>>()
+    else {
+        return bidders.iter().map(|_| vec![]).collect();
+    };
+    let mut columns = Columns {
+        items: &items,
+        stock,
+        bid_sets: bidders.iter().map(|_| vec![]).collect(),
+    };
+    columns.generate(bidders, None);
+    for left_out in 0..bidders.len() {
+        columns.generate(bidders, Some(left_out));
+    }
+    columns
+        .bid_sets
+        .into_iter()
+        .map(|bid_set| bid_set.into_iter().map(|(bid, _, _)| bid).collect())
+        .collect()
+}
+
+/// The bids generated so far, with their values and the quantity of each
+/// item in stock they use.
+struct Columns<'s, 'i, B: Bid> {
+    items: &'s ItemStock<'i, B>,
+    stock: Vec<f64>,
+    bid_sets: Vec<Vec<(B, f64, Vec<f64>)>>,
+}
+
+impl<B> Columns<'_, '_, B>
+where
+    B: Bid,
+    B::Value: ToPrimitive,
+    B::Quantity: ToPrimitive,
+{
+    /// Generate bids until no bidder has one worth more than their share of
+    /// the relaxation's value, optionally leaving a bidder out.
+    fn generate(
+        &mut self,
+        bidders: &[impl DemandOracle<B>],
+        left_out: Option<usize>,
+    ) {
+        for _ in 0..MAX_ROUNDS {
+            let Some((prices, shares)) = self.dual(left_out) else {
+                return;
+            };
+            let priced = self
+                .items
+                .items()
+                .iter()
+                .copied()
+                .zip(prices.iter().copied())
+                .collect::<Vec<_>>();
+            let mut added = false;
+            for (bidder, oracle) in bidders.iter().enumerate() {
+                if Some(bidder) == left_out {
+                    continue;
+                }
+                let Some(bid) = oracle.demand(&priced) else {
+                    continue;
+                };
+                let Some((value, quantities)) = self.measure(&bid) else {
+                    continue;
+                };
+                let cost = quantities
+                    .iter()
+                    .zip(&prices)
+                    .map(|(quantity, price)| quantity * price)
+                    .sum::<f64>();
+                // add the bid only if it's clearly worth more, so rounding
+                // can't add the same bid forever
+                let margin = 1e-9 * value.abs().max(1.0);
+                if value - cost - shares[bidder] > margin {
+                    self.bid_sets[bidder].push((bid, value, quantities));
+                    added = true;
+                }
+            }
+            if !added {
+                return;
+            }
+        }
+    }
+
+    /// The value of a bid and the quantity of each item in stock it uses, or
+    /// `None` if one can't be converted or it needs more than is in stock.
+    fn measure(&self, bid: &B) -> Option<(f64, Vec<f64>)> {
+        let selected = self
+            .items
+            .select_bid_items(&self.items.no_items_selected(), bid);
+        let quantities = selected
+            .iter()
+            .map(ToPrimitive::to_f64)
+            .collect::<Option<Vec<_>>>()?;
+        let fits = quantities
+            .iter()
+            .zip(&self.stock)
+            .all(|(quantity, stock)| quantity <= stock);
+        fits.then_some((bid.bid_value().to_f64()?, quantities))
+    }
+
+    /// Solve the dual of the relaxation over the bids generated so far, for
+    /// the price of each item and the share of each bidder, or `None` if the
+    /// solver fails. Prices and shares are as low as possible while every bid
+    /// generated is worth at most its bidder's share plus the price of its
+    /// items.
+    fn dual(&self, left_out: Option<usize>) -> Option<(Vec<f64>, Vec<f64>)> {
+        let mut problem = Problem::new(OptimizationDirection::Minimize);
+        let prices = self
+            .stock
+            .iter()
+            .map(|&stock| problem.add_var(stock, (0.0, f64::INFINITY)))
+            .collect::<Vec<_>>();
+        let shares = self
+            .bid_sets
+            .iter()
+            .map(|_| problem.add_var(1.0, (0.0, f64::INFINITY)))
+            .collect::<Vec<_>>();
+        for (bidder, bid_set) in self.bid_sets.iter().enumerate() {
+            if Some(bidder) == left_out {
+                continue;
+            }
+            for (_, value, quantities) in bid_set {
+                let mut terms = vec![(shares[bidder], 1.0)];
+                terms.extend(
+                    prices
+                        .iter()
+                        .zip(quantities)
+                        .filter(|(_, quantity)| **quantity != 0.0)
+                        .map(|(price, quantity)| (*price, *quantity)),
+                );
+                problem.add_constraint(terms, ComparisonOp::Ge, *value);
+            }
+        }
+        let solution = problem.solve().ok()?;
+        Some((
+            prices.iter().map(|var| *solution.var_value(*var)).collect(),
+            shares.iter().map(|var| *solution.var_value(*var)).collect(),
+        ))
+    }
+}
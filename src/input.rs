@@ -0,0 +1,188 @@
+//! Conversion of the items and bid sets passed to an auction into the shapes
+//! used by the search.
+
+use num_traits::Zero;
+
+use crate::{AddSubSelf, Bid, StockEntry};
+
+/// A bid's items as positions in the stock, with their quantities, so they
+/// can be added to the selected quantities without looking up each item.
+pub(crate) type IndexedItems<'a, B> = Vec<(usize, &'a <B as Bid>::Quantity)>;
+
+/// The items of an auction and their stock quantities. Quantities are kept in
+/// their own contiguous slice, which selected quantities are compared with.
+///
+/// Entries listing an item already listed are merged into its first entry,
+/// adding up their quantities, so every auction and check sees the same
+/// stock.
+pub(crate) struct ItemStock<'i, B: Bid> {
+    items: Vec<&'i B::Item>,
+    quantities: Vec<B::Quantity>,
+    first_duplicate: Option<usize>,
+}
+
+impl<'i, B: Bid> ItemStock<'i, B> {
+    pub(crate) fn new<E>(items: impl IntoIterator<Item = E>) -> Self
+    where
+        E: StockEntry<'i, B::Item, B::Quantity>,
+        B::Quantity: 'i,
+    {
+        let mut stock = Self {
+            items: vec![],
+            quantities: vec![],
+            first_duplicate: None,
+        };
+        for (i, entry) in items.into_iter().enumerate() {
+            let (item, quantity) = entry.into_entry();
+            match stock.items.iter().position(|other| *other == item) {
+                Some(first) => {
+                    let merged = stock.quantities[first].add(quantity);
+                    stock.quantities[first] = merged;
+                    stock.first_duplicate.get_or_insert(i);
+                }
+                None => {
+                    stock.items.push(item);
+                    stock.quantities.push(quantity.clone());
+                }
+            }
+        }
+        stock
+    }
+
+    /// The items in stock.
+    #[cfg(feature = "lp")]
+    pub(crate) fn items(&self) -> &[&'i B::Item] {
+        &self.items
+    }
+
+    /// The stock quantity of each item.
+    pub(crate) fn quantities(&self) -> &[B::Quantity] {
+        &self.quantities
+    }
+
+    /// Each item in stock with its quantity.
+    pub(crate) fn entries(&self) -> Vec<(&'i B::Item, &B::Quantity)> {
+        self.items.iter().copied().zip(&self.quantities).collect()
+    }
+
+    /// The position among the entries given of the first entry listing an
+    /// item already listed.
+    pub(crate) fn first_duplicate(&self) -> Option<usize> {
+        self.first_duplicate
+    }
+
+    /// The quantities selected before any bids are chosen, all zero.
+    pub(crate) fn no_items_selected(&self) -> Vec<B::Quantity> {
+        self.items.iter().map(|_| B::Quantity::zero()).collect()
+    }
+
+    /// Add the items of a bid to the quantities selected so far, which are in
+    /// the same order as the stock.
+    pub(crate) fn select_bid_items(
+        &self,
+        items_selected: &[B::Quantity],
+        bid: &B,
+    ) -> Vec<B::Quantity> {
+        let mut items_selected_with_new_bid = items_selected.to_vec();
+        for (item, qty) in self
+            .items
+            .iter()
+            .zip(items_selected_with_new_bid.iter_mut())
+        {
+            if let Some((_, bid_qty)) =
+                bid.bid_items().iter().find(|(id, _)| id == *item)
+            {
+                *qty = qty.add(bid_qty)
+            }
+        }
+        items_selected_with_new_bid
+    }
+
+    /// The position in the stock of each of a bid's items, in the order of
+    /// the stock. Like [`select_bid_items`](Self::select_bid_items), items
+    /// not in stock are left out, and only the first quantity of an item the
+    /// bid lists twice is kept.
+    pub(crate) fn index_bid_items<'a>(
+        &self,
+        bid: &'a B,
+    ) -> IndexedItems<'a, B> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| {
+                let (_, qty) =
+                    bid.bid_items().iter().find(|(id, _)| id == *item)?;
+                Some((i, qty))
+            })
+            .collect()
+    }
+
+    /// Add a bid's indexed items to the quantities selected so far, the same
+    /// as [`select_bid_items`](Self::select_bid_items) with the bid.
+    pub(crate) fn select_indexed_items(
+        &self,
+        items_selected: &[B::Quantity],
+        bid_items: &IndexedItems<B>,
+    ) -> Vec<B::Quantity> {
+        let mut items_selected_with_new_bid = items_selected.to_vec();
+        add_indexed_items::<B>(&mut items_selected_with_new_bid, bid_items);
+        items_selected_with_new_bid
+    }
+}
+
+/// Add a bid's indexed items to the quantities selected so far in place.
+pub(crate) fn add_indexed_items<B: Bid>(
+    items_selected: &mut [B::Quantity],
+    bid_items: &IndexedItems<B>,
+) {
+    for (i, bid_qty) in bid_items {
+        let qty = &mut items_selected[*i];
+        *qty = qty.add(bid_qty);
+    }
+}
+
+/// Collect references to the bids of each bid set.
+pub(crate) fn bid_set_refs<'a, B: 'a, S>(
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+) -> Vec<Vec<&'a B>>
+where
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    exclusive_bid_sets
+        .into_iter()
+        .map(|bs| bs.as_ref().iter().collect::<Vec<_>>())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SimpleBid;
+
+    #[test]
+    fn indexed_items_select_like_bid_items() {
+        let items = vec![
+            ("chair".to_string(), 4),
+            ("table".to_string(), 2),
+            ("lamp".to_string(), 1),
+        ];
+        let stock = ItemStock::<SimpleBid>::new(&items);
+        let bids = [
+            SimpleBid::new("Alice", 5, [("lamp", 1), ("chair", 2)]),
+            // an item not in stock, and an item listed twice
+            SimpleBid::new("Bob", 3, [("sofa", 1), ("table", 1)]),
+            SimpleBid::new("Carol", 2, [("chair", 1), ("chair", 3)]),
+        ];
+        let selected = vec![1, 0, 1];
+        for bid in &bids {
+            assert_eq!(
+                stock.select_indexed_items(
+                    &selected,
+                    &stock.index_bid_items(bid)
+                ),
+                stock.select_bid_items(&selected, bid)
+            );
+        }
+        assert_eq!(stock.index_bid_items(&bids[0]), [(0, &2), (2, &1)]);
+    }
+}
@@ -0,0 +1,296 @@
+//! Random auctions of [`SimpleBid`]s, for property testing code that runs
+//! auctions.
+//!
+//! [`InstanceShape`] generates them as a `rand` distribution. The `proptest`
+//! and `quickcheck` features implement those libraries' `Arbitrary` for
+//! [`AuctionInstance`] and [`SimpleBid`] on top of it, shrinking failing
+//! auctions by dropping bid sets and bids.
+
+use rand::{distributions::Distribution, Rng};
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::types::SimpleBid;
+
+/// The items and bid sets of an auction, generated by an [`InstanceShape`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct AuctionInstance {
+    pub items: Vec<(String, u64)>,
+    pub bid_sets: Vec<Vec<SimpleBid>>,
+}
+
+/// Bounds on the size of generated auctions, which are sampled as a
+/// [`Distribution`].
+///
+/// Generated auctions are valid: items are named `item0`, `item1` and so on,
+/// every bid is for distinct items of the stock, and each bid set belongs to
+/// one bidder, who may have several. Bid sets can be empty and values zero,
+/// but quantities are at least one, and bids are only for no items if the
+/// stock is empty.
+///
+/// With the `proptest` feature, [`InstanceShape::instances`] and
+/// [`InstanceShape::stocks`] are proptest strategies, and `any::<T>()` or
+/// `any_with::<T>(shape)` generate an [`AuctionInstance`] or a [`SimpleBid`].
+/// With the `quickcheck` feature, both implement quickcheck's `Arbitrary`,
+/// with the default shape. Any other property testing library that can
+/// produce a seed can sample auctions from a seeded [`StdRng`].
+///
+/// [`StdRng`]: rand::rngs::StdRng
+///
+/// ```
+/// use rand::{distributions::Distribution, rngs::StdRng, SeedableRng};
+/// use vcg_auction::{
+///     vcg_auction_with_tiebreaker, verify_result, AuctionInstance,
+///     InstanceShape,
+/// };
+///
+/// let shape = InstanceShape::default();
+/// let mut rng = StdRng::seed_from_u64(0);
+/// for _ in 0..100 {
+///     let AuctionInstance { items, bid_sets } = shape.sample(&mut rng);
+///     let result =
+///         vcg_auction_with_tiebreaker(&items, &bid_sets, |_| 0).unwrap();
+///     verify_result(&items, &bid_sets, &result).unwrap();
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "rng")))]
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct InstanceShape {
+    /// Most items in stock.
+    pub max_items: usize,
+    /// Most stock of each item, which is at least one.
+    pub max_stock: u64,
+    /// Most bidders. Bid sets are shared among them.
+    pub max_bidders: usize,
+    /// Most bid sets.
+    pub max_bid_sets: usize,
+    /// Most bids in each bid set.
+    pub max_bids_per_set: usize,
+    /// Most items in each bid, which has at least one if there are any.
+    pub max_items_per_bid: usize,
+    /// Most quantity of an item in a bid, which is at least one.
+    pub max_quantity: u64,
+    /// Most value of a bid.
+    pub max_value: u64,
+}
+
+impl Default for InstanceShape {
+    /// Auctions small enough to solve in well under a millisecond.
+    fn default() -> Self {
+        Self {
+            max_items: 4,
+            max_stock: 3,
+            max_bidders: 4,
+            max_bid_sets: 6,
+            max_bids_per_set: 3,
+            max_items_per_bid: 3,
+            max_quantity: 2,
+            max_value: 100,
+        }
+    }
+}
+
+impl InstanceShape {
+    /// A random stock of items.
+    pub fn stock<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<(String, u64)> {
+        (0..rng.gen_range(0..=self.max_items))
+            .map(|i| {
+                (format!("item{i}"), rng.gen_range(1..=self.max_stock.max(1)))
+            })
+            .collect()
+    }
+
+    /// A random bid of a bidder for items of the stock.
+    pub fn bid<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        bidder: impl Into<String>,
+        stock: &[(String, u64)],
+    ) -> SimpleBid {
+        let most = self.max_items_per_bid.min(stock.len());
+        let count = rng.gen_range(most.min(1)..=most);
+        // the first `count` of a partial shuffle
+        let mut picks = (0..stock.len()).collect::<Vec<_>>();
+        for i in 0..count {
+            picks.swap(i, rng.gen_range(i..stock.len()));
+        }
+        let items = picks[..count]
+            .iter()
+            .map(|&i| {
+                let quantity = rng.gen_range(1..=self.max_quantity.max(1));
+                (stock[i].0.clone(), quantity)
+            })
+            .collect::<Vec<_>>();
+        SimpleBid::new(bidder, rng.gen_range(0..=self.max_value), items)
+    }
+}
+
+impl Distribution<AuctionInstance> for InstanceShape {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> AuctionInstance {
+        let items = self.stock(rng);
+        let bidders = rng.gen_range(1..=self.max_bidders.max(1));
+        let bid_sets = (0..rng.gen_range(0..=self.max_bid_sets))
+            .map(|_| {
+                let bidder = format!("bidder{}", rng.gen_range(0..bidders));
+                (0..rng.gen_range(0..=self.max_bids_per_set))
+                    .map(|_| self.bid(rng, bidder.clone(), &items))
+                    .collect()
+            })
+            .collect();
+        AuctionInstance { items, bid_sets }
+    }
+}
+
+/// The auction of `instance` with only the bid sets kept, in order.
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+fn with_bid_sets(
+    instance: &AuctionInstance,
+    keep: &[usize],
+) -> AuctionInstance {
+    AuctionInstance {
+        items: instance.items.clone(),
+        bid_sets: keep.iter().map(|&i| instance.bid_sets[i].clone()).collect(),
+    }
+}
+
+#[cfg(feature = "proptest")]
+impl InstanceShape {
+    /// Strategy generating auctions of this shape, which shrink to fewer bid
+    /// sets and then to fewer bids in each.
+    pub fn instances(
+        &self,
+    ) -> impl proptest::strategy::Strategy<Value = AuctionInstance> {
+        use proptest::{prelude::*, sample::subsequence};
+
+        let shape = self.clone();
+        any::<u64>()
+            .no_shrink()
+            .prop_map(move |seed| {
+                shape.sample(&mut StdRng::seed_from_u64(seed))
+            })
+            .prop_flat_map(|instance| {
+                let all = (0..instance.bid_sets.len()).collect::<Vec<_>>();
+                let bid_sets = subsequence(all.clone(), 0..=all.len());
+                (Just(instance), bid_sets)
+            })
+            .prop_map(|(instance, keep)| with_bid_sets(&instance, &keep))
+            .prop_flat_map(|instance| {
+                let bids = instance
+                    .bid_sets
+                    .iter()
+                    .map(|bid_set| {
+                        let all = (0..bid_set.len()).collect::<Vec<_>>();
+                        subsequence(all.clone(), 0..=all.len())
+                    })
+                    .collect::<Vec<_>>();
+                (Just(instance), bids)
+            })
+            .prop_map(|(mut instance, keep)| {
+                for (bid_set, keep) in instance.bid_sets.iter_mut().zip(keep) {
+                    *bid_set =
+                        keep.iter().map(|&i| bid_set[i].clone()).collect();
+                }
+                instance
+            })
+    }
+
+    /// Strategy generating stocks of items of this shape.
+    pub fn stocks(
+        &self,
+    ) -> impl proptest::strategy::Strategy<Value = Vec<(String, u64)>> {
+        use proptest::prelude::*;
+
+        let shape = self.clone();
+        any::<u64>()
+            .no_shrink()
+            .prop_map(move |seed| shape.stock(&mut StdRng::seed_from_u64(seed)))
+    }
+}
+
+#[cfg(feature = "proptest")]
+impl proptest::arbitrary::Arbitrary for AuctionInstance {
+    type Parameters = InstanceShape;
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(shape: InstanceShape) -> Self::Strategy {
+        use proptest::strategy::Strategy;
+
+        shape.instances().boxed()
+    }
+}
+
+#[cfg(feature = "proptest")]
+impl proptest::arbitrary::Arbitrary for SimpleBid {
+    /// Shape of the stock the bid is for, and of the bid.
+    type Parameters = InstanceShape;
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(shape: InstanceShape) -> Self::Strategy {
+        use proptest::prelude::*;
+
+        any::<u64>()
+            .no_shrink()
+            .prop_map(move |seed| {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let stock = shape.stock(&mut rng);
+                shape.bid(&mut rng, "bidder0", &stock)
+            })
+            .boxed()
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl quickcheck::Arbitrary for AuctionInstance {
+    /// An auction of the default [`InstanceShape`].
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        let seed = u64::arbitrary(g);
+        InstanceShape::default().sample(&mut StdRng::seed_from_u64(seed))
+    }
+
+    /// The auction without one of its bid sets, then without one of the bids
+    /// of a bid set.
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let instance = self.clone();
+        let bid_sets = instance.bid_sets.len();
+        let without_bid_sets = (0..bid_sets).map({
+            let instance = instance.clone();
+            move |dropped| {
+                let keep =
+                    (0..bid_sets).filter(|&i| i != dropped).collect::<Vec<_>>();
+                with_bid_sets(&instance, &keep)
+            }
+        });
+        let bids = instance
+            .bid_sets
+            .iter()
+            .enumerate()
+            .flat_map(|(i, bid_set)| (0..bid_set.len()).map(move |j| (i, j)))
+            .collect::<Vec<_>>();
+        let without_bids = bids.into_iter().map(move |(i, j)| {
+            let mut smaller = instance.clone();
+            smaller.bid_sets[i].remove(j);
+            smaller
+        });
+        Box::new(without_bid_sets.chain(without_bids))
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+impl quickcheck::Arbitrary for SimpleBid {
+    /// A bid for a stock of the default [`InstanceShape`].
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        let mut rng = StdRng::seed_from_u64(u64::arbitrary(g));
+        let shape = InstanceShape::default();
+        let stock = shape.stock(&mut rng);
+        shape.bid(&mut rng, "bidder0", &stock)
+    }
+
+    /// The bid with a lower value.
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let bid = self.clone();
+        Box::new(self.value.shrink().map(move |value| SimpleBid {
+            value,
+            ..bid.clone()
+        }))
+    }
+}
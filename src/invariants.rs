@@ -0,0 +1,116 @@
+//! Checks of a solved auction against the invariants of a correct search,
+//! enabled with [`SearchOptions::check_invariants`](crate::SearchOptions).
+
+use std::fmt::Write;
+
+use num_traits::Zero;
+
+use crate::{input::ItemStock, vcg::total_value, AddSubSelf, Bid};
+
+/// Panic with a report of every invariant the result of an auction breaks.
+/// `highest_value` is the value the search found for the winning bids, and
+/// payments are in the order of the winners.
+pub(crate) fn check_invariants<B: Bid>(
+    items: &ItemStock<B>,
+    winning_bid_set: &[&B],
+    highest_value: &B::Value,
+    payments: &[(&B::Name, B::Value)],
+) {
+    let mut report = String::new();
+    let selected = winning_bid_set
+        .iter()
+        .fold(items.no_items_selected(), |selected, bid| {
+            items.select_bid_items(&selected, *bid)
+        });
+    for (i, (selected, stock)) in
+        selected.iter().zip(items.quantities()).enumerate()
+    {
+        if selected > stock {
+            let _ = writeln!(
+                report,
+                "- the winning bids use more of stock item {i} than is in \
+                 stock"
+            );
+        }
+    }
+    let welfare = total_value(winning_bid_set.iter().copied());
+    if welfare != *highest_value {
+        let _ = writeln!(
+            report,
+            "- the winning bids aren't worth the highest value found"
+        );
+    }
+    for (i, (bidder_name, payment)) in payments.iter().enumerate() {
+        if *payment < B::Value::zero() {
+            let _ = writeln!(report, "- payment {i} is below zero");
+        }
+        let other_bids = total_value(
+            winning_bid_set
+                .iter()
+                .copied()
+                .filter(|bid| bid.bidder_name() != *bidder_name),
+        );
+        // the payment is the value without the winner less the other bids
+        if payment.add(&other_bids) < other_bids {
+            let _ = writeln!(
+                report,
+                "- the value without the winner of payment {i} is less than \
+                 the other winning bids"
+            );
+        }
+    }
+    if !report.is_empty() {
+        panic!(
+            "auction result breaks invariants, with {} winning bids and {} \
+             payments:\n{report}",
+            winning_bid_set.len(),
+            payments.len()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SimpleBid;
+
+    fn chair_bids() -> (Vec<(String, u64)>, Vec<SimpleBid>) {
+        let items = vec![("chair".to_string(), 1)];
+        let bids = vec![
+            SimpleBid::new("Alice", 10, [("chair", 1)]),
+            SimpleBid::new("Bob", 7, [("chair", 1)]),
+        ];
+        (items, bids)
+    }
+
+    #[test]
+    fn correct_result_passes() {
+        let (items, bids) = chair_bids();
+        let alice = "Alice".to_string();
+        check_invariants(
+            &ItemStock::new(&items),
+            &[&bids[0]],
+            &10,
+            &[(&alice, 7)],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "more of stock item 0 than is in stock")]
+    fn over_stock_panics() {
+        let (items, bids) = chair_bids();
+        check_invariants(
+            &ItemStock::new(&items),
+            &[&bids[0], &bids[1]],
+            &17,
+            &[],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "aren't worth the highest value found")]
+    fn wrong_welfare_panics() {
+        let (items, bids) = chair_bids();
+        check_invariants(&ItemStock::new(&items), &[&bids[0]], &12, &[]);
+    }
+}
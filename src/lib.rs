@@ -9,7 +9,41 @@
 //! Compatible bid types implement the [`Bid`] trait.
 //!
 //! The default feature `rand` can be disabled if only the non-tiebreaking
-//! implementation is desired.
+//! implementation is desired. Its random tiebreaks use `thread_rng`, which
+//! needs an operating system source of randomness. The `rng` feature alone
+//! adds [`vcg_auction_with_rng`], [`Tiebreak::Seeded`] and [`AuctionRng`],
+//! which take randomness from the caller instead, for targets like
+//! `wasm32-unknown-unknown`, [`InstanceShape`], which generates random
+//! auctions for property tests, with `Arbitrary` impls for proptest and
+//! quickcheck under the `proptest` and `quickcheck` features, and
+//! [`Aggregates`], which adds noise to published statistics of a result for
+//! differential privacy. The `tokio`
+//! feature adds async versions of the auction functions that run on tokio's
+//! blocking thread pool. The `rayon` feature adds `vcg_auction_parallel`,
+//! which splits the search across threads while producing the same result as
+//! the serial search. The
+//! `tracing` feature instruments auctions with
+//! [`tracing`](https://crates.io/crates/tracing) spans for each auction,
+//! search and payment, and events with the node, improvement and prune counts
+//! of each search. The `serde` feature implements `Serialize` for the
+//! reports of [`run_with_report`] and the types they contain, and adds
+//! `ArchivedResult`, which serializes and deserializes results tagged with
+//! a schema version. The `schemars` feature, which enables `serde`, derives
+//! [`schemars`](https://crates.io/crates/schemars)' `JsonSchema` for the bid,
+//! config, error and archived result types, for documenting and validating
+//! APIs that exchange them. The `lp`
+//! feature adds `vcg_auction_lp`, which prunes hard searches with a linear
+//! programming relaxation solved by
+//! [`microlp`](https://crates.io/crates/microlp), and `generate_bid_sets`,
+//! which lists only the bids the relaxation needs for bidders with too many
+//! to list, and `core_auction`, which raises VCG payments to the core when
+//! they're too low. The `openrtb` feature adds the `openrtb` module, which prices
+//! OpenRTB-style bid responses with a VCG auction. The `test-utils` feature
+//! adds `BruteForce`, a solver trying every combination of bids, for testing
+//! other solvers against. The `commitment` feature adds `commitment_hash`,
+//! a SHA-256 commitment to the inputs of an auction, and `SealedBids` for
+//! commit-reveal sealed bidding, hashing with
+//! [`sha2`](https://crates.io/crates/sha2).
 //!
 //! # Bid Combinations
 //!
@@ -132,18 +166,199 @@
 //! ```
 //!
 //! See the tests directory for examples using floating point numbers for bid
-//! values and item quantities, and the
-//! [`secrecy`](https://crates.io/crates/secrecy) crate to help keep bid values
-//! confidential. For floating point bid values, which must implement [`Ord`],
-//! you may want to use
+//! values and item quantities. For floating point bid values, which must
+//! implement [`Ord`], you may want to use
 //! [`ordered-float`](https://crates.io/crates/ordered-float) or a similar
 //! crate.
+//!
+//! The `secrecy` feature adds `SecretValue`, a bid value using the
+//! [`secrecy`](https://crates.io/crates/secrecy) crate to help keep bid
+//! values confidential.
+//!
+//! # Threading
+//!
+//! The auction functions keep no global state, so independent auctions can
+//! run on separate threads at the same time, and several threads can run
+//! auctions over the same shared bids. [`vcg_auction`] draws its tiebreaker
+//! from `thread_rng`, which is local to the calling thread.
+//!
+//! Result types hold references to the bids and bidder names, and are
+//! [`Send`] and [`Sync`] whenever the bid type allows:
+//!
+//! - [`AuctionResult`] is `Send` when `B`, `B::Name` are `Sync` and
+//!   `B::Value` is `Send`, and `Sync` when all three are `Sync`.
+//! - [`OwnedAuctionResult`] is `Send` or `Sync` when `B`, `B::Name` and
+//!   `B::Value` are.
+//!
+//! The futures returned by the async functions of the `tokio` feature are
+//! `Send`, so they can be passed to `tokio::spawn`.
+//!
+//! To run many auctions with a bound on how many run at once, submit them to
+//! an [`AuctionQueue`]. To take bids over time and keep them in a database,
+//! run the auction as an [`AuctionState`] over a [`BidStore`].
+//!
+//! # Determinism
+//!
+//! With [`AuctionConfig::deterministic`], the result of [`run`] is a function
+//! of the items and bid sets alone, so replicated nodes computing the same
+//! auction agree exactly:
+//!
+//! - No randomness is used. Tied outcomes are resolved by taking the first
+//!   one found, and the search visits bid sets in the order given.
+//! - The order of the items doesn't matter, so they can be taken from a
+//!   [`HashMap`](std::collections::HashMap).
+//! - Winning bids are listed in the order of their bid sets, and payments in
+//!   the order of each bidder's first winning bid.
+//! - There is no time limit, which would make the result depend on the speed
+//!   of the machine.
+//!
+//! The order of the bid sets is part of the input, so they shouldn't be
+//! collected from a container with an unspecified iteration order. The
+//! parallel search gives the same result as the serial search, whatever the
+//! number of threads.
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+mod affine;
+mod allocation;
+#[cfg(feature = "serde")]
+mod archive;
+mod assignment;
+#[cfg(feature = "tokio")]
+mod async_auction;
+mod backend;
+#[cfg(feature = "test-utils")]
+mod brute_force;
+pub mod catalog;
+mod clinching;
+#[cfg(feature = "commitment")]
+mod commitment;
+mod complexity;
+mod config;
+mod conflict;
+mod control;
+#[cfg(feature = "lp")]
+mod core_pricing;
+pub mod courses;
+mod decomposition;
+mod diagnosis;
+mod dropped;
+mod dry_run;
+pub mod energy;
+mod error;
+mod field;
+pub mod freight;
+#[cfg(feature = "lp")]
+mod generation;
+mod input;
+#[cfg(feature = "rng")]
+mod instances;
+mod invariants;
+mod local_search;
+#[cfg(feature = "lp")]
+mod lp;
+mod macros;
+mod objectives;
+#[cfg(feature = "openrtb")]
+pub mod openrtb;
+mod options;
+mod ordering;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod partial;
+mod pipeline;
+pub mod points;
+pub mod presets;
+mod pricing;
+#[cfg(feature = "rng")]
+mod privacy;
+#[cfg(kani)]
+mod proofs;
+mod propagation;
+mod pruning;
+mod pseudonyms;
+mod quantities;
+mod queue;
+mod redistribution;
+mod report;
+#[cfg(feature = "rng")]
+mod rng;
+#[cfg(feature = "commitment")]
+mod sealed;
+mod search_tree;
+#[cfg(feature = "secrecy")]
+mod secret;
+mod simulation;
+mod solver;
+mod sorted;
+pub mod spectrum;
+mod state;
+mod stats;
+mod submission;
 mod traits;
 pub mod types;
+mod unique;
+mod unit_dp;
 mod vcg;
+mod verify;
 
+pub use affine::*;
+pub use allocation::*;
+#[cfg(feature = "serde")]
+pub use archive::*;
+pub use assignment::*;
+#[cfg(feature = "tokio")]
+pub use async_auction::*;
+pub use backend::*;
+#[cfg(feature = "test-utils")]
+pub use brute_force::*;
+pub use clinching::*;
+#[cfg(feature = "commitment")]
+pub use commitment::*;
+pub use complexity::*;
+pub use config::*;
+#[cfg(feature = "lp")]
+pub use core_pricing::*;
+pub use diagnosis::*;
+pub use dropped::*;
+pub use dry_run::*;
+pub use error::*;
+pub use field::*;
+#[cfg(feature = "lp")]
+pub use generation::*;
+#[cfg(feature = "rng")]
+pub use instances::*;
+#[cfg(feature = "lp")]
+pub use lp::*;
+pub use objectives::*;
+pub use options::*;
+#[cfg(feature = "rayon")]
+pub use parallel::*;
+pub use partial::*;
+pub use pipeline::*;
+pub use pricing::*;
+#[cfg(feature = "rng")]
+pub use privacy::*;
+pub use pruning::*;
+pub use pseudonyms::*;
+pub use queue::*;
+pub use redistribution::*;
+pub use report::*;
+#[cfg(feature = "rng")]
+pub use rng::*;
+#[cfg(feature = "commitment")]
+pub use sealed::*;
+pub use search_tree::*;
+#[cfg(feature = "secrecy")]
+pub use secret::*;
+pub use simulation::*;
+pub use solver::*;
+pub use sorted::*;
+pub use state::*;
+pub use stats::*;
+pub use submission::*;
 pub use traits::*;
+pub use unique::*;
+pub use unit_dp::*;
 pub use vcg::*;
+pub use verify::*;
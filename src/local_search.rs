@@ -0,0 +1,259 @@
+//! Local search improving the best combination of bids found by a winner
+//! determination search that timed out.
+//!
+//! Each pass makes the move that raises the combination's value the most:
+//! changing the bid chosen from one bid set, which adds, replaces or drops a
+//! bid, or swapping a chosen bid for a bid from a bid set with none chosen.
+//! Passes repeat until no move raises the value, which ends with a
+//! combination no single move can improve.
+
+use std::ptr;
+
+use num_traits::Zero;
+
+use crate::{
+    control::{Interrupt, SearchControl},
+    input::ItemStock,
+    quantities::within_stock,
+    vcg::{copy_value, Highest, SearchResult},
+    AddSubSelf, Bid, OnTimeout, SearchPhase,
+};
+
+/// Most passes of the local search, which ends early once no move raises the
+/// value.
+const MAX_PASSES: usize = 1000;
+
+/// Improve the highest value bid sets of a winner determination search that
+/// timed out with [`OnTimeout::BestSoFar`], to return with a smaller gap.
+/// Other results are returned as they are.
+pub(crate) fn improve_timed_out<'a, B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
+    control: &SearchControl<B::Value>,
+    phase: SearchPhase,
+    result: SearchResult<'a, B>,
+) -> SearchResult<'a, B> {
+    match result {
+        Err((Interrupt::TimedOut, highest))
+            if phase == SearchPhase::WinnerDetermination
+                && control.on_timeout() == OnTimeout::BestSoFar =>
+        {
+            Err((
+                Interrupt::TimedOut,
+                improve(items, exclusive_bid_sets, highest),
+            ))
+        }
+        result => result,
+    }
+}
+
+/// The value of a combination of bids raised by local search, or `None` if
+/// the bids aren't a combination of the bid sets that fits the stock.
+pub(crate) fn improved_value<B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&B>], // sets of mutually-exclusive bids
+    bids: &[&B],
+) -> Option<B::Value> {
+    let mut combination = Combination::locate(items, exclusive_bid_sets, bids)?;
+    combination.climb(items, exclusive_bid_sets);
+    Some(combination.value)
+}
+
+/// Improve each of the tied bid sets, keeping those improved the most. If
+/// none can be improved, they're returned unchanged.
+fn improve<'a, B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>],
+    (highest_bid_sets, highest_value): Highest<'a, B>,
+) -> Highest<'a, B> {
+    // a search timing out before finding any combination starts from none
+    let starts = if highest_bid_sets.is_empty() {
+        vec![vec![]]
+    } else {
+        highest_bid_sets.clone()
+    };
+    let mut improved: Vec<Vec<Option<usize>>> = vec![];
+    let mut improved_value = copy_value::<B>(&highest_value);
+    for start in &starts {
+        let Some(mut combination) =
+            Combination::locate(items, exclusive_bid_sets, start)
+        else {
+            continue;
+        };
+        combination.climb(items, exclusive_bid_sets);
+        if combination.value > improved_value {
+            improved = vec![combination.choices];
+            improved_value = combination.value;
+        } else if combination.value == improved_value
+            && !improved.is_empty()
+            && !improved.contains(&combination.choices)
+        {
+            improved.push(combination.choices);
+        }
+    }
+    if improved.is_empty() {
+        return (highest_bid_sets, highest_value);
+    }
+    let bid_sets = improved
+        .iter()
+        .map(|choices| {
+            choices
+                .iter()
+                .zip(exclusive_bid_sets)
+                .filter_map(|(choice, bid_set)| choice.map(|j| bid_set[j]))
+                .collect()
+        })
+        .collect();
+    (bid_sets, improved_value)
+}
+
+/// A combination of bids, with at most one chosen from each bid set.
+struct Combination<B: Bid> {
+    choices: Vec<Option<usize>>, // index of the bid chosen from each bid set
+    items_selected: Vec<B::Quantity>, // items in the chosen bids
+    value: B::Value,             // value of the chosen bids
+}
+
+/// Changes to the bids chosen from some bid sets.
+type Move = Vec<(usize, Option<usize>)>;
+
+impl<B: Bid> Combination<B> {
+    /// The combination of the given bids, or `None` if one isn't in the bid
+    /// sets, two are in the same bid set, or they don't fit the stock.
+    fn locate(
+        items: &ItemStock<B>,
+        exclusive_bid_sets: &[Vec<&B>],
+        bids: &[&B],
+    ) -> Option<Self> {
+        let mut choices = vec![None; exclusive_bid_sets.len()];
+        for bid in bids {
+            let (i, j) = exclusive_bid_sets.iter().enumerate().find_map(
+                |(i, bid_set)| {
+                    let j = bid_set.iter().position(|b| ptr::eq(*b, *bid))?;
+                    Some((i, j))
+                },
+            )?;
+            if choices[i].replace(j).is_some() {
+                return None;
+            }
+        }
+        let mut combination = Combination {
+            choices: vec![None; exclusive_bid_sets.len()],
+            items_selected: items.no_items_selected(),
+            value: B::Value::zero(),
+        };
+        let moves = choices.into_iter().enumerate().collect::<Move>();
+        combination.apply(items, exclusive_bid_sets, &moves);
+        // dropping a bid with a negative quantity can leave the rest over
+        // stock
+        within_stock(&combination.items_selected, items.quantities())
+            .then_some(combination)
+    }
+
+    /// Make the best move until none raises the value.
+    fn climb(&mut self, items: &ItemStock<B>, exclusive_bid_sets: &[Vec<&B>]) {
+        for _ in 0..MAX_PASSES {
+            let Some(best) = self.best_move(items, exclusive_bid_sets) else {
+                return;
+            };
+            self.apply(items, exclusive_bid_sets, &best);
+        }
+    }
+
+    /// The move raising the value the most, or `None` if none does. Of moves
+    /// raising it equally, the first found is chosen.
+    fn best_move(
+        &self,
+        items: &ItemStock<B>,
+        exclusive_bid_sets: &[Vec<&B>],
+    ) -> Option<Move> {
+        let stock = items.quantities();
+        let mut best = None;
+        let mut best_value = copy_value::<B>(&self.value);
+        for (i, bid_set) in exclusive_bid_sets.iter().enumerate() {
+            // the combination without the bid from this bid set
+            let (without_items, without_value) = match self.choices[i] {
+                Some(j) => self.without(items, bid_set[j]),
+                None => {
+                    (self.items_selected.clone(), copy_value::<B>(&self.value))
+                }
+            };
+            let mut consider = |changes: Move, value: B::Value, bid: &B| {
+                if value > best_value
+                    && within_stock(
+                        &items.select_bid_items(&without_items, bid),
+                        stock,
+                    )
+                {
+                    best = Some(changes);
+                    best_value = value;
+                }
+            };
+            // change the bid chosen from this bid set
+            for (j, bid) in bid_set.iter().enumerate() {
+                if self.choices[i] != Some(j) {
+                    let value = without_value.add(bid.bid_value());
+                    consider(vec![(i, Some(j))], value, bid);
+                }
+            }
+            if self.choices[i].is_none() {
+                continue;
+            }
+            // swap it for a bid from a bid set with none chosen
+            for (k, other_set) in exclusive_bid_sets.iter().enumerate() {
+                if self.choices[k].is_some() {
+                    continue;
+                }
+                for (j, bid) in other_set.iter().enumerate() {
+                    let value = without_value.add(bid.bid_value());
+                    consider(vec![(i, None), (k, Some(j))], value, bid);
+                }
+            }
+            // drop it
+            if without_value > best_value && within_stock(&without_items, stock)
+            {
+                best = Some(vec![(i, None)]);
+                best_value = without_value;
+            }
+        }
+        best
+    }
+
+    /// The items and value of the combination without one of its bids.
+    fn without(
+        &self,
+        items: &ItemStock<B>,
+        bid: &B,
+    ) -> (Vec<B::Quantity>, B::Value) {
+        let bid_items = items.select_bid_items(&items.no_items_selected(), bid);
+        let items_selected = self
+            .items_selected
+            .iter()
+            .zip(&bid_items)
+            .map(|(selected, bid_quantity)| selected.sub(bid_quantity))
+            .collect();
+        (items_selected, self.value.sub(bid.bid_value()))
+    }
+
+    /// Change the bids chosen from some bid sets.
+    fn apply(
+        &mut self,
+        items: &ItemStock<B>,
+        exclusive_bid_sets: &[Vec<&B>],
+        changes: &[(usize, Option<usize>)],
+    ) {
+        for &(i, choice) in changes {
+            if let Some(j) = self.choices[i] {
+                (self.items_selected, self.value) =
+                    self.without(items, exclusive_bid_sets[i][j]);
+            }
+            if let Some(j) = choice {
+                let bid = exclusive_bid_sets[i][j];
+                self.items_selected =
+                    items.select_bid_items(&self.items_selected, bid);
+                self.value = self.value.add(bid.bid_value());
+            }
+            self.choices[i] = choice;
+        }
+    }
+}
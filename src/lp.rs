@@ -0,0 +1,249 @@
+//! Pruning with the linear programming relaxation of winner determination.
+//!
+//! Choosing bids is relaxed to choosing a fraction between zero and one of
+//! each bid, with the fractions of a bid set summing to at most one and the
+//! fractions of each item's quantities summing to at most the stock left. The
+//! highest value of the relaxation is at least the value of any combination
+//! of the remaining bids, and is often far below the sum of their highest
+//! bids when bids compete for the same items. Nodes the sum of the highest
+//! bids doesn't prune are pruned when the relaxation can't reach the best
+//! value found.
+
+use microlp::{ComparisonOp, OptimizationDirection, Problem};
+use num_traits::ToPrimitive;
+
+use crate::{
+    control::SearchControl,
+    input::{bid_set_refs, ItemStock},
+    local_search::improve_timed_out,
+    propagation::propagate,
+    vcg::{
+        payment_for_bidder, run_auction_with, start_search, winning_bidders,
+        Relaxation, SearchResult,
+    },
+    AuctionError, AuctionOutcome, Bid, SearchOptions, SearchPhase, StockEntry,
+};
+
+/// Version of [`vcg_auction_with_options`](crate::vcg_auction_with_options)
+/// that also prunes the search with a linear programming relaxation.
+///
+/// Values and quantities are converted to `f64` for the relaxation, with
+/// [`ToPrimitive`]. The relaxation is solved at each node the sum of the
+/// highest remaining bids doesn't prune, which is slower per node but
+/// explores far fewer nodes when many bids compete for the same items. A
+/// node is pruned only if its bound is clearly below the best value found, so
+/// rounding never changes the result.
+/// If a value or quantity can't be converted, the search runs without the
+/// relaxation. Either way the result is identical to
+/// [`vcg_auction_with_options`](crate::vcg_auction_with_options), including
+/// the tied outcomes given to the tiebreaker.
+///
+/// The auction isn't split into sub-auctions that share no items, so each
+/// search, and its relaxation, covers every bid set.
+///
+/// ```
+/// use vcg_auction::{types::SimpleBid, vcg_auction_lp, SearchOptions};
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+/// ];
+/// let outcome =
+///     vcg_auction_lp(&items, &bids, |_| 0, &SearchOptions::default())?;
+/// let result = outcome.complete().unwrap();
+/// assert_eq!(result.winning_bids, [&bids[0][0]]);
+/// assert_eq!(result.payments, [(&"Alice".to_string(), 7)]);
+/// # Ok::<(), vcg_auction::AuctionError>(())
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "lp")))]
+pub fn vcg_auction_lp<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
+    options: &SearchOptions<B::Value>,
+) -> Result<AuctionOutcome<'a, B>, AuctionError>
+where
+    B: Bid + 'i,
+    B::Value: ToPrimitive,
+    B::Quantity: ToPrimitive,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let items = ItemStock::new(items);
+    let control = SearchControl::new(options.clone());
+    run_auction_with(
+        &items,
+        bid_set_refs(exclusive_bid_sets),
+        tiebreaker,
+        &control,
+        |bid_sets, phase| {
+            find_highest_value_bid_sets(&items, bid_sets, &control, phase)
+        },
+        |winning_bid_set, bid_sets| {
+            winning_bidders(winning_bid_set)
+                .into_iter()
+                .map(|bidder_name| {
+                    let payment = payment_for_bidder(
+                        winning_bid_set,
+                        bidder_name,
+                        bid_sets,
+                        |bid_sets| {
+                            find_highest_value_bid_sets(
+                                &items,
+                                bid_sets,
+                                &control,
+                                SearchPhase::Payments,
+                            )
+                            .map(|(_, value)| value)
+                            .map_err(|(interrupt, _)| interrupt)
+                        },
+                    )?;
+                    Ok((bidder_name, payment))
+                })
+                .collect()
+        },
+    )
+}
+
+/// Search with the relaxation, if the values and quantities convert to `f64`.
+fn find_highest_value_bid_sets<'a, B>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
+    control: &SearchControl<B::Value>,
+    phase: SearchPhase,
+) -> SearchResult<'a, B>
+where
+    B: Bid,
+    B::Value: ToPrimitive,
+    B::Quantity: ToPrimitive,
+{
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("search", ?phase).entered();
+    let exclusive_bid_sets = propagate(items, exclusive_bid_sets);
+    let mut search = start_search(items, &exclusive_bid_sets, phase, control);
+    if let Some(relaxation) = LpRelaxation::new(items, &exclusive_bid_sets) {
+        search = search.with_relaxation(relaxation);
+    }
+    search.run(items, control, u64::MAX);
+    let result = search.into_highest(control);
+    improve_timed_out(items, &exclusive_bid_sets, control, phase, result)
+}
+
+/// The non-empty bid sets of a search, in the order searched, with each bid's
+/// value and the quantity of each item in stock it uses.
+struct LpRelaxation {
+    stock: Vec<f64>,
+    bid_sets: Vec<Vec<(f64, Vec<f64>)>>,
+}
+
+impl LpRelaxation {
+    /// The relaxation of a search, or `None` if a value or quantity can't be
+    /// converted.
+    fn new<B>(
+        items: &ItemStock<B>,
+        exclusive_bid_sets: &[Vec<&B>],
+    ) -> Option<Self>
+    where
+        B: Bid,
+        B::Value: ToPrimitive,
+        B::Quantity: ToPrimitive,
+    {
+        let stock = to_f64s(items.quantities())?;
+        let bid_sets = exclusive_bid_sets
+            .iter()
+            .filter(|bid_set| !bid_set.is_empty())
+            .map(|bid_set| {
+                bid_set
+                    .iter()
+                    .map(|bid| {
+                        let selected = items
+                            .select_bid_items(&items.no_items_selected(), bid);
+                        Some((bid.bid_value().to_f64()?, to_f64s(&selected)?))
+                    })
+                    .collect::<Option<Vec<_>>>()
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { stock, bid_sets })
+    }
+
+    /// Highest value of the relaxation of the bid sets from `depth` on, with
+    /// `left` of each item in stock, or `None` if the solver fails.
+    fn solve(&self, depth: usize, left: &[f64]) -> Option<f64> {
+        let mut problem = Problem::new(OptimizationDirection::Maximize);
+        let mut item_terms = vec![vec![]; left.len()];
+        for bid_set in &self.bid_sets[depth..] {
+            let mut set_terms = vec![];
+            for (value, quantities) in bid_set {
+                // bids worth nothing, or needing more than is left, can't
+                // raise the value
+                let fits = quantities
+                    .iter()
+                    .zip(left)
+                    .all(|(quantity, left)| quantity <= left);
+                if *value <= 0.0 || !fits {
+                    continue;
+                }
+                let var = problem.add_var(*value, (0.0, 1.0));
+                set_terms.push((var, 1.0));
+                for (terms, &quantity) in item_terms.iter_mut().zip(quantities)
+                {
+                    if quantity > 0.0 {
+                        terms.push((var, quantity));
+                    }
+                }
+            }
+            if set_terms.len() > 1 {
+                problem.add_constraint(set_terms, ComparisonOp::Le, 1.0);
+            }
+        }
+        for (terms, &left) in item_terms.into_iter().zip(left) {
+            let needed: f64 = terms.iter().map(|(_, quantity)| quantity).sum();
+            if needed > left {
+                problem.add_constraint(terms, ComparisonOp::Le, left);
+            }
+        }
+        problem.solve().ok().map(|solution| solution.objective())
+    }
+}
+
+impl<B> Relaxation<B> for LpRelaxation
+where
+    B: Bid,
+    B::Value: ToPrimitive,
+    B::Quantity: ToPrimitive,
+{
+    fn prunes(
+        &self,
+        depth: usize,
+        items_selected: &[B::Quantity],
+        selected_value: &B::Value,
+        highest_value: &B::Value,
+    ) -> bool {
+        let (Some(selected), Some(highest), Some(used)) = (
+            selected_value.to_f64(),
+            highest_value.to_f64(),
+            to_f64s(items_selected),
+        ) else {
+            return false;
+        };
+        // the stock left is rounded up, so bids that fit are never dropped
+        let left = self
+            .stock
+            .iter()
+            .zip(used)
+            .map(|(stock, used)| stock - used + 1e-9 * stock.abs().max(1.0))
+            .collect::<Vec<_>>();
+        let Some(relaxed) = self.solve(depth, &left) else {
+            return false;
+        };
+        // prune only with a margin above the solver's rounding errors, so
+        // nodes that could tie are never pruned
+        let margin = 1e-6 * highest.abs().max(1.0);
+        selected + relaxed < highest - margin
+    }
+}
+
+/// Values or quantities as `f64`, or `None` if any can't be converted.
+fn to_f64s<T: ToPrimitive>(values: &[T]) -> Option<Vec<f64>> {
+    values.iter().map(ToPrimitive::to_f64).collect()
+}
@@ -0,0 +1,79 @@
+//! Macros for writing bids of [`SimpleBid`](crate::types::SimpleBid) in the
+//! notation used by the crate documentation.
+
+/// Create bid sets of [`SimpleBid`](crate::types::SimpleBid)s. Each bid set is
+/// written in braces, holding `(name, value, [(item, quantity), ...])` bids.
+/// Expands to a `Vec<Vec<SimpleBid>>`.
+///
+/// ```
+/// use vcg_auction::{bids, types::SimpleBid};
+///
+/// let bids = bids![
+///     {
+///         ("Alice", 5, [("chair", 1)]),
+///         ("Alice", 7, [("chair", 2)]),
+///     },
+///     { ("Bob", 4, [("chair", 1)]) },
+/// ];
+/// assert_eq!(
+///     bids,
+///     [
+///         vec![
+///             SimpleBid::new("Alice", 5, [("chair", 1)]),
+///             SimpleBid::new("Alice", 7, [("chair", 2)]),
+///         ],
+///         vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+///     ]
+/// );
+/// ```
+#[macro_export]
+macro_rules! bids {
+    ($({
+        $(($name:expr, $value:expr, [$(($item:expr, $qty:expr)),* $(,)?])),*
+        $(,)?
+    }),* $(,)?) => {
+        ::std::vec![$(
+            ::std::vec![$(
+                $crate::types::SimpleBid::new(
+                    $name,
+                    $value,
+                    [$((::std::string::String::from($item), $qty)),*],
+                )
+            ),*]
+        ),*]
+    };
+}
+
+/// Create the items and bid sets of an auction. Items are written as
+/// `(item, quantity)` tuples, and bid sets as in [`bids!`]. Expands to a tuple
+/// of a `Vec<(String, u64)>` of items and a `Vec<Vec<SimpleBid>>` of bid sets.
+///
+/// ```
+/// use vcg_auction::{auction, vcg_auction};
+///
+/// let (items, bids) = auction! {
+///     items: [("chair", 2)],
+///     bids: [
+///         {
+///             ("Alice", 5, [("chair", 1)]),
+///             ("Alice", 7, [("chair", 2)]),
+///         },
+///         { ("Bob", 4, [("chair", 1)]) },
+///     ],
+/// };
+/// let result = vcg_auction(&items, &bids).unwrap();
+/// assert_eq!(result.winning_bids, [&bids[0][0], &bids[1][0]]);
+/// ```
+#[macro_export]
+macro_rules! auction {
+    (
+        items: [$(($item:expr, $qty:expr)),* $(,)?],
+        bids: [$($bid_sets:tt)*] $(,)?
+    ) => {
+        {
+            let items: ::std::vec::Vec<(::std::string::String, u64)> =
+                ::std::vec![$((::std::string::String::from($item), $qty)),*];
+            (items, $crate::bids![$($bid_sets)*])
+        }
+    };
+}
@@ -0,0 +1,120 @@
+//! Secondary objectives, choosing between the outcomes tied for the highest
+//! welfare before the tiebreak does.
+
+use num_traits::Zero;
+
+use crate::{AddSubSelf, Bid};
+
+/// A goal to choose between outcomes tied for the highest welfare by, set in
+/// [`AuctionConfig::objectives`](crate::AuctionConfig::objectives).
+///
+/// Objectives are applied in order: each keeps only the tied outcomes best
+/// by it, and the next one chooses among those. The tiebreak then chooses
+/// between the outcomes left.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum Objective {
+    /// The least paid in total, with the config's
+    /// [`PricingRule`](crate::PricingRule). The payments of every tied
+    /// outcome are calculated, which with VCG pricing takes a search for each
+    /// of its winning bidders. Skipped if the auction stops before they're
+    /// found.
+    MinPayments,
+    /// The smallest quantity won by any one bidder, adding up the quantities
+    /// of every item of its winning bids.
+    MinConcentration,
+    /// The largest quantity won in total, adding up the quantities of every
+    /// item of the winning bids.
+    MaxUtilization,
+}
+
+/// Indices of the tied outcomes best by the objectives, in order.
+/// `total_payment` is the total paid in an outcome, or `None` if it can't be
+/// found.
+pub(crate) fn preferred<B: Bid>(
+    tied: &[Vec<&B>],
+    objectives: &[Objective],
+    total_payment: impl Fn(&[&B]) -> Option<B::Value>,
+) -> Vec<usize> {
+    let mut candidates = (0..tied.len()).collect::<Vec<_>>();
+    for objective in objectives {
+        match objective {
+            Objective::MinPayments => {
+                let payments = candidates
+                    .iter()
+                    .map(|&i| total_payment(&tied[i]))
+                    .collect::<Option<Vec<_>>>();
+                if let Some(payments) = payments {
+                    keep_best(&mut candidates, &payments, |a, b| a < b);
+                }
+            }
+            Objective::MinConcentration => {
+                let concentrations = candidates
+                    .iter()
+                    .map(|&i| concentration(&tied[i]))
+                    .collect::<Vec<_>>();
+                keep_best(&mut candidates, &concentrations, |a, b| a < b);
+            }
+            Objective::MaxUtilization => {
+                let utilizations = candidates
+                    .iter()
+                    .map(|&i| total_quantity(tied[i].iter().copied()))
+                    .collect::<Vec<_>>();
+                keep_best(&mut candidates, &utilizations, |a, b| a > b);
+            }
+        }
+    }
+    candidates
+}
+
+/// Keep the candidates no other candidate is better than, by their scores.
+/// Scores that can't be compared, like NaN, are never better.
+fn keep_best<T>(
+    candidates: &mut Vec<usize>,
+    scores: &[T],
+    better: impl Fn(&T, &T) -> bool,
+) {
+    let Some(mut best) = scores.first() else {
+        return;
+    };
+    for score in scores {
+        if better(score, best) {
+            best = score;
+        }
+    }
+    let mut scores = scores.iter();
+    candidates
+        .retain(|_| !scores.next().is_some_and(|score| better(best, score)));
+}
+
+/// The largest total quantity won by one bidder, or zero if no bid won.
+fn concentration<B: Bid>(outcome: &[&B]) -> B::Quantity {
+    let mut largest = B::Quantity::zero();
+    for (i, bid) in outcome.iter().enumerate() {
+        let name = bid.bidder_name();
+        if outcome[..i].iter().any(|b| b.bidder_name() == name) {
+            continue;
+        }
+        let won = total_quantity(
+            outcome[i..]
+                .iter()
+                .copied()
+                .filter(|b| b.bidder_name() == name),
+        );
+        if won > largest {
+            largest = won;
+        }
+    }
+    largest
+}
+
+/// The quantities of every item of the bids, added up.
+fn total_quantity<'b, B: Bid + 'b>(
+    bids: impl IntoIterator<Item = &'b B>,
+) -> B::Quantity {
+    bids.into_iter()
+        .flat_map(|bid| bid.bid_items())
+        .fold(B::Quantity::zero(), |sum, (_, quantity)| sum.add(quantity))
+}
@@ -0,0 +1,373 @@
+//! Adapter pricing OpenRTB-style bid responses with a VCG auction.
+//!
+//! Each impression of a bid request is an item with a stock of one. The bids
+//! a seat makes on one impression are mutually-exclusive alternatives, and
+//! the bids of a seat bid with `group` set must all win together, so they
+//! become one bid on every impression they're for. An impression's floor
+//! becomes a reserve bid, so winners pay at least the floor. Prices are CPM
+//! in the request's currency, and are converted to whole micros for the
+//! auction.
+//!
+//! ```
+//! use vcg_auction::{
+//!     openrtb::{BidRequest, BidResponse, Imp, ResponseBid, RtbAuction, SeatBid},
+//!     vcg_auction_with_tiebreaker,
+//! };
+//!
+//! let request = BidRequest {
+//!     id: "request".into(),
+//!     imp: vec![Imp { id: "banner".into(), bidfloor: 0.5 }],
+//! };
+//! let response = |seat: &str, price: f64| BidResponse {
+//!     id: "request".into(),
+//!     seatbid: vec![SeatBid {
+//!         seat: seat.into(),
+//!         group: 0,
+//!         bid: vec![ResponseBid {
+//!             id: format!("{seat}-bid"),
+//!             impid: "banner".into(),
+//!             price,
+//!         }],
+//!     }],
+//! };
+//! let responses = [response("dsp-a", 2.0), response("dsp-b", 1.25)];
+//! let auction = RtbAuction::new(&request, &responses);
+//! let result = vcg_auction_with_tiebreaker(
+//!     auction.items(),
+//!     auction.bid_sets(),
+//!     |_| 0,
+//! )
+//! .unwrap();
+//! let prices = auction.clearing_prices(&result);
+//! assert_eq!(prices[0].seat, "dsp-a");
+//! assert_eq!(prices[0].price, 1.25);
+//! ```
+
+use std::ptr;
+
+use crate::{types::SimpleBid, AuctionResult, DropReason};
+
+/// Micros in a unit of price.
+const MICROS: f64 = 1_000_000.0;
+
+/// A bid request, with the impressions on offer.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BidRequest {
+    pub id: String,
+    pub imp: Vec<Imp>,
+}
+
+/// An impression on offer, with its floor price.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Imp {
+    pub id: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bidfloor: f64,
+}
+
+/// A bidder's response to a bid request.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BidResponse {
+    pub id: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub seatbid: Vec<SeatBid>,
+}
+
+/// The bids of one seat. With `group` set to 1, the bids must all win or
+/// all lose.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SeatBid {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub seat: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub group: u8,
+    pub bid: Vec<ResponseBid>,
+}
+
+/// A bid on one impression.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResponseBid {
+    pub id: String,
+    pub impid: String,
+    pub price: f64,
+}
+
+/// The price a winning response bid clears at.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ClearingPrice {
+    /// `id` of the response.
+    pub response_id: String,
+    pub seat: String,
+    /// `id` of the winning bid.
+    pub bid_id: String,
+    pub impid: String,
+    /// Price charged for the impression, at most the bid's price.
+    pub price: f64,
+}
+
+/// A response bid left out of an [`RtbAuction`], so its bidder can be told
+/// why it couldn't win.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DroppedResponseBid {
+    /// `id` of the response.
+    pub response_id: String,
+    pub seat: String,
+    /// `id` of the bid.
+    pub bid_id: String,
+    pub impid: String,
+    /// [`DropReason::UnknownItem`] for an impression that isn't in the
+    /// request, [`DropReason::InvalidValue`] for a price that isn't a finite
+    /// positive number of micros, [`DropReason::BelowReserve`] for a price
+    /// below the floor, or for the bids of a group,
+    /// [`DropReason::RepeatedItem`], [`DropReason::PartDropped`] or
+    /// [`DropReason::ValueOverflow`].
+    pub reason: DropReason,
+}
+
+/// The response bids an auction bid was made from, by response, seat bid and
+/// bid index.
+type Sources = Vec<(usize, usize, usize)>;
+
+/// A bid request and its responses, as the items and bid sets of an auction.
+#[derive(Clone, Debug)]
+pub struct RtbAuction<'r> {
+    responses: &'r [BidResponse],
+    items: Vec<(String, u64)>,
+    bid_sets: Vec<Vec<SimpleBid>>,
+    /// Response bids of each auction bid, empty for floors.
+    sources: Vec<Vec<Sources>>,
+    dropped: Vec<DroppedResponseBid>,
+}
+
+impl<'r> RtbAuction<'r> {
+    /// Convert the responses to a request into an auction. Bids on
+    /// impressions that aren't in the request, with a price below the floor,
+    /// or with a price that isn't a finite positive number of micros, are
+    /// dropped, as are groups with any such bid or with two bids on the same
+    /// impression. The bids dropped are listed by
+    /// [`dropped_bids`](Self::dropped_bids).
+    pub fn new(request: &BidRequest, responses: &'r [BidResponse]) -> Self {
+        let items = request
+            .imp
+            .iter()
+            .map(|imp| (imp.id.clone(), 1))
+            .collect::<Vec<_>>();
+        let floor = |impid: &str| {
+            request
+                .imp
+                .iter()
+                .find(|imp| imp.id == impid)
+                .map(|imp| imp.bidfloor)
+        };
+        let micros = |bid: &ResponseBid| {
+            let floor = floor(&bid.impid).ok_or(DropReason::UnknownItem)?;
+            let micros = (bid.price * MICROS).round();
+            if !(micros.is_finite()
+                && micros > 0.0
+                && micros <= u64::MAX as f64)
+            {
+                return Err(DropReason::InvalidValue);
+            }
+            if bid.price < floor {
+                return Err(DropReason::BelowReserve);
+            }
+            Ok(micros as u64)
+        };
+
+        let mut bid_sets = vec![];
+        let mut sources = vec![];
+        let mut dropped = vec![];
+        for imp in &request.imp {
+            let floor_micros = (imp.bidfloor * MICROS).round();
+            if floor_micros.is_finite() && floor_micros > 0.0 {
+                let name = format!("floor/{}", imp.id);
+                let value = floor_micros.min(u64::MAX as f64) as u64;
+                bid_sets.push(vec![SimpleBid::new(
+                    name,
+                    value,
+                    [(&imp.id, 1)],
+                )]);
+                sources.push(vec![vec![]]);
+            }
+        }
+        for (r, response) in responses.iter().enumerate() {
+            for (s, seat_bid) in response.seatbid.iter().enumerate() {
+                let name = |suffix: &str| {
+                    format!("{}/{}/{}/{suffix}", response.id, seat_bid.seat, s)
+                };
+                let mut drop_bid = |bid: &ResponseBid, reason| {
+                    dropped.push(DroppedResponseBid {
+                        response_id: response.id.clone(),
+                        seat: seat_bid.seat.clone(),
+                        bid_id: bid.id.clone(),
+                        impid: bid.impid.clone(),
+                        reason,
+                    });
+                };
+                let mut values =
+                    seat_bid.bid.iter().map(micros).collect::<Vec<_>>();
+                if seat_bid.group != 0 {
+                    // one bid on every impression of the group, which can't
+                    // win an impression twice
+                    for (b, bid) in seat_bid.bid.iter().enumerate() {
+                        let repeats = seat_bid.bid[..b]
+                            .iter()
+                            .any(|other| other.impid == bid.impid);
+                        if repeats && values[b].is_ok() {
+                            values[b] = Err(DropReason::RepeatedItem);
+                        }
+                    }
+                    let any_dropped = values.iter().any(Result::is_err);
+                    let value = if any_dropped {
+                        None
+                    } else {
+                        values
+                            .iter()
+                            .flatten()
+                            .try_fold(0u64, |sum, v| sum.checked_add(*v))
+                    };
+                    let Some(value) = value else {
+                        for (bid, value) in seat_bid.bid.iter().zip(&values) {
+                            let reason = match value {
+                                Err(reason) => *reason,
+                                Ok(_) if any_dropped => DropReason::PartDropped,
+                                Ok(_) => DropReason::ValueOverflow,
+                            };
+                            drop_bid(bid, reason);
+                        }
+                        continue;
+                    };
+                    if seat_bid.bid.is_empty() {
+                        continue;
+                    }
+                    let bid = SimpleBid::new(
+                        name("group"),
+                        value,
+                        seat_bid.bid.iter().map(|bid| (&bid.impid, 1)),
+                    );
+                    bid_sets.push(vec![bid]);
+                    sources.push(vec![(0..seat_bid.bid.len())
+                        .map(|b| (r, s, b))
+                        .collect()]);
+                    continue;
+                }
+                for (bid, value) in seat_bid.bid.iter().zip(&values) {
+                    if let Err(reason) = value {
+                        drop_bid(bid, *reason);
+                    }
+                }
+                // the seat's bids on each impression are alternatives
+                for (impid, _) in &items {
+                    let (bids, bid_sources): (Vec<_>, Vec<_>) = seat_bid
+                        .bid
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, bid)| bid.impid == *impid)
+                        .filter_map(|(b, _)| {
+                            let bid = SimpleBid::new(
+                                name(impid),
+                                values[b].ok()?,
+                                [(impid, 1)],
+                            );
+                            Some((bid, vec![(r, s, b)]))
+                        })
+                        .unzip();
+                    if !bids.is_empty() {
+                        bid_sets.push(bids);
+                        sources.push(bid_sources);
+                    }
+                }
+            }
+        }
+        Self {
+            responses,
+            items,
+            bid_sets,
+            sources,
+            dropped,
+        }
+    }
+
+    /// The response bids left out of the auction and why, in the order of
+    /// the responses.
+    pub fn dropped_bids(&self) -> &[DroppedResponseBid] {
+        &self.dropped
+    }
+
+    /// Each impression, with a stock of one.
+    pub fn items(&self) -> &[(String, u64)] {
+        &self.items
+    }
+
+    /// Bid sets of the auction, with values in micros.
+    pub fn bid_sets(&self) -> &[Vec<SimpleBid>] {
+        &self.bid_sets
+    }
+
+    /// The price of each winning response bid, in the order of the winning
+    /// bids. The payment of a group is split between its bids in proportion
+    /// to their prices. `result` must be of an auction of this adapter's
+    /// items and bid sets.
+    pub fn clearing_prices(
+        &self,
+        result: &AuctionResult<SimpleBid>,
+    ) -> Vec<ClearingPrice> {
+        let mut prices = vec![];
+        for winning_bid in &result.winning_bids {
+            let Some(sources) = self.sources_of(winning_bid) else {
+                continue;
+            };
+            let Some(payment) = result
+                .payments
+                .iter()
+                .find(|(name, _)| **name == winning_bid.name)
+                .map(|(_, payment)| *payment)
+            else {
+                continue;
+            };
+            // split the payment, with the remainder on the last bid so the
+            // micros add up
+            let mut left = payment;
+            for (i, &(r, s, b)) in sources.iter().enumerate() {
+                let response = &self.responses[r];
+                let seat_bid = &response.seatbid[s];
+                let bid = &seat_bid.bid[b];
+                let share = if i + 1 == sources.len() {
+                    left
+                } else {
+                    let price = (bid.price * MICROS).round();
+                    let share =
+                        payment as f64 * price / winning_bid.value as f64;
+                    (share.floor() as u64).min(left)
+                };
+                left -= share;
+                prices.push(ClearingPrice {
+                    response_id: response.id.clone(),
+                    seat: seat_bid.seat.clone(),
+                    bid_id: bid.id.clone(),
+                    impid: bid.impid.clone(),
+                    price: share as f64 / MICROS,
+                });
+            }
+        }
+        prices
+    }
+
+    /// The response bids of an auction bid, or `None` if it isn't one of the
+    /// auction's bids.
+    fn sources_of(&self, bid: &SimpleBid) -> Option<&Sources> {
+        self.bid_sets.iter().zip(&self.sources).find_map(
+            |(bid_set, sources)| {
+                let i = bid_set.iter().position(|b| ptr::eq(b, bid))?;
+                Some(&sources[i])
+            },
+        )
+    }
+}
@@ -0,0 +1,271 @@
+//! Options for running an auction search.
+
+use std::time::Duration;
+
+use crate::{AuctionError, ResourceLimit};
+
+/// Options controlling how the winning bids and payments are searched for.
+/// Use [`Default`] for any options that aren't needed.
+///
+/// ```
+/// use vcg_auction::{Progress, SearchOptions};
+///
+/// let log_progress = |progress: &Progress<u64>| {
+///     println!(
+///         "best {} of at most {} after {} nodes",
+///         progress.best_value, progress.upper_bound, progress.nodes
+///     );
+/// };
+/// let options = SearchOptions {
+///     progress: Some(&log_progress),
+///     ..Default::default()
+/// };
+/// ```
+pub struct SearchOptions<'o, V> {
+    /// Called periodically while the search is running. In a parallel search
+    /// this may be called from any of the search threads.
+    pub progress: Option<&'o ProgressCallback<'o, V>>,
+    /// Minimum time between calls to `progress`. Defaults to one second.
+    pub progress_interval: Duration,
+    /// Stop searching once the auction has run for this long. The clock is
+    /// checked every few thousand search nodes, so the auction can overrun
+    /// slightly. Defaults to no limit.
+    pub max_duration: Option<Duration>,
+    /// What to return when `max_duration` is exceeded.
+    pub on_timeout: OnTimeout,
+    /// Stop with
+    /// [`AuctionError::ResourceLimit`](crate::AuctionError::ResourceLimit)
+    /// once this many search nodes have been explored, across every phase of
+    /// the auction. A parallel search can overrun by up to a thousand nodes
+    /// per thread. Defaults to no limit.
+    pub max_nodes: Option<u64>,
+    /// Stop with
+    /// [`AuctionError::ResourceLimit`](crate::AuctionError::ResourceLimit)
+    /// if more combinations of bids than this are tied for the highest value
+    /// found so far. Tied combinations are kept until the tiebreak, so along
+    /// with the number of bids this bounds the memory of a search. Defaults
+    /// to no limit.
+    pub max_tied_outcomes: Option<usize>,
+    /// Fail with
+    /// [`AuctionError::ResourceLimit`](crate::AuctionError::ResourceLimit)
+    /// before searching if there are more bid sets than this. Defaults to no
+    /// limit.
+    pub max_bid_sets: Option<usize>,
+    /// Fail with
+    /// [`AuctionError::ResourceLimit`](crate::AuctionError::ResourceLimit)
+    /// before searching if any bid set has more bids than this. Defaults to
+    /// no limit.
+    pub max_bids_per_set: Option<usize>,
+    /// Order the search explores combinations of bids in. Defaults to
+    /// [`SearchStrategy::DepthFirst`].
+    pub strategy: SearchStrategy,
+    /// Order the bid sets are searched in. A heuristic order can prune far
+    /// more of the search, and the outcomes found are put back in the order
+    /// of the bid sets given, so the result of an auction doesn't depend on
+    /// it. The parallel search and the step-by-step [`Solver`](crate::Solver)
+    /// search in this order too, while the search pruned by a linear
+    /// relaxation searches the bid sets in the order given. Defaults to
+    /// [`BidSetOrder::Input`].
+    pub bid_set_order: BidSetOrder,
+    /// Check each complete result against the invariants of a correct
+    /// search, and panic with a report of every invariant it breaks: the
+    /// winning bids fit in the stock and are worth the highest value found,
+    /// and each payment is at least zero, with the value of the auction
+    /// without its winner at least the value of the other winning bids. The
+    /// checks are cheap next to the search, and meant for debugging it.
+    /// Values are compared exactly, so values that round, like floats, can
+    /// fail the check on the highest value. Defaults to `false`.
+    pub check_invariants: bool,
+    /// Check each payment by solving the auction without its winner again,
+    /// with a search that visits the bid sets and bids in reverse order, uses
+    /// the other [`SearchStrategy`], and skips the shortcuts of the first
+    /// search, returning [`AuctionError::PaymentMismatch`] if the two values
+    /// differ. This guards against bugs in the search, at the cost of
+    /// searching for each payment twice. Defaults to `false`.
+    ///
+    /// [`AuctionError::PaymentMismatch`]: crate::AuctionError::PaymentMismatch
+    pub verify_payments: bool,
+    /// Thread pool to run a parallel search on, instead of rayon's global
+    /// pool.
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    pub thread_pool: Option<&'o rayon::ThreadPool>,
+    /// Number of threads for a parallel search, when no `thread_pool` is
+    /// given. A pool with this many threads is created for the auction.
+    /// Defaults to rayon's global pool.
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    pub num_threads: Option<usize>,
+}
+
+impl<V> Default for SearchOptions<'_, V> {
+    fn default() -> Self {
+        Self {
+            progress: None,
+            progress_interval: Duration::from_secs(1),
+            max_duration: None,
+            on_timeout: OnTimeout::Error,
+            max_nodes: None,
+            max_tied_outcomes: None,
+            max_bid_sets: None,
+            max_bids_per_set: None,
+            strategy: SearchStrategy::DepthFirst,
+            bid_set_order: BidSetOrder::Input,
+            check_invariants: false,
+            verify_payments: false,
+            #[cfg(feature = "rayon")]
+            thread_pool: None,
+            #[cfg(feature = "rayon")]
+            num_threads: None,
+        }
+    }
+}
+
+impl<V> Clone for SearchOptions<'_, V> {
+    fn clone(&self) -> Self {
+        Self {
+            progress: self.progress,
+            progress_interval: self.progress_interval,
+            max_duration: self.max_duration,
+            on_timeout: self.on_timeout,
+            max_nodes: self.max_nodes,
+            max_tied_outcomes: self.max_tied_outcomes,
+            max_bid_sets: self.max_bid_sets,
+            max_bids_per_set: self.max_bids_per_set,
+            strategy: self.strategy,
+            bid_set_order: self.bid_set_order,
+            check_invariants: self.check_invariants,
+            verify_payments: self.verify_payments,
+            #[cfg(feature = "rayon")]
+            thread_pool: self.thread_pool,
+            #[cfg(feature = "rayon")]
+            num_threads: self.num_threads,
+        }
+    }
+}
+
+impl<V> SearchOptions<'_, V> {
+    /// Check the bid sets against the limits on the size of an auction.
+    pub(crate) fn check_size<B>(
+        &self,
+        exclusive_bid_sets: &[Vec<&B>],
+    ) -> Result<(), AuctionError> {
+        let exceeds = |limit: Option<usize>, size: usize| {
+            limit.is_some_and(|limit| size > limit)
+        };
+        if exceeds(self.max_bid_sets, exclusive_bid_sets.len()) {
+            return Err(AuctionError::ResourceLimit(ResourceLimit::BidSets));
+        }
+        let max_bids = exclusive_bid_sets.iter().map(Vec::len).max();
+        if exceeds(self.max_bids_per_set, max_bids.unwrap_or(0)) {
+            return Err(AuctionError::ResourceLimit(ResourceLimit::BidsPerSet));
+        }
+        Ok(())
+    }
+}
+
+/// What an auction returns when it exceeds its
+/// [`max_duration`](SearchOptions::max_duration).
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum OnTimeout {
+    /// Return [`AuctionError::TimedOut`](crate::AuctionError::TimedOut).
+    #[default]
+    Error,
+    /// Return [`AuctionOutcome::TimedOut`](crate::AuctionOutcome::TimedOut)
+    /// with the best combination of bids found so far.
+    BestSoFar,
+}
+
+/// Order in which a search explores combinations of bids. Every strategy
+/// finds the same tied outcomes in the same order, so the result of an
+/// auction doesn't depend on it.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum SearchStrategy {
+    /// Choose from one bid set after another, backtracking once every choice
+    /// from a bid set has been explored. Memory grows with the number of
+    /// bids.
+    #[default]
+    DepthFirst,
+    /// Explore the combination whose bound is highest first, from a priority
+    /// queue. The highest value is found after fewer nodes when many bids
+    /// compete for the same items, so more of the search is pruned, but many
+    /// more combinations may be held in memory at once.
+    BestFirst,
+}
+
+/// Order in which a search considers the bid sets, choosing a bid or none
+/// from each in turn. Combinations of bids that can't reach the highest
+/// value found are pruned, so orders finding a high value early, or
+/// conflicts near the root, explore fewer nodes.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum BidSetOrder {
+    /// The order the bid sets are given in.
+    #[default]
+    Input,
+    /// Bid sets with the highest bids first, so a combination worth close
+    /// to the highest value is found early.
+    MaxValue,
+    /// Bid sets asking for the most contended items first, where an item is
+    /// contended if the bids together ask for more of it than is in stock.
+    /// A bid set is ranked by the number of bids asking for the contended
+    /// items its bids ask for, and then by its highest bid.
+    Contention,
+}
+
+/// Callback receiving the progress of a running search.
+pub type ProgressCallback<'o, V> = dyn Fn(&Progress<V>) + Sync + 'o;
+
+/// Snapshot of a running search, passed to the
+/// [`progress`](SearchOptions::progress) callback.
+///
+/// The progress of an auction can be sent to another thread by converting it
+/// to an owned type first.
+/// ```
+/// use std::sync::{mpsc, Mutex};
+/// use vcg_auction::{Progress, SearchOptions};
+///
+/// let (tx, rx) = mpsc::channel();
+/// let tx = Mutex::new(tx);
+/// let send_progress = move |progress: &Progress<u64>| {
+///     let _ = tx.lock().unwrap().send((*progress.best_value, progress.nodes));
+/// };
+/// let options = SearchOptions {
+///     progress: Some(&send_progress),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug)]
+pub struct Progress<'p, V> {
+    /// Which part of the auction is being calculated.
+    pub phase: SearchPhase,
+    /// Value of the best combination of bids found so far in this phase. In a
+    /// parallel search, this is the best found by the reporting thread.
+    pub best_value: &'p V,
+    /// Upper bound on the value of any combination of bids in this phase, from
+    /// the sum of the highest bid in each bid set.
+    pub upper_bound: &'p V,
+    /// Number of search nodes explored so far, across all phases.
+    pub nodes: u64,
+    /// Time since the auction started.
+    pub elapsed: Duration,
+}
+
+impl<V> Progress<'_, V> {
+    /// Average number of search nodes explored per second.
+    pub fn nodes_per_second(&self) -> f64 {
+        self.nodes as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// The parts of an auction calculation.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SearchPhase {
+    /// Searching for the highest value combination of bids.
+    WinnerDetermination,
+    /// Searching for the auction value without each winning bidder, to
+    /// calculate their payments.
+    Payments,
+}
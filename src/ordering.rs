@@ -0,0 +1,119 @@
+//! Reordering of the bid sets before searching, by a [`BidSetOrder`], and of
+//! the outcomes found back to the order of the bid sets given.
+//!
+//! The order bid sets are searched in doesn't change which outcomes are tied
+//! for the highest value, only how much of the search is pruned before they
+//! are found. Putting each outcome's bids back in the order of their bid
+//! sets, and the outcomes in the order a search of the bid sets as given
+//! would find them, keeps the result and the tiebreak independent of it.
+
+use crate::{input::ItemStock, report::position, AddSubSelf, Bid, BidSetOrder};
+
+/// The bid sets in the order to search them in. Sorts are stable, so bid
+/// sets the heuristic ranks the same keep their order.
+pub(crate) fn order_bid_sets<'a, B: Bid>(
+    items: &ItemStock<B>,
+    mut exclusive_bid_sets: Vec<Vec<&'a B>>,
+    order: BidSetOrder,
+) -> Vec<Vec<&'a B>> {
+    let max_value =
+        |bid_set: &[&'a B]| bid_set.iter().map(|bid| bid.bid_value()).max();
+    match order {
+        BidSetOrder::Input => (),
+        BidSetOrder::MaxValue => {
+            exclusive_bid_sets.sort_by(|a, b| max_value(b).cmp(&max_value(a)))
+        }
+        BidSetOrder::Contention => {
+            let contention = contention(items, &exclusive_bid_sets);
+            let mut ranked = contention
+                .into_iter()
+                .zip(exclusive_bid_sets)
+                .collect::<Vec<_>>();
+            ranked.sort_by(|(a_contention, a), (b_contention, b)| {
+                b_contention
+                    .cmp(a_contention)
+                    .then_with(|| max_value(b).cmp(&max_value(a)))
+            });
+            exclusive_bid_sets =
+                ranked.into_iter().map(|(_, bid_set)| bid_set).collect();
+        }
+    }
+    exclusive_bid_sets
+}
+
+/// Contention of each bid set: the number of bids of every bid set asking
+/// for a contended item that any of its bids asks for. An item is contended
+/// if the bids together ask for more of it than is in stock.
+fn contention<B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&B>],
+) -> Vec<usize> {
+    let no_items = items.no_items_selected();
+    let bid_items = exclusive_bid_sets
+        .iter()
+        .map(|bid_set| {
+            bid_set
+                .iter()
+                .map(|bid| items.index_bid_items(*bid))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut demand = no_items.clone();
+    let mut bids = vec![0; no_items.len()];
+    for bid_items in bid_items.iter().flatten() {
+        for &(i, quantity) in bid_items {
+            demand[i] = demand[i].add(quantity);
+            bids[i] += 1;
+        }
+    }
+    let contended = demand
+        .iter()
+        .zip(items.quantities())
+        .map(|(demand, stock)| demand > stock)
+        .collect::<Vec<_>>();
+    bid_items
+        .iter()
+        .map(|bid_set| {
+            let mut asked = vec![false; no_items.len()];
+            for &(i, _) in bid_set.iter().flatten() {
+                asked[i] = true;
+            }
+            (0..asked.len())
+                .filter(|&i| asked[i] && contended[i])
+                .map(|i| bids[i])
+                .sum()
+        })
+        .collect()
+}
+
+/// Put outcomes found by a search of reordered bid sets in the order a
+/// search of `exclusive_bid_sets`, as given, would find them: each outcome's
+/// bids in the order of their bid sets, and the outcomes in depth-first
+/// order, choosing each bid of a bid set before no bid from it.
+pub(crate) fn restore_order<'a, B: Bid>(
+    exclusive_bid_sets: &[Vec<&'a B>],
+    outcomes: &mut [Vec<&'a B>],
+) {
+    let mut paths = outcomes
+        .iter_mut()
+        .map(|bids| {
+            let mut positioned = bids
+                .iter()
+                .map(|bid| (position(exclusive_bid_sets, *bid), *bid))
+                .collect::<Vec<_>>();
+            positioned.sort_by_key(|&(position, _)| position);
+            // the choice from each bid set, its length for no bid
+            let mut path =
+                exclusive_bid_sets.iter().map(Vec::len).collect::<Vec<_>>();
+            for (i, ((bid_set, bid), b)) in positioned.into_iter().enumerate() {
+                path[bid_set] = bid;
+                bids[i] = b;
+            }
+            (path, std::mem::take(bids))
+        })
+        .collect::<Vec<_>>();
+    paths.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (outcome, (_, bids)) in outcomes.iter_mut().zip(paths) {
+        *outcome = bids;
+    }
+}
@@ -0,0 +1,347 @@
+//! Parallel search using rayon.
+//!
+//! The search tree is split into a fixed number of subtrees at the top few bid
+//! sets, and each subtree is searched independently on rayon's thread pool.
+//! Every subtree keeps its own tied outcomes, and these are merged in the same
+//! order a serial search would visit them. The tied outcomes passed to the
+//! tiebreaker, and so the auction result, are identical to a serial search
+//! regardless of thread scheduling.
+
+use std::cmp::Ordering;
+
+use num_traits::Zero;
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+
+use crate::{
+    conflict::clique_cover,
+    control::{Interrupt, SearchControl},
+    decomposition::{components, Combined},
+    input::{bid_set_refs, ItemStock},
+    local_search::improve_timed_out,
+    ordering::{order_bid_sets, restore_order},
+    propagation::propagate,
+    vcg::{
+        annotate_max_values, payment_for_bidder, run_auction_with, upper_bound,
+        winning_bidders, Search, SearchResult,
+    },
+    AddSubSelf, AuctionError, AuctionOutcome, AuctionResult, Bid, BidSetOrder,
+    ResourceLimit, SearchOptions, SearchPhase, StockEntry,
+};
+
+/// Minimum number of subtrees to split the search into. This is fixed rather
+/// than derived from the thread count, so that the work done is the same on
+/// every machine.
+const SPLIT_TARGET: usize = 64;
+
+/// Parallel version of
+/// [`vcg_auction_with_tiebreaker`](crate::vcg_auction_with_tiebreaker). The
+/// search for the winning bids and the payment of each winner are computed on
+/// rayon's global thread pool. The result is identical to the serial version.
+///
+/// Like the serial version, bid sets that split into sub-auctions sharing no
+/// items are searched a sub-auction at a time.
+#[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+pub fn vcg_auction_parallel<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
+) -> Option<AuctionResult<'a, B>>
+where
+    S: AsRef<[B]> + ?Sized + 'a,
+    B: Bid + Sync + 'i,
+    B::Name: Sync,
+    B::Value: Send + Sync,
+    B::Item: Sync,
+    B::Quantity: Send + Sync,
+{
+    run_auction_parallel(
+        &ItemStock::new(items),
+        bid_set_refs(exclusive_bid_sets),
+        tiebreaker,
+        &SearchControl::new(SearchOptions::default()),
+        None,
+    )
+    .ok()
+    .and_then(AuctionOutcome::complete)
+}
+
+/// Parallel version of
+/// [`vcg_auction_with_options`](crate::vcg_auction_with_options). The search
+/// runs on [`SearchOptions::thread_pool`] if given, or a new pool with
+/// [`SearchOptions::num_threads`] threads, or else rayon's global pool.
+///
+/// ```
+/// use vcg_auction::{types::SimpleBid, vcg_auction_parallel_with_options, SearchOptions};
+///
+/// let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![vec![SimpleBid::new("Alice", 10, [("chair", 1)])]];
+/// let options = SearchOptions {
+///     thread_pool: Some(&pool),
+///     ..Default::default()
+/// };
+/// let outcome =
+///     vcg_auction_parallel_with_options(&items, &bids, |_| 0, &options);
+/// assert!(outcome.is_ok());
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+pub fn vcg_auction_parallel_with_options<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
+    options: &SearchOptions<B::Value>,
+) -> Result<AuctionOutcome<'a, B>, AuctionError>
+where
+    S: AsRef<[B]> + ?Sized + 'a,
+    B: Bid + Sync + 'i,
+    B::Name: Sync,
+    B::Value: Send + Sync,
+    B::Item: Sync,
+    B::Quantity: Send + Sync,
+{
+    let owned_pool;
+    let pool = match (options.thread_pool, options.num_threads) {
+        (Some(pool), _) => Some(pool),
+        (None, Some(num_threads)) => {
+            owned_pool = ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .map_err(|_| AuctionError::ThreadPool)?;
+            Some(&owned_pool)
+        }
+        (None, None) => None,
+    };
+    run_auction_parallel(
+        &ItemStock::new(items),
+        bid_set_refs(exclusive_bid_sets),
+        tiebreaker,
+        &SearchControl::new(options.clone()),
+        pool,
+    )
+}
+
+pub(crate) fn run_auction_parallel<'a, B>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: Vec<Vec<&'a B>>,
+    tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
+    control: &SearchControl<B::Value>,
+    pool: Option<&ThreadPool>,
+) -> Result<AuctionOutcome<'a, B>, AuctionError>
+where
+    B: Bid + Sync,
+    B::Name: Sync,
+    B::Value: Send + Sync,
+    B::Item: Sync,
+    B::Quantity: Send + Sync,
+{
+    run_auction_with(
+        items,
+        exclusive_bid_sets,
+        tiebreaker,
+        control,
+        |bid_sets, phase| {
+            in_pool(pool, || {
+                find_highest_value_bid_sets(items, bid_sets, control, phase)
+            })
+        },
+        |winning_bid_set, bid_sets| {
+            // payments are independent of each other, compute them in
+            // parallel and collect them in the serial order
+            in_pool(pool, || {
+                winning_bidders(winning_bid_set)
+                    .into_par_iter()
+                    .map(|bidder_name| {
+                        let payment = payment_for_bidder(
+                            winning_bid_set,
+                            bidder_name,
+                            bid_sets,
+                            |bid_sets| {
+                                find_highest_value_bid_sets(
+                                    items,
+                                    bid_sets,
+                                    control,
+                                    SearchPhase::Payments,
+                                )
+                                .map(|(_, value)| value)
+                                .map_err(|(interrupt, _)| interrupt)
+                            },
+                        )?;
+                        Ok((bidder_name, payment))
+                    })
+                    .collect()
+            })
+        },
+    )
+}
+
+/// Run `op` on `pool`, or on the current pool if none is given.
+fn in_pool<R: Send>(
+    pool: Option<&ThreadPool>,
+    op: impl FnOnce() -> R + Send,
+) -> R {
+    match pool {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+/// A partially explored node of the search tree, the root of a subtree
+/// searched on its own thread.
+struct Subtree<'a, B: Bid> {
+    items_selected: Vec<B::Quantity>,
+    bids_selected: Vec<&'a B>,
+    selected_value: B::Value,
+}
+
+/// Parallel version of the serial search. Bid sets split into independent
+/// components are searched a component at a time, each in parallel.
+fn find_highest_value_bid_sets<'a, B>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
+    control: &SearchControl<B::Value>,
+    phase: SearchPhase,
+) -> SearchResult<'a, B>
+where
+    B: Bid + Sync,
+    B::Value: Send + Sync,
+    B::Item: Sync,
+    B::Quantity: Send + Sync,
+{
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("search", ?phase).entered();
+    let propagated = propagate(items, exclusive_bid_sets);
+    let components = components(items, &propagated);
+    if components.len() <= 1 {
+        return search_component(items, &propagated, control, phase);
+    }
+
+    #[cfg(feature = "tracing")]
+    tracing::debug!(components = components.len(), "decomposed search");
+    let mut combined = Combined::decomposed();
+    for component in components {
+        let bid_sets = component
+            .into_iter()
+            .map(|bid_set| propagated[bid_set].clone())
+            .collect::<Vec<_>>();
+        let result = search_component(items, &bid_sets, control, phase);
+        if !combined.add(control, result) {
+            break;
+        }
+    }
+    combined.into_result(&propagated)
+}
+
+/// Search the bid sets of one component in the order of the search options,
+/// split into subtrees searched in parallel, and return the outcomes in the
+/// order of the bid sets given.
+fn search_component<'a, B>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
+    control: &SearchControl<B::Value>,
+    phase: SearchPhase,
+) -> SearchResult<'a, B>
+where
+    B: Bid + Sync,
+    B::Value: Send + Sync,
+    B::Item: Sync,
+    B::Quantity: Send + Sync,
+{
+    let order = control.options().bid_set_order;
+    let ordered = (order != BidSetOrder::Input)
+        .then(|| order_bid_sets(items, exclusive_bid_sets.to_vec(), order));
+    let bid_sets =
+        annotate_max_values(ordered.as_deref().unwrap_or(exclusive_bid_sets));
+    let cliques = clique_cover(items, &bid_sets);
+    // split at the first bid sets until there are enough subtrees
+    let mut split_depth = 0;
+    let mut n_subtrees = 1;
+    while split_depth < bid_sets.len() && n_subtrees < SPLIT_TARGET {
+        // every bid in the set, or none of them
+        n_subtrees *= bid_sets[split_depth].0.len() + 1;
+        split_depth += 1;
+    }
+    // subtrees are listed in the order the serial search visits them
+    let mut subtrees = vec![Subtree {
+        items_selected: items.no_items_selected(),
+        bids_selected: vec![],
+        selected_value: B::Value::zero(),
+    }];
+    for (bid_set, _max_bid_value) in &bid_sets[..split_depth] {
+        subtrees = subtrees
+            .into_iter()
+            .flat_map(|subtree| {
+                let with_bids = bid_set.iter().map(|bid| {
+                    let mut bids_selected = subtree.bids_selected.clone();
+                    bids_selected.push(*bid);
+                    Subtree {
+                        items_selected: items
+                            .select_bid_items(&subtree.items_selected, *bid),
+                        bids_selected,
+                        selected_value: subtree
+                            .selected_value
+                            .add(bid.bid_value()),
+                    }
+                });
+                with_bids.collect::<Vec<_>>().into_iter().chain([subtree])
+            })
+            .collect();
+    }
+    // search each subtree with its own highest value, so ties are found the
+    // same way no matter which subtree finishes first
+    let results = subtrees
+        .into_par_iter()
+        .map(|subtree| {
+            // progress reports the upper bound of the whole search
+            let mut search = Search::new(
+                items,
+                phase,
+                bid_sets[split_depth..].to_vec(),
+                upper_bound::<B>(&bid_sets),
+                control.options().strategy,
+            )
+            .with_cliques(&cliques[split_depth..]);
+            search.start(
+                subtree.items_selected,
+                subtree.bids_selected,
+                subtree.selected_value,
+            );
+            search.run(items, control, u64::MAX);
+            search.into_highest(control)
+        })
+        .collect::<Vec<_>>();
+    // merge in subtree order, keeping the ties of the highest value
+    let mut interrupt: Option<Interrupt> = None;
+    let mut highest_value_bid_sets = vec![];
+    let mut highest_value = B::Value::zero();
+    for result in results {
+        let (bid_sets, value) = result.unwrap_or_else(|(stopped, highest)| {
+            interrupt.get_or_insert(stopped);
+            highest
+        });
+        match value.cmp(&highest_value) {
+            Ordering::Greater => {
+                highest_value_bid_sets = bid_sets;
+                highest_value = value;
+            }
+            Ordering::Equal => highest_value_bid_sets.extend(bid_sets),
+            Ordering::Less => (),
+        }
+    }
+    if !control.allows_ties(highest_value_bid_sets.len()) {
+        interrupt.get_or_insert(Interrupt::ResourceLimit(
+            ResourceLimit::TiedOutcomes,
+        ));
+    }
+    let highest = (highest_value_bid_sets, highest_value);
+    let result = match interrupt {
+        None => Ok(highest),
+        Some(interrupt) => Err((interrupt, highest)),
+    };
+    let mut result =
+        improve_timed_out(items, exclusive_bid_sets, control, phase, result);
+    if ordered.is_some() {
+        let (Ok((outcomes, _)) | Err((_, (outcomes, _)))) = &mut result;
+        restore_order(exclusive_bid_sets, outcomes);
+    }
+    result
+}
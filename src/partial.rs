@@ -0,0 +1,137 @@
+//! Running an auction without the bid sets that are malformed, so one
+//! bidder's bad submission doesn't fail everyone else's auction.
+
+use std::fmt;
+
+use crate::{
+    config::{item_stock, run_with_stock},
+    dry_run::input_problems,
+    input::bid_set_refs,
+    AuctionConfig, AuctionError, AuctionOutcome, Bid, InputError,
+    ResourceLimit, StockEntry,
+};
+
+/// A bid set left out of an auction by [`run_skipping_malformed`], with
+/// every problem found with it.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SkippedBidSet {
+    /// Position of the bid set among those given to the auction.
+    pub bid_set: usize,
+    /// An [`AuctionError::Input`] for each bid with unknown or repeated items,
+    /// in order, then [`ResourceLimit::BidsPerSet`] if the bid set has more
+    /// bids than the config allows.
+    pub errors: Vec<AuctionError>,
+}
+
+/// The outcome of an auction and the bid sets it was run without, returned
+/// by [`run_skipping_malformed`].
+pub struct PartialOutcome<'a, B: Bid> {
+    /// The outcome of the auction of the remaining bid sets. Bids are those
+    /// passed to the auction, so they can be found among all the bid sets.
+    pub outcome: AuctionOutcome<'a, B>,
+    /// The bid sets left out, in order.
+    pub skipped: Vec<SkippedBidSet>,
+}
+
+// manual impl, since the derive can't bound on the fields' associated types
+impl<'a, B: Bid> fmt::Debug for PartialOutcome<'a, B>
+where
+    AuctionOutcome<'a, B>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartialOutcome")
+            .field("outcome", &self.outcome)
+            .field("skipped", &self.skipped)
+            .finish()
+    }
+}
+
+/// Run an auction like [`run`](crate::run), but leave out the bid sets that
+/// have a bid with an item not in stock or listed twice, or more bids than
+/// [`max_bids_per_set`](crate::SearchOptions::max_bids_per_set), instead of
+/// failing or searching them. The bid sets left out are returned with their
+/// problems, so their bidders can be told.
+///
+/// Problems with the auction as a whole, such as with its stock or its
+/// number of bid sets, still fail it.
+///
+/// ```
+/// use vcg_auction::{
+///     run_skipping_malformed, types::SimpleBid, AuctionConfig, AuctionError,
+///     InputError,
+/// };
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 20, [("chair", 1), ("sofa", 1)])],
+///     vec![SimpleBid::new("Carol", 7, [("chair", 1)])],
+/// ];
+/// let config = AuctionConfig::deterministic();
+/// let partial = run_skipping_malformed(&items, &bids, &config)?;
+/// // Bob's bid is for a sofa that isn't for sale, so Alice wins
+/// let result = partial.outcome.complete().unwrap();
+/// assert_eq!(result.winning_bids, [&bids[0][0]]);
+/// assert_eq!(result.payments, [(&"Alice".to_string(), 7)]);
+/// assert_eq!(partial.skipped[0].bid_set, 1);
+/// assert_eq!(
+///     partial.skipped[0].errors,
+///     [AuctionError::Input(InputError::UnknownItem { bid_set: 1, bid: 0 })]
+/// );
+/// # Ok::<(), AuctionError>(())
+/// ```
+pub fn run_skipping_malformed<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    config: &AuctionConfig<B>,
+) -> Result<PartialOutcome<'a, B>, AuctionError>
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let items = items
+        .into_iter()
+        .map(StockEntry::into_entry)
+        .collect::<Vec<_>>();
+    let stock = item_stock(items.iter().copied(), config)?;
+    let stock_items = items.iter().map(|(item, _)| *item).collect::<Vec<_>>();
+    let bid_sets = bid_set_refs(exclusive_bid_sets);
+
+    let mut skipped = (0..bid_sets.len())
+        .map(|bid_set| SkippedBidSet {
+            bid_set,
+            errors: vec![],
+        })
+        .collect::<Vec<_>>();
+    for problem in input_problems::<B>(&stock_items, &bid_sets) {
+        match problem {
+            InputError::UnknownItem { bid_set, .. }
+            | InputError::DuplicateBidItem { bid_set, .. } => {
+                skipped[bid_set].errors.push(AuctionError::Input(problem));
+            }
+            // the stock was already checked as the config says
+            _ => {}
+        }
+    }
+    if let Some(max_bids) = config.search.max_bids_per_set {
+        for (bid_set, bids) in bid_sets.iter().enumerate() {
+            if bids.len() > max_bids {
+                let limit = ResourceLimit::BidsPerSet;
+                skipped[bid_set]
+                    .errors
+                    .push(AuctionError::ResourceLimit(limit));
+            }
+        }
+    }
+    skipped.retain(|bid_set| !bid_set.errors.is_empty());
+
+    let bid_sets = bid_sets
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !skipped.iter().any(|s| s.bid_set == *i))
+        .map(|(_, bids)| bids)
+        .collect();
+    let outcome = run_with_stock(&stock, bid_sets, config)?;
+    Ok(PartialOutcome { outcome, skipped })
+}
@@ -0,0 +1,171 @@
+//! Multi-stage auctions: qualification rounds that screen the bids, then a
+//! final round clearing the bids that qualified, as regulated auctions often
+//! require.
+
+use std::fmt;
+
+use crate::{
+    config::{item_stock, run_with_stock},
+    input::bid_set_refs,
+    AuctionConfig, AuctionError, AuctionOutcome, Bid, StockEntry,
+};
+
+/// Whether a bid qualifies in a [`Qualification::Custom`] round.
+pub type QualifyFn<'q, B> = dyn Fn(&B) -> bool + 'q;
+
+/// A qualification round of [`run_pipeline`], which bids must pass to go on
+/// to the next round.
+pub enum Qualification<'q, B: Bid> {
+    /// Bids worth at least this much qualify, like a preliminary price
+    /// threshold.
+    MinValue(B::Value),
+    /// Bids the function accepts qualify, such as those of bidders found
+    /// eligible or scoring high enough.
+    Custom(&'q QualifyFn<'q, B>),
+}
+
+impl<B: Bid> Qualification<'_, B> {
+    /// Whether the bid passes the round.
+    pub fn qualifies(&self, bid: &B) -> bool {
+        match self {
+            Qualification::MinValue(value) => bid.bid_value() >= value,
+            Qualification::Custom(qualifies) => qualifies(bid),
+        }
+    }
+}
+
+// manual impl, since the derive can't bound on the fields' associated types
+impl<B: Bid> fmt::Debug for Qualification<'_, B>
+where
+    B::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Qualification::MinValue(value) => {
+                f.debug_tuple("MinValue").field(value).finish()
+            }
+            Qualification::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// What happened to the bids in a qualification round of a pipeline.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RoundReport {
+    /// Number of bids that entered the round.
+    pub entered: usize,
+    /// Bids that didn't qualify, as positions among the bid sets given to
+    /// the pipeline, in order.
+    pub disqualified: Vec<(usize, usize)>,
+}
+
+/// Every round of an auction run by [`run_pipeline`].
+pub struct PipelineReport<'a, B: Bid> {
+    /// The qualification rounds, in order.
+    pub rounds: Vec<RoundReport>,
+    /// The outcome of the final round, clearing the bids that passed every
+    /// qualification round. Bids are those passed to the pipeline.
+    pub outcome: AuctionOutcome<'a, B>,
+}
+
+// manual impl, since the derive can't bound on the fields' associated types
+impl<'a, B: Bid> fmt::Debug for PipelineReport<'a, B>
+where
+    AuctionOutcome<'a, B>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PipelineReport")
+            .field("rounds", &self.rounds)
+            .field("outcome", &self.outcome)
+            .finish()
+    }
+}
+
+/// Run the qualification rounds in order, each screening the bids that
+/// passed the rounds before it, then clear the bids that passed them all
+/// like [`run`](crate::run). Returns a report of every round.
+///
+/// ```
+/// use vcg_auction::{
+///     run_pipeline, types::SimpleBid, AuctionConfig, Qualification,
+/// };
+///
+/// let items = vec![("licence".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("licence", 1)])],
+///     vec![SimpleBid::new("Bob", 7, [("licence", 1)])],
+///     vec![SimpleBid::new("Carol", 3, [("licence", 1)])],
+/// ];
+/// let eligible = |bid: &SimpleBid| bid.name != "Alice";
+/// let rounds = [
+///     Qualification::MinValue(5),
+///     Qualification::Custom(&eligible),
+/// ];
+/// let config = AuctionConfig::deterministic();
+/// let report = run_pipeline(&items, &bids, &rounds, &config)?;
+/// assert_eq!(report.rounds[0].disqualified, [(2, 0)]);
+/// assert_eq!(report.rounds[1].disqualified, [(0, 0)]);
+/// // Bob is the only bidder left, so pays nothing
+/// let result = report.outcome.complete().unwrap();
+/// assert_eq!(result.winning_bids, [&bids[1][0]]);
+/// assert_eq!(result.payments, [(&"Bob".to_string(), 0)]);
+/// # Ok::<(), vcg_auction::AuctionError>(())
+/// ```
+pub fn run_pipeline<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    rounds: &[Qualification<B>],
+    config: &AuctionConfig<B>,
+) -> Result<PipelineReport<'a, B>, AuctionError>
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let stock = item_stock(items, config)?;
+    let bid_sets = bid_set_refs(exclusive_bid_sets);
+    // positions of the bids still in the auction
+    let mut remaining = bid_sets
+        .iter()
+        .enumerate()
+        .map(|(bid_set, bids)| {
+            (0..bids.len())
+                .map(|bid| (bid_set, bid))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut reports = vec![];
+    for round in rounds {
+        let mut report = RoundReport {
+            entered: remaining.iter().map(Vec::len).sum(),
+            disqualified: vec![],
+        };
+        for positions in &mut remaining {
+            positions.retain(|&(bid_set, bid)| {
+                let qualifies = round.qualifies(bid_sets[bid_set][bid]);
+                if !qualifies {
+                    report.disqualified.push((bid_set, bid));
+                }
+                qualifies
+            });
+        }
+        reports.push(report);
+    }
+
+    let qualified = remaining
+        .into_iter()
+        .filter(|positions| !positions.is_empty())
+        .map(|positions| {
+            positions
+                .into_iter()
+                .map(|(bid_set, bid)| bid_sets[bid_set][bid])
+                .collect()
+        })
+        .collect();
+    let outcome = run_with_stock(&stock, qualified, config)?;
+    Ok(PipelineReport {
+        rounds: reports,
+        outcome,
+    })
+}
@@ -0,0 +1,241 @@
+//! Preset for auctions paid in points or another virtual currency, such as
+//! loyalty points or an internal budget for allocating shared resources.
+//!
+//! Every bidder has a balance of points, which bounds what they can pay. The
+//! balance is enforced as a hard constraint: each bidder's balance is an item
+//! of the auction, and every bid spends its value of it, so the bids a bidder
+//! wins are worth no more than their balance in total. A VCG payment is never
+//! more than the value of the bids won, so no bidder pays more than they
+//! have. The result includes each bidder's balance after paying.
+//!
+//! ```
+//! use vcg_auction::points::PointsMarket;
+//!
+//! let mut market = PointsMarket::new([("desk", 1), ("parking", 1)]);
+//! market.balance("Alice", 100).balance("Bob", 50);
+//! let bids = vec![
+//!     vec![market.bid("Alice", 80, [("desk", 1)])?],
+//!     vec![market.bid("Alice", 60, [("parking", 1)])?],
+//!     vec![market.bid("Bob", 50, [("desk", 1)])?],
+//! ];
+//! let cleared = market.clear(&bids, |_| 0).unwrap();
+//! // Alice can't spend 140 points, so she gets parking and Bob the desk
+//! assert_eq!(cleared.winning_bids, [&bids[1][0], &bids[2][0]]);
+//! assert_eq!(
+//!     cleared.balances,
+//!     [(&"Alice".to_string(), 100), (&"Bob".to_string(), 30)]
+//! );
+//! # Ok::<(), vcg_auction::points::PointsError>(())
+//! ```
+
+use std::fmt;
+
+use crate::{vcg_auction_with_tiebreaker, Bid};
+
+/// Item of a points auction.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum PointsItem {
+    /// A good for sale.
+    Good(String),
+    /// Points a bidder can still spend.
+    Balance(String),
+}
+
+/// A bid paid in points, created with [`PointsMarket::bid`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PointsBid {
+    pub name: String,
+    /// Most points the bidder would pay for the goods.
+    pub value: u64,
+    items: Vec<(PointsItem, u64)>,
+}
+
+impl PointsBid {
+    /// Quantity of each good of the bid.
+    pub fn goods(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.items.iter().filter_map(|(item, quantity)| match item {
+            PointsItem::Good(good) => Some((good.as_str(), *quantity)),
+            PointsItem::Balance(_) => None,
+        })
+    }
+}
+
+impl Bid for PointsBid {
+    type Name = String;
+    type Value = u64;
+    type Item = PointsItem;
+    type Quantity = u64;
+
+    fn bidder_name(&self) -> &Self::Name {
+        &self.name
+    }
+    fn bid_value(&self) -> &Self::Value {
+        &self.value
+    }
+    fn bid_items(&self) -> &[(Self::Item, Self::Quantity)] {
+        &self.items
+    }
+}
+
+/// Reasons a bid can't be made in a [`PointsMarket`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum PointsError {
+    /// The good isn't for sale.
+    UnknownGood(String),
+    /// The bidder has no balance.
+    NoBalance(String),
+    /// The bid is worth more than the bidder's balance.
+    OverBalance { value: u64, balance: u64 },
+}
+
+impl fmt::Display for PointsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PointsError::UnknownGood(good) => {
+                write!(f, "good {good:?} isn't for sale")
+            }
+            PointsError::NoBalance(bidder) => {
+                write!(f, "bidder {bidder:?} has no balance")
+            }
+            PointsError::OverBalance { value, balance } => write!(
+                f,
+                "bid of {value} points is more than the balance of {balance}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PointsError {}
+
+/// The result of clearing a [`PointsMarket`].
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct PointsClearing<'a> {
+    /// Winning bids, in the order of their bid sets.
+    pub winning_bids: Vec<&'a PointsBid>,
+    /// Points paid by each winner.
+    pub payments: Vec<(&'a String, u64)>,
+    /// Balance of every bidder after paying, in the order they were given.
+    pub balances: Vec<(&'a String, u64)>,
+}
+
+/// The goods of a points auction and the balance of each bidder.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct PointsMarket {
+    goods: Vec<(String, u64)>,
+    balances: Vec<(String, u64)>,
+}
+
+impl PointsMarket {
+    /// A market for the goods, with the quantity of each. A good listed more
+    /// than once keeps its first quantity.
+    pub fn new<G: Into<String>>(
+        goods: impl IntoIterator<Item = (G, u64)>,
+    ) -> Self {
+        let mut unique: Vec<(String, u64)> = vec![];
+        for (good, quantity) in goods {
+            let good = good.into();
+            if !unique.iter().any(|(g, _)| *g == good) {
+                unique.push((good, quantity));
+            }
+        }
+        Self {
+            goods: unique,
+            balances: vec![],
+        }
+    }
+
+    /// Set a bidder's balance, replacing any balance set before. Balances
+    /// are checked when bids are made.
+    pub fn balance(
+        &mut self,
+        bidder: impl Into<String>,
+        points: u64,
+    ) -> &mut Self {
+        let bidder = bidder.into();
+        match self.balances.iter_mut().find(|(b, _)| *b == bidder) {
+            Some((_, balance)) => *balance = points,
+            None => self.balances.push((bidder, points)),
+        }
+        self
+    }
+
+    /// Balance of each bidder, in the order they were given.
+    pub fn balances(&self) -> &[(String, u64)] {
+        &self.balances
+    }
+
+    /// A bidder's bid of points for goods. Quantities of the same good are
+    /// added together.
+    pub fn bid<G: AsRef<str>>(
+        &self,
+        bidder: impl Into<String>,
+        value: u64,
+        goods: impl IntoIterator<Item = (G, u64)>,
+    ) -> Result<PointsBid, PointsError> {
+        let name = bidder.into();
+        let Some(&(_, balance)) =
+            self.balances.iter().find(|(b, _)| *b == name)
+        else {
+            return Err(PointsError::NoBalance(name));
+        };
+        if value > balance {
+            return Err(PointsError::OverBalance { value, balance });
+        }
+        let mut items: Vec<(PointsItem, u64)> = vec![];
+        for (good, quantity) in goods {
+            let good = good.as_ref();
+            if !self.goods.iter().any(|(g, _)| g == good) {
+                return Err(PointsError::UnknownGood(good.into()));
+            }
+            let item = PointsItem::Good(good.into());
+            match items.iter_mut().find(|(i, _)| *i == item) {
+                Some((_, total)) => *total = total.saturating_add(quantity),
+                None => items.push((item, quantity)),
+            }
+        }
+        if value > 0 {
+            items.push((PointsItem::Balance(name.clone()), value));
+        }
+        Ok(PointsBid { name, value, items })
+    }
+
+    /// Items of the auction: the quantity of each good, then the balance of
+    /// each bidder.
+    pub fn items(&self) -> Vec<(PointsItem, u64)> {
+        let goods = self.goods.iter().map(|(good, quantity)| {
+            (PointsItem::Good(good.clone()), *quantity)
+        });
+        let balances = self.balances.iter().map(|(bidder, points)| {
+            (PointsItem::Balance(bidder.clone()), *points)
+        });
+        goods.chain(balances).collect()
+    }
+
+    /// Clear the market, breaking ties between outcomes with `tiebreaker`.
+    /// Returns `None` if the tiebreaker returns an invalid index.
+    pub fn clear<'a>(
+        &'a self,
+        bid_sets: &'a [Vec<PointsBid>],
+        tiebreaker: impl FnOnce(&[Vec<&PointsBid>]) -> usize,
+    ) -> Option<PointsClearing<'a>> {
+        let items = self.items();
+        let result = vcg_auction_with_tiebreaker(&items, bid_sets, tiebreaker)?;
+        let balances = self
+            .balances
+            .iter()
+            .map(|(bidder, points)| {
+                let paid = result.payment_for(bidder).copied().unwrap_or(0);
+                // payments are at most the value won, which the balance bounds
+                (bidder, points - paid)
+            })
+            .collect();
+        Some(PointsClearing {
+            winning_bids: result.winning_bids,
+            payments: result.payments,
+            balances,
+        })
+    }
+}
@@ -0,0 +1,277 @@
+//! Vetted configurations for common kinds of auction, runnable by name.
+//!
+//! Each [`Preset`] bundles a pricing rule, a tiebreak and the checks of its
+//! input that make sense together, so new users can start from a setup known
+//! to behave as its name says, and later build their own [`AuctionConfig`]
+//! from [`Preset::config`]. Every preset breaks ties with [`Tiebreak::First`]
+//! and returns results in [`ResultOrder::Input`], so its runs can be
+//! reproduced, and rejects stock listing an item twice and bids with unknown
+//! or repeated items.
+//!
+//! ```
+//! use vcg_auction::{presets, types::SimpleBid};
+//!
+//! let items = vec![("painting".to_string(), 1)];
+//! let bids = vec![
+//!     vec![SimpleBid::new("Alice", 10, [("painting", 1)])],
+//!     vec![SimpleBid::new("Bob", 7, [("painting", 1)])],
+//! ];
+//! let outcome = presets::run("single-item-vickrey", &items, &bids)?;
+//! let result = outcome.complete().unwrap();
+//! // the highest bidder wins, paying the second highest bid
+//! assert_eq!(result.winning_bids, [&bids[0][0]]);
+//! assert_eq!(result.payments, [(&"Alice".to_string(), 7)]);
+//! # Ok::<(), vcg_auction::presets::PresetError>(())
+//! ```
+
+use std::{fmt, str::FromStr};
+
+use num_traits::Zero;
+
+use crate::{
+    config::{item_stock, run_with_stock},
+    dry_run::input_problems,
+    input::bid_set_refs,
+    AuctionConfig, AuctionError, AuctionOutcome, Bid, DuplicateItems,
+    Objective, PricingRule, ResultOrder, StockEntry, Tiebreak,
+};
+
+/// A vetted configuration for a common kind of auction.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum Preset {
+    /// A Vickrey auction of one item: the stock is a single item, every bid
+    /// is for all of it, and the highest bidder wins paying the second
+    /// highest bid.
+    SingleItemVickrey,
+    /// A uniform price auction of identical units: the stock is a single
+    /// item, and every bid is for the same quantity of it. Winners pay the
+    /// highest rejected bid, with [`PricingRule::FirstRejectedBid`]. A bidder
+    /// wanting several lots bids for each in its own bid set.
+    MultiUnitUniform,
+    /// A combinatorial VCG auction, of any bids for packages of items.
+    CombinatorialVcg,
+    /// A reverse auction buying from suppliers, bid at the savings of each
+    /// package over the buyer's reserve, as in the
+    /// [`freight`](crate::freight) module. Every bid must save something,
+    /// and between tied outcomes the one awarding the least to any one
+    /// supplier is chosen, with [`Objective::MinConcentration`].
+    Procurement,
+}
+
+impl Preset {
+    /// Every preset, in the order they're documented.
+    pub const ALL: [Preset; 4] = [
+        Preset::SingleItemVickrey,
+        Preset::MultiUnitUniform,
+        Preset::CombinatorialVcg,
+        Preset::Procurement,
+    ];
+
+    /// The name the preset is run by.
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::SingleItemVickrey => "single-item-vickrey",
+            Preset::MultiUnitUniform => "multi-unit-uniform",
+            Preset::CombinatorialVcg => "combinatorial-vcg",
+            Preset::Procurement => "procurement",
+        }
+    }
+
+    /// The config the preset runs with.
+    pub fn config<'c, B: Bid>(self) -> AuctionConfig<'c, B> {
+        let builder = AuctionConfig::builder()
+            .tiebreak(Tiebreak::First)
+            .duplicate_items(DuplicateItems::Reject)
+            .result_order(ResultOrder::Input);
+        match self {
+            Preset::SingleItemVickrey | Preset::CombinatorialVcg => {
+                builder.pricing(PricingRule::Vcg)
+            }
+            Preset::MultiUnitUniform => {
+                builder.pricing(PricingRule::FirstRejectedBid)
+            }
+            Preset::Procurement => builder
+                .pricing(PricingRule::Vcg)
+                .objectives(&[Objective::MinConcentration]),
+        }
+        .build()
+    }
+
+    /// Check that the items and bid sets are ones the preset is for, without
+    /// running the auction.
+    pub fn validate<'a, 'i, B, S, E>(
+        self,
+        items: impl IntoIterator<Item = E>,
+        exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    ) -> Result<(), PresetError>
+    where
+        B: Bid + 'i + 'a,
+        S: AsRef<[B]> + ?Sized + 'a,
+        E: StockEntry<'i, B::Item, B::Quantity>,
+    {
+        let items = items
+            .into_iter()
+            .map(StockEntry::into_entry)
+            .collect::<Vec<_>>();
+        self.check(&items, &bid_set_refs(exclusive_bid_sets))
+    }
+
+    /// Run an auction with the preset, after checking its input.
+    pub fn run<'a, 'i, B, S, E>(
+        self,
+        items: impl IntoIterator<Item = E>,
+        exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    ) -> Result<AuctionOutcome<'a, B>, PresetError>
+    where
+        B: Bid + 'i,
+        S: AsRef<[B]> + ?Sized + 'a,
+        E: StockEntry<'i, B::Item, B::Quantity>,
+    {
+        let items = items
+            .into_iter()
+            .map(StockEntry::into_entry)
+            .collect::<Vec<_>>();
+        let bid_sets = bid_set_refs(exclusive_bid_sets);
+        self.check(&items, &bid_sets)?;
+        let config = self.config();
+        let stock = item_stock(items.iter().copied(), &config)?;
+        Ok(run_with_stock(&stock, bid_sets, &config)?)
+    }
+
+    /// The first problem with the items and bid sets for the preset.
+    fn check<B: Bid>(
+        self,
+        items: &[(&B::Item, &B::Quantity)],
+        exclusive_bid_sets: &[Vec<&B>],
+    ) -> Result<(), PresetError> {
+        let stock_items =
+            items.iter().map(|(item, _)| *item).collect::<Vec<_>>();
+        if let Some(problem) =
+            input_problems::<B>(&stock_items, exclusive_bid_sets)
+                .into_iter()
+                .next()
+        {
+            return Err(AuctionError::Input(problem).into());
+        }
+
+        let unsupported_stock = PresetError::UnsupportedStock(self);
+        let single_item = match self {
+            Preset::SingleItemVickrey | Preset::MultiUnitUniform => {
+                let &[(_, quantity)] = items else {
+                    return Err(unsupported_stock);
+                };
+                Some(quantity)
+            }
+            Preset::CombinatorialVcg | Preset::Procurement => None,
+        };
+        let mut lot = None;
+        for (bid_set, bids) in exclusive_bid_sets.iter().enumerate() {
+            for (bid, b) in bids.iter().enumerate() {
+                let supported = match self {
+                    Preset::SingleItemVickrey => match b.bid_items() {
+                        [(_, q)] => Some(q) == single_item,
+                        _ => false,
+                    },
+                    Preset::MultiUnitUniform => match b.bid_items() {
+                        [(_, q)] => q == *lot.get_or_insert(q),
+                        _ => false,
+                    },
+                    Preset::CombinatorialVcg => true,
+                    Preset::Procurement => *b.bid_value() > B::Value::zero(),
+                };
+                if !supported {
+                    return Err(PresetError::UnsupportedBid {
+                        preset: self,
+                        bid_set,
+                        bid,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Preset {
+    type Err = PresetError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Preset::ALL
+            .into_iter()
+            .find(|preset| preset.name() == name)
+            .ok_or_else(|| PresetError::UnknownPreset(name.into()))
+    }
+}
+
+/// Reasons a preset can't be run.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum PresetError {
+    /// No preset has the name.
+    UnknownPreset(String),
+    /// The stock isn't one the preset is for, such as several items for a
+    /// single item auction.
+    UnsupportedStock(Preset),
+    /// The bid isn't one the preset is for.
+    UnsupportedBid {
+        preset: Preset,
+        bid_set: usize,
+        bid: usize,
+    },
+    /// The auction failed.
+    Auction(AuctionError),
+}
+
+impl From<AuctionError> for PresetError {
+    fn from(error: AuctionError) -> Self {
+        PresetError::Auction(error)
+    }
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetError::UnknownPreset(name) => {
+                write!(f, "no preset is named {name:?}")
+            }
+            PresetError::UnsupportedStock(preset) => {
+                write!(f, "the stock isn't one preset {preset} is for")
+            }
+            PresetError::UnsupportedBid {
+                preset,
+                bid_set,
+                bid,
+            } => write!(
+                f,
+                "bid {bid} of bid set {bid_set} isn't one preset {preset} is \
+                 for"
+            ),
+            PresetError::Auction(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for PresetError {}
+
+/// Run an auction with the preset of a [name](Preset::name), after checking
+/// its input.
+pub fn run<'a, 'i, B, S>(
+    name: &str,
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+) -> Result<AuctionOutcome<'a, B>, PresetError>
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    name.parse::<Preset>()?.run(items, exclusive_bid_sets)
+}
@@ -0,0 +1,98 @@
+//! Rules for pricing the winning bids of an auction.
+
+use num_traits::Zero;
+
+use std::cmp::Ordering;
+
+use crate::{vcg::winning_bidders, AddSubSelf, Bid};
+
+/// How winners pay for the bids they won, used with
+/// [`Allocation::price`](crate::Allocation::price).
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum PricingRule {
+    /// Each winner pays the harm they cause to the other bidders.
+    #[default]
+    Vcg,
+    /// Each winner pays the value of their winning bids.
+    FirstPrice,
+    /// Each winning bid pays the value of the highest rejected bid for the
+    /// same items, up to its own value, or nothing if no such bid was
+    /// rejected. A bid is rejected when none of the bids in its bid set won.
+    ///
+    /// This is the uniform price of an auction of identical units with a bid
+    /// for each unit, like a treasury auction, where every winner pays the
+    /// highest losing bid. Bids for several units, or for different items,
+    /// are only priced against rejected bids for the same quantities of the
+    /// same items.
+    FirstRejectedBid,
+}
+
+/// First-price payments: the total value of each winner's winning bids.
+pub(crate) fn first_price_payments<'a, B: Bid>(
+    winning_bids: &[&'a B],
+) -> Vec<(&'a B::Name, B::Value)> {
+    winning_bidders(winning_bids)
+        .into_iter()
+        .map(|bidder_name| {
+            let payment = winning_bids
+                .iter()
+                .filter(|b| b.bidder_name() == bidder_name)
+                .fold(B::Value::zero(), |acc, b| acc.add(b.bid_value()));
+            (bidder_name, payment)
+        })
+        .collect()
+}
+
+/// First-rejected-bid payments: each winning bid pays the highest value of a
+/// rejected bid for the same items, capped at its own value.
+pub(crate) fn first_rejected_bid_payments<'a, B: Bid>(
+    winning_bids: &[&'a B],
+    exclusive_bid_sets: &[Vec<&'a B>],
+) -> Vec<(&'a B::Name, B::Value)> {
+    let rejected = exclusive_bid_sets
+        .iter()
+        .filter(|bid_set| {
+            !bid_set.iter().any(|bid| {
+                winning_bids
+                    .iter()
+                    .any(|winner| std::ptr::eq(*winner, *bid))
+            })
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+    let price = |winning_bid: &'a B| {
+        rejected
+            .iter()
+            .filter(|bid| same_items(***bid, winning_bid))
+            .map(|bid| bid.bid_value())
+            .max()
+            .map(|value| value.min(winning_bid.bid_value()))
+    };
+    winning_bidders(winning_bids)
+        .into_iter()
+        .map(|bidder_name| {
+            let payment = winning_bids
+                .iter()
+                .filter(|b| b.bidder_name() == bidder_name)
+                .filter_map(|b| price(b))
+                .fold(B::Value::zero(), |acc, price| acc.add(price));
+            (bidder_name, payment)
+        })
+        .collect()
+}
+
+/// Whether two bids are for the same quantities of the same items.
+fn same_items<B: Bid>(a: &B, b: &B) -> bool {
+    let (a, b) = (a.bid_items(), b.bid_items());
+    a.len() == b.len()
+        && a.iter().all(|(item, quantity)| {
+            b.iter().any(|(other_item, other_quantity)| {
+                item == other_item
+                    && quantity.partial_cmp(other_quantity)
+                        == Some(Ordering::Equal)
+            })
+        })
+}
@@ -0,0 +1,137 @@
+//! Aggregate statistics of an auction for publishing, with noise added so
+//! they don't give away individual bids.
+//!
+//! Settlements use the exact [`AuctionResult`]; only the published
+//! [`Aggregates`] are made noisy, with the Laplace mechanism of differential
+//! privacy.
+
+use num_traits::ToPrimitive;
+use rand::{distributions::Open01, Rng};
+
+use crate::{AuctionResult, Bid, StockEntry};
+
+/// How much noise [`Aggregates::noisy`] adds.
+///
+/// Each statistic gets Laplace noise of scale `sensitivity / ε`, where ε is
+/// `epsilon` split evenly between the statistics published, so together they
+/// are `epsilon`-differentially private with respect to one bidder's bids,
+/// as long as the sensitivities bound what one bidder can change.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "rng")))]
+pub struct AggregateNoise {
+    /// Privacy budget of all the statistics together. Smaller is more
+    /// private and noisier.
+    pub epsilon: f64,
+    /// The most one bidder's bids can change the total revenue by, such as
+    /// the largest bid allowed.
+    pub revenue_sensitivity: f64,
+    /// The most one bidder's bids can change the quantity allocated of any
+    /// item by, such as the largest quantity one bid may ask for.
+    pub quantity_sensitivity: f64,
+}
+
+/// Aggregate statistics of an auction's result, to publish instead of the
+/// result itself.
+///
+/// ```
+/// use rand::{rngs::StdRng, SeedableRng};
+/// use vcg_auction::{
+///     types::SimpleBid, vcg_auction_with_tiebreaker, AggregateNoise,
+///     Aggregates,
+/// };
+///
+/// let items = vec![("chair".to_string(), 2)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 8, [("chair", 1)])],
+///     vec![SimpleBid::new("Carol", 7, [("chair", 1)])],
+/// ];
+/// let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+/// let exact = Aggregates::of(&items, &result);
+/// assert_eq!(exact.total_revenue, 14.0);
+/// assert_eq!(exact.allocated, [(&"chair".to_string(), 2.0)]);
+///
+/// let noise = AggregateNoise {
+///     epsilon: 1.0,
+///     revenue_sensitivity: 10.0,
+///     quantity_sensitivity: 1.0,
+/// };
+/// let published = exact.noisy(&noise, &mut StdRng::seed_from_u64(7));
+/// assert_ne!(published.total_revenue, exact.total_revenue);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "rng")))]
+pub struct Aggregates<'i, I> {
+    /// Total paid by the winners.
+    pub total_revenue: f64,
+    /// Quantity allocated to the winning bids of each item in stock, in the
+    /// stock's order.
+    pub allocated: Vec<(&'i I, f64)>,
+}
+
+impl<'i, I> Aggregates<'i, I> {
+    /// The exact statistics of a result of an auction of the items. Values
+    /// and quantities that can't be converted to `f64` are counted as NaN.
+    pub fn of<'a, B>(
+        items: impl IntoIterator<Item = impl StockEntry<'i, I, B::Quantity>>,
+        result: &AuctionResult<'a, B>,
+    ) -> Self
+    where
+        I: Eq,
+        B: Bid<Item = I> + 'i,
+        B::Value: ToPrimitive,
+        B::Quantity: ToPrimitive,
+    {
+        let to_f64 = |x: Option<f64>| x.unwrap_or(f64::NAN);
+        let total_revenue = result
+            .payments
+            .iter()
+            .map(|(_, payment)| to_f64(payment.to_f64()))
+            .sum();
+        let allocated = items
+            .into_iter()
+            .map(|entry| {
+                let (item, _) = entry.into_entry();
+                let quantity = result
+                    .winning_bids
+                    .iter()
+                    .flat_map(|bid| bid.bid_items())
+                    .filter(|(i, _)| i == item)
+                    .map(|(_, quantity)| to_f64(quantity.to_f64()))
+                    .sum();
+                (item, quantity)
+            })
+            .collect();
+        Self {
+            total_revenue,
+            allocated,
+        }
+    }
+
+    /// The statistics with Laplace noise added as `noise` says, drawn from
+    /// `rng`.
+    pub fn noisy(&self, noise: &AggregateNoise, rng: &mut impl Rng) -> Self {
+        let statistics = 1 + self.allocated.len();
+        let epsilon = noise.epsilon / statistics as f64;
+        let mut laplace = |sensitivity: f64| {
+            // inverse of the Laplace distribution's CDF at a uniform draw,
+            // which is infinite at the ends of the interval
+            let u = rng.sample::<f64, _>(Open01) - 0.5;
+            let scale = sensitivity / epsilon;
+            -scale * u.signum() * (1.0 - 2.0 * u.abs()).ln()
+        };
+        Self {
+            total_revenue: self.total_revenue
+                + laplace(noise.revenue_sensitivity),
+            allocated: self
+                .allocated
+                .iter()
+                .map(|&(item, quantity)| {
+                    (item, quantity + laplace(noise.quantity_sensitivity))
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Proof harnesses for the [Kani](https://github.com/model-checking/kani)
+//! model checker, run with `cargo kani`.
+//!
+//! Auctions are bounded to three bidders, three bid sets of at most two bids,
+//! two items, quantities of at most two and values of at most 15, and every
+//! auction within the bounds is checked. Arithmetic overflow fails a proof, so
+//! the harnesses also check that no sum or payment overflows or underflows.
+//!
+//! **Unverified:** these harnesses have not been run with Kani, or even
+//! compiled, since the `kani` crate only exists under `cargo kani`. Until a
+//! run is recorded here, treat them as a specification of what should hold,
+//! not as proofs that it does.
+
+use crate::{
+    control::SearchControl,
+    input::{bid_set_refs, ItemStock},
+    quantities::within_stock,
+    vcg::{run_auction, total_value},
+    AuctionOutcome, AuctionResult, Bid, SearchOptions,
+};
+
+const ITEMS: [u8; 2] = [0, 1];
+
+/// A bid with small types, to keep the state space of a proof small.
+#[derive(Debug)]
+struct ProofBid {
+    bidder: u8,
+    value: u16,
+    items: Vec<(u8, u8)>,
+}
+
+impl Bid for ProofBid {
+    type Name = u8;
+    type Value = u16;
+    type Item = u8;
+    type Quantity = u8;
+
+    fn bidder_name(&self) -> &u8 {
+        &self.bidder
+    }
+
+    fn bid_value(&self) -> &u16 {
+        &self.value
+    }
+
+    fn bid_items(&self) -> &[(u8, u8)] {
+        &self.items
+    }
+}
+
+fn any_quantity() -> u8 {
+    let quantity = kani::any();
+    kani::assume(quantity <= 2);
+    quantity
+}
+
+fn any_stock() -> Vec<(u8, u8)> {
+    ITEMS.iter().map(|&item| (item, any_quantity())).collect()
+}
+
+fn any_bid() -> ProofBid {
+    let bidder = kani::any();
+    kani::assume(bidder < 3);
+    let value = kani::any();
+    kani::assume(value <= 15);
+    let items = ITEMS
+        .iter()
+        .map(|&item| (item, any_quantity()))
+        .filter(|(_, quantity)| *quantity > 0)
+        .collect();
+    ProofBid {
+        bidder,
+        value,
+        items,
+    }
+}
+
+fn any_bid_sets() -> Vec<Vec<ProofBid>> {
+    let count: usize = kani::any();
+    kani::assume(count <= 3);
+    (0..count)
+        .map(|_| {
+            let len: usize = kani::any();
+            kani::assume(len <= 2);
+            (0..len).map(|_| any_bid()).collect()
+        })
+        .collect()
+}
+
+/// Run an auction without limits, choosing the first tied outcome.
+fn solve<'a>(
+    stock: &[(u8, u8)],
+    bid_sets: &'a [Vec<ProofBid>],
+) -> AuctionResult<'a, ProofBid> {
+    let control = SearchControl::new(SearchOptions::default());
+    match run_auction(
+        &ItemStock::new(stock),
+        bid_set_refs(bid_sets),
+        |_| 0,
+        &control,
+    ) {
+        Ok(AuctionOutcome::Complete(result)) => result,
+        _ => unreachable!("searches without limits complete"),
+    }
+}
+
+/// The highest value of the combinations of bids that fit in the stock, found
+/// by trying each one.
+fn highest_value(stock: &[(u8, u8)], bid_sets: &[Vec<ProofBid>]) -> u16 {
+    let combinations = bid_sets
+        .iter()
+        .map(|bid_set| bid_set.len() + 1)
+        .product::<usize>();
+    let mut highest = 0;
+    for mut combination in 0..combinations {
+        let mut used = [0; ITEMS.len()];
+        let mut value = 0;
+        // each bid set chooses a bid, or none with the index past its bids
+        for bid_set in bid_sets {
+            if let Some(bid) = bid_set.get(combination % (bid_set.len() + 1)) {
+                value += bid.value;
+                for &(item, quantity) in &bid.items {
+                    used[usize::from(item)] += quantity;
+                }
+            }
+            combination /= bid_set.len() + 1;
+        }
+        if used
+            .iter()
+            .zip(stock)
+            .all(|(used, (_, stock))| used <= stock)
+        {
+            highest = highest.max(value);
+        }
+    }
+    highest
+}
+
+#[kani::proof]
+#[kani::unwind(28)]
+fn winning_bids_have_the_highest_value() {
+    let stock = any_stock();
+    let bid_sets = any_bid_sets();
+    let result = solve(&stock, &bid_sets);
+    assert_eq!(
+        total_value(result.winning_bids.iter().copied()),
+        highest_value(&stock, &bid_sets)
+    );
+}
+
+#[kani::proof]
+#[kani::unwind(28)]
+fn payments_are_at_most_winning_bids() {
+    let stock = any_stock();
+    let bid_sets = any_bid_sets();
+    let result = solve(&stock, &bid_sets);
+    for (bidder, payment) in &result.payments {
+        let bids = result.winning_bids.iter().copied();
+        let value = total_value(bids.filter(|bid| bid.bidder == **bidder));
+        assert!(*payment <= value);
+    }
+}
+
+#[kani::proof]
+#[kani::unwind(11)]
+fn within_stock_compares_every_item() {
+    // a full chunk and a remainder
+    let selected: [u8; 10] = kani::any();
+    let stock: [u8; 10] = kani::any();
+    assert_eq!(
+        within_stock(&selected, &stock),
+        !selected
+            .iter()
+            .zip(&stock)
+            .any(|(selected, stock)| selected > stock)
+    );
+}
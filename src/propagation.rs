@@ -0,0 +1,183 @@
+//! Deductions made before searching, which shrink the bid sets without
+//! changing the outcomes tied for the highest value.
+//!
+//! A bid can't be in a highest-value outcome if its value is negative, since
+//! leaving it out would be worth more, or if it needs more of an item than is
+//! in stock. Nor can a bid if another bid of its bid set is worth more and
+//! uses no more of any item, since swapping them would be worth more.
+//!
+//! A bid is compulsory if it's alone in its bid set and worth more than the
+//! highest bids of the other bid sets on any of its items together: any
+//! outcome without it is worth less than dropping those bids and taking it
+//! instead. So it's in every highest-value outcome, and the bids of other bid
+//! sets that don't fit beside every compulsory bid can't be. Dropping bids
+//! can make more bids compulsory, so the deductions are repeated until none
+//! apply.
+//!
+//! None of this holds if bids can use negative quantities, which make room
+//! for other bids, so bid sets with any are searched as given.
+
+use num_traits::Zero;
+
+use crate::{
+    input::ItemStock, quantities::within_stock, AddSubSelf, Bid, DropReason,
+};
+
+/// A bid with the quantities of the items in stock it uses.
+type Demand<'a, B> = (&'a B, Vec<<B as Bid>::Quantity>);
+
+/// The bid sets without the bids that can't be in a highest-value outcome.
+/// The bids left keep their order, so searches find tied outcomes in the same
+/// order.
+pub(crate) fn propagate<'a, B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
+) -> Vec<Vec<&'a B>> {
+    propagate_logged(items, exclusive_bid_sets, |_, _| {})
+}
+
+/// Like [`propagate`], calling `dropped` with each bid dropped and why.
+pub(crate) fn propagate_logged<'a, B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
+    mut dropped: impl FnMut(&'a B, DropReason),
+) -> Vec<Vec<&'a B>> {
+    let stock = items.quantities();
+    let no_items = items.no_items_selected();
+    let zero = B::Value::zero();
+    let demands = exclusive_bid_sets
+        .iter()
+        .map(|bid_set| {
+            bid_set
+                .iter()
+                .map(|bid| (*bid, items.select_bid_items(&no_items, bid)))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    // a negative quantity could make room for other bids
+    let negative = demands.iter().flatten().any(|(_, quantities)| {
+        quantities
+            .iter()
+            .any(|quantity| *quantity < B::Quantity::zero())
+    });
+    if negative {
+        return exclusive_bid_sets.to_vec();
+    }
+    let mut bid_sets = demands
+        .into_iter()
+        .map(|bid_set| {
+            let bid_set = bid_set
+                .into_iter()
+                .filter(|(bid, quantities)| {
+                    if *bid.bid_value() < zero {
+                        dropped(bid, DropReason::NegativeValue);
+                        false
+                    } else if !within_stock(quantities, stock) {
+                        dropped(bid, DropReason::OverStock);
+                        false
+                    } else {
+                        true
+                    }
+                })
+                .collect::<Vec<_>>();
+            undominated(bid_set, &mut dropped)
+        })
+        .collect::<Vec<_>>();
+
+    loop {
+        let compulsory = (0..bid_sets.len())
+            .map(|i| is_compulsory(&bid_sets, i))
+            .collect::<Vec<_>>();
+        let mut used = no_items.clone();
+        for (bid_set, _) in bid_sets
+            .iter()
+            .zip(&compulsory)
+            .filter(|(_, compulsory)| **compulsory)
+        {
+            used = items.select_bid_items(&used, bid_set[0].0);
+        }
+
+        let mut any_dropped = false;
+        for (bid_set, compulsory) in bid_sets.iter_mut().zip(compulsory) {
+            if compulsory {
+                continue;
+            }
+            bid_set.retain(|(bid, _)| {
+                let fits =
+                    within_stock(&items.select_bid_items(&used, bid), stock);
+                if !fits {
+                    dropped(bid, DropReason::Excluded);
+                    any_dropped = true;
+                }
+                fits
+            });
+        }
+        if !any_dropped {
+            break;
+        }
+    }
+
+    bid_sets
+        .into_iter()
+        .map(|bid_set| bid_set.into_iter().map(|(bid, _)| bid).collect())
+        .collect()
+}
+
+/// The bids of a bid set that no other bid of the set is worth more than
+/// while using no more of any item.
+fn undominated<'a, B: Bid>(
+    bid_set: Vec<Demand<'a, B>>,
+    dropped: &mut impl FnMut(&'a B, DropReason),
+) -> Vec<Demand<'a, B>> {
+    let dominated = bid_set
+        .iter()
+        .map(|(bid, quantities)| {
+            bid_set.iter().any(|(other, other_quantities)| {
+                other.bid_value() > bid.bid_value()
+                    && other_quantities
+                        .iter()
+                        .zip(quantities)
+                        .all(|(other, quantity)| other <= quantity)
+            })
+        })
+        .collect::<Vec<_>>();
+    bid_set
+        .into_iter()
+        .zip(dominated)
+        .filter(|((bid, _), dominated)| {
+            if *dominated {
+                dropped(bid, DropReason::Dominated);
+            }
+            !dominated
+        })
+        .map(|(demand, _)| demand)
+        .collect()
+}
+
+/// Whether the bid set at `index` has one bid, worth more than the highest
+/// bids of the other bid sets that use any of its items.
+fn is_compulsory<B: Bid>(bid_sets: &[Vec<Demand<B>>], index: usize) -> bool {
+    let [(bid, quantities)] = &bid_sets[index][..] else {
+        return false;
+    };
+    let zero = B::Quantity::zero();
+    let overlaps = |other: &[B::Quantity]| {
+        quantities
+            .iter()
+            .zip(other)
+            .any(|(quantity, other)| *quantity > zero && *other > zero)
+    };
+    let displaced = bid_sets
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != index)
+        .filter_map(|(_, bid_set)| {
+            bid_set
+                .iter()
+                .filter(|(_, other)| overlaps(other))
+                .map(|(other, _)| other.bid_value())
+                .max()
+        })
+        .fold(B::Value::zero(), |sum, value| sum.add(value));
+    *bid.bid_value() > displaced
+}
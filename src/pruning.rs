@@ -0,0 +1,143 @@
+//! Log of the parts of an auction's search space that were discarded without
+//! being explored, so auditors can check that none could have won.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::{Bid, DropReason, DroppedBid, SearchPhase};
+
+/// What an auction discarded without exploring, returned by
+/// [`run_with_pruning_log`](crate::run_with_pruning_log).
+///
+/// Bids are identified by their position in the bid sets given to the
+/// auction, as `(bid set, bid)` indices.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PruningLog<V> {
+    /// Bid sets with no bids, which are dropped before searching.
+    pub empty_bid_sets: Vec<usize>,
+    /// Bids dropped before each search because they can't be in any outcome
+    /// tied for the highest value, with the search they were dropped from.
+    /// The searches for a payment may drop bids the search for the winners
+    /// kept, since they leave out the bids of the bidder paying.
+    pub dropped_bids: Vec<(SearchPhase, DroppedBid)>,
+    /// Branches cut because no choice of bids from the remaining bid sets
+    /// could beat the best value found, in the order they were cut.
+    pub bound_cuts: Vec<BoundCut<V>>,
+}
+
+// manual impl, since the derive would require `V: Default`
+impl<V> Default for PruningLog<V> {
+    fn default() -> Self {
+        Self {
+            empty_bid_sets: vec![],
+            dropped_bids: vec![],
+            bound_cuts: vec![],
+        }
+    }
+}
+
+/// A branch of a search cut by its bound. Every combination of `bids` with at
+/// most one bid from each of `remaining_bid_sets` was discarded.
+///
+/// The remaining bid sets are grouped into `cliques`, whose bid sets' bids
+/// all conflict: two bids of different bid sets of a clique together need
+/// more of some item than is in stock, so at most one bid of a clique can be
+/// chosen. The cut is valid if they do, and `bound` is the sum of `value` and
+/// the highest bid of each clique, and is below `best`. Bids the search
+/// dropped, as listed in [`PruningLog::dropped_bids`], are left out of the
+/// cliques and aren't among the highest. In the
+/// searches for a payment, the bids of the bidder paying are left out of the
+/// remaining bid sets, so their highest bid is among the other bidders'
+/// bids.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct BoundCut<V> {
+    /// The search the branch belongs to.
+    pub phase: SearchPhase,
+    /// Bids chosen on the branch.
+    pub bids: Vec<(usize, usize)>,
+    /// Bid sets not yet considered on the branch.
+    pub remaining_bid_sets: Vec<usize>,
+    /// The remaining bid sets grouped into cliques, each listed in the order
+    /// of its first bid set. A bid set that conflicts with no other is a
+    /// clique of its own.
+    pub cliques: Vec<Vec<usize>>,
+    /// Value of the bids chosen.
+    pub value: V,
+    /// Highest value the branch could reach.
+    pub bound: V,
+    /// Highest value the search had found, which the bound is below.
+    pub best: V,
+}
+
+/// Positions of the bids of an auction, and the cuts logged so far.
+pub(crate) struct PruningRecorder<V> {
+    /// `(bid set, bid)` indices by the address of the bid.
+    positions: HashMap<usize, (usize, usize)>,
+    log: Mutex<PruningLog<V>>,
+}
+
+impl<V> PruningRecorder<V> {
+    pub(crate) fn new<B: Bid>(exclusive_bid_sets: &[Vec<&B>]) -> Self {
+        let mut log = PruningLog::default();
+        let mut positions = HashMap::new();
+        for (i, bid_set) in exclusive_bid_sets.iter().enumerate() {
+            if bid_set.is_empty() {
+                log.empty_bid_sets.push(i);
+            }
+            for (j, bid) in bid_set.iter().enumerate() {
+                positions.insert(address(*bid), (i, j));
+            }
+        }
+        Self {
+            positions,
+            log: Mutex::new(log),
+        }
+    }
+
+    /// The position of a bid given to the auction.
+    pub(crate) fn position<B>(&self, bid: &B) -> (usize, usize) {
+        self.positions[&address(bid)]
+    }
+
+    /// Add the bids dropped before a search.
+    pub(crate) fn add_dropped<B>(
+        &self,
+        phase: SearchPhase,
+        dropped: Vec<(&B, DropReason)>,
+    ) {
+        let dropped = dropped.into_iter().map(|(bid, reason)| {
+            let (bid_set, bid) = self.position(bid);
+            let dropped = DroppedBid {
+                bid_set,
+                bid,
+                reason,
+            };
+            (phase, dropped)
+        });
+        self.log
+            .lock()
+            .unwrap_or_else(|poison| poison.into_inner())
+            .dropped_bids
+            .extend(dropped);
+    }
+
+    /// Add the cuts of a finished search.
+    pub(crate) fn add_cuts(&self, cuts: Vec<BoundCut<V>>) {
+        self.log
+            .lock()
+            .unwrap_or_else(|poison| poison.into_inner())
+            .bound_cuts
+            .extend(cuts);
+    }
+
+    /// Take the log recorded so far.
+    pub(crate) fn take_log(&self) -> PruningLog<V> {
+        std::mem::take(
+            &mut *self.log.lock().unwrap_or_else(|poison| poison.into_inner()),
+        )
+    }
+}
+
+fn address<B>(bid: &B) -> usize {
+    bid as *const B as usize
+}
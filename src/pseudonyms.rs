@@ -0,0 +1,229 @@
+//! Bidder names replaced with pseudonyms for the duration of an auction.
+//!
+//! Results, reports, logs and debug output of an auction of
+//! [`PseudonymousBid`]s only ever contain [`Pseudonym`]s, never bidder names.
+//! The names are kept in a [`Pseudonyms`] table, which maps the final result
+//! back to them.
+
+use std::fmt;
+
+use crate::{AuctionOutcome, AuctionResult, Bid};
+
+/// An opaque token standing in for a bidder, assigned by [`Pseudonyms`].
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Pseudonym(u32);
+
+impl fmt::Display for Pseudonym {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bidder-{}", self.0)
+    }
+}
+
+/// A bid with its bidder's name replaced by a [`Pseudonym`]. Its value and
+/// items are those of the bid.
+///
+/// The bid it wraps is left out of its debug output and, with the `serde`
+/// feature, of its serialization, which has the fields `name`, `value` and
+/// `items`.
+pub struct PseudonymousBid<'b, B> {
+    bid: &'b B,
+    pseudonym: Pseudonym,
+}
+
+impl<'b, B> PseudonymousBid<'b, B> {
+    /// The bid with its real bidder name.
+    pub fn reveal(&self) -> &'b B {
+        self.bid
+    }
+}
+
+impl<B: Bid> Bid for PseudonymousBid<'_, B> {
+    type Name = Pseudonym;
+    type Value = B::Value;
+    type Item = B::Item;
+    type Quantity = B::Quantity;
+
+    fn bidder_name(&self) -> &Self::Name {
+        &self.pseudonym
+    }
+    fn bid_value(&self) -> &Self::Value {
+        self.bid.bid_value()
+    }
+    fn bid_items(&self) -> &[(Self::Item, Self::Quantity)] {
+        self.bid.bid_items()
+    }
+}
+
+// manual impls, since the derives would include the bidder name
+impl<B> Clone for PseudonymousBid<'_, B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B> Copy for PseudonymousBid<'_, B> {}
+
+impl<B: Bid> fmt::Debug for PseudonymousBid<'_, B>
+where
+    B::Value: fmt::Debug,
+    B::Item: fmt::Debug,
+    B::Quantity: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PseudonymousBid")
+            .field("name", &self.pseudonym)
+            .field("value", self.bid.bid_value())
+            .field("items", &self.bid.bid_items())
+            .finish()
+    }
+}
+
+impl<B> PartialEq for PseudonymousBid<'_, B> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.bid, other.bid)
+    }
+}
+
+impl<B> Eq for PseudonymousBid<'_, B> {}
+
+#[cfg(feature = "serde")]
+impl<B: Bid> serde::Serialize for PseudonymousBid<'_, B>
+where
+    B::Value: serde::Serialize,
+    B::Item: serde::Serialize,
+    B::Quantity: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut bid = serializer.serialize_struct("PseudonymousBid", 3)?;
+        bid.serialize_field("name", &self.pseudonym)?;
+        bid.serialize_field("value", self.bid.bid_value())?;
+        bid.serialize_field("items", self.bid.bid_items())?;
+        bid.end()
+    }
+}
+
+/// Bid sets with every bidder name replaced by a [`Pseudonym`], and the
+/// table mapping the pseudonyms back to the names.
+///
+/// Bidders are given pseudonyms in the order of their first bid, so the same
+/// bid sets always get the same pseudonyms. Names are compared only for
+/// equality, so building the table takes time in the number of bids times
+/// the number of bidders.
+///
+/// ```
+/// use vcg_auction::{types::SimpleBid, AuctionConfig, Pseudonyms};
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+/// ];
+/// let pseudonyms = Pseudonyms::new(&bids);
+/// let config = AuctionConfig::deterministic();
+/// let outcome = vcg_auction::run(&items, pseudonyms.bid_sets(), &config)?;
+/// // nothing about the auction mentions Alice or Bob
+/// assert!(!format!("{outcome:?}").contains("Alice"));
+///
+/// let result = pseudonyms.reveal(outcome).complete().unwrap();
+/// assert_eq!(result.winning_bids, [&bids[0][0]]);
+/// assert_eq!(result.payments, [(&"Alice".to_string(), 7)]);
+/// # Ok::<(), vcg_auction::AuctionError>(())
+/// ```
+pub struct Pseudonyms<'b, B: Bid> {
+    names: Vec<&'b B::Name>,
+    bid_sets: Vec<Vec<PseudonymousBid<'b, B>>>,
+}
+
+impl<'b, B: Bid> Pseudonyms<'b, B> {
+    /// Give the bidders of the bid sets pseudonyms.
+    ///
+    /// # Panics
+    ///
+    /// If there are more than `u32::MAX` bidders.
+    pub fn new<S>(exclusive_bid_sets: impl IntoIterator<Item = &'b S>) -> Self
+    where
+        S: AsRef<[B]> + ?Sized + 'b,
+    {
+        let mut names = vec![];
+        let bid_sets = exclusive_bid_sets
+            .into_iter()
+            .map(|bid_set| {
+                bid_set
+                    .as_ref()
+                    .iter()
+                    .map(|bid| {
+                        let name = bid.bidder_name();
+                        let index = match names.iter().position(|n| *n == name)
+                        {
+                            Some(index) => index,
+                            None => {
+                                names.push(name);
+                                names.len() - 1
+                            }
+                        };
+                        let index = u32::try_from(index)
+                            .expect("at most u32::MAX bidders");
+                        PseudonymousBid {
+                            bid,
+                            pseudonym: Pseudonym(index),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { names, bid_sets }
+    }
+
+    /// The bid sets with pseudonyms, to run the auction with.
+    pub fn bid_sets(&self) -> &[Vec<PseudonymousBid<'b, B>>] {
+        &self.bid_sets
+    }
+
+    /// The bidder a pseudonym stands for.
+    pub fn name(&self, pseudonym: Pseudonym) -> Option<&'b B::Name> {
+        self.names.get(pseudonym.0 as usize).copied()
+    }
+
+    /// The outcome of an auction of the pseudonymous bid sets, with its bids
+    /// and bidder names revealed.
+    ///
+    /// # Panics
+    ///
+    /// If the outcome has a pseudonym not given by this table.
+    pub fn reveal(
+        &self,
+        outcome: AuctionOutcome<'_, PseudonymousBid<'b, B>>,
+    ) -> AuctionOutcome<'b, B> {
+        let reveal_bids = |bids: Vec<&PseudonymousBid<'b, B>>| {
+            bids.into_iter().map(|bid| bid.bid).collect()
+        };
+        match outcome {
+            AuctionOutcome::Complete(result) => {
+                AuctionOutcome::Complete(AuctionResult {
+                    winning_bids: reveal_bids(result.winning_bids),
+                    payments: result
+                        .payments
+                        .into_iter()
+                        .map(|(pseudonym, payment)| {
+                            let name = self
+                                .name(*pseudonym)
+                                .expect("pseudonyms are given by the table");
+                            (name, payment)
+                        })
+                        .collect(),
+                })
+            }
+            AuctionOutcome::TimedOut { best_so_far, gap } => {
+                AuctionOutcome::TimedOut {
+                    best_so_far: reveal_bids(best_so_far),
+                    gap,
+                }
+            }
+        }
+    }
+}
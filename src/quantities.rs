@@ -0,0 +1,75 @@
+//! Lane-wise operations on item quantities, used in the search's inner loop.
+//!
+//! Quantities are kept in contiguous slices aligned with the item stock, and
+//! processed in fixed-size chunks without early exits, so that the compiler
+//! can turn each chunk into SIMD instructions for primitive quantity types.
+//! The `search_speed` example times it on auctions of many item types.
+//!
+//! Bid quantities are still stored sparsely, as the stock positions of a
+//! bid's items with their quantities, so adding a bid's items to the selected
+//! quantities is done item by item.
+
+/// Number of quantities processed per chunk.
+const LANES: usize = 8;
+
+/// Whether every selected quantity is within the stock quantity at the same
+/// position. Only a quantity greater than its stock is over it, so one that
+/// can't be compared with its stock, such as a NaN, is within it.
+pub(crate) fn within_stock<Q: PartialOrd>(selected: &[Q], stock: &[Q]) -> bool {
+    debug_assert_eq!(selected.len(), stock.len());
+    let over = |(selected, stock): (&Q, &Q)| selected > stock;
+    let mut selected_chunks = selected.chunks_exact(LANES);
+    let mut stock_chunks = stock.chunks_exact(LANES);
+    for (selected, stock) in (&mut selected_chunks).zip(&mut stock_chunks) {
+        // compare the whole chunk before branching
+        let over_stock = selected
+            .iter()
+            .zip(stock)
+            .fold(false, |over_stock, pair| over_stock | over(pair));
+        if over_stock {
+            return false;
+        }
+    }
+    !selected_chunks
+        .remainder()
+        .iter()
+        .zip(stock_chunks.remainder())
+        .any(over)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn within_stock_across_chunks() {
+        let stock = (0..19).collect::<Vec<u64>>();
+        assert!(within_stock(&stock, &stock));
+        assert!(within_stock(&[0; 19], &stock));
+        // over stock in a full chunk, and in the remainder
+        for i in [3, 17] {
+            let mut selected = stock.clone();
+            selected[i] += 1;
+            assert!(!within_stock(&selected, &stock));
+        }
+    }
+
+    #[test]
+    fn within_stock_floats() {
+        assert!(within_stock(&[0.5; 9], &[1.0; 9]));
+        assert!(!within_stock(&[1.5; 9], &[1.0; 9]));
+    }
+
+    #[test]
+    fn nan_is_within_stock() {
+        // in a full chunk, and in the remainder
+        for i in [3, 17] {
+            let mut selected = vec![0.0; 19];
+            selected[i] = f64::NAN;
+            assert!(within_stock(&selected, &[1.0; 19]));
+            // other quantities over stock are still found
+            selected[(i + 8) % 19] = 2.0;
+            assert!(!within_stock(&selected, &[1.0; 19]));
+        }
+    }
+}
@@ -0,0 +1,309 @@
+//! A job queue running many auctions on a fixed set of worker threads.
+//!
+//! Services that clear many independent auctions need to bound how many run
+//! at once, run urgent auctions first, and abandon auctions nobody is waiting
+//! for. [`AuctionQueue`] does this with plain threads, returning a
+//! [`JobHandle`] for each submitted auction.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{
+    atomic::{self, AtomicBool},
+    Arc, Condvar, Mutex, MutexGuard,
+};
+use std::thread;
+
+use crate::{
+    control::SearchControl,
+    input::{bid_set_refs, ItemStock},
+    vcg::run_auction,
+    AuctionError, AuctionOutcome, Bid, OwnedAuctionResult, SearchOptions,
+};
+
+/// Result of a job run by an [`AuctionQueue`].
+pub type JobResult<B> = Result<OwnedAuctionResult<B>, AuctionError>;
+
+/// Tiebreaker of a queued job.
+type Tiebreaker<B> = Box<dyn FnOnce(&[Vec<&B>]) -> usize + Send>;
+
+/// Runs auctions on a bounded number of worker threads. Jobs with a higher
+/// priority are started first, and jobs with the same priority are started in
+/// the order they were submitted.
+///
+/// Dropping the queue stops it from accepting jobs, but the workers finish
+/// the jobs already submitted before exiting.
+///
+/// ```
+/// use vcg_auction::{types::SimpleBid, AuctionQueue};
+///
+/// let queue = AuctionQueue::new(2).unwrap();
+/// let handles = (1..=4)
+///     .map(|value| {
+///         let items = vec![("chair".to_string(), 1)];
+///         let bids = vec![vec![SimpleBid::new("Alice", value, [("chair", 1)])]];
+///         queue.submit(items, bids, |_| 0, 0)
+///     })
+///     .collect::<Vec<_>>();
+/// for handle in handles {
+///     let result = handle.wait().unwrap();
+///     assert_eq!(result.winning_bids.len(), 1);
+/// }
+/// ```
+pub struct AuctionQueue<B: Bid> {
+    shared: Arc<Shared<B>>,
+}
+
+/// State shared between the queue and its workers.
+struct Shared<B: Bid> {
+    state: Mutex<QueueState<B>>,
+    available: Condvar,
+}
+
+struct QueueState<B: Bid> {
+    jobs: BinaryHeap<QueuedJob<B>>,
+    submitted: u64,
+    closed: bool,
+}
+
+/// A submitted job waiting for a worker.
+struct QueuedJob<B: Bid> {
+    priority: i32,
+    sequence: u64,
+    items: Vec<(B::Item, B::Quantity)>,
+    exclusive_bid_sets: Vec<Vec<B>>,
+    tiebreaker: Tiebreaker<B>,
+    slot: Arc<JobSlot<B>>,
+}
+
+// the heap pops the greatest job: highest priority, then earliest submitted
+impl<B: Bid> Ord for QueuedJob<B> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+impl<B: Bid> PartialOrd for QueuedJob<B> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<B: Bid> PartialEq for QueuedJob<B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<B: Bid> Eq for QueuedJob<B> {}
+
+/// Where a worker leaves the result of a job for its handle.
+struct JobSlot<B: Bid> {
+    result: Mutex<Option<thread::Result<JobResult<B>>>>,
+    done: Condvar,
+    cancel: AtomicBool,
+}
+
+impl<B: Bid> JobSlot<B> {
+    fn finish(&self, result: thread::Result<JobResult<B>>) {
+        *lock(&self.result) = Some(result);
+        self.done.notify_all();
+    }
+}
+
+/// Handle to a job submitted to an [`AuctionQueue`]. Dropping the handle
+/// cancels the job, since its result can no longer be retrieved.
+pub struct JobHandle<B: Bid> {
+    slot: Arc<JobSlot<B>>,
+}
+
+impl<B> AuctionQueue<B>
+where
+    B: Bid + Clone + Send + 'static,
+    B::Name: Clone + Send,
+    B::Value: Send,
+    B::Item: Send,
+    B::Quantity: Send,
+{
+    /// Create a queue with `num_workers` worker threads. Returns an error if
+    /// the threads can't be spawned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_workers` is zero.
+    pub fn new(num_workers: usize) -> Result<Self, AuctionError> {
+        assert!(
+            num_workers > 0,
+            "an auction queue needs at least one worker"
+        );
+        let shared = Arc::new(Shared {
+            state: Mutex::new(QueueState {
+                jobs: BinaryHeap::new(),
+                submitted: 0,
+                closed: false,
+            }),
+            available: Condvar::new(),
+        });
+        for i in 0..num_workers {
+            let shared = Arc::clone(&shared);
+            thread::Builder::new()
+                .name(format!("vcg-auction-worker-{i}"))
+                .spawn(move || work(&shared))
+                .map_err(|_| AuctionError::ThreadPool)?;
+        }
+        Ok(Self { shared })
+    }
+
+    /// Submit an auction to run with the given tiebreaker. Jobs with a higher
+    /// `priority` are started before jobs with a lower one.
+    pub fn submit(
+        &self,
+        items: Vec<(B::Item, B::Quantity)>,
+        exclusive_bid_sets: Vec<Vec<B>>,
+        tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize + Send + 'static,
+        priority: i32,
+    ) -> JobHandle<B> {
+        let slot = Arc::new(JobSlot {
+            result: Mutex::new(None),
+            done: Condvar::new(),
+            cancel: AtomicBool::new(false),
+        });
+        let mut state = lock(&self.shared.state);
+        let sequence = state.submitted;
+        state.submitted += 1;
+        state.jobs.push(QueuedJob {
+            priority,
+            sequence,
+            items,
+            exclusive_bid_sets,
+            tiebreaker: Box::new(tiebreaker),
+            slot: Arc::clone(&slot),
+        });
+        drop(state);
+        self.shared.available.notify_one();
+        JobHandle { slot }
+    }
+
+    /// Number of jobs waiting for a worker.
+    pub fn pending(&self) -> usize {
+        lock(&self.shared.state).jobs.len()
+    }
+}
+
+impl<B: Bid> Drop for AuctionQueue<B> {
+    fn drop(&mut self) {
+        lock(&self.shared.state).closed = true;
+        self.shared.available.notify_all();
+    }
+}
+
+impl<B: Bid> JobHandle<B> {
+    /// Cancel the job. A job waiting for a worker is never started, and a
+    /// running job stops at the next search step. The result of a cancelled
+    /// job is [`AuctionError::Cancelled`], unless it had already finished.
+    pub fn cancel(&self) {
+        self.slot.cancel.store(true, atomic::Ordering::Relaxed);
+    }
+
+    /// Whether the job has finished, so [`wait`](Self::wait) won't block.
+    pub fn is_finished(&self) -> bool {
+        lock(&self.slot.result).is_some()
+    }
+
+    /// Block until the job finishes and return its result. If the auction
+    /// panicked, the panic is resumed on the calling thread.
+    pub fn wait(self) -> JobResult<B> {
+        let mut result = lock(&self.slot.result);
+        loop {
+            match result.take() {
+                Some(Ok(result)) => return result,
+                Some(Err(panic)) => panic::resume_unwind(panic),
+                None => {
+                    result = self
+                        .slot
+                        .done
+                        .wait(result)
+                        .unwrap_or_else(|err| err.into_inner())
+                }
+            }
+        }
+    }
+}
+
+impl<B: Bid> Drop for JobHandle<B> {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Take jobs off the queue and run them until the queue is closed and empty.
+fn work<B>(shared: &Shared<B>)
+where
+    B: Bid + Clone,
+    B::Name: Clone,
+{
+    loop {
+        let mut state = lock(&shared.state);
+        let job = loop {
+            match state.jobs.pop() {
+                Some(job) => break job,
+                None if state.closed => return,
+                None => {
+                    state = shared
+                        .available
+                        .wait(state)
+                        .unwrap_or_else(|err| err.into_inner())
+                }
+            }
+        };
+        drop(state);
+        let result = run_job(
+            job.items,
+            job.exclusive_bid_sets,
+            job.tiebreaker,
+            &job.slot.cancel,
+        );
+        job.slot.finish(result);
+    }
+}
+
+/// Run a job, catching panics so they reach the job's handle instead of
+/// killing the worker.
+fn run_job<B>(
+    items: Vec<(B::Item, B::Quantity)>,
+    exclusive_bid_sets: Vec<Vec<B>>,
+    tiebreaker: Tiebreaker<B>,
+    cancel: &AtomicBool,
+) -> thread::Result<JobResult<B>>
+where
+    B: Bid + Clone,
+    B::Name: Clone,
+{
+    if cancel.load(atomic::Ordering::Relaxed) {
+        return Ok(Err(AuctionError::Cancelled));
+    }
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        let control =
+            SearchControl::new(SearchOptions::default()).with_cancel(cancel);
+        run_auction(
+            &ItemStock::new(&items),
+            bid_set_refs(&exclusive_bid_sets),
+            tiebreaker,
+            &control,
+        )
+        .map(AuctionOutcome::complete)
+        .map(|result| {
+            result
+                .expect("auctions without a max duration don't time out")
+                .into_owned()
+        })
+    }))
+}
+
+/// Lock a mutex, ignoring poisoning. Panics in auctions are caught before they
+/// can poison the queue's locks.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
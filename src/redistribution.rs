@@ -0,0 +1,142 @@
+//! Returning VCG payments to the bidders, for settings where the auctioneer
+//! shouldn't keep the revenue, such as allocating a good owned by the bidders
+//! themselves.
+
+use std::ops::Div;
+
+use num_traits::{FromPrimitive, Zero};
+
+use crate::{
+    input::{bid_set_refs, ItemStock},
+    vcg::{copy_value, winning_bidders},
+    AddSubSelf, Allocation, AuctionResult, Bid, StockEntry,
+};
+
+/// Rebates of the VCG payments of an auction, returned by
+/// [`cavallo_redistribution`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(bound(serialize = "B::Name: serde::Serialize, \
+                             B::Value: serde::Serialize"))
+)]
+pub struct Redistribution<'a, B: Bid> {
+    /// Total of the VCG payments.
+    pub revenue: B::Value,
+    /// The rebate of every bidder, winning or not, in the order of their
+    /// first bid.
+    pub rebates: Vec<(&'a B::Name, B::Value)>,
+}
+
+impl<'a, B: Bid> Redistribution<'a, B> {
+    /// The revenue left after paying the rebates, or `None` if the rebates
+    /// exceed the revenue.
+    pub fn retained(&self) -> Option<B::Value> {
+        let rebates = self
+            .rebates
+            .iter()
+            .fold(B::Value::zero(), |sum, (_, rebate)| sum.add(rebate));
+        (rebates <= self.revenue).then(|| self.revenue.sub(&rebates))
+    }
+}
+
+/// Redistribute the VCG payments of an auction with Cavallo's mechanism.
+/// Items and bid sets are given as for [`vcg_auction`](crate::vcg_auction),
+/// and `result` is the result of a VCG auction run on them.
+///
+/// Of `n` bidders, each bidder is rebated `1/n` of the revenue the auction
+/// would have collected without their bids. A bidder's rebate doesn't depend
+/// on their own bids, so bidding their values is still their best strategy,
+/// and it's never negative, so bidders are no worse off for taking part.
+/// Each rebate takes an auction without one bidder, searched with default
+/// options and the first of any tied outcomes.
+///
+/// The rebates are at most the revenue whenever removing a bidder can't
+/// raise the revenue, as when bidders want one unit of an item, or items are
+/// substitutes. With complementary items VCG revenue can fall when a bidder
+/// joins, and then the rebates can exceed it; check with
+/// [`Redistribution::retained`]. Returns `None` if the number of bidders
+/// can't be converted to a value.
+///
+/// ```
+/// use vcg_auction::{
+///     cavallo_redistribution, types::SimpleBid, vcg_auction_with_tiebreaker,
+/// };
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+///     vec![SimpleBid::new("Carol", 4, [("chair", 1)])],
+/// ];
+/// let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+/// let redistribution =
+///     cavallo_redistribution(&items, &bids, &result).unwrap();
+/// assert_eq!(redistribution.revenue, 7);
+/// // without Alice Bob pays 4, and without Bob or Carol Alice pays 4 or 7
+/// let rebates = redistribution
+///     .rebates
+///     .iter()
+///     .map(|(name, rebate)| (name.as_str(), *rebate))
+///     .collect::<Vec<_>>();
+/// assert_eq!(rebates, [("Alice", 4 / 3), ("Bob", 4 / 3), ("Carol", 7 / 3)]);
+/// assert_eq!(redistribution.retained(), Some(3));
+/// ```
+pub fn cavallo_redistribution<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    result: &AuctionResult<B>,
+) -> Option<Redistribution<'a, B>>
+where
+    B: Bid + 'a + 'i,
+    B::Value: FromPrimitive + Div<Output = B::Value>,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let items = items
+        .into_iter()
+        .map(StockEntry::into_entry)
+        .collect::<Vec<_>>();
+    let exclusive_bid_sets = bid_set_refs(exclusive_bid_sets);
+    let bids = exclusive_bid_sets
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<Vec<_>>();
+    let bidders = winning_bidders(&bids);
+    let count = B::Value::from_usize(bidders.len())?;
+    let rebates = bidders
+        .into_iter()
+        .map(|bidder_name| {
+            let others = exclusive_bid_sets
+                .iter()
+                .map(|bid_set| {
+                    bid_set
+                        .iter()
+                        .filter(|bid| bid.bidder_name() != bidder_name)
+                        .copied()
+                        .collect()
+                })
+                .collect();
+            let result = Allocation::find(
+                ItemStock::new(items.iter().copied()),
+                others,
+                |_| 0,
+            )
+            .expect("the first tied outcome is valid")
+            .into_result();
+            (bidder_name, revenue(&result) / copy_value::<B>(&count))
+        })
+        .collect();
+    Some(Redistribution {
+        revenue: revenue::<B>(result),
+        rebates,
+    })
+}
+
+fn revenue<B: Bid>(result: &AuctionResult<B>) -> B::Value {
+    result
+        .payments
+        .iter()
+        .fold(B::Value::zero(), |sum, (_, payment)| sum.add(payment))
+}
@@ -0,0 +1,310 @@
+//! Reports of auction runs, for archiving each clearing run as one document
+//! that it can later be reproduced or audited from.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::{
+    vcg::copy_value, AuctionConfig, AuctionError, AuctionOutcome, Bid,
+    DroppedBid, DuplicateItems, InputError, Objective, OnTimeout, PricingRule,
+    ResultOrder, SolveStats, Tiebreak,
+};
+
+/// Everything about an auction run, returned by
+/// [`run_with_report`](crate::run_with_report).
+///
+/// The inputs are kept in the order given, so the run can be repeated from
+/// the report. Bids in the result are identified by their position in
+/// `bid_sets`, as `(bid set, bid)` indices.
+///
+/// With the `serde` feature the report implements `Serialize` when the bid
+/// type, its names, values, items and quantities do.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(bound(serialize = "B: serde::Serialize, \
+                             B::Name: serde::Serialize, \
+                             B::Value: serde::Serialize, \
+                             B::Item: serde::Serialize, \
+                             B::Quantity: serde::Serialize"))
+)]
+pub struct RunReport<'a, 'i, B: Bid> {
+    /// The items and their stock quantities.
+    pub items: Vec<(&'i B::Item, &'i B::Quantity)>,
+    /// The bid sets.
+    pub bid_sets: Vec<Vec<&'a B>>,
+    /// Sizes of the inputs.
+    pub summary: InputSummary,
+    /// The options the auction ran with.
+    pub config: ConfigSummary,
+    /// What the auction returned.
+    pub result: ReportedResult<'a, B>,
+    /// Statistics of the searches.
+    pub stats: SolveStats,
+    /// Problems with the inputs, which the auction ran with regardless.
+    pub warnings: Vec<InputError>,
+    /// Bids the search for the winners left out because they can't win,
+    /// with why. A bid on an item not in stock isn't left out, since the
+    /// search ignores the item, so it's listed in `warnings` instead.
+    pub dropped_bids: Vec<DroppedBid>,
+    /// How tied outcomes were chosen between, or `None` if the highest value
+    /// wasn't tied.
+    pub tie: Option<TieReport>,
+}
+
+// manual impl, since the derive can't bound on the fields' associated types
+impl<'a, 'i, B: Bid> fmt::Debug for RunReport<'a, 'i, B>
+where
+    B: fmt::Debug,
+    B::Item: fmt::Debug,
+    B::Quantity: fmt::Debug,
+    ReportedResult<'a, B>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RunReport")
+            .field("items", &self.items)
+            .field("bid_sets", &self.bid_sets)
+            .field("summary", &self.summary)
+            .field("config", &self.config)
+            .field("result", &self.result)
+            .field("stats", &self.stats)
+            .field("warnings", &self.warnings)
+            .field("dropped_bids", &self.dropped_bids)
+            .field("tie", &self.tie)
+            .finish()
+    }
+}
+
+impl<'a, 'i, B: Bid> RunReport<'a, 'i, B> {
+    /// Bidders with a bid [passed over](TieReport::passed_over) by the
+    /// tiebreak who won nothing, each once, in the order of their bids. They
+    /// could have won had the tiebreak gone the other way.
+    pub fn passed_over_bidders(&self) -> Vec<&'a B::Name> {
+        let Some(tie) = &self.tie else {
+            return vec![];
+        };
+        let winners = match &self.result {
+            ReportedResult::Complete { winning_bids, .. } => &winning_bids[..],
+            _ => &[],
+        };
+        let name = |&(i, j): &(usize, usize)| self.bid_sets[i][j].bidder_name();
+        let mut bidders = vec![];
+        for bidder_name in tie.passed_over.iter().map(name) {
+            if !winners.iter().map(name).any(|winner| winner == bidder_name)
+                && !bidders.contains(&bidder_name)
+            {
+                bidders.push(bidder_name);
+            }
+        }
+        bidders
+    }
+}
+
+/// Sizes of the inputs of an auction.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct InputSummary {
+    /// Number of items in stock.
+    pub items: usize,
+    /// Number of bid sets, including empty ones.
+    pub bid_sets: usize,
+    /// Number of bid sets with no bids.
+    pub empty_bid_sets: usize,
+    /// Total number of bids.
+    pub bids: usize,
+}
+
+/// The options of an [`AuctionConfig`] that affect the result. Progress
+/// callbacks and thread pools aren't included.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ConfigSummary {
+    /// [`AuctionConfig::tiebreak`]
+    pub tiebreak: TiebreakKind,
+    /// [`AuctionConfig::pricing`]
+    pub pricing: PricingRule,
+    /// [`AuctionConfig::duplicate_items`]
+    pub duplicate_items: DuplicateItems,
+    /// [`AuctionConfig::result_order`]
+    pub result_order: ResultOrder,
+    /// [`AuctionConfig::objectives`]
+    pub objectives: Vec<Objective>,
+    /// [`SearchOptions::max_duration`](crate::SearchOptions::max_duration)
+    pub max_duration: Option<Duration>,
+    /// [`SearchOptions::on_timeout`](crate::SearchOptions::on_timeout)
+    pub on_timeout: OnTimeout,
+    /// [`SearchOptions::max_nodes`](crate::SearchOptions::max_nodes)
+    pub max_nodes: Option<u64>,
+    /// [`SearchOptions::max_tied_outcomes`](crate::SearchOptions::max_tied_outcomes)
+    pub max_tied_outcomes: Option<usize>,
+    /// [`SearchOptions::max_bid_sets`](crate::SearchOptions::max_bid_sets)
+    pub max_bid_sets: Option<usize>,
+    /// [`SearchOptions::max_bids_per_set`](crate::SearchOptions::max_bids_per_set)
+    pub max_bids_per_set: Option<usize>,
+}
+
+impl ConfigSummary {
+    pub(crate) fn new<B: Bid>(config: &AuctionConfig<B>) -> Self {
+        Self {
+            tiebreak: TiebreakKind::new(&config.tiebreak),
+            pricing: config.pricing,
+            duplicate_items: config.duplicate_items,
+            result_order: config.result_order,
+            objectives: config.objectives.to_vec(),
+            max_duration: config.search.max_duration,
+            on_timeout: config.search.on_timeout,
+            max_nodes: config.search.max_nodes,
+            max_tied_outcomes: config.search.max_tied_outcomes,
+            max_bid_sets: config.search.max_bid_sets,
+            max_bids_per_set: config.search.max_bids_per_set,
+        }
+    }
+}
+
+/// Which [`Tiebreak`] an auction used. A custom tiebreaker is recorded only
+/// as being custom, but the choice it made is in the report's
+/// [`TieReport`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum TiebreakKind {
+    /// [`Tiebreak::Random`]
+    Random,
+    /// [`Tiebreak::Seeded`], with its seed.
+    Seeded(u64),
+    /// [`Tiebreak::First`]
+    First,
+    /// [`Tiebreak::Custom`]
+    Custom,
+}
+
+impl TiebreakKind {
+    fn new<B: Bid>(tiebreak: &Tiebreak<B>) -> Self {
+        match tiebreak {
+            #[cfg(feature = "rand")]
+            Tiebreak::Random => TiebreakKind::Random,
+            #[cfg(feature = "rng")]
+            Tiebreak::Seeded(seed) => TiebreakKind::Seeded(*seed),
+            Tiebreak::First => TiebreakKind::First,
+            Tiebreak::Custom(_) => TiebreakKind::Custom,
+        }
+    }
+}
+
+/// The tiebreak between outcomes tied for the highest value. Rerunning the
+/// auction with a [`Tiebreak::Custom`] returning `chosen` reproduces the
+/// result, even if the tiebreak was random.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TieReport {
+    /// Number of outcomes tied.
+    pub tied_outcomes: usize,
+    /// Index of the outcome chosen, in the order the search found them.
+    pub chosen: usize,
+    /// Bids that win in another of the tied outcomes but not in the one
+    /// chosen, as `(bid set, bid)` positions, in the order of the outcomes.
+    /// They lost only to the tiebreak.
+    pub passed_over: Vec<(usize, usize)>,
+}
+
+impl TieReport {
+    pub(crate) fn new<B>(
+        tied: &[Vec<&B>],
+        chosen: usize,
+        bid_sets: &[Vec<&B>],
+    ) -> Self {
+        let chosen_bids = tied.get(chosen).map_or(&[][..], Vec::as_slice);
+        let mut passed_over = vec![];
+        for bid in tied.iter().flatten() {
+            let position = position(bid_sets, bid);
+            if !chosen_bids.iter().any(|b| std::ptr::eq(*b, *bid))
+                && !passed_over.contains(&position)
+            {
+                passed_over.push(position);
+            }
+        }
+        Self {
+            tied_outcomes: tied.len(),
+            chosen,
+            passed_over,
+        }
+    }
+}
+
+/// What an auction returned, with bids identified by their position.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(bound(serialize = "B::Name: serde::Serialize, \
+                             B::Value: serde::Serialize"))
+)]
+pub enum ReportedResult<'a, B: Bid> {
+    /// The search finished; see [`AuctionOutcome::Complete`].
+    Complete {
+        winning_bids: Vec<(usize, usize)>,
+        payments: Vec<(&'a B::Name, B::Value)>,
+    },
+    /// The search timed out; see [`AuctionOutcome::TimedOut`].
+    TimedOut {
+        best_so_far: Vec<(usize, usize)>,
+        gap: B::Value,
+    },
+    /// The auction failed.
+    Failed(AuctionError),
+}
+
+impl<'a, B: Bid> ReportedResult<'a, B> {
+    pub(crate) fn new(
+        outcome: Result<&AuctionOutcome<'a, B>, AuctionError>,
+        bid_sets: &[Vec<&'a B>],
+    ) -> Self {
+        let positions = |bids: &[&B]| {
+            bids.iter()
+                .map(|bid| position(bid_sets, bid))
+                .collect::<Vec<_>>()
+        };
+        match outcome {
+            Ok(AuctionOutcome::Complete(result)) => ReportedResult::Complete {
+                winning_bids: positions(&result.winning_bids),
+                payments: result
+                    .payments
+                    .iter()
+                    .map(|(name, payment)| (*name, copy_value::<B>(payment)))
+                    .collect(),
+            },
+            Ok(AuctionOutcome::TimedOut { best_so_far, gap }) => {
+                ReportedResult::TimedOut {
+                    best_so_far: positions(best_so_far),
+                    gap: copy_value::<B>(gap),
+                }
+            }
+            Err(error) => ReportedResult::Failed(error),
+        }
+    }
+}
+
+/// Sizes of the items and bid sets of an auction.
+pub(crate) fn summarize<B>(items: usize, bid_sets: &[Vec<&B>]) -> InputSummary {
+    InputSummary {
+        items,
+        bid_sets: bid_sets.len(),
+        empty_bid_sets: bid_sets.iter().filter(|bids| bids.is_empty()).count(),
+        bids: bid_sets.iter().map(Vec::len).sum(),
+    }
+}
+
+/// The position of a bid among the bid sets it was taken from.
+pub(crate) fn position<B>(bid_sets: &[Vec<&B>], bid: &B) -> (usize, usize) {
+    bid_sets
+        .iter()
+        .enumerate()
+        .find_map(|(i, bids)| {
+            let j = bids.iter().position(|b| std::ptr::eq(*b, bid))?;
+            Some((i, j))
+        })
+        .expect("result bids are taken from the bid sets")
+}
@@ -0,0 +1,76 @@
+//! A random number generator shared by the runs of an auction config.
+
+use std::fmt;
+use std::sync::Mutex;
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+use crate::{vcg::random_index, Bid};
+
+/// The random number generator of an [`AuctionConfig`](crate::AuctionConfig),
+/// set with [`AuctionConfigBuilder::rng`](crate::AuctionConfigBuilder::rng).
+/// Every random choice of a run with the config is drawn from it, in the
+/// order the run makes them, so a sequence of runs is reproduced by seeding
+/// it the same way. It can be shared between threads, but then the order of
+/// the runs, and so their choices, depends on scheduling.
+///
+/// ```
+/// use rand::{rngs::StdRng, SeedableRng};
+/// use vcg_auction::{types::SimpleBid, AuctionConfig, AuctionRng, Tiebreak};
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 10, [("chair", 1)])],
+/// ];
+/// let rng = AuctionRng::seed_from_u64(7);
+/// let config = AuctionConfig::builder()
+///     .tiebreak(Tiebreak::Random)
+///     .rng(&rng)
+///     .build();
+/// // the same choices as drawing from `StdRng` directly
+/// let mut std_rng = StdRng::seed_from_u64(7);
+/// for _ in 0..3 {
+///     let outcome = vcg_auction::run(&items, &bids, &config).unwrap();
+///     assert_eq!(
+///         outcome.complete(),
+///         vcg_auction::vcg_auction_with_rng(&items, &bids, &mut std_rng)
+///     );
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "rng")))]
+pub struct AuctionRng {
+    rng: Mutex<StdRng>,
+}
+
+impl AuctionRng {
+    /// A generator of rand's `StdRng` seeded from a value. `StdRng`'s
+    /// algorithm may change between versions of rand.
+    pub fn seed_from_u64(seed: u64) -> Self {
+        Self::new(StdRng::seed_from_u64(seed))
+    }
+
+    /// A generator of rand's `StdRng` seeded from another generator.
+    pub fn from_rng(rng: impl RngCore) -> Result<Self, rand::Error> {
+        Ok(Self::new(StdRng::from_rng(rng)?))
+    }
+
+    fn new(rng: StdRng) -> Self {
+        Self {
+            rng: Mutex::new(rng),
+        }
+    }
+
+    /// Uniform random choice of one of the tied outcomes.
+    pub(crate) fn choose<B: Bid>(&self, tied: &[Vec<&B>]) -> usize {
+        let mut rng =
+            self.rng.lock().unwrap_or_else(|poison| poison.into_inner());
+        random_index(&mut *rng, tied)
+    }
+}
+
+impl fmt::Debug for AuctionRng {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuctionRng").finish_non_exhaustive()
+    }
+}
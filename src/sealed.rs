@@ -0,0 +1,177 @@
+//! Commit-reveal bookkeeping for sealed-bid auctions.
+//!
+//! Each bidder first publishes a commitment to their bid set, the hash of the
+//! bids and a secret salt. Once bidding closes, bidders reveal their bids and
+//! salts, and only reveals matching a commitment are accepted. Since bids
+//! can't be changed after they're committed, no bidder gains by seeing
+//! another's commitment.
+
+use std::fmt;
+
+use sha2::{Digest, Sha256};
+
+use crate::{commitment::encode_bid, Bid, CanonicalEncode};
+
+/// Prefix of every hashed bid set, versioning the encoding.
+const BID_SET_TAG: &[u8] = b"vcg-auction/bid-set/v1";
+
+/// SHA-256 commitment to a bid set.
+pub type Commitment = [u8; 32];
+
+/// Commit to a bid set with a salt, which should be at least 16 random bytes
+/// kept secret until the reveal. Bids are encoded like those of
+/// [`encode_instance`](crate::encode_instance).
+pub fn commit_bid_set<B>(bid_set: &[B], salt: &[u8]) -> Commitment
+where
+    B: Bid,
+    B::Name: CanonicalEncode,
+    B::Value: CanonicalEncode,
+    B::Item: CanonicalEncode,
+    B::Quantity: CanonicalEncode,
+{
+    let mut out = BID_SET_TAG.to_vec();
+    (bid_set.len() as u64).encode(&mut out);
+    for bid in bid_set {
+        encode_bid(bid, &mut out);
+    }
+    (salt.len() as u64).encode(&mut out);
+    out.extend_from_slice(salt);
+    Sha256::digest(out).into()
+}
+
+/// Identifies a commitment accepted by [`SealedBids::commit`].
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct CommitmentId(usize);
+
+/// Reasons a commitment or reveal can be rejected.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum SealedBidError {
+    /// The same commitment was already made.
+    DuplicateCommitment,
+    /// The commitment ID wasn't issued by these sealed bids.
+    UnknownCommitment,
+    /// The commitment was already revealed.
+    AlreadyRevealed,
+    /// The bid set and salt don't hash to the commitment.
+    RevealMismatch,
+}
+
+impl fmt::Display for SealedBidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SealedBidError::DuplicateCommitment => {
+                write!(f, "commitment was already made")
+            }
+            SealedBidError::UnknownCommitment => {
+                write!(f, "unknown commitment")
+            }
+            SealedBidError::AlreadyRevealed => {
+                write!(f, "commitment was already revealed")
+            }
+            SealedBidError::RevealMismatch => {
+                write!(f, "revealed bids don't match the commitment")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SealedBidError {}
+
+/// Commitments to bid sets, and the bid sets revealed so far.
+///
+/// ```
+/// use vcg_auction::{commit_bid_set, types::SimpleBid, SealedBids};
+///
+/// let alice = vec![SimpleBid::new("Alice", 10, [("chair", 1)])];
+/// let bob = vec![SimpleBid::new("Bob", 7, [("chair", 1)])];
+///
+/// // bidding: only commitments are published
+/// let mut sealed = SealedBids::new();
+/// let alice_id = sealed.commit(commit_bid_set(&alice, b"alice's salt"))?;
+/// let bob_id = sealed.commit(commit_bid_set(&bob, b"bob's salt"))?;
+///
+/// // reveal: bids are accepted only if they match the commitment
+/// assert!(sealed.reveal(bob_id, bob.clone(), b"wrong salt").is_err());
+/// sealed.reveal(alice_id, alice.clone(), b"alice's salt")?;
+/// sealed.reveal(bob_id, bob.clone(), b"bob's salt")?;
+///
+/// assert_eq!(sealed.into_bid_sets(), [alice, bob]);
+/// # Ok::<(), vcg_auction::SealedBidError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct SealedBids<B> {
+    entries: Vec<(Commitment, Option<Vec<B>>)>,
+}
+
+impl<B> Default for SealedBids<B> {
+    fn default() -> Self {
+        Self { entries: vec![] }
+    }
+}
+
+impl<B> SealedBids<B>
+where
+    B: Bid,
+    B::Name: CanonicalEncode,
+    B::Value: CanonicalEncode,
+    B::Item: CanonicalEncode,
+    B::Quantity: CanonicalEncode,
+{
+    /// No commitments yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept a commitment to a bid set, returning the ID to reveal it with.
+    pub fn commit(
+        &mut self,
+        commitment: Commitment,
+    ) -> Result<CommitmentId, SealedBidError> {
+        if self.entries.iter().any(|(other, _)| *other == commitment) {
+            return Err(SealedBidError::DuplicateCommitment);
+        }
+        self.entries.push((commitment, None));
+        Ok(CommitmentId(self.entries.len() - 1))
+    }
+
+    /// Reveal the bid set of a commitment. The bid set is accepted if it
+    /// hashes to the commitment with the salt.
+    pub fn reveal(
+        &mut self,
+        id: CommitmentId,
+        bid_set: Vec<B>,
+        salt: &[u8],
+    ) -> Result<(), SealedBidError> {
+        let (commitment, revealed) = self
+            .entries
+            .get_mut(id.0)
+            .ok_or(SealedBidError::UnknownCommitment)?;
+        if revealed.is_some() {
+            return Err(SealedBidError::AlreadyRevealed);
+        }
+        if commit_bid_set(&bid_set, salt) != *commitment {
+            return Err(SealedBidError::RevealMismatch);
+        }
+        *revealed = Some(bid_set);
+        Ok(())
+    }
+
+    /// Commitments that haven't been revealed.
+    pub fn unrevealed(&self) -> impl Iterator<Item = CommitmentId> + '_ {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, revealed))| revealed.is_none())
+            .map(|(i, _)| CommitmentId(i))
+    }
+
+    /// The revealed bid sets in the order they were committed, ready to be
+    /// auctioned. Unrevealed commitments are dropped.
+    pub fn into_bid_sets(self) -> Vec<Vec<B>> {
+        self.entries
+            .into_iter()
+            .filter_map(|(_, revealed)| revealed)
+            .collect()
+    }
+}
@@ -0,0 +1,214 @@
+//! Recording of the search tree an auction explores, for finding out why an
+//! instance is slow to solve.
+
+use std::fmt::Display;
+
+use crate::SearchPhase;
+
+/// The nodes explored by the searches of an auction, returned by
+/// [`run_with_search_tree`](crate::run_with_search_tree).
+///
+/// Every search of the auction adds a root node with no parent: for the
+/// winners, and for each winner's payment, a search of each part of the
+/// auction that shares no items with the rest. Nodes are listed in the order
+/// they were explored, so a node always comes after its parent. Nodes that
+/// weren't explored because the search was interrupted aren't recorded.
+///
+/// Depths and bid indices refer to the bid sets as searched: bids that can't
+/// win are dropped first, as listed by
+/// [`run_with_pruning_log`](crate::run_with_pruning_log), bid sets left with
+/// no bids are skipped, and the searches for a payment leave out the bids of
+/// the bidder paying. Bid sets are searched in the order set by
+/// [`bid_set_order`](crate::SearchOptions::bid_set_order).
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SearchTree<V> {
+    /// The explored nodes, indexed by [`SearchTreeNode::parent`].
+    pub nodes: Vec<SearchTreeNode<V>>,
+}
+
+/// An explored combination of bids.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SearchTreeNode<V> {
+    /// Index of the node this one was reached from, or `None` for the root of
+    /// a search.
+    pub parent: Option<usize>,
+    /// The search the node belongs to.
+    pub phase: SearchPhase,
+    /// Number of bid sets considered.
+    pub depth: usize,
+    /// Index of the bid chosen from the last bid set considered, or `None` if
+    /// no bid was chosen from it.
+    pub bid: Option<usize>,
+    /// Value of the bids chosen.
+    pub value: V,
+    /// Highest value the bids chosen could reach with the remaining bid sets,
+    /// for nodes that were compared against it.
+    pub bound: Option<V>,
+    /// Highest value the search had found when the node was explored.
+    pub best: V,
+    /// What the search did with the node.
+    pub outcome: NodeOutcome,
+}
+
+/// What a search did with an explored node.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum NodeOutcome {
+    /// Children were added for each bid of the next bid set, and for choosing
+    /// none of them.
+    Expanded,
+    /// Pruned, since the bids chosen need more of an item than is in stock.
+    OverAllocated,
+    /// Pruned, since the bound is below the best value found.
+    Bounded,
+    /// Every bid set was considered, and the bids chosen beat the best value.
+    Improved,
+    /// Every bid set was considered, and the bids chosen tie the best value.
+    Tied,
+    /// Every bid set was considered, and the bids chosen are below the best
+    /// value.
+    Worse,
+}
+
+impl NodeOutcome {
+    fn name(self) -> &'static str {
+        match self {
+            NodeOutcome::Expanded => "expanded",
+            NodeOutcome::OverAllocated => "over_allocated",
+            NodeOutcome::Bounded => "bounded",
+            NodeOutcome::Improved => "improved",
+            NodeOutcome::Tied => "tied",
+            NodeOutcome::Worse => "worse",
+        }
+    }
+}
+
+fn phase_name(phase: SearchPhase) -> &'static str {
+    match phase {
+        SearchPhase::WinnerDetermination => "winner_determination",
+        SearchPhase::Payments => "payments",
+    }
+}
+
+// manual impl, since the derive would require `V: Default`
+impl<V> Default for SearchTree<V> {
+    fn default() -> Self {
+        Self { nodes: vec![] }
+    }
+}
+
+impl<V> SearchTree<V> {
+    /// Add the nodes of a search, whose parents index into `nodes`.
+    pub(crate) fn extend(&mut self, nodes: Vec<SearchTreeNode<V>>) {
+        let offset = self.nodes.len();
+        self.nodes
+            .extend(nodes.into_iter().map(|node| SearchTreeNode {
+                parent: node.parent.map(|parent| parent + offset),
+                ..node
+            }));
+    }
+}
+
+impl<V: Display> SearchTree<V> {
+    /// The tree as a JSON object with a `nodes` array. Values are written as
+    /// strings, since they may not fit in a JSON number.
+    ///
+    /// ```
+    /// use vcg_auction::{types::SimpleBid, AuctionConfig};
+    ///
+    /// let items = vec![("chair".to_string(), 1)];
+    /// let bids = vec![vec![SimpleBid::new("Alice", 10, [("chair", 1)])]];
+    /// let config = AuctionConfig::deterministic();
+    /// let (_outcome, tree) =
+    ///     vcg_auction::run_with_search_tree(&items, &bids, &config);
+    /// assert!(tree.to_json().starts_with(r#"{"nodes":[{"parent":null,"#));
+    /// ```
+    pub fn to_json(&self) -> String {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| {
+                format!(
+                    "{{\"parent\":{},\"phase\":\"{}\",\"depth\":{},\
+                     \"bid\":{},\"value\":{},\"bound\":{},\"best\":{},\
+                     \"outcome\":\"{}\"}}",
+                    json_option(node.parent),
+                    phase_name(node.phase),
+                    node.depth,
+                    json_option(node.bid),
+                    json_string(&node.value),
+                    node.bound.as_ref().map_or("null".to_string(), json_string),
+                    json_string(&node.best),
+                    node.outcome.name(),
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("{{\"nodes\":[{}]}}", nodes.join(","))
+    }
+
+    /// The tree as a Graphviz DOT digraph. Edges are labelled with the bid
+    /// chosen, and nodes with their value, bound and outcome.
+    pub fn to_dot(&self) -> String {
+        let mut dot = "digraph search_tree {\n".to_string();
+        for (i, node) in self.nodes.iter().enumerate() {
+            let bound = node
+                .bound
+                .as_ref()
+                .map_or(String::new(), |bound| format!(" bound {bound}"));
+            let label = format!(
+                "{} depth {}\nvalue {}{bound} best {}\n{}",
+                phase_name(node.phase),
+                node.depth,
+                node.value,
+                node.best,
+                node.outcome.name(),
+            );
+            dot.push_str(&format!("  n{i} [label={}];\n", dot_string(&label)));
+            if let Some(parent) = node.parent {
+                let edge = node
+                    .bid
+                    .map_or("none".to_string(), |bid| format!("bid {bid}"));
+                dot.push_str(&format!(
+                    "  n{parent} -> n{i} [label=\"{edge}\"];\n"
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn json_option(index: Option<usize>) -> String {
+    index.map_or("null".to_string(), |index| index.to_string())
+}
+
+/// A displayed value as a quoted JSON string.
+fn json_string(value: &impl Display) -> String {
+    let mut quoted = "\"".to_string();
+    for c in value.to_string().chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => {
+                quoted.push_str(&format!("\\u{:04x}", u32::from(c)))
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// A quoted DOT string, with newlines as line breaks.
+fn dot_string(text: &str) -> String {
+    let mut quoted = "\"".to_string();
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
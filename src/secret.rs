@@ -0,0 +1,100 @@
+//! Bid values kept confidential with the `secrecy` crate.
+//!
+//! Combinations of bids can include only a single bid, so the sums calculated
+//! during an auction can reveal individual bid values. Keeping every value
+//! and sum in a [`Secret`] zeroes each of them on drop, and keeps them out of
+//! debug output.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Add;
+
+use num_traits::Zero;
+use secrecy::{ExposeSecret, Secret, Zeroize};
+
+use crate::AddSubSelf;
+
+/// A bid value that is zeroed on drop and redacted from debug output, as are
+/// the sums and payments calculated from it.
+///
+/// ```
+/// use secrecy::ExposeSecret;
+/// use vcg_auction::SecretValue;
+///
+/// let value = SecretValue::new(5u64);
+/// assert_eq!(format!("{value:?}"), "[REDACTED]");
+/// assert_eq!(*value.expose_secret(), 5);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "secrecy")))]
+pub struct SecretValue<V: Zeroize>(Secret<V>);
+
+impl<V: Zeroize> SecretValue<V> {
+    pub fn new(value: V) -> Self {
+        Self(Secret::new(value))
+    }
+}
+
+impl<V: Zeroize> ExposeSecret<V> for SecretValue<V> {
+    fn expose_secret(&self) -> &V {
+        self.0.expose_secret()
+    }
+}
+
+impl<V: Zeroize + Copy> Clone for SecretValue<V> {
+    fn clone(&self) -> Self {
+        Self::new(*self.expose_secret())
+    }
+}
+
+impl<V: Zeroize> fmt::Debug for SecretValue<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[REDACTED]")
+    }
+}
+
+// Trait impls for Ord
+impl<V: Zeroize + Eq> PartialEq for SecretValue<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.expose_secret() == other.expose_secret()
+    }
+}
+
+impl<V: Zeroize + Eq> Eq for SecretValue<V> {}
+
+impl<V: Zeroize + Ord> Ord for SecretValue<V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.expose_secret().cmp(other.expose_secret())
+    }
+}
+
+impl<V: Zeroize + Ord> PartialOrd for SecretValue<V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<V: Zeroize + AddSubSelf> AddSubSelf for SecretValue<V> {
+    fn add(&self, other: &Self) -> Self {
+        Self::new(self.expose_secret().add(other.expose_secret()))
+    }
+    fn sub(&self, other: &Self) -> Self {
+        Self::new(self.expose_secret().sub(other.expose_secret()))
+    }
+}
+
+// Required by Zero
+impl<V: Zeroize + AddSubSelf> Add for SecretValue<V> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        AddSubSelf::add(&self, &other)
+    }
+}
+
+impl<V: Zeroize + AddSubSelf + Zero> Zero for SecretValue<V> {
+    fn zero() -> Self {
+        Self::new(V::zero())
+    }
+    fn is_zero(&self) -> bool {
+        self.expose_secret().is_zero()
+    }
+}
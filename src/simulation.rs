@@ -0,0 +1,307 @@
+//! Replaying recorded auctions under different pricing and fee policies, so
+//! marketplace operators can compare the effect of a change before rolling it
+//! out.
+
+use std::cmp::Ordering;
+
+use num_traits::Zero;
+
+use crate::{input::ItemStock, AddSubSelf, Allocation, Bid, PricingRule};
+
+/// The items and bid sets of an auction that was run.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct RecordedAuction<B: Bid> {
+    pub items: Vec<(B::Item, B::Quantity)>,
+    pub bid_sets: Vec<Vec<B>>,
+}
+
+/// Fee of a [`FeePolicy`], taken by the operator from a winner's payment.
+pub type FeeFn<'f, V> = dyn Fn(&V) -> V + 'f;
+
+/// How the auctions of a simulation are priced.
+///
+/// Bids are replayed as they were recorded, as if bidders bid their values
+/// whatever the policy.
+pub struct FeePolicy<'f, V> {
+    pricing: PricingRule,
+    min_bid: Option<V>,
+    reserve: Option<V>,
+    fee: Option<&'f FeeFn<'f, V>>,
+}
+
+impl<'f, V> FeePolicy<'f, V> {
+    /// A policy pricing winners with a rule, without fees.
+    pub fn new(pricing: PricingRule) -> Self {
+        Self {
+            pricing,
+            min_bid: None,
+            reserve: None,
+            fee: None,
+        }
+    }
+
+    /// Leave out bids worth less than `min_bid`.
+    pub fn min_bid(mut self, min_bid: V) -> Self {
+        self.min_bid = Some(min_bid);
+        self
+    }
+
+    /// Leave out bids worth less than `reserve`, and have each winner pay at
+    /// least `reserve` for each bid they win, like the reserve price of a
+    /// second-price auction.
+    pub fn reserve(mut self, reserve: V) -> Self {
+        self.reserve = Some(reserve);
+        self
+    }
+
+    /// Take a fee from each winner's payment.
+    pub fn fee(mut self, fee: &'f FeeFn<'f, V>) -> Self {
+        self.fee = Some(fee);
+        self
+    }
+}
+
+/// Totals of a policy over the auctions of a simulation, returned by
+/// [`simulate_policies`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PolicyTotals<V> {
+    /// Payments of the winners, fees included.
+    pub revenue: V,
+    /// Fees taken from the payments.
+    pub fees: V,
+    /// Value of the winning bids.
+    pub welfare: V,
+    /// Value of the winning bids less the payments.
+    pub surplus: V,
+}
+
+impl<V: Ord + AddSubSelf> PolicyTotals<V> {
+    /// How the totals differ from those of a baseline policy.
+    pub fn change_from(&self, baseline: &Self) -> PolicyChange<V> {
+        PolicyChange {
+            revenue: Change::between(&baseline.revenue, &self.revenue),
+            fees: Change::between(&baseline.fees, &self.fees),
+            welfare: Change::between(&baseline.welfare, &self.welfare),
+            surplus: Change::between(&baseline.surplus, &self.surplus),
+        }
+    }
+}
+
+/// How the [`PolicyTotals`] of a policy differ from a baseline.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PolicyChange<V> {
+    pub revenue: Change<V>,
+    pub fees: Change<V>,
+    pub welfare: Change<V>,
+    pub surplus: Change<V>,
+}
+
+/// Change of a total from a baseline.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Change<V> {
+    Unchanged,
+    Increase(V),
+    Decrease(V),
+}
+
+impl<V: Ord + AddSubSelf> Change<V> {
+    /// The change from `before` to `after`.
+    pub fn between(before: &V, after: &V) -> Self {
+        match after.cmp(before) {
+            Ordering::Equal => Change::Unchanged,
+            Ordering::Greater => Change::Increase(after.sub(before)),
+            Ordering::Less => Change::Decrease(before.sub(after)),
+        }
+    }
+}
+
+/// Replay recorded auctions under each policy, returning the totals of each
+/// policy in order. Ties are broken by choosing the first tied outcome, so
+/// each policy sees the same winners unless it leaves out bids.
+///
+/// ```
+/// use vcg_auction::{
+///     simulate_policies, types::SimpleBid, Change, FeePolicy, PricingRule,
+///     RecordedAuction,
+/// };
+///
+/// let chair = |bids| RecordedAuction {
+///     items: vec![("chair".to_string(), 1)],
+///     bid_sets: bids,
+/// };
+/// let corpus = [
+///     chair(vec![
+///         vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///         vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+///     ]),
+///     chair(vec![vec![SimpleBid::new("Carol", 4, [("chair", 1)])]]),
+/// ];
+/// let tenth = |payment: &u64| payment / 10;
+/// let policies = [
+///     FeePolicy::new(PricingRule::Vcg),
+///     FeePolicy::new(PricingRule::FirstPrice).fee(&tenth),
+///     FeePolicy::new(PricingRule::Vcg).min_bid(5),
+/// ];
+/// let totals = simulate_policies(&corpus, &policies);
+/// assert_eq!((totals[0].revenue, totals[0].surplus), (7, 7));
+/// assert_eq!((totals[1].revenue, totals[1].fees), (14, 1));
+///
+/// let first_price = totals[1].change_from(&totals[0]);
+/// assert_eq!(first_price.revenue, Change::Increase(7));
+/// assert_eq!(first_price.welfare, Change::Unchanged);
+/// // Carol's bid is left out with a minimum bid
+/// let min_bid = totals[2].change_from(&totals[0]);
+/// assert_eq!(min_bid.welfare, Change::Decrease(4));
+/// assert_eq!(min_bid.revenue, Change::Unchanged);
+/// ```
+pub fn simulate_policies<B: Bid>(
+    corpus: &[RecordedAuction<B>],
+    policies: &[FeePolicy<B::Value>],
+) -> Vec<PolicyTotals<B::Value>>
+where
+    B::Value: Clone,
+{
+    let mut totals = policies
+        .iter()
+        .map(|_| PolicyTotals {
+            revenue: B::Value::zero(),
+            fees: B::Value::zero(),
+            welfare: B::Value::zero(),
+            surplus: B::Value::zero(),
+        })
+        .collect::<Vec<_>>();
+    for auction in corpus {
+        // policies with the same minimum bid share their allocation
+        let mut allocations: Vec<(Option<&B::Value>, Allocation<B>)> = vec![];
+        for (policy, total) in policies.iter().zip(&mut totals) {
+            let min_bid = match (&policy.min_bid, &policy.reserve) {
+                (Some(min_bid), Some(reserve)) => Some(min_bid.max(reserve)),
+                (min_bid, reserve) => min_bid.as_ref().or(reserve.as_ref()),
+            };
+            let at = match allocations.iter().position(|(m, _)| *m == min_bid) {
+                Some(at) => at,
+                None => {
+                    let bid_sets = auction
+                        .bid_sets
+                        .iter()
+                        .map(|bid_set| {
+                            bid_set
+                                .iter()
+                                .filter(|b| {
+                                    min_bid.map_or(true, |m| b.bid_value() >= m)
+                                })
+                                .collect()
+                        })
+                        .collect();
+                    let allocation = Allocation::find(
+                        ItemStock::new(&auction.items),
+                        bid_sets,
+                        |_| 0,
+                    )
+                    .expect("the first tied outcome is valid");
+                    allocations.push((min_bid, allocation));
+                    allocations.len() - 1
+                }
+            };
+            let mut result = allocations[at].1.price(policy.pricing);
+            if let Some(reserve) = &policy.reserve {
+                for (bidder_name, payment) in &mut result.payments {
+                    let floor = result
+                        .winning_bids
+                        .iter()
+                        .filter(|b| b.bidder_name() == *bidder_name)
+                        .fold(B::Value::zero(), |sum, _| sum.add(reserve));
+                    if *payment < floor {
+                        *payment = floor;
+                    }
+                }
+            }
+            let welfare = result
+                .winning_bids
+                .iter()
+                .fold(B::Value::zero(), |sum, b| sum.add(b.bid_value()));
+            let revenue = result
+                .payments
+                .iter()
+                .fold(B::Value::zero(), |sum, (_, payment)| sum.add(payment));
+            if let Some(fee) = policy.fee {
+                for (_, payment) in &result.payments {
+                    total.fees = total.fees.add(&fee(payment));
+                }
+            }
+            total.surplus = total.surplus.add(&welfare.sub(&revenue));
+            total.welfare = total.welfare.add(&welfare);
+            total.revenue = total.revenue.add(&revenue);
+        }
+    }
+    totals
+}
+
+/// Totals of a reserve price over the auctions of a simulation, returned by
+/// [`sweep_reserve_prices`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ReservePoint<V> {
+    pub reserve: V,
+    pub totals: PolicyTotals<V>,
+}
+
+/// Replay recorded auctions with each candidate reserve price, set with
+/// [`FeePolicy::reserve`], returning the totals of each reserve in order.
+///
+/// Raising the reserve trades welfare, lost when bids below it are left
+/// out, for revenue, gained when winners pay up to it, until it turns away
+/// more winners than it raises from the rest. The points trace that curve
+/// for choosing a reserve from past bids, assuming bidders keep bidding the
+/// same whatever the reserve.
+///
+/// ```
+/// use vcg_auction::{
+///     sweep_reserve_prices, types::SimpleBid, PricingRule, RecordedAuction,
+/// };
+///
+/// let chair = |bids: &[(&str, u64)]| RecordedAuction {
+///     items: vec![("chair".to_string(), 1)],
+///     bid_sets: bids
+///         .iter()
+///         .map(|(name, value)| {
+///             vec![SimpleBid::new(*name, *value, [("chair", 1)])]
+///         })
+///         .collect(),
+/// };
+/// let corpus = [
+///     chair(&[("Alice", 10), ("Bob", 4)]),
+///     chair(&[("Carol", 8)]),
+///     chair(&[("Dave", 5), ("Erin", 3)]),
+/// ];
+/// let points = sweep_reserve_prices(&corpus, PricingRule::Vcg, [0, 6, 9]);
+/// let revenue = points
+///     .iter()
+///     .map(|point| (point.reserve, point.totals.revenue))
+///     .collect::<Vec<_>>();
+/// // a reserve of 6 raises the most, turning away Dave's auction
+/// assert_eq!(revenue, [(0, 7), (6, 12), (9, 9)]);
+/// assert_eq!(points[1].totals.welfare, 18);
+/// ```
+pub fn sweep_reserve_prices<B: Bid>(
+    corpus: &[RecordedAuction<B>],
+    pricing: PricingRule,
+    reserves: impl IntoIterator<Item = B::Value>,
+) -> Vec<ReservePoint<B::Value>>
+where
+    B::Value: Clone,
+{
+    let reserves = reserves.into_iter().collect::<Vec<_>>();
+    let policies = reserves
+        .iter()
+        .map(|reserve| FeePolicy::new(pricing).reserve(reserve.clone()))
+        .collect::<Vec<_>>();
+    simulate_policies(corpus, &policies)
+        .into_iter()
+        .zip(reserves)
+        .map(|(totals, reserve)| ReservePoint { reserve, totals })
+        .collect()
+}
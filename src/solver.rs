@@ -0,0 +1,480 @@
+//! A resumable auction solver, advanced a bounded number of search nodes at a
+//! time.
+//!
+//! The auction functions block until the result is found, which can take a
+//! long time for large auctions. [`Solver`] instead does a bounded amount of
+//! work per call to [`Solver::step`], so it can be driven from a cooperative
+//! scheduler or a game loop, and paused by not stepping it. With
+//! [`Solver::strict_budget`], moving between searches is counted against
+//! each step's budget too, for loops with a deadline on every step, though
+//! starting a search still takes time that grows with the auction.
+
+use std::mem;
+use std::task::Poll;
+
+use crate::{
+    control::SearchControl,
+    decomposition::ComponentSearches,
+    input::{bid_set_refs, ItemStock},
+    invariants::check_invariants,
+    vcg::{
+        bid_sets_without_bidder, break_tie, other_winning_bids,
+        payment_from_value, verification_search, winning_bidders,
+    },
+    AuctionError, AuctionResult, Bid, OnTimeout, SearchBuffers, SearchOptions,
+    SearchPhase, StockEntry,
+};
+
+/// An auction that is solved in steps. Gives the same result as
+/// [`vcg_auction_with_tiebreaker`](crate::vcg_auction_with_tiebreaker), and
+/// splits its searches into the same sub-auctions sharing no items, so
+/// explores the same nodes however it's stepped. With a [strict
+/// budget](Self::strict_budget), payment searches start without the value of
+/// the other winners' bids, so can explore more.
+///
+/// ```
+/// use std::task::Poll;
+/// use vcg_auction::{types::SimpleBid, Solver};
+///
+/// let items = vec![("chair".to_string(), 2)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 5, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+/// ];
+/// let mut solver = Solver::new(&items, &bids, |_| 0);
+/// let result = loop {
+///     match solver.step(2) {
+///         Poll::Ready(result) => break result.unwrap(),
+///         // other work can be done between steps
+///         Poll::Pending => (),
+///     }
+/// };
+/// assert_eq!(result.winning_bids, [&bids[0][0], &bids[1][0]]);
+/// ```
+pub struct Solver<'a, B: Bid, T> {
+    items: ItemStock<'a, B>,
+    exclusive_bid_sets: Vec<Vec<&'a B>>,
+    control: SearchControl<'a, B::Value>,
+    stage: Stage<'a, B, T>,
+    nodes: u64,
+    strict: bool,
+    buffers: SearchBuffers<B>, // lent to the current search
+}
+
+/// What the solver is searching for.
+enum Stage<'a, B: Bid, T> {
+    WinnerDetermination {
+        search: ComponentSearches<'a, B>,
+        tiebreaker: T,
+    },
+    Payments {
+        winning_bid_set: Vec<&'a B>,
+        highest_value: B::Value,
+        // bidders without a payment yet, the current one last
+        bidders: Vec<&'a B::Name>,
+        search: ComponentSearches<'a, B>, // auction without the bidder
+        payments: Vec<(&'a B::Name, B::Value)>,
+    },
+    /// Checking the payments, with
+    /// [`verify_payments`](SearchOptions::verify_payments).
+    Verification {
+        winning_bid_set: Vec<&'a B>,
+        highest_value: B::Value,
+        payments: Vec<(&'a B::Name, B::Value)>,
+        verified: usize, // payments checked, the current one next
+        search: ComponentSearches<'a, B>, // auction without its bidder
+    },
+    /// The auction exceeds a limit on its size, or asks for an option the
+    /// solver can't honour, so nothing is searched.
+    Rejected(AuctionError),
+    Finished,
+}
+
+impl<'a, B: Bid, T> Stage<'a, B, T> {
+    /// The search running, if any.
+    fn search_mut(&mut self) -> Option<&mut ComponentSearches<'a, B>> {
+        match self {
+            Stage::WinnerDetermination { search, .. }
+            | Stage::Payments { search, .. }
+            | Stage::Verification { search, .. } => Some(search),
+            Stage::Rejected(_) | Stage::Finished => None,
+        }
+    }
+}
+
+impl<'a, B, T> Solver<'a, B, T>
+where
+    B: Bid,
+    T: FnOnce(&[Vec<&B>]) -> usize,
+{
+    /// Set up an auction to solve, without exploring any bids yet. Takes the
+    /// same items and bid sets as [`vcg_auction`](crate::vcg_auction).
+    pub fn new<S>(
+        items: impl IntoIterator<Item = impl StockEntry<'a, B::Item, B::Quantity>>,
+        exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+        tiebreaker: T,
+    ) -> Self
+    where
+        S: AsRef<[B]> + ?Sized + 'a,
+    {
+        Self::with_options(
+            items,
+            exclusive_bid_sets,
+            tiebreaker,
+            &SearchOptions::default(),
+        )
+    }
+
+    /// Set up an auction to solve with search options, like
+    /// [`vcg_auction_with_options`](crate::vcg_auction_with_options). With
+    /// [`max_nodes`](SearchOptions::max_nodes), the total work of the solver
+    /// is capped no matter how it's stepped, and exceeding the cap is
+    /// returned as an error from [`step`](Self::step). So is exceeding the
+    /// limits on the number of bid sets and bids, on the first step. With
+    /// [`verify_payments`](SearchOptions::verify_payments), the searches
+    /// checking the payments are stepped through after the payment searches.
+    ///
+    /// A solver only returns complete results, so
+    /// [`OnTimeout::BestSoFar`] is rejected with
+    /// [`AuctionError::UnsupportedOption`] on the first step.
+    pub fn with_options<S>(
+        items: impl IntoIterator<Item = impl StockEntry<'a, B::Item, B::Quantity>>,
+        exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+        tiebreaker: T,
+        options: &SearchOptions<'a, B::Value>,
+    ) -> Self
+    where
+        S: AsRef<[B]> + ?Sized + 'a,
+    {
+        let items = ItemStock::new(items);
+        let exclusive_bid_sets = bid_set_refs(exclusive_bid_sets);
+        let control = SearchControl::new(options.clone());
+        let stage = match options.check_size(&exclusive_bid_sets) {
+            // results are only returned complete, with no best so far
+            Ok(()) if options.on_timeout == OnTimeout::BestSoFar => {
+                Stage::Rejected(AuctionError::UnsupportedOption)
+            }
+            Ok(()) => {
+                let search = ComponentSearches::new(
+                    &items,
+                    &exclusive_bid_sets,
+                    &control,
+                    SearchPhase::WinnerDetermination,
+                    &[],
+                );
+                Stage::WinnerDetermination { search, tiebreaker }
+            }
+            Err(error) => Stage::Rejected(error),
+        };
+        Self {
+            items,
+            exclusive_bid_sets,
+            control,
+            stage,
+            nodes: 0,
+            strict: false,
+            buffers: SearchBuffers::new(),
+        }
+    }
+
+    /// Keep steps close to their budget, counting each move from one search
+    /// to the next as a node, and leaving out the local search that seeds
+    /// payment searches, whose work isn't bounded by nodes. This suits a
+    /// loop with a deadline for each step, like a frame of a game.
+    ///
+    /// The budget bounds the nodes explored, not all the work of a step.
+    /// Starting a search, which a step moving on to it does, propagates its
+    /// bid sets, splits them into sub-auctions and covers them with cliques,
+    /// in time that grows with the number of bids however small the budget.
+    /// So the first step of each payment search, and of each sub-auction
+    /// after the first, isn't bounded by its budget. Setting up the solver
+    /// does the same for the first search of winner determination.
+    ///
+    /// ```
+    /// use std::task::Poll;
+    /// use vcg_auction::{types::SimpleBid, Solver};
+    ///
+    /// let items = vec![("chair".to_string(), 1)];
+    /// let bids = vec![
+    ///     vec![SimpleBid::new("Alice", 5, [("chair", 1)])],
+    ///     vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+    /// ];
+    /// let mut solver = Solver::new(&items, &bids, |_| 0).strict_budget();
+    /// let mut frames = 0;
+    /// let result = loop {
+    ///     frames += 1;
+    ///     if let Poll::Ready(result) = solver.step(1) {
+    ///         break result.unwrap();
+    ///     }
+    /// };
+    /// assert_eq!(result.payments, [(&"Alice".to_string(), 4)]);
+    /// assert!(frames > 1);
+    /// ```
+    pub fn strict_budget(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Search with `buffers`, and with them again in each later search of the
+    /// auction, rather than with memory allocated for each search. Only
+    /// depth-first searches use them.
+    pub fn with_buffers(mut self, mut buffers: SearchBuffers<B>) -> Self {
+        if let Some(search) = self.stage.search_mut() {
+            search.use_buffers(&mut buffers);
+        }
+        self.buffers = buffers;
+        self
+    }
+
+    /// The buffers searched with, to reuse in another solver. Taking them
+    /// before the auction is solved abandons it.
+    pub fn into_buffers(mut self) -> SearchBuffers<B> {
+        let mut buffers = std::mem::take(&mut self.buffers);
+        if let Some(search) = self.stage.search_mut() {
+            search.return_buffers(&mut buffers);
+        }
+        buffers
+    }
+
+    /// Explore up to `max_nodes` search nodes. Returns the result once the
+    /// auction is solved, or [`Poll::Pending`] if there's more to explore.
+    /// With a [strict budget](Self::strict_budget), moving on to the next
+    /// search takes one of the nodes, though starting it isn't bounded by
+    /// them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called again after returning [`Poll::Ready`].
+    pub fn step(
+        &mut self,
+        max_nodes: u64,
+    ) -> Poll<Result<AuctionResult<'a, B>, AuctionError>> {
+        let mut budget = max_nodes;
+        loop {
+            let search = match &mut self.stage {
+                Stage::WinnerDetermination { search, .. }
+                | Stage::Payments { search, .. }
+                | Stage::Verification { search, .. } => search,
+                Stage::Rejected(error) => {
+                    let error = *error;
+                    self.stage = Stage::Finished;
+                    return Poll::Ready(Err(error));
+                }
+                Stage::Finished => panic!("solver stepped after finishing"),
+            };
+            let explored = search.run(&self.items, &self.control, budget);
+            self.nodes += explored;
+            budget -= explored;
+            if !search.component_finished() {
+                return Poll::Pending;
+            }
+            if self.strict {
+                // moving on is left to the next step if out of budget
+                if budget == 0 {
+                    return Poll::Pending;
+                }
+                budget -= 1;
+            }
+            search.return_buffers(&mut self.buffers);
+            if search.advance(&self.items, &self.control) {
+                search.use_buffers(&mut self.buffers);
+                continue;
+            }
+            if let Some(result) = self.advance() {
+                return Poll::Ready(result);
+            }
+        }
+    }
+
+    /// Number of search nodes explored so far. Moves from one search to the
+    /// next aren't counted, even with a [strict
+    /// budget](Self::strict_budget).
+    pub fn nodes_explored(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Move on from a finished search, returning the result if there are no
+    /// searches left.
+    fn advance(
+        &mut self,
+    ) -> Option<Result<AuctionResult<'a, B>, AuctionError>> {
+        match mem::replace(&mut self.stage, Stage::Finished) {
+            Stage::WinnerDetermination { search, tiebreaker } => {
+                let (highest_bid_sets, highest_value) =
+                    match search.into_highest() {
+                        Ok(highest) => highest,
+                        Err((interrupt, _)) => {
+                            return Some(Err(interrupt.into_error()))
+                        }
+                    };
+                let Some(winning_bid_set) =
+                    break_tie(&highest_bid_sets, tiebreaker)
+                else {
+                    return Some(Err(AuctionError::InvalidTiebreak));
+                };
+                let mut bidders = winning_bidders(winning_bid_set);
+                bidders.reverse();
+                self.next_payment(
+                    winning_bid_set.clone(),
+                    highest_value,
+                    bidders,
+                    vec![],
+                )
+            }
+            Stage::Payments {
+                winning_bid_set,
+                highest_value,
+                mut bidders,
+                search,
+                mut payments,
+            } => {
+                let bidder_name =
+                    bidders.pop().expect("payment search has a bidder");
+                let value = match search.into_highest() {
+                    Ok((_, value)) => value,
+                    Err((interrupt, _)) => {
+                        return Some(Err(interrupt.into_error()))
+                    }
+                };
+                payments.push((
+                    bidder_name,
+                    payment_from_value(&winning_bid_set, bidder_name, value),
+                ));
+                self.next_payment(
+                    winning_bid_set,
+                    highest_value,
+                    bidders,
+                    payments,
+                )
+            }
+            Stage::Verification {
+                winning_bid_set,
+                highest_value,
+                payments,
+                verified,
+                search,
+            } => {
+                let (bidder_name, payment) = &payments[verified];
+                let value = match search.into_highest() {
+                    Ok((_, value)) => value,
+                    Err((interrupt, _)) => {
+                        return Some(Err(interrupt.into_error()))
+                    }
+                };
+                if payment_from_value(&winning_bid_set, bidder_name, value)
+                    != *payment
+                {
+                    return Some(Err(AuctionError::PaymentMismatch));
+                }
+                self.next_verification(
+                    winning_bid_set,
+                    highest_value,
+                    payments,
+                    verified + 1,
+                )
+            }
+            Stage::Rejected(_) | Stage::Finished => {
+                unreachable!("only searching solvers are advanced")
+            }
+        }
+    }
+
+    /// Start the payment search of the next bidder, or return the result if
+    /// every winner has a payment.
+    fn next_payment(
+        &mut self,
+        winning_bid_set: Vec<&'a B>,
+        highest_value: B::Value,
+        bidders: Vec<&'a B::Name>,
+        payments: Vec<(&'a B::Name, B::Value)>,
+    ) -> Option<Result<AuctionResult<'a, B>, AuctionError>> {
+        let Some(bidder_name) = bidders.last() else {
+            if self.control.options().verify_payments {
+                return self.next_verification(
+                    winning_bid_set,
+                    highest_value,
+                    payments,
+                    0,
+                );
+            }
+            return Some(self.finish(winning_bid_set, highest_value, payments));
+        };
+        let bid_sets =
+            bid_sets_without_bidder(&self.exclusive_bid_sets, bidder_name);
+        // the other winners' bids still fit without the bidder, though the
+        // local search raising their value isn't bounded by nodes
+        let other_bids = if self.strict {
+            vec![]
+        } else {
+            other_winning_bids(&winning_bid_set, bidder_name)
+        };
+        let mut search = ComponentSearches::new(
+            &self.items,
+            &bid_sets,
+            &self.control,
+            SearchPhase::Payments,
+            &other_bids,
+        );
+        search.use_buffers(&mut self.buffers);
+        self.stage = Stage::Payments {
+            winning_bid_set,
+            highest_value,
+            bidders,
+            search,
+            payments,
+        };
+        None
+    }
+
+    /// Start the search checking the next payment, or return the result if
+    /// every payment is checked.
+    fn next_verification(
+        &mut self,
+        winning_bid_set: Vec<&'a B>,
+        highest_value: B::Value,
+        payments: Vec<(&'a B::Name, B::Value)>,
+        verified: usize,
+    ) -> Option<Result<AuctionResult<'a, B>, AuctionError>> {
+        let Some((bidder_name, _)) = payments.get(verified) else {
+            return Some(self.finish(winning_bid_set, highest_value, payments));
+        };
+        let search = verification_search(
+            &self.items,
+            &self.exclusive_bid_sets,
+            bidder_name,
+            &self.control,
+        );
+        let mut search = ComponentSearches::single(search);
+        search.use_buffers(&mut self.buffers);
+        self.stage = Stage::Verification {
+            winning_bid_set,
+            highest_value,
+            payments,
+            verified,
+            search,
+        };
+        None
+    }
+
+    /// The result of the solved auction, checked against the invariants if
+    /// asked to.
+    fn finish(
+        &self,
+        winning_bid_set: Vec<&'a B>,
+        highest_value: B::Value,
+        payments: Vec<(&'a B::Name, B::Value)>,
+    ) -> Result<AuctionResult<'a, B>, AuctionError> {
+        if self.control.options().check_invariants {
+            check_invariants(
+                &self.items,
+                &winning_bid_set,
+                &highest_value,
+                &payments,
+            );
+        }
+        Ok(AuctionResult {
+            winning_bids: winning_bid_set,
+            payments,
+        })
+    }
+}
@@ -0,0 +1,140 @@
+//! Results in a canonical order, for comparing and snapshotting them.
+
+use std::cmp::Ordering;
+
+use crate::{vcg::copy_value, AuctionResult, Bid, OwnedAuctionResult};
+
+/// An auction result with its winning bids and payments sorted, returned by
+/// [`AuctionResult::sorted`] and [`OwnedAuctionResult::sorted`].
+///
+/// Results with the same winning bids and payments are equal, and serialize
+/// the same with the `serde` feature, whatever order the bid sets were given
+/// in. This keeps snapshot tests, such as with
+/// [`insta`](https://crates.io/crates/insta), from changing when bid sets are
+/// collected from a container without a stable order. Which of several tied
+/// outcomes wins still depends on the tiebreak, so tests should break ties
+/// with [`Tiebreak::First`](crate::Tiebreak::First) or a seed.
+///
+/// ```
+/// use vcg_auction::{types::SimpleBid, vcg_auction_with_tiebreaker};
+///
+/// let items = vec![("chair".to_string(), 2)];
+/// let alice = vec![SimpleBid::new("Alice", 5, [("chair", 1)])];
+/// let bob = vec![SimpleBid::new("Bob", 3, [("chair", 1)])];
+/// let first = [&alice, &bob];
+/// let second = [&bob, &alice];
+/// let first = vcg_auction_with_tiebreaker(&items, first, |_| 0).unwrap();
+/// let second = vcg_auction_with_tiebreaker(&items, second, |_| 0).unwrap();
+/// assert_ne!(first, second);
+/// assert_eq!(first.sorted(), second.sorted());
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(bound(serialize = "B: serde::Serialize, \
+                             B::Name: serde::Serialize, \
+                             B::Value: serde::Serialize"))
+)]
+pub struct SortedResult<'r, B: Bid> {
+    /// The winning bids, by bidder name, then value, then items.
+    pub winning_bids: Vec<&'r B>,
+    /// The payments, by bidder name.
+    pub payments: Vec<(&'r B::Name, B::Value)>,
+}
+
+impl<'r, B: Bid + 'r> SortedResult<'r, B>
+where
+    B::Name: Ord,
+    B::Item: Ord,
+{
+    fn new<'p>(
+        winning_bids: impl IntoIterator<Item = &'r B>,
+        payments: impl IntoIterator<Item = (&'r B::Name, &'p B::Value)>,
+    ) -> Self
+    where
+        B::Value: 'p,
+    {
+        let mut winning_bids = winning_bids.into_iter().collect::<Vec<_>>();
+        winning_bids.sort_by(|a, b| {
+            a.bidder_name()
+                .cmp(b.bidder_name())
+                .then_with(|| a.bid_value().cmp(b.bid_value()))
+                .then_with(|| compare_items(*a, *b))
+        });
+        let mut payments = payments
+            .into_iter()
+            .map(|(name, payment)| (name, copy_value::<B>(payment)))
+            .collect::<Vec<_>>();
+        payments.sort_by_key(|(name, _)| *name);
+        Self {
+            winning_bids,
+            payments,
+        }
+    }
+}
+
+/// Order bids by their items, each sorted by item then quantity, compared
+/// like slices. Quantities that can't be ordered, like NaN, compare equal.
+fn compare_items<B: Bid>(a: &B, b: &B) -> Ordering
+where
+    B::Item: Ord,
+{
+    let (a, b) = (sorted_items(a), sorted_items(b));
+    a.iter()
+        .zip(&b)
+        .map(|(a, b)| compare_item::<B>(a, b))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+fn sorted_items<B: Bid>(bid: &B) -> Vec<&(B::Item, B::Quantity)>
+where
+    B::Item: Ord,
+{
+    let mut items = bid.bid_items().iter().collect::<Vec<_>>();
+    items.sort_by(|a, b| compare_item::<B>(a, b));
+    items
+}
+
+fn compare_item<B: Bid>(
+    (a_item, a_quantity): &(B::Item, B::Quantity),
+    (b_item, b_quantity): &(B::Item, B::Quantity),
+) -> Ordering
+where
+    B::Item: Ord,
+{
+    a_item.cmp(b_item).then_with(|| {
+        a_quantity
+            .partial_cmp(b_quantity)
+            .unwrap_or(Ordering::Equal)
+    })
+}
+
+impl<'a, B: Bid> AuctionResult<'a, B> {
+    /// The result with its winning bids and payments in a canonical order.
+    pub fn sorted(&self) -> SortedResult<'a, B>
+    where
+        B::Name: Ord,
+        B::Item: Ord,
+    {
+        SortedResult::new(
+            self.winning_bids.iter().copied(),
+            self.payments.iter().map(|(name, payment)| (*name, payment)),
+        )
+    }
+}
+
+impl<B: Bid> OwnedAuctionResult<B> {
+    /// The result with its winning bids and payments in a canonical order.
+    pub fn sorted(&self) -> SortedResult<'_, B>
+    where
+        B::Name: Ord,
+        B::Item: Ord,
+    {
+        SortedResult::new(
+            &self.winning_bids,
+            self.payments.iter().map(|(name, payment)| (name, payment)),
+        )
+    }
+}
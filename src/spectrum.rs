@@ -0,0 +1,376 @@
+//! Preset for spectrum auctions, where licenses for blocks of a frequency
+//! band in a region are sold together.
+//!
+//! A [`SpectrumMarket`] lists the blocks of each band, the regions they're
+//! offered in, which regions are adjacent, and caps on how many blocks one
+//! bidder may win. Every band and region is an item, with a stock of the
+//! band's blocks. A cap is an item of its own for each bidder, with a stock of
+//! the cap's limit, which each bid of the bidder takes its blocks in the cap's
+//! scope from.
+//!
+//! Bundles of adjacent regions are often worth more to a bidder than the
+//! regions alone, so the market suggests footprints of a region and its
+//! neighbours to bid on.
+//!
+//! ```
+//! use vcg_auction::{
+//!     spectrum::{SpectrumCap, SpectrumMarket},
+//!     vcg_auction_with_tiebreaker,
+//! };
+//!
+//! let mut market = SpectrumMarket::new([("700MHz", 2)], ["north", "south"]);
+//! market.adjacent("north", "south");
+//! // nobody may hold more than 2 blocks of 700MHz
+//! market.cap(SpectrumCap {
+//!     name: "700MHz cap".into(),
+//!     bands: vec!["700MHz".into()],
+//!     regions: vec![],
+//!     blocks: 2,
+//! });
+//! let bids = vec![
+//!     vec![
+//!         market.bid("Alice", 10, market.national("700MHz", 1))?,
+//!         // over the cap, so it can't win
+//!         market.bid("Alice", 15, market.national("700MHz", 2))?,
+//!     ],
+//!     vec![market.bid("Bob", 3, [("700MHz", "south", 1)])?],
+//! ];
+//! let items = market.items(&bids);
+//! let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+//! assert_eq!(result.winning_bids, [&bids[0][0], &bids[1][0]]);
+//! # Ok::<(), vcg_auction::spectrum::SpectrumError>(())
+//! ```
+
+use std::fmt;
+
+use crate::Bid;
+
+/// A license for blocks of a band in a region.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct License {
+    pub band: String,
+    pub region: String,
+}
+
+/// Item of a spectrum auction.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SpectrumItem {
+    /// Blocks of a band in a region.
+    License(License),
+    /// The blocks a bidder may still win under a cap.
+    Cap { cap: String, bidder: String },
+}
+
+/// A limit on the blocks one bidder may win of some bands in some regions.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SpectrumCap {
+    pub name: String,
+    /// Bands the cap counts blocks of, or every band if empty.
+    pub bands: Vec<String>,
+    /// Regions the cap counts blocks in, or every region if empty.
+    pub regions: Vec<String>,
+    /// Most blocks a bidder may win in the cap's scope.
+    pub blocks: u64,
+}
+
+impl SpectrumCap {
+    /// Whether the cap counts the blocks of a license.
+    pub fn applies_to(&self, license: &License) -> bool {
+        (self.bands.is_empty() || self.bands.contains(&license.band))
+            && (self.regions.is_empty()
+                || self.regions.contains(&license.region))
+    }
+}
+
+/// Reasons a bid can't be made in a [`SpectrumMarket`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum SpectrumError {
+    /// The band isn't offered in the region.
+    UnknownLicense(License),
+    /// The bid asks for more blocks of a license than are offered.
+    TooManyBlocks {
+        license: License,
+        requested: u64,
+        offered: u64,
+    },
+}
+
+impl fmt::Display for SpectrumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpectrumError::UnknownLicense(License { band, region }) => {
+                write!(f, "band {band:?} isn't offered in region {region:?}")
+            }
+            SpectrumError::TooManyBlocks {
+                license: License { band, region },
+                requested,
+                offered,
+            } => write!(
+                f,
+                "{requested} blocks of band {band:?} requested in region \
+                 {region:?}, which offers {offered}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SpectrumError {}
+
+/// A bid for blocks of licenses, created with [`SpectrumMarket::bid`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SpectrumBid {
+    pub name: String,
+    pub value: u64,
+    items: Vec<(SpectrumItem, u64)>,
+}
+
+impl SpectrumBid {
+    /// The blocks of each license the bid is for.
+    pub fn licenses(&self) -> impl Iterator<Item = (&License, u64)> {
+        self.items.iter().filter_map(|(item, blocks)| match item {
+            SpectrumItem::License(license) => Some((license, *blocks)),
+            SpectrumItem::Cap { .. } => None,
+        })
+    }
+}
+
+impl Bid for SpectrumBid {
+    type Name = String;
+    type Value = u64;
+    type Item = SpectrumItem;
+    type Quantity = u64;
+
+    fn bidder_name(&self) -> &Self::Name {
+        &self.name
+    }
+    fn bid_value(&self) -> &Self::Value {
+        &self.value
+    }
+    fn bid_items(&self) -> &[(Self::Item, Self::Quantity)] {
+        &self.items
+    }
+}
+
+/// The licenses of a spectrum auction, the adjacency of their regions, and
+/// the caps on what each bidder may win.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SpectrumMarket {
+    regions: Vec<String>,
+    licenses: Vec<(License, u64)>,
+    adjacency: Vec<(usize, usize)>,
+    caps: Vec<SpectrumCap>,
+}
+
+impl SpectrumMarket {
+    /// A market offering the blocks of every band in every region.
+    pub fn new<B: Into<String>, R: Into<String>>(
+        bands: impl IntoIterator<Item = (B, u64)>,
+        regions: impl IntoIterator<Item = R>,
+    ) -> Self {
+        let bands = bands
+            .into_iter()
+            .map(|(band, blocks)| (band.into(), blocks))
+            .collect::<Vec<(String, u64)>>();
+        let regions = regions.into_iter().map(Into::into).collect::<Vec<_>>();
+        let licenses = bands
+            .iter()
+            .flat_map(|(band, blocks)| {
+                regions.iter().map(|region| {
+                    let license = License {
+                        band: band.clone(),
+                        region: region.clone(),
+                    };
+                    (license, *blocks)
+                })
+            })
+            .collect();
+        Self {
+            regions,
+            licenses,
+            adjacency: vec![],
+            caps: vec![],
+        }
+    }
+
+    /// Record that two regions are adjacent. Regions that aren't in the
+    /// market are ignored.
+    pub fn adjacent(&mut self, region: &str, other: &str) -> &mut Self {
+        let position = |region| self.regions.iter().position(|r| r == region);
+        if let (Some(a), Some(b)) = (position(region), position(other)) {
+            let pair = (a.min(b), a.max(b));
+            if a != b && !self.adjacency.contains(&pair) {
+                self.adjacency.push(pair);
+            }
+        }
+        self
+    }
+
+    /// Limit the blocks one bidder may win. Caps apply to bids made after
+    /// they're added.
+    pub fn cap(&mut self, cap: SpectrumCap) -> &mut Self {
+        self.caps.push(cap);
+        self
+    }
+
+    /// Each license, with the blocks offered.
+    pub fn licenses(&self) -> &[(License, u64)] {
+        &self.licenses
+    }
+
+    /// The caps of the market.
+    pub fn caps(&self) -> &[SpectrumCap] {
+        &self.caps
+    }
+
+    /// Whether two regions are adjacent.
+    pub fn is_adjacent(&self, region: &str, other: &str) -> bool {
+        self.neighbours(region).any(|neighbour| neighbour == other)
+    }
+
+    /// The regions adjacent to a region, in the market's order.
+    pub fn neighbours<'m>(
+        &'m self,
+        region: &'m str,
+    ) -> impl Iterator<Item = &'m str> + 'm {
+        self.regions
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, other)| {
+                let at = self.regions.iter().position(|r| r == region)?;
+                let pair = (at.min(i), at.max(i));
+                self.adjacency.contains(&pair).then_some(other.as_str())
+            })
+    }
+
+    /// Suggested bundles of a band: each region with its neighbours, for
+    /// `blocks` blocks in each region. Regions without neighbours are left
+    /// out, since their footprint is the region alone.
+    pub fn footprints<'m>(
+        &'m self,
+        band: &'m str,
+        blocks: u64,
+    ) -> Vec<Vec<(&'m str, &'m str, u64)>> {
+        self.regions
+            .iter()
+            .filter_map(|region| {
+                let mut footprint = vec![(band, region.as_str(), blocks)];
+                footprint.extend(
+                    self.neighbours(region)
+                        .map(|neighbour| (band, neighbour, blocks)),
+                );
+                (footprint.len() > 1).then_some(footprint)
+            })
+            .collect()
+    }
+
+    /// Suggested bundle of a band in every region, for `blocks` blocks in
+    /// each.
+    pub fn national<'m>(
+        &'m self,
+        band: &'m str,
+        blocks: u64,
+    ) -> Vec<(&'m str, &'m str, u64)> {
+        self.regions
+            .iter()
+            .map(|region| (band, region.as_str(), blocks))
+            .collect()
+    }
+
+    /// A bid for blocks of each band in each region, which also takes from
+    /// the bidder's caps. Blocks of the same license are added together.
+    pub fn bid<B: AsRef<str>, R: AsRef<str>>(
+        &self,
+        bidder: impl Into<String>,
+        value: u64,
+        blocks: impl IntoIterator<Item = (B, R, u64)>,
+    ) -> Result<SpectrumBid, SpectrumError> {
+        let bidder = bidder.into();
+        let mut items: Vec<(SpectrumItem, u64)> = vec![];
+        for (band, region, requested) in blocks {
+            let license = License {
+                band: band.as_ref().into(),
+                region: region.as_ref().into(),
+            };
+            let offered = self
+                .licenses
+                .iter()
+                .find(|(l, _)| *l == license)
+                .map(|(_, offered)| *offered)
+                .ok_or_else(|| {
+                    SpectrumError::UnknownLicense(license.clone())
+                })?;
+            let item = SpectrumItem::License(license.clone());
+            let total = match items.iter_mut().find(|(i, _)| *i == item) {
+                Some((_, total)) => {
+                    *total = total.saturating_add(requested);
+                    *total
+                }
+                None => {
+                    items.push((item, requested));
+                    requested
+                }
+            };
+            if total > offered {
+                return Err(SpectrumError::TooManyBlocks {
+                    license,
+                    requested: total,
+                    offered,
+                });
+            }
+        }
+        for cap in &self.caps {
+            let capped = items
+                .iter()
+                .filter(|(item, _)| match item {
+                    SpectrumItem::License(license) => cap.applies_to(license),
+                    SpectrumItem::Cap { .. } => false,
+                })
+                .fold(0u64, |sum, (_, blocks)| sum.saturating_add(*blocks));
+            if capped > 0 {
+                let item = SpectrumItem::Cap {
+                    cap: cap.name.clone(),
+                    bidder: bidder.clone(),
+                };
+                items.push((item, capped));
+            }
+        }
+        Ok(SpectrumBid {
+            name: bidder,
+            value,
+            items,
+        })
+    }
+
+    /// Items of an auction of the bid sets: each license with its blocks,
+    /// then each cap of each bidder with the cap's limit.
+    pub fn items(
+        &self,
+        bid_sets: &[Vec<SpectrumBid>],
+    ) -> Vec<(SpectrumItem, u64)> {
+        let mut items = self
+            .licenses
+            .iter()
+            .map(|(license, blocks)| {
+                (SpectrumItem::License(license.clone()), *blocks)
+            })
+            .collect::<Vec<_>>();
+        for cap in &self.caps {
+            for bid in bid_sets.iter().flatten() {
+                let item = SpectrumItem::Cap {
+                    cap: cap.name.clone(),
+                    bidder: bid.name.clone(),
+                };
+                if !items.iter().any(|(i, _)| *i == item) {
+                    items.push((item, cap.blocks));
+                }
+            }
+        }
+        items
+    }
+}
@@ -0,0 +1,365 @@
+//! Live auctions that take bid sets as they arrive, keeping the items, bids
+//! and result in a pluggable [`BidStore`].
+//!
+//! Services running auctions over time need the bids to outlive the process
+//! taking them. Rather than wrapping the whole crate, they implement
+//! [`BidStore`] over their own database, such as sled or Postgres, and drive
+//! the auction with an [`AuctionState`]. [`MemoryStore`] keeps everything in
+//! memory, for tests and for auctions that don't need persisting.
+
+use std::{convert::Infallible, fmt, marker::PhantomData};
+
+use crate::{
+    run, AuctionConfig, AuctionError, Bid, OwnedAuctionResult,
+    SubmissionLimits, SubmissionRejection,
+};
+
+/// Identifies a bid set stored by a [`BidStore`], to withdraw it by.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct BidSetId(pub u64);
+
+/// Items for sale and their quantities, as kept in a [`BidStore`].
+pub type Stock<B> = Vec<(<B as Bid>::Item, <B as Bid>::Quantity)>;
+
+/// Bid sets with their IDs, as kept in a [`BidStore`].
+pub type StoredBidSets<B> = Vec<(BidSetId, Vec<B>)>;
+
+/// Storage for the items, bid sets and result of an [`AuctionState`].
+///
+/// Bid sets must be loaded in the order they were inserted, since the order
+/// of the bid sets is part of an auction's input. Loading takes `&self`, so
+/// backends that need mutable access to a connection can keep it behind a
+/// lock or use a pool.
+pub trait BidStore<B: Bid> {
+    /// Error of the storage backend.
+    type Error;
+
+    /// Replace the items for sale and their quantities.
+    fn save_items(&mut self, items: Stock<B>) -> Result<(), Self::Error>;
+
+    /// The items for sale and their quantities.
+    fn load_items(&self) -> Result<Stock<B>, Self::Error>;
+
+    /// Store a bid set, returning a new ID for it.
+    fn insert_bid_set(
+        &mut self,
+        bid_set: Vec<B>,
+    ) -> Result<BidSetId, Self::Error>;
+
+    /// Remove a bid set, returning it, or `None` if no bid set has the ID.
+    fn remove_bid_set(
+        &mut self,
+        id: BidSetId,
+    ) -> Result<Option<Vec<B>>, Self::Error>;
+
+    /// Every stored bid set with its ID, in the order they were inserted.
+    fn load_bid_sets(&self) -> Result<StoredBidSets<B>, Self::Error>;
+
+    /// Store the result of the auction, or clear it with `None`.
+    fn save_result(
+        &mut self,
+        result: Option<&OwnedAuctionResult<B>>,
+    ) -> Result<(), Self::Error>;
+
+    /// The stored result of the auction, if any.
+    fn load_result(&self)
+        -> Result<Option<OwnedAuctionResult<B>>, Self::Error>;
+}
+
+/// A [`BidStore`] keeping everything in memory, which never fails.
+pub struct MemoryStore<B: Bid> {
+    items: Stock<B>,
+    bid_sets: StoredBidSets<B>,
+    next_id: u64,
+    result: Option<OwnedAuctionResult<B>>,
+}
+
+// manual impl, since the derive would require `B: Default`
+impl<B: Bid> Default for MemoryStore<B> {
+    fn default() -> Self {
+        Self {
+            items: vec![],
+            bid_sets: vec![],
+            next_id: 0,
+            result: None,
+        }
+    }
+}
+
+impl<B: Bid> MemoryStore<B> {
+    /// An empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<B> BidStore<B> for MemoryStore<B>
+where
+    B: Bid + Clone,
+    B::Name: Clone,
+    B::Value: Clone,
+    B::Item: Clone,
+    B::Quantity: Clone,
+{
+    type Error = Infallible;
+
+    fn save_items(&mut self, items: Stock<B>) -> Result<(), Infallible> {
+        self.items = items;
+        Ok(())
+    }
+
+    fn load_items(&self) -> Result<Stock<B>, Infallible> {
+        Ok(self.items.clone())
+    }
+
+    fn insert_bid_set(
+        &mut self,
+        bid_set: Vec<B>,
+    ) -> Result<BidSetId, Infallible> {
+        let id = BidSetId(self.next_id);
+        self.next_id += 1;
+        self.bid_sets.push((id, bid_set));
+        Ok(id)
+    }
+
+    fn remove_bid_set(
+        &mut self,
+        id: BidSetId,
+    ) -> Result<Option<Vec<B>>, Infallible> {
+        let at = self.bid_sets.iter().position(|(other, _)| *other == id);
+        Ok(at.map(|at| self.bid_sets.remove(at).1))
+    }
+
+    fn load_bid_sets(&self) -> Result<StoredBidSets<B>, Infallible> {
+        Ok(self.bid_sets.clone())
+    }
+
+    fn save_result(
+        &mut self,
+        result: Option<&OwnedAuctionResult<B>>,
+    ) -> Result<(), Infallible> {
+        self.result = result.cloned();
+        Ok(())
+    }
+
+    fn load_result(&self) -> Result<Option<OwnedAuctionResult<B>>, Infallible> {
+        Ok(self.result.clone())
+    }
+}
+
+/// Reasons an [`AuctionState`] operation can fail.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum StateError<E> {
+    /// The store failed.
+    Store(E),
+    /// No stored bid set has the ID.
+    UnknownBidSet(BidSetId),
+    /// The auction failed.
+    Auction(AuctionError),
+    /// The auction stopped at its time limit without an exact result.
+    TimedOut,
+    /// The bid set was turned away by the auction's
+    /// [`SubmissionLimits`].
+    Rejected(SubmissionRejection),
+}
+
+impl<E> From<AuctionError> for StateError<E> {
+    fn from(error: AuctionError) -> Self {
+        StateError::Auction(error)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for StateError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::Store(error) => write!(f, "store failed: {error}"),
+            StateError::UnknownBidSet(id) => {
+                write!(f, "no stored bid set has ID {}", id.0)
+            }
+            StateError::Auction(error) => error.fmt(f),
+            StateError::TimedOut => {
+                write!(f, "auction timed out without an exact result")
+            }
+            StateError::Rejected(rejection) => {
+                write!(f, "bid set rejected: {rejection}")
+            }
+        }
+    }
+}
+
+impl<E: fmt::Display + fmt::Debug> std::error::Error for StateError<E> {}
+
+/// A live auction, taking bid sets as they arrive and running the auction
+/// when it closes, with everything kept in a [`BidStore`].
+///
+/// A stored result is the result of the last close. Submitting or
+/// withdrawing a bid set clears it, since it no longer matches the bids.
+///
+/// ```
+/// use vcg_auction::{
+///     types::SimpleBid, AuctionConfig, AuctionState, MemoryStore,
+/// };
+///
+/// let mut state = AuctionState::new(MemoryStore::new());
+/// state.set_stock([("chair".to_string(), 1)])?;
+/// state.submit(vec![SimpleBid::new("Alice", 10, [("chair", 1)])])?;
+/// let bob = state.submit(vec![SimpleBid::new("Bob", 12, [("chair", 1)])])?;
+/// state.submit(vec![SimpleBid::new("Carol", 7, [("chair", 1)])])?;
+/// // Bob changes his mind
+/// state.withdraw(bob)?;
+///
+/// let result = state.close(&AuctionConfig::deterministic())?;
+/// assert_eq!(result.payments, [("Alice".to_string(), 7)]);
+/// assert_eq!(state.result()?, Some(result));
+/// # Ok::<(), vcg_auction::StateError<std::convert::Infallible>>(())
+/// ```
+pub struct AuctionState<B: Bid, S = MemoryStore<B>> {
+    store: S,
+    limits: SubmissionLimits,
+    bids: PhantomData<B>,
+}
+
+impl<B, S> AuctionState<B, S>
+where
+    B: Bid,
+    S: BidStore<B>,
+{
+    /// An auction kept in a store, which may already hold one in progress.
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            limits: SubmissionLimits::default(),
+            bids: PhantomData,
+        }
+    }
+
+    /// Check each bid set submitted against `limits`, rejecting those that
+    /// would put a bidder over one. The limits aren't stored, so they're set
+    /// again when resuming an auction from its store.
+    ///
+    /// ```
+    /// use vcg_auction::{
+    ///     types::SimpleBid, AuctionState, MemoryStore, StateError,
+    ///     SubmissionLimit, SubmissionLimits, SubmissionRejection,
+    /// };
+    ///
+    /// let limits = SubmissionLimits {
+    ///     max_bid_sets: Some(1),
+    ///     ..Default::default()
+    /// };
+    /// let mut state =
+    ///     AuctionState::new(MemoryStore::new()).with_limits(limits);
+    /// state.submit(vec![SimpleBid::new("Alice", 10, [("chair", 1)])])?;
+    /// let rejection = SubmissionRejection {
+    ///     bid: 0,
+    ///     limit: SubmissionLimit::BidSets,
+    /// };
+    /// assert_eq!(
+    ///     state.submit(vec![SimpleBid::new("Alice", 12, [("table", 1)])]),
+    ///     Err(StateError::Rejected(rejection))
+    /// );
+    /// # Ok::<(), vcg_auction::StateError<std::convert::Infallible>>(())
+    /// ```
+    pub fn with_limits(mut self, limits: SubmissionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// The store the auction is kept in.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Stop managing the auction, returning its store.
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    /// Set the items for sale and their quantities.
+    pub fn set_stock(
+        &mut self,
+        items: impl IntoIterator<Item = (B::Item, B::Quantity)>,
+    ) -> Result<(), StateError<S::Error>> {
+        self.store
+            .save_items(items.into_iter().collect())
+            .map_err(StateError::Store)?;
+        self.clear_result()
+    }
+
+    /// Accept a bid set, returning the ID to withdraw it by, unless it's
+    /// over one of the auction's [limits](Self::with_limits).
+    pub fn submit(
+        &mut self,
+        bid_set: Vec<B>,
+    ) -> Result<BidSetId, StateError<S::Error>> {
+        // without limits, there's no need to load the bid sets
+        if self.limits != SubmissionLimits::default() {
+            let accepted =
+                self.store.load_bid_sets().map_err(StateError::Store)?;
+            self.limits
+                .admit(accepted.iter().map(|(_, bids)| bids), &bid_set)
+                .map_err(StateError::Rejected)?;
+        }
+        let id = self
+            .store
+            .insert_bid_set(bid_set)
+            .map_err(StateError::Store)?;
+        self.clear_result()?;
+        Ok(id)
+    }
+
+    /// Withdraw a bid set, returning it.
+    pub fn withdraw(
+        &mut self,
+        id: BidSetId,
+    ) -> Result<Vec<B>, StateError<S::Error>> {
+        let bid_set = self
+            .store
+            .remove_bid_set(id)
+            .map_err(StateError::Store)?
+            .ok_or(StateError::UnknownBidSet(id))?;
+        self.clear_result()?;
+        Ok(bid_set)
+    }
+
+    /// The bid sets accepted so far, with their IDs, in the order submitted.
+    pub fn bid_sets(&self) -> Result<StoredBidSets<B>, StateError<S::Error>> {
+        self.store.load_bid_sets().map_err(StateError::Store)
+    }
+
+    /// Run the auction over the stored items and bid sets, storing and
+    /// returning its result. The bid sets are auctioned in the order they
+    /// were submitted.
+    pub fn close(
+        &mut self,
+        config: &AuctionConfig<B>,
+    ) -> Result<OwnedAuctionResult<B>, StateError<S::Error>>
+    where
+        B: Clone,
+        B::Name: Clone,
+    {
+        let items = self.store.load_items().map_err(StateError::Store)?;
+        let bid_sets = self.store.load_bid_sets().map_err(StateError::Store)?;
+        let result = run(&items, bid_sets.iter().map(|(_, b)| b), config)?
+            .complete()
+            .ok_or(StateError::TimedOut)?
+            .into_owned();
+        self.store
+            .save_result(Some(&result))
+            .map_err(StateError::Store)?;
+        Ok(result)
+    }
+
+    /// The result of the last close, unless the items or bid sets changed
+    /// since.
+    pub fn result(
+        &self,
+    ) -> Result<Option<OwnedAuctionResult<B>>, StateError<S::Error>> {
+        self.store.load_result().map_err(StateError::Store)
+    }
+
+    fn clear_result(&mut self) -> Result<(), StateError<S::Error>> {
+        self.store.save_result(None).map_err(StateError::Store)
+    }
+}
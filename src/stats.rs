@@ -0,0 +1,79 @@
+//! Statistics of the searches an auction ran, for monitoring how long
+//! auctions take to clear.
+
+use std::time::Duration;
+
+use crate::SearchPhase;
+
+/// Statistics of a finished auction, returned by
+/// [`run_with_stats`](crate::run_with_stats).
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SolveStats {
+    /// The search for the winning bids.
+    pub winner_determination: PhaseStats,
+    /// The searches for each winner's payment.
+    pub payments: PhaseStats,
+}
+
+impl SolveStats {
+    /// Statistics of one phase.
+    pub fn phase(&self, phase: SearchPhase) -> &PhaseStats {
+        match phase {
+            SearchPhase::WinnerDetermination => &self.winner_determination,
+            SearchPhase::Payments => &self.payments,
+        }
+    }
+
+    /// Total number of search nodes explored.
+    pub fn nodes(&self) -> u64 {
+        self.winner_determination.nodes + self.payments.nodes
+    }
+
+    pub(crate) fn phase_mut(&mut self, phase: SearchPhase) -> &mut PhaseStats {
+        match phase {
+            SearchPhase::WinnerDetermination => &mut self.winner_determination,
+            SearchPhase::Payments => &mut self.payments,
+        }
+    }
+}
+
+/// Statistics of the searches of one [`SearchPhase`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PhaseStats {
+    /// Number of searches run. A parallel search counts each of the subtrees
+    /// it's split into, and a search of bid sets that split into independent
+    /// sub-auctions counts each of them.
+    pub searches: u32,
+    /// Time spent searching. In a parallel search this is summed across
+    /// threads, so it can exceed the wall time.
+    pub search_time: Duration,
+    /// Number of search nodes explored.
+    pub nodes: u64,
+    /// Number of times a search found a combination of bids with a higher
+    /// value than its best so far.
+    pub improvements: u64,
+    /// Number of nodes pruned, either for needing more of an item than is in
+    /// stock or for a bound below the best value found.
+    pub prunes: u64,
+    /// Most nodes waiting to be explored at once in any search.
+    pub peak_stack_len: usize,
+    /// Most combinations of bids tied for the highest value at once in any
+    /// search.
+    pub peak_tied_outcomes: usize,
+}
+
+impl PhaseStats {
+    /// Add the statistics of another search of the phase.
+    pub(crate) fn merge(&mut self, other: &PhaseStats) {
+        self.searches += other.searches;
+        self.search_time += other.search_time;
+        self.nodes += other.nodes;
+        self.improvements += other.improvements;
+        self.prunes += other.prunes;
+        self.peak_stack_len = self.peak_stack_len.max(other.peak_stack_len);
+        self.peak_tied_outcomes =
+            self.peak_tied_outcomes.max(other.peak_tied_outcomes);
+    }
+}
@@ -0,0 +1,154 @@
+//! Limits on what one bidder may submit, checked as bid sets arrive so a
+//! single bidder can't slow every auction with a combinatorial explosion of
+//! bids.
+
+use std::fmt;
+
+use crate::Bid;
+
+/// Caps on what each bidder may submit to an auction, checked with
+/// [`admit`](Self::admit) before a bid set is accepted. Use [`Default`] for
+/// any caps that aren't needed.
+///
+/// ```
+/// use vcg_auction::{
+///     types::SimpleBid, SubmissionLimit, SubmissionLimits,
+///     SubmissionRejection,
+/// };
+///
+/// let limits = SubmissionLimits {
+///     max_bids: Some(2),
+///     ..Default::default()
+/// };
+/// let mut accepted = vec![];
+/// for bid_set in [
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+///     vec![SimpleBid::new("Alice", 12, [("table", 1)])],
+///     vec![SimpleBid::new("Alice", 15, [("sofa", 1)])],
+/// ] {
+///     match limits.admit(&accepted, &bid_set) {
+///         Ok(()) => accepted.push(bid_set),
+///         Err(rejection) => {
+///             // Alice's third bid is over her limit
+///             assert_eq!(
+///                 rejection,
+///                 SubmissionRejection { bid: 0, limit: SubmissionLimit::Bids }
+///             );
+///         }
+///     }
+/// }
+/// assert_eq!(accepted.len(), 3);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SubmissionLimits {
+    /// Most bid sets with a bid by any one bidder. Defaults to no limit.
+    pub max_bid_sets: Option<usize>,
+    /// Most bids by any one bidder, across all their bid sets. Defaults to
+    /// no limit.
+    pub max_bids: Option<usize>,
+    /// Most items listed by any one bid. Defaults to no limit.
+    pub max_bundle_size: Option<usize>,
+}
+
+impl SubmissionLimits {
+    /// Check whether a bid set can be accepted after those already
+    /// accepted, without any of its bidders going over a limit. Fails with
+    /// the first bid of the bid set that would, in order.
+    ///
+    /// Bidders are compared only for equality, so this takes time in the
+    /// number of accepted bids for each bid of the bid set.
+    pub fn admit<'a, B, S>(
+        &self,
+        accepted: impl IntoIterator<Item = &'a S>,
+        bid_set: &[B],
+    ) -> Result<(), SubmissionRejection>
+    where
+        B: Bid + 'a,
+        S: AsRef<[B]> + ?Sized + 'a,
+    {
+        let accepted =
+            accepted.into_iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        let by_bidder = |bids: &[B], name: &B::Name| {
+            bids.iter().filter(|b| b.bidder_name() == name).count()
+        };
+        for (bid, b) in bid_set.iter().enumerate() {
+            let name = b.bidder_name();
+            let reject = |limit| Err(SubmissionRejection { bid, limit });
+            if self
+                .max_bundle_size
+                .is_some_and(|max| b.bid_items().len() > max)
+            {
+                return reject(SubmissionLimit::BundleSize);
+            }
+            // the bidder's earlier bids in this bid set count too
+            let earlier = by_bidder(&bid_set[..bid], name);
+            if let Some(max) = self.max_bids {
+                let bids = accepted
+                    .iter()
+                    .map(|bids| by_bidder(bids, name))
+                    .sum::<usize>();
+                if bids + earlier >= max {
+                    return reject(SubmissionLimit::Bids);
+                }
+            }
+            if let Some(max) = self.max_bid_sets {
+                let bid_sets = accepted
+                    .iter()
+                    .filter(|bids| by_bidder(bids, name) > 0)
+                    .count();
+                // a bidder's later bids add no bid set to this one
+                if earlier == 0 && bid_sets >= max {
+                    return reject(SubmissionLimit::BidSets);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A limit of [`SubmissionLimits`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum SubmissionLimit {
+    /// [`max_bid_sets`](SubmissionLimits::max_bid_sets)
+    BidSets,
+    /// [`max_bids`](SubmissionLimits::max_bids)
+    Bids,
+    /// [`max_bundle_size`](SubmissionLimits::max_bundle_size)
+    BundleSize,
+}
+
+/// A bid set turned away by [`SubmissionLimits::admit`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SubmissionRejection {
+    /// Position of the first bid over a limit in the bid set.
+    pub bid: usize,
+    /// The limit its bidder would go over.
+    pub limit: SubmissionLimit,
+}
+
+impl fmt::Display for SubmissionRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bid = self.bid;
+        match self.limit {
+            SubmissionLimit::BidSets => {
+                write!(f, "bid {bid} is over its bidder's limit of bid sets")
+            }
+            SubmissionLimit::Bids => {
+                write!(f, "bid {bid} is over its bidder's limit of bids")
+            }
+            SubmissionLimit::BundleSize => {
+                write!(f, "bid {bid} lists more items than allowed")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SubmissionRejection {}
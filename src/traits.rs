@@ -30,10 +30,18 @@ pub trait Bid {
     type Name: Eq;
     /// Bid value. E.g. integers. Floats can be used with a type wrapper for
     /// [`Ord`]. See the tests for an example.
+    ///
+    /// The largest value an auction calculates is the sum of the highest bid
+    /// of every bid set, so that sum must fit in the type. Every other sum of
+    /// bid values, and every payment, is at most that. Integer overflow panics
+    /// in debug builds and wraps in release builds, giving wrong results, so
+    /// use a wider type like `u128` when sums of bids could exceed `u64`.
     type Value: Ord + AddSubSelf + Zero;
     /// Identifier for items. E.g. strings or integers.
     type Item: Eq;
-    /// Quantity of an item. E.g. integers or floats.
+    /// Quantity of an item. E.g. integers or floats. A bid's quantity is added
+    /// to quantities already within the stock, so the stock of an item plus
+    /// the quantity of any one bid must fit in the type.
     type Quantity: PartialOrd + AddSubSelf + Zero + Clone;
 
     /// Get the name of the bidder.
@@ -43,3 +51,25 @@ pub trait Bid {
     /// Get the items that are bid on, and their quantities
     fn bid_items(&self) -> &[(Self::Item, Self::Quantity)];
 }
+
+/// An item and its quantity in the stock of an auction. Auctions accept any
+/// collection of stock entries, such as a slice of `(item, quantity)` tuples
+/// or a map from items to quantities.
+pub trait StockEntry<'i, I, Q> {
+    /// Get references to the item and its quantity.
+    fn into_entry(self) -> (&'i I, &'i Q);
+}
+
+/// Entries of a slice or `Vec` of tuples.
+impl<'i, I, Q> StockEntry<'i, I, Q> for &'i (I, Q) {
+    fn into_entry(self) -> (&'i I, &'i Q) {
+        (&self.0, &self.1)
+    }
+}
+
+/// Entries of a map, such as a `HashMap` or `BTreeMap`.
+impl<'i, I, Q> StockEntry<'i, I, Q> for (&'i I, &'i Q) {
+    fn into_entry(self) -> (&'i I, &'i Q) {
+        self
+    }
+}
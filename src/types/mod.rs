@@ -1,3 +1,7 @@
+mod resource_bid;
 mod simple_bid;
+mod wide_bid;
 
+pub use resource_bid::*;
 pub use simple_bid::*;
+pub use wide_bid::*;
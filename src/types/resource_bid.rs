@@ -0,0 +1,309 @@
+//! A bid type for allocating machine capacity, where each bid requests an
+//! amount of several resources, such as vCPUs, memory and GPUs, on one or
+//! more machine types.
+//!
+//! Every resource of a machine type is an item of its own, so a bid for a
+//! machine type only wins if all the resources it asks for are left. Bids
+//! are checked against the capacity of a [`ResourcePool`] before the auction,
+//! since a bid asking for more than a machine type has can never win.
+//!
+//! ```
+//! use vcg_auction::{
+//!     types::{ResourceBid, ResourcePool, Resources},
+//!     vcg_auction_with_tiebreaker,
+//! };
+//!
+//! let node = |vcpus, memory_mib, gpus| Resources { vcpus, memory_mib, gpus };
+//! let pool = ResourcePool::new([("gpu-node", node(32, 131_072, 4))]);
+//! let bid = |name, value, request| {
+//!     vec![ResourceBid::new(name, value, [("gpu-node", request)])]
+//! };
+//! let bids = vec![
+//!     bid("training", 90, node(16, 65_536, 4)),
+//!     bid("inference", 30, node(8, 16_384, 1)),
+//!     bid("batch", 20, node(16, 32_768, 0)),
+//! ];
+//! for bid in bids.iter().flatten() {
+//!     pool.check(bid)?;
+//! }
+//! let result =
+//!     vcg_auction_with_tiebreaker(pool.items(), &bids, |_| 0).unwrap();
+//! // the training job takes every GPU, leaving vCPUs for the batch job
+//! assert_eq!(result.winning_bids, [&bids[0][0], &bids[2][0]]);
+//! # Ok::<(), vcg_auction::types::ResourceError>(())
+//! ```
+
+use std::fmt;
+
+use crate::Bid;
+
+/// Amounts of each resource of a machine type, as a capacity or a request.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Resources {
+    pub vcpus: u64,
+    pub memory_mib: u64,
+    pub gpus: u64,
+}
+
+impl Resources {
+    /// The amount of one resource.
+    pub fn get(&self, dimension: ResourceDimension) -> u64 {
+        match dimension {
+            ResourceDimension::Vcpus => self.vcpus,
+            ResourceDimension::MemoryMib => self.memory_mib,
+            ResourceDimension::Gpus => self.gpus,
+        }
+    }
+
+    /// Whether every resource is within the same resource of `capacity`.
+    pub fn fits_within(&self, capacity: &Resources) -> bool {
+        ResourceDimension::ALL
+            .iter()
+            .all(|&dimension| self.get(dimension) <= capacity.get(dimension))
+    }
+
+    /// Sum of two amounts of resources, each at most `u64::MAX`.
+    fn saturating_add(&self, other: &Resources) -> Resources {
+        Resources {
+            vcpus: self.vcpus.saturating_add(other.vcpus),
+            memory_mib: self.memory_mib.saturating_add(other.memory_mib),
+            gpus: self.gpus.saturating_add(other.gpus),
+        }
+    }
+
+    /// Sum of two amounts of resources, or `None` if a resource overflows.
+    fn checked_add(&self, other: &Resources) -> Option<Resources> {
+        Some(Resources {
+            vcpus: self.vcpus.checked_add(other.vcpus)?,
+            memory_mib: self.memory_mib.checked_add(other.memory_mib)?,
+            gpus: self.gpus.checked_add(other.gpus)?,
+        })
+    }
+}
+
+/// One of the resources of a machine type.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ResourceDimension {
+    Vcpus,
+    MemoryMib,
+    Gpus,
+}
+
+impl ResourceDimension {
+    /// Every resource, in the order items are listed.
+    pub const ALL: [ResourceDimension; 3] = [
+        ResourceDimension::Vcpus,
+        ResourceDimension::MemoryMib,
+        ResourceDimension::Gpus,
+    ];
+}
+
+/// Item of an auction of resources: one resource of a machine type.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ResourceItem {
+    pub machine_type: String,
+    pub dimension: ResourceDimension,
+}
+
+/// The machine types on offer and the capacity of each.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ResourcePool {
+    machine_types: Vec<(String, Resources)>,
+    items: Vec<(ResourceItem, u64)>,
+}
+
+impl ResourcePool {
+    /// A pool with the capacity of each machine type. The capacities of a
+    /// machine type listed more than once are added together.
+    pub fn new<T: Into<String>>(
+        machine_types: impl IntoIterator<Item = (T, Resources)>,
+    ) -> Self {
+        let mut merged: Vec<(String, Resources)> = vec![];
+        for (machine_type, capacity) in machine_types {
+            let machine_type = machine_type.into();
+            match merged.iter_mut().find(|(name, _)| *name == machine_type) {
+                Some((_, total)) => *total = total.saturating_add(&capacity),
+                None => merged.push((machine_type, capacity)),
+            }
+        }
+        let items = merged
+            .iter()
+            .flat_map(|(machine_type, capacity)| {
+                ResourceDimension::ALL.into_iter().map(|dimension| {
+                    let item = ResourceItem {
+                        machine_type: machine_type.clone(),
+                        dimension,
+                    };
+                    (item, capacity.get(dimension))
+                })
+            })
+            .collect();
+        Self {
+            machine_types: merged,
+            items,
+        }
+    }
+
+    /// The capacity of a machine type, if it's in the pool.
+    pub fn capacity(&self, machine_type: &str) -> Option<&Resources> {
+        self.machine_types
+            .iter()
+            .find(|(name, _)| name == machine_type)
+            .map(|(_, capacity)| capacity)
+    }
+
+    /// Items of the auction, with the stock of every resource of every
+    /// machine type.
+    pub fn items(&self) -> &[(ResourceItem, u64)] {
+        &self.items
+    }
+
+    /// Check that every machine type of a bid is in the pool, with enough of
+    /// each resource to meet the bid's request.
+    pub fn check(&self, bid: &ResourceBid) -> Result<(), ResourceError> {
+        for (machine_type, requested) in bid.requests() {
+            let capacity = self.capacity(machine_type).ok_or_else(|| {
+                ResourceError::UnknownMachineType(machine_type.clone())
+            })?;
+            if let Some(dimension) = ResourceDimension::ALL
+                .into_iter()
+                .find(|&d| requested.get(d) > capacity.get(d))
+            {
+                return Err(ResourceError::OverCapacity {
+                    machine_type: machine_type.clone(),
+                    dimension,
+                    requested: requested.get(dimension),
+                    capacity: capacity.get(dimension),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reasons a [`ResourceBid`] can't win in a [`ResourcePool`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum ResourceError {
+    /// The bid requests a machine type that isn't in the pool.
+    UnknownMachineType(String),
+    /// The bid requests more of a resource than the machine type has.
+    OverCapacity {
+        machine_type: String,
+        dimension: ResourceDimension,
+        requested: u64,
+        capacity: u64,
+    },
+}
+
+impl fmt::Display for ResourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceError::UnknownMachineType(machine_type) => {
+                write!(f, "machine type {machine_type:?} isn't in the pool")
+            }
+            ResourceError::OverCapacity {
+                machine_type,
+                dimension,
+                requested,
+                capacity,
+            } => write!(
+                f,
+                "{requested} {dimension:?} requested of machine type \
+                 {machine_type:?}, which has {capacity}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ResourceError {}
+
+/// A bid for an amount of resources on one or more machine types.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ResourceBid {
+    pub name: String,
+    pub value: u64,
+    requests: Vec<(String, Resources)>,
+    items: Vec<(ResourceItem, u64)>,
+}
+
+impl ResourceBid {
+    /// A bid for the resources requested of each machine type. Requests for
+    /// the same machine type are added together, and resources of zero are
+    /// left out of the bid's items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the requests for a machine type overflow `u64`.
+    pub fn new<T: Into<String>>(
+        name: impl Into<String>,
+        value: u64,
+        requests: impl IntoIterator<Item = (T, Resources)>,
+    ) -> Self {
+        let mut merged: Vec<(String, Resources)> = vec![];
+        for (machine_type, requested) in requests {
+            let machine_type = machine_type.into();
+            match merged.iter_mut().find(|(name, _)| *name == machine_type) {
+                Some((_, total)) => {
+                    *total = total
+                        .checked_add(&requested)
+                        .expect("resources requested overflow u64")
+                }
+                None => merged.push((machine_type, requested)),
+            }
+        }
+        let items = merged
+            .iter()
+            .flat_map(|(machine_type, requested)| {
+                ResourceDimension::ALL
+                    .into_iter()
+                    .filter(|&dimension| requested.get(dimension) > 0)
+                    .map(|dimension| {
+                        let item = ResourceItem {
+                            machine_type: machine_type.clone(),
+                            dimension,
+                        };
+                        (item, requested.get(dimension))
+                    })
+            })
+            .collect();
+        Self {
+            name: name.into(),
+            value,
+            requests: merged,
+            items,
+        }
+    }
+
+    /// The resources requested of each machine type.
+    pub fn requests(&self) -> &[(String, Resources)] {
+        &self.requests
+    }
+}
+
+impl Bid for ResourceBid {
+    type Name = String;
+    type Value = u64;
+    type Item = ResourceItem;
+    type Quantity = u64;
+
+    fn bidder_name(&self) -> &Self::Name {
+        &self.name
+    }
+    fn bid_value(&self) -> &Self::Value {
+        &self.value
+    }
+    fn bid_items(&self) -> &[(Self::Item, Self::Quantity)] {
+        &self.items
+    }
+}
@@ -5,10 +5,28 @@
 //! use vcg_auction::types::SimpleBid;
 //! SimpleBid::new("Alice", 10, [("chair", 1)]);
 //! ```
+//!
+//! Bids with many items read better with the builder, which also checks the
+//! items.
+//!
+//! ```
+//! use vcg_auction::types::SimpleBid;
+//! let bid = SimpleBid::builder("Alice")
+//!     .value(7)
+//!     .item("chair", 2)
+//!     .item("table", 1)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(bid, SimpleBid::new("Alice", 7, [("chair", 2), ("table", 1)]));
+//! ```
+
+use std::fmt;
 
 use crate::Bid;
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SimpleBid {
     pub name: String,
     pub value: u64,
@@ -31,8 +49,81 @@ impl SimpleBid {
             ),
         }
     }
+
+    /// Start building a bid for the named bidder, with a value of zero and no
+    /// items.
+    pub fn builder(name: impl Into<String>) -> SimpleBidBuilder {
+        SimpleBidBuilder {
+            name: name.into(),
+            value: 0,
+            items: vec![],
+        }
+    }
+}
+
+/// Builder for a [`SimpleBid`], created with [`SimpleBid::builder`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SimpleBidBuilder {
+    name: String,
+    value: u64,
+    items: Vec<(String, u64)>,
 }
 
+impl SimpleBidBuilder {
+    /// Set the value of the bid.
+    pub fn value(mut self, value: u64) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Add an item and the quantity bid on.
+    pub fn item(mut self, item: impl Into<String>, quantity: u64) -> Self {
+        self.items.push((item.into(), quantity));
+        self
+    }
+
+    /// Build the bid. Returns an error if no items were added, or an item was
+    /// added more than once.
+    pub fn build(self) -> Result<SimpleBid, SimpleBidError> {
+        if self.items.is_empty() {
+            return Err(SimpleBidError::NoItems);
+        }
+        for (i, (item, _)) in self.items.iter().enumerate() {
+            if self.items[..i].iter().any(|(other, _)| other == item) {
+                return Err(SimpleBidError::DuplicateItem(item.clone()));
+            }
+        }
+        Ok(SimpleBid {
+            name: self.name,
+            value: self.value,
+            items: self.items,
+        })
+    }
+}
+
+/// Reasons a [`SimpleBidBuilder`] can fail to build a bid.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum SimpleBidError {
+    /// The bid has no items.
+    NoItems,
+    /// The item was added to the bid more than once.
+    DuplicateItem(String),
+}
+
+impl fmt::Display for SimpleBidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimpleBidError::NoItems => write!(f, "bid has no items"),
+            SimpleBidError::DuplicateItem(item) => {
+                write!(f, "item {item:?} is in the bid more than once")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SimpleBidError {}
+
 impl Bid for SimpleBid {
     type Name = String;
     type Value = u64;
@@ -69,4 +160,20 @@ mod tests {
         assert_eq!(result.winning_bids, [&bids[0][0], &bids[1][0]]);
         assert_eq!(result.payments, [(&"Alice".into(), 0), (&"Bob".into(), 2)]);
     }
+
+    #[test]
+    fn builder_validation() {
+        assert_eq!(
+            SimpleBid::builder("Alice").value(5).build(),
+            Err(SimpleBidError::NoItems)
+        );
+        assert_eq!(
+            SimpleBid::builder("Alice")
+                .item("chair", 1)
+                .item("table", 1)
+                .item("chair", 2)
+                .build(),
+            Err(SimpleBidError::DuplicateItem("chair".into()))
+        );
+    }
 }
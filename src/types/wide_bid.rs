@@ -0,0 +1,63 @@
+//! A bid type like [`SimpleBid`](super::SimpleBid), with `u128` bid values
+//! for currencies whose smallest units overflow `u64` when summed, such as
+//! tokens with 18 decimal places.
+//!
+//! ```
+//! use vcg_auction::{types::WideBid, vcg_auction_with_tiebreaker};
+//!
+//! const TOKEN: u128 = 10u128.pow(18);
+//! let items = vec![("chair".to_string(), 2)];
+//! let bids = vec![
+//!     vec![WideBid::new("Alice", 40 * TOKEN, [("chair", 1)])],
+//!     vec![WideBid::new("Bob", 30 * TOKEN, [("chair", 1)])],
+//!     vec![WideBid::new("Carol", 50 * TOKEN, [("chair", 2)])],
+//! ];
+//! let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+//! assert_eq!(result.winning_bids, [&bids[0][0], &bids[1][0]]);
+//! assert_eq!(result.payment_for(&"Alice".to_string()), Some(&(20 * TOKEN)));
+//! ```
+
+use crate::Bid;
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct WideBid {
+    pub name: String,
+    pub value: u128,
+    pub items: Vec<(String, u64)>,
+}
+
+impl WideBid {
+    pub fn new<T: Into<String>>(
+        name: impl Into<String>,
+        value: u128,
+        items: impl IntoIterator<Item = (T, u64)>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            value,
+            items: items
+                .into_iter()
+                .map(|(item, quantity)| (item.into(), quantity))
+                .collect(),
+        }
+    }
+}
+
+impl Bid for WideBid {
+    type Name = String;
+    type Value = u128;
+    type Item = String;
+    type Quantity = u64;
+
+    fn bidder_name(&self) -> &Self::Name {
+        &self.name
+    }
+    fn bid_value(&self) -> &Self::Value {
+        &self.value
+    }
+    fn bid_items(&self) -> &[(Self::Item, Self::Quantity)] {
+        &self.items
+    }
+}
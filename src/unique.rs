@@ -0,0 +1,136 @@
+//! Auctions of unique items, such as digital collectibles, where there's one
+//! of each item and every bid is for one of them.
+
+use num_traits::One;
+
+use crate::{
+    control::SearchControl,
+    dry_run::input_problems,
+    input::{bid_set_refs, ItemStock},
+    vcg::run_auction,
+    AuctionOutcome, Bid, SearchOptions, StockEntry, UniqueItemError,
+};
+
+/// A unique item sold by [`vcg_auction_unique`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct UniqueSale<'a, B: Bid> {
+    pub item: &'a B::Item,
+    pub winner: &'a B::Name,
+    /// The winner's VCG payment for the item.
+    pub price: B::Value,
+}
+
+/// Calculate a VCG auction of unique items with a tiebreaker, returning the
+/// winner and price of each item sold, in the order of the winning bid sets.
+///
+/// The auction is checked first: every item of the stock must be distinct
+/// with a quantity of one, and every bid must be for one unit of one item of
+/// the stock. A bidder's bids must all be in one bid set, so each bidder buys
+/// one item at most, and their payment is the price of that item.
+///
+/// ```
+/// use vcg_auction::{types::SimpleBid, vcg_auction_unique, UniqueItemError};
+///
+/// let items = vec![("punk-1".to_string(), 1), ("punk-2".to_string(), 1)];
+/// let bids = vec![
+///     vec![
+///         SimpleBid::new("Alice", 10, [("punk-1", 1)]),
+///         SimpleBid::new("Alice", 8, [("punk-2", 1)]),
+///     ],
+///     vec![SimpleBid::new("Bob", 9, [("punk-1", 1)])],
+/// ];
+/// let sales = vcg_auction_unique(&items, &bids, |_| 0)?;
+/// assert_eq!(sales[0].item, "punk-2");
+/// assert_eq!(sales[0].winner, "Alice");
+/// assert_eq!(sales[1].item, "punk-1");
+/// assert_eq!(sales[1].price, 2);
+///
+/// let bids = vec![vec![SimpleBid::new("Alice", 10, [("punk-1", 2)])]];
+/// assert_eq!(
+///     vcg_auction_unique(&items, &bids, |_| 0),
+///     Err(UniqueItemError::NotOneUnit { bid_set: 0, bid: 0 })
+/// );
+/// # Ok::<(), UniqueItemError>(())
+/// ```
+pub fn vcg_auction_unique<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
+) -> Result<Vec<UniqueSale<'a, B>>, UniqueItemError>
+where
+    B: Bid + 'a + 'i,
+    B::Quantity: One,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let items = items
+        .into_iter()
+        .map(StockEntry::into_entry)
+        .collect::<Vec<_>>();
+    let exclusive_bid_sets = bid_set_refs(exclusive_bid_sets);
+    check_unique::<B>(&items, &exclusive_bid_sets)?;
+
+    let control = SearchControl::new(SearchOptions::default());
+    let result = match run_auction(
+        &ItemStock::new(items),
+        exclusive_bid_sets,
+        tiebreaker,
+        &control,
+    ) {
+        Ok(AuctionOutcome::Complete(result)) => result,
+        // only the tiebreaker can fail without limits
+        _ => return Err(UniqueItemError::InvalidTiebreak),
+    };
+    let mut payments = result.payments;
+    Ok(result
+        .winning_bids
+        .into_iter()
+        .map(|bid| {
+            let winner = bid.bidder_name();
+            let at = payments
+                .iter()
+                .position(|(name, _)| *name == winner)
+                .expect("every winner has a payment");
+            let (_, price) = payments.swap_remove(at);
+            UniqueSale {
+                item: &bid.bid_items()[0].0,
+                winner,
+                price,
+            }
+        })
+        .collect())
+}
+
+/// Check that the items are distinct units, and that each bid is for one
+/// unit of one of them, with every bidder's bids in one bid set.
+fn check_unique<B: Bid>(
+    items: &[(&B::Item, &B::Quantity)],
+    exclusive_bid_sets: &[Vec<&B>],
+) -> Result<(), UniqueItemError>
+where
+    B::Quantity: One,
+{
+    let stock = items.iter().map(|(item, _)| *item).collect::<Vec<_>>();
+    if let Some(problem) =
+        input_problems::<B>(&stock, exclusive_bid_sets).first()
+    {
+        return Err(UniqueItemError::Input(*problem));
+    }
+    if let Some(i) = items.iter().position(|(_, quantity)| !quantity.is_one()) {
+        return Err(UniqueItemError::NotOneInStock(i));
+    }
+    for (bid_set, bids) in exclusive_bid_sets.iter().enumerate() {
+        for (bid, b) in bids.iter().enumerate() {
+            if !matches!(b.bid_items(), [(_, quantity)] if quantity.is_one()) {
+                return Err(UniqueItemError::NotOneUnit { bid_set, bid });
+            }
+            let earlier = exclusive_bid_sets[..bid_set]
+                .iter()
+                .flatten()
+                .any(|other| other.bidder_name() == b.bidder_name());
+            if earlier {
+                return Err(UniqueItemError::SeveralBidSets { bid_set, bid });
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,314 @@
+//! Winner determination by dynamic programming over the units of a small
+//! stock.
+//!
+//! When every item's stock is a small whole number of units, the items a
+//! combination of bids uses can be encoded as one index into the product of
+//! the stocks. A table of the highest value reachable from each bid set with
+//! each amount of the stock used is filled in from the last bid set to the
+//! first, in time linear in the number of bid sets rather than exponential.
+//! Walking the table forwards then lists every combination tied for the
+//! highest value, in the order the branch-and-bound search finds them, so the
+//! tiebreaker sees the same outcomes.
+
+use std::time::Instant;
+
+use num_traits::Zero;
+
+use crate::{
+    control::{Interrupt, SearchControl},
+    input::{bid_set_refs, ItemStock},
+    vcg::{
+        annotate_max_values, copy_value, payment_for_bidder, run_auction_with,
+        upper_bound, winning_bidders, SearchResult,
+    },
+    AddSubSelf, AuctionError, AuctionOutcome, Bid, PhaseStats, ResourceLimit,
+    SearchOptions, SearchPhase, StockEntry,
+};
+
+/// Most values the table can hold, one for each amount of the stock used at
+/// each bid set. Larger auctions fall back to the branch-and-bound search.
+pub const MAX_DP_TABLE_CELLS: usize = 1 << 24;
+
+/// Version of [`vcg_auction_with_options`](crate::vcg_auction_with_options)
+/// that solves auctions with few whole units of stock by dynamic programming.
+///
+/// Quantities are converted to whole units with `TryInto<u64>`. The dynamic
+/// program is used for a search when the number of ways to use the stock,
+/// the product of each item's stock plus one, times the number of bid sets is
+/// at most [`MAX_DP_TABLE_CELLS`], such as twenty items of one unit each
+/// with up to fifteen bid sets. Otherwise, or if a quantity can't be
+/// converted, the search falls back to branch and bound. Either way the result is identical to
+/// [`vcg_auction_with_options`](crate::vcg_auction_with_options).
+///
+/// The table's cells count as search nodes for
+/// [`max_nodes`](SearchOptions::max_nodes).
+///
+/// ```
+/// use vcg_auction::{types::SimpleBid, vcg_auction_dp, SearchOptions};
+///
+/// let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 5, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 4, [("chair", 1), ("table", 1)])],
+///     vec![SimpleBid::new("Carol", 3, [("chair", 2)])],
+/// ];
+/// let outcome =
+///     vcg_auction_dp(&items, &bids, |_| 0, &SearchOptions::default())?;
+/// let result = outcome.complete().unwrap();
+/// assert_eq!(result.winning_bids, [&bids[0][0], &bids[1][0]]);
+/// # Ok::<(), vcg_auction::AuctionError>(())
+/// ```
+pub fn vcg_auction_dp<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
+    options: &SearchOptions<B::Value>,
+) -> Result<AuctionOutcome<'a, B>, AuctionError>
+where
+    B: Bid + 'i,
+    B::Quantity: TryInto<u64>,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let items = ItemStock::new(items);
+    let control = SearchControl::new(options.clone());
+    run_auction_with(
+        &items,
+        bid_set_refs(exclusive_bid_sets),
+        tiebreaker,
+        &control,
+        |bid_sets, phase| {
+            find_highest_value_bid_sets(&items, bid_sets, &control, phase)
+        },
+        |winning_bid_set, bid_sets| {
+            winning_bidders(winning_bid_set)
+                .into_iter()
+                .map(|bidder_name| {
+                    let payment = payment_for_bidder(
+                        winning_bid_set,
+                        bidder_name,
+                        bid_sets,
+                        |bid_sets| {
+                            find_highest_value_bid_sets(
+                                &items,
+                                bid_sets,
+                                &control,
+                                SearchPhase::Payments,
+                            )
+                            .map(|(_, value)| value)
+                            .map_err(|(interrupt, _)| interrupt)
+                        },
+                    )?;
+                    Ok((bidder_name, payment))
+                })
+                .collect()
+        },
+    )
+}
+
+/// Search with the dynamic program if the stock is small enough, otherwise
+/// with branch and bound.
+fn find_highest_value_bid_sets<'a, B>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
+    control: &SearchControl<B::Value>,
+    phase: SearchPhase,
+) -> SearchResult<'a, B>
+where
+    B: Bid,
+    B::Quantity: TryInto<u64>,
+{
+    match UnitTable::new(items, exclusive_bid_sets) {
+        Some(table) => table.solve(control, phase),
+        None => crate::vcg::find_highest_value_bid_sets(
+            items,
+            exclusive_bid_sets,
+            control,
+            phase,
+        ),
+    }
+}
+
+/// The non-empty bid sets of a search, with the units of stock each bid
+/// uses.
+struct UnitTable<'a, B: Bid> {
+    stock: Vec<u64>,
+    /// Step of the state index for one unit of each item.
+    strides: Vec<usize>,
+    /// Number of ways to use the stock.
+    states: usize,
+    bid_sets: Vec<Vec<(&'a B, Vec<u64>)>>,
+}
+
+impl<'a, B: Bid> UnitTable<'a, B>
+where
+    B::Quantity: TryInto<u64>,
+{
+    /// The table of a search, or `None` if a quantity isn't a whole number of
+    /// units or the table would be too large.
+    fn new(
+        items: &ItemStock<B>,
+        exclusive_bid_sets: &[Vec<&'a B>],
+    ) -> Option<Self> {
+        let stock = units(items.quantities())?;
+        let mut strides = Vec::with_capacity(stock.len());
+        let mut states = 1usize;
+        for &units in &stock {
+            strides.push(states);
+            states = usize::try_from(units)
+                .ok()
+                .and_then(|units| units.checked_add(1))
+                .and_then(|radix| states.checked_mul(radix))?;
+        }
+        let bid_sets = exclusive_bid_sets
+            .iter()
+            .filter(|bid_set| !bid_set.is_empty())
+            .map(|bid_set| {
+                bid_set
+                    .iter()
+                    .map(|bid| {
+                        let selected = items
+                            .select_bid_items(&items.no_items_selected(), bid);
+                        Some((*bid, units(&selected)?))
+                    })
+                    .collect::<Option<Vec<_>>>()
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let cells = states.checked_mul(bid_sets.len() + 1)?;
+        (cells <= MAX_DP_TABLE_CELLS).then_some(Self {
+            stock,
+            strides,
+            states,
+            bid_sets,
+        })
+    }
+
+    /// The state reached by also using `units` in `state`, or `None` if that
+    /// needs more of an item than is in stock.
+    fn add(&self, state: usize, units: &[u64]) -> Option<usize> {
+        let mut next = state;
+        for ((&stock, &stride), &units) in
+            self.stock.iter().zip(&self.strides).zip(units)
+        {
+            if units == 0 {
+                continue;
+            }
+            let used = (state / stride) as u64 % (stock + 1);
+            if units > stock - used {
+                return None;
+            }
+            next += units as usize * stride;
+        }
+        Some(next)
+    }
+
+    /// Fill in the table, then list the combinations of bids tied for the
+    /// highest value.
+    fn solve(
+        self,
+        control: &SearchControl<B::Value>,
+        phase: SearchPhase,
+    ) -> SearchResult<'a, B> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("search", ?phase).entered();
+        let started = Instant::now();
+        let mut stats = PhaseStats {
+            searches: 1,
+            ..PhaseStats::default()
+        };
+        let finish = |stats: &mut PhaseStats| {
+            stats.search_time = started.elapsed();
+            control.record(phase, stats);
+        };
+        let zero = B::Value::zero();
+        let upper_bound = upper_bound::<B>(&annotate_max_values(
+            &self
+                .bid_sets
+                .iter()
+                .map(|bid_set| bid_set.iter().map(|(bid, _)| *bid).collect())
+                .collect::<Vec<_>>(),
+        ));
+
+        // best[d][s]: highest value of the bid sets from `d` on, with the
+        // stock used as in state `s`
+        let layers = self.bid_sets.len();
+        let mut best = Vec::with_capacity(layers + 1);
+        best.push((0..self.states).map(|_| B::Value::zero()).collect());
+        for bid_set in self.bid_sets.iter().rev() {
+            let next: &Vec<B::Value> = best.last().expect("a layer is pushed");
+            let layer = (0..self.states)
+                .map(|state| {
+                    let mut value = copy_value::<B>(&next[state]);
+                    for (bid, units) in bid_set {
+                        if let Some(reached) = self.add(state, units) {
+                            let with_bid = bid.bid_value().add(&next[reached]);
+                            value = value.max(with_bid);
+                        }
+                    }
+                    value
+                })
+                .collect();
+            best.push(layer);
+            stats.nodes += self.states as u64;
+            control.checkpoint(self.states as u64, phase, &zero, &upper_bound);
+            let interrupt = control.interrupt().or_else(|| {
+                control
+                    .out_of_nodes(0)
+                    .then_some(Interrupt::ResourceLimit(ResourceLimit::Nodes))
+            });
+            if let Some(interrupt) = interrupt {
+                finish(&mut stats);
+                return Err((interrupt, (vec![], zero)));
+            }
+        }
+        best.reverse();
+
+        // walk the choices reaching the highest value, taking each bid in
+        // order then no bid, as the branch-and-bound search does
+        let mut tied = vec![];
+        let mut stack = vec![(0, 0, vec![])];
+        while let Some((depth, state, bids)) = stack.pop() {
+            if depth == layers {
+                if !control.allows_ties(tied.len() + 1) {
+                    finish(&mut stats);
+                    let limit = ResourceLimit::TiedOutcomes;
+                    return Err((
+                        Interrupt::ResourceLimit(limit),
+                        (vec![], zero),
+                    ));
+                }
+                tied.push(bids);
+                continue;
+            }
+            let target = &best[depth][state];
+            let mut children = vec![];
+            for (bid, units) in &self.bid_sets[depth] {
+                if let Some(reached) = self.add(state, units) {
+                    if bid.bid_value().add(&best[depth + 1][reached]) == *target
+                    {
+                        let mut with_bid = bids.clone();
+                        with_bid.push(*bid);
+                        children.push((depth + 1, reached, with_bid));
+                    }
+                }
+            }
+            if best[depth + 1][state] == *target {
+                children.push((depth + 1, state, bids));
+            }
+            stack.extend(children.into_iter().rev());
+        }
+        stats.peak_tied_outcomes = tied.len();
+        finish(&mut stats);
+        let highest_value = best.swap_remove(0).swap_remove(0);
+        Ok((tied, highest_value))
+    }
+}
+
+/// Quantities as whole units, or `None` if any can't be converted.
+pub(crate) fn units<Q: Clone + TryInto<u64>>(
+    quantities: &[Q],
+) -> Option<Vec<u64>> {
+    quantities
+        .iter()
+        .map(|quantity| quantity.clone().try_into().ok())
+        .collect()
+}
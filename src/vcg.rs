@@ -1,12 +1,32 @@
 //! Main VCG auction implementation.
 
 use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+use std::time::Instant;
 
 use num_traits::Zero;
 #[cfg(feature = "rand")]
-use rand::{thread_rng, Rng};
+use rand::thread_rng;
+#[cfg(feature = "rng")]
+use rand::Rng;
 
-use crate::{AddSubSelf, Bid};
+#[cfg(feature = "rand")]
+use crate::AuctionConfig;
+use crate::{
+    conflict::clique_cover,
+    control::{Interrupt, SearchControl, CHECK_INTERVAL},
+    decomposition::ComponentSearches,
+    input::{add_indexed_items, bid_set_refs, IndexedItems, ItemStock},
+    invariants::check_invariants,
+    pruning::PruningRecorder,
+    quantities::within_stock,
+    report::position,
+    AddSubSelf, AuctionError, Bid, BoundCut, NodeOutcome, OnTimeout,
+    PhaseStats, ResourceLimit, SearchOptions, SearchPhase, SearchStrategy,
+    SearchTreeNode, StockEntry,
+};
 
 /// Result of a VCG auction. Contains the set of winning bids, and the payments
 /// to be made by each bidder.
@@ -16,26 +36,484 @@ pub struct AuctionResult<'a, B: Bid> {
     pub payments: Vec<(&'a B::Name, B::Value)>,
 }
 
+impl<'a, B: Bid> AuctionResult<'a, B> {
+    /// One row per winning bid, with the bidder, the items won and the
+    /// bidder's payment, for exporting to tables.
+    ///
+    /// ```
+    /// use vcg_auction::{types::SimpleBid, vcg_auction_with_tiebreaker};
+    ///
+    /// let items = vec![("chair".to_string(), 1)];
+    /// let bids = vec![
+    ///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+    ///     vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+    /// ];
+    /// let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    /// for row in result.rows() {
+    ///     println!("{},{},{}", row.bidder_name, row.bid.value, row.payment);
+    /// }
+    /// ```
+    pub fn rows(&self) -> impl Iterator<Item = WinningRow<'_, B>> {
+        winning_rows(self.winning_bids.iter().copied(), |bidder_name| {
+            self.payment_for(bidder_name)
+        })
+    }
+
+    /// The payment of a bidder, or `None` if they didn't win anything.
+    pub fn payment_for(&self, bidder_name: &B::Name) -> Option<&B::Value> {
+        self.payments
+            .iter()
+            .find(|(name, _)| *name == bidder_name)
+            .map(|(_, payment)| payment)
+    }
+
+    /// The payments as a map from bidder names.
+    pub fn payments_map(&self) -> HashMap<&'a B::Name, &B::Value>
+    where
+        B::Name: Hash,
+    {
+        self.payments
+            .iter()
+            .map(|(name, payment)| (*name, payment))
+            .collect()
+    }
+
+    /// The winning bids grouped by bidder, in the order of each bidder's
+    /// first winning bid.
+    pub fn winners_by_bidder(&self) -> Vec<BidderWinnings<'a, B>> {
+        group_by_bidder(self.winning_bids.iter().copied())
+    }
+
+    /// The position of each winning bid in the bid sets the auction was run
+    /// with, as `(bid set, bid)` indices in the order of `winning_bids`, to
+    /// find the winners in the caller's own storage.
+    ///
+    /// Bids are matched by address, so `exclusive_bid_sets` must be the bid
+    /// sets passed to the auction, not a copy of them.
+    ///
+    /// ```
+    /// use vcg_auction::{types::SimpleBid, vcg_auction};
+    ///
+    /// let items = vec![("chair".to_string(), 1), ("table".to_string(), 1)];
+    /// let bids = vec![
+    ///     vec![SimpleBid::new("Alice", 5, [("chair", 1)])],
+    ///     vec![
+    ///         SimpleBid::new("Bob", 4, [("table", 1)]),
+    ///         SimpleBid::new("Bob", 7, [("chair", 1)]),
+    ///     ],
+    /// ];
+    /// let result = vcg_auction(&items, &bids).unwrap();
+    /// assert_eq!(result.winning_positions(&bids), vec![(0, 0), (1, 0)]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If a winning bid isn't in `exclusive_bid_sets`.
+    pub fn winning_positions<'s, S>(
+        &self,
+        exclusive_bid_sets: impl IntoIterator<Item = &'s S>,
+    ) -> Vec<(usize, usize)>
+    where
+        B: 's,
+        S: AsRef<[B]> + ?Sized + 's,
+    {
+        let bid_sets = bid_set_refs(exclusive_bid_sets);
+        self.winning_bids
+            .iter()
+            .map(|bid| position(&bid_sets, bid))
+            .collect()
+    }
+
+    /// Copy to an [`OwnedAuctionResult`] that no longer borrows from the bids
+    /// passed into the auction, keeping this result.
+    pub fn to_owned(&self) -> OwnedAuctionResult<B>
+    where
+        B: Clone,
+        B::Name: Clone,
+        B::Value: Clone,
+    {
+        self.clone().into_owned()
+    }
+
+    /// Convert to an [`OwnedAuctionResult`] that no longer borrows from the
+    /// bids passed into the auction.
+    pub fn into_owned(self) -> OwnedAuctionResult<B>
+    where
+        B: Clone,
+        B::Name: Clone,
+    {
+        OwnedAuctionResult {
+            winning_bids: self.winning_bids.into_iter().cloned().collect(),
+            payments: self
+                .payments
+                .into_iter()
+                .map(|(name, payment)| (name.clone(), payment))
+                .collect(),
+        }
+    }
+}
+
+/// An [`AuctionResult`] with owned bids and bidder names, for results that
+/// need to outlive the bids they were calculated from.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct OwnedAuctionResult<B: Bid> {
+    pub winning_bids: Vec<B>,
+    pub payments: Vec<(B::Name, B::Value)>,
+}
+
+impl<'a, B> From<AuctionResult<'a, B>> for OwnedAuctionResult<B>
+where
+    B: Bid + Clone,
+    B::Name: Clone,
+{
+    fn from(result: AuctionResult<'a, B>) -> Self {
+        result.into_owned()
+    }
+}
+
+impl<B: Bid> OwnedAuctionResult<B> {
+    /// One row per winning bid, with the bidder, the items won and the
+    /// bidder's payment, for exporting to tables.
+    pub fn rows(&self) -> impl Iterator<Item = WinningRow<'_, B>> {
+        winning_rows(self.winning_bids.iter(), |bidder_name| {
+            self.payment_for(bidder_name)
+        })
+    }
+
+    /// Borrow as an [`AuctionResult`], to pass to code written for results
+    /// that borrow from the bids.
+    pub fn as_borrowed(&self) -> AuctionResult<'_, B>
+    where
+        B::Value: Clone,
+    {
+        AuctionResult {
+            winning_bids: self.winning_bids.iter().collect(),
+            payments: self
+                .payments
+                .iter()
+                .map(|(name, payment)| (name, payment.clone()))
+                .collect(),
+        }
+    }
+
+    /// The winning bids grouped by bidder, in the order of each bidder's
+    /// first winning bid.
+    pub fn winners_by_bidder(&self) -> Vec<BidderWinnings<'_, B>> {
+        group_by_bidder(self.winning_bids.iter())
+    }
+
+    /// The payment of a bidder, or `None` if they didn't win anything.
+    pub fn payment_for(&self, bidder_name: &B::Name) -> Option<&B::Value> {
+        self.payments
+            .iter()
+            .find(|(name, _)| name == bidder_name)
+            .map(|(_, payment)| payment)
+    }
+
+    /// The payments as a map from bidder names.
+    pub fn payments_map(&self) -> HashMap<&B::Name, &B::Value>
+    where
+        B::Name: Hash,
+    {
+        self.payments
+            .iter()
+            .map(|(name, payment)| (name, payment))
+            .collect()
+    }
+}
+
+/// A winning bid joined with its bidder's payment, yielded by
+/// [`AuctionResult::rows`] and [`OwnedAuctionResult::rows`].
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct WinningRow<'r, B: Bid> {
+    pub bidder_name: &'r B::Name,
+    pub bid: &'r B,
+    pub items: &'r [(B::Item, B::Quantity)],
+    /// The total payment of the bidder, repeated on each of their winning
+    /// bids.
+    pub payment: &'r B::Value,
+}
+
+fn winning_rows<'r, B>(
+    winning_bids: impl IntoIterator<Item = &'r B>,
+    payments: impl Fn(&B::Name) -> Option<&'r B::Value>,
+) -> impl Iterator<Item = WinningRow<'r, B>>
+where
+    B: Bid + 'r,
+    B::Value: 'r,
+{
+    winning_bids.into_iter().map(move |bid| WinningRow {
+        bidder_name: bid.bidder_name(),
+        bid,
+        items: bid.bid_items(),
+        payment: payments(bid.bidder_name())
+            .expect("every winning bidder has a payment"),
+    })
+}
+
+/// The winning bids of one bidder, with the total quantity of each item they
+/// won.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct BidderWinnings<'r, B: Bid> {
+    pub bidder_name: &'r B::Name,
+    pub bids: Vec<&'r B>,
+    pub items: Vec<(&'r B::Item, B::Quantity)>,
+}
+
+fn group_by_bidder<'r, B: Bid>(
+    winning_bids: impl IntoIterator<Item = &'r B>,
+) -> Vec<BidderWinnings<'r, B>> {
+    let mut winners: Vec<BidderWinnings<B>> = vec![];
+    for bid in winning_bids {
+        let bidder_name = bid.bidder_name();
+        let index = match winners
+            .iter()
+            .position(|winner| winner.bidder_name == bidder_name)
+        {
+            Some(index) => index,
+            None => {
+                winners.push(BidderWinnings {
+                    bidder_name,
+                    bids: vec![],
+                    items: vec![],
+                });
+                winners.len() - 1
+            }
+        };
+        let winner = &mut winners[index];
+        winner.bids.push(bid);
+        for (item, qty) in bid.bid_items() {
+            match winner.items.iter_mut().find(|(id, _)| *id == item) {
+                Some((_, total)) => *total = total.add(qty),
+                None => winner.items.push((item, qty.clone())),
+            }
+        }
+    }
+    winners
+}
+
+/// How a winning bidder's VCG payment was derived, returned by
+/// [`run_with_payment_derivations`](crate::run_with_payment_derivations).
+///
+/// The payment is the harm the bidder does to the others: the value the
+/// others could have had without them, minus the value they get in the
+/// chosen outcome.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PaymentDerivation<'a, B: Bid> {
+    pub bidder_name: &'a B::Name,
+    /// Highest value combination of the other bidders' bids, the outcome of
+    /// the auction had the bidder not taken part. If several are tied, the
+    /// first found.
+    pub counterfactual_bids: Vec<&'a B>,
+    /// Counterfactual bids that lose in the chosen outcome: the bids the
+    /// bidder displaced by taking part, in the order of the counterfactual
+    /// bids. Their bidders are the ones harmed.
+    pub displaced_bids: Vec<&'a B>,
+    /// Value of the counterfactual bids.
+    pub counterfactual_value: B::Value,
+    /// Value of the other bidders' winning bids in the chosen outcome.
+    pub others_value: B::Value,
+    /// `counterfactual_value - others_value`.
+    pub payment: B::Value,
+}
+
+/// Outcome of an auction run with [`SearchOptions`], which may have stopped
+/// before finding the exact result.
+pub enum AuctionOutcome<'a, B: Bid> {
+    /// The search finished and the result is exact.
+    Complete(AuctionResult<'a, B>),
+    /// The search exceeded its
+    /// [`max_duration`](SearchOptions::max_duration) with
+    /// [`OnTimeout::BestSoFar`]. Payments aren't included, since they can only
+    /// be calculated from the exact winning bids.
+    TimedOut {
+        /// The highest value combination of bids found before the timeout,
+        /// improved by a local search swapping single bids in and out until
+        /// no swap raises its value.
+        best_so_far: Vec<&'a B>,
+        /// How much more value the exact winning bids could have at most. Zero
+        /// if the timeout happened while calculating payments.
+        gap: B::Value,
+    },
+}
+
+// manual impls, since derives can't bound on the fields' associated types
+impl<'a, B: Bid> Clone for AuctionOutcome<'a, B>
+where
+    AuctionResult<'a, B>: Clone,
+    B::Value: Clone,
+{
+    fn clone(&self) -> Self {
+        match self {
+            AuctionOutcome::Complete(result) => {
+                AuctionOutcome::Complete(result.clone())
+            }
+            AuctionOutcome::TimedOut { best_so_far, gap } => {
+                AuctionOutcome::TimedOut {
+                    best_so_far: best_so_far.clone(),
+                    gap: gap.clone(),
+                }
+            }
+        }
+    }
+}
+
+impl<'a, B: Bid> PartialEq for AuctionOutcome<'a, B>
+where
+    AuctionResult<'a, B>: PartialEq,
+    B: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (AuctionOutcome::Complete(a), AuctionOutcome::Complete(b)) => {
+                a == b
+            }
+            (
+                AuctionOutcome::TimedOut {
+                    best_so_far: a,
+                    gap: a_gap,
+                },
+                AuctionOutcome::TimedOut {
+                    best_so_far: b,
+                    gap: b_gap,
+                },
+            ) => a == b && a_gap == b_gap,
+            _ => false,
+        }
+    }
+}
+
+impl<'a, B: Bid> Eq for AuctionOutcome<'a, B>
+where
+    AuctionResult<'a, B>: Eq,
+    B: Eq,
+{
+}
+
+impl<'a, B: Bid> fmt::Debug for AuctionOutcome<'a, B>
+where
+    AuctionResult<'a, B>: fmt::Debug,
+    B: fmt::Debug,
+    B::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuctionOutcome::Complete(result) => {
+                f.debug_tuple("Complete").field(result).finish()
+            }
+            AuctionOutcome::TimedOut { best_so_far, gap } => f
+                .debug_struct("TimedOut")
+                .field("best_so_far", best_so_far)
+                .field("gap", gap)
+                .finish(),
+        }
+    }
+}
+
+impl<'a, B: Bid> AuctionOutcome<'a, B> {
+    /// The result, if the search finished.
+    pub fn complete(self) -> Option<AuctionResult<'a, B>> {
+        match self {
+            AuctionOutcome::Complete(result) => Some(result),
+            AuctionOutcome::TimedOut { .. } => None,
+        }
+    }
+}
+
 /// Calculate a Vickrey-Clarke-Groves auction. Takes a set of items with the
 /// quantities to be auctioned, and a collection of "bid sets", each containing
 /// bids which are mutually-exclusive of one another. Bids are typically grouped
 /// by bidder in these mutually-exclusive bid sets, but bidders can also put
 /// bids in separate bid sets if they are independent of each other. If multiple
 /// outcomes are tied, one is selected at random using a uniform distribution.
+///
+/// Items can be given as any collection of [`StockEntry`], such as a slice of
+/// `(item, quantity)` tuples or a map from items to quantities. Bid sets can be
+/// any collection of bid collections that can be viewed as slices, such as
+/// `Vec`s, arrays or slices. Bids stored in one flat collection can be passed
+/// as slices of it without copying them into separate bid sets.
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use vcg_auction::{types::SimpleBid, vcg_auction};
+///
+/// let items = BTreeMap::from([("chair".to_string(), 2)]);
+/// // one bid set per bidder, stored back to back
+/// let bids = [
+///     SimpleBid::new("Alice", 5, [("chair", 1)]),
+///     SimpleBid::new("Alice", 7, [("chair", 2)]),
+///     SimpleBid::new("Bob", 4, [("chair", 1)]),
+/// ];
+/// let bid_sets = [&bids[0..2], &bids[2..3]];
+/// let result = vcg_auction(&items, bid_sets).unwrap();
+/// assert_eq!(result.winning_bids, [&bids[0], &bids[2]]);
+/// ```
 #[cfg(feature = "rand")]
 #[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
-pub fn vcg_auction<'a, B: Bid>(
-    items: &[(B::Item, B::Quantity)],
-    exclusive_bid_sets: &'a [Vec<B>],
-) -> Option<AuctionResult<'a, B>> {
-    let tiebreaker = |options: &[Vec<&B>]| {
-        if !options.is_empty() {
-            thread_rng().gen_range::<usize, _>(0..options.len())
-        } else {
-            0
-        }
-    };
-    vcg_auction_with_tiebreaker(items, exclusive_bid_sets, tiebreaker)
+pub fn vcg_auction<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+) -> Option<AuctionResult<'a, B>>
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    crate::run(items, exclusive_bid_sets, &AuctionConfig::default())
+        .ok()
+        .and_then(AuctionOutcome::complete)
+}
+
+/// Uniform random tiebreaker used by [`vcg_auction`].
+#[cfg(feature = "rand")]
+pub(crate) fn random_tiebreaker<B: Bid>(options: &[Vec<&B>]) -> usize {
+    random_index(&mut thread_rng(), options)
+}
+
+/// Uniform random choice of one of the tied outcomes.
+#[cfg(feature = "rng")]
+pub(crate) fn random_index<B: Bid>(
+    rng: &mut impl Rng,
+    options: &[Vec<&B>],
+) -> usize {
+    if !options.is_empty() {
+        rng.gen_range::<usize, _>(0..options.len())
+    } else {
+        0
+    }
+}
+
+/// Calculate a VCG auction, breaking ties uniformly at random with a random
+/// number generator. Unlike [`vcg_auction`], no operating system source of
+/// randomness is needed, so with only the `rng` feature enabled this works on
+/// targets like `wasm32-unknown-unknown` without configuring `getrandom`.
+///
+/// ```
+/// use rand::{rngs::StdRng, SeedableRng};
+/// use vcg_auction::{types::SimpleBid, vcg_auction_with_rng};
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 10, [("chair", 1)])],
+/// ];
+/// let mut rng = StdRng::seed_from_u64(7);
+/// let result = vcg_auction_with_rng(&items, &bids, &mut rng).unwrap();
+/// assert_eq!(result.winning_bids.len(), 1);
+/// ```
+#[cfg(feature = "rng")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rng")))]
+pub fn vcg_auction_with_rng<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    rng: &mut impl Rng,
+) -> Option<AuctionResult<'a, B>>
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    vcg_auction_with_tiebreaker(items, exclusive_bid_sets, |options| {
+        random_index(rng, options)
+    })
 }
 
 /// Calculate a VCG auction with a tiebreaking scheme passed in as a closure.
@@ -50,180 +528,1397 @@ pub fn vcg_auction<'a, B: Bid>(
 /// ```
 ///
 /// [`vcg_auction`] uses a random uniform tiebreaker.
-pub fn vcg_auction_with_tiebreaker<'a, B: Bid>(
-    items: &[(B::Item, B::Quantity)],
-    exclusive_bid_sets: &'a [Vec<B>],
+pub fn vcg_auction_with_tiebreaker<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
     tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
-) -> Option<AuctionResult<'a, B>> {
-    let exclusive_bid_sets = exclusive_bid_sets
-        .iter()
-        .map(|bs| bs.iter().collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+) -> Option<AuctionResult<'a, B>>
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    run_auction(
+        &ItemStock::new(items),
+        bid_set_refs(exclusive_bid_sets),
+        tiebreaker,
+        &SearchControl::new(SearchOptions::default()),
+    )
+    .ok()
+    .and_then(AuctionOutcome::complete)
+}
+
+/// Calculate a VCG auction with a tiebreaker and [`SearchOptions`]. Returns
+/// an error if the tiebreaker returns an invalid index, or the search times out
+/// with [`OnTimeout::Error`].
+///
+/// ```
+/// use std::time::Duration;
+/// use vcg_auction::{
+///     types::SimpleBid, vcg_auction_with_options, AuctionOutcome, OnTimeout,
+///     SearchOptions,
+/// };
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![vec![SimpleBid::new("Alice", 10, [("chair", 1)])]];
+/// let options = SearchOptions {
+///     max_duration: Some(Duration::from_secs(10)),
+///     on_timeout: OnTimeout::BestSoFar,
+///     ..Default::default()
+/// };
+/// match vcg_auction_with_options(&items, &bids, |_| 0, &options) {
+///     Ok(AuctionOutcome::Complete(result)) => {
+///         assert_eq!(result.winning_bids, [&bids[0][0]])
+///     }
+///     Ok(AuctionOutcome::TimedOut { best_so_far, gap }) => {
+///         println!("best so far {best_so_far:?}, at most {gap} short")
+///     }
+///     Err(err) => panic!("{err}"),
+/// }
+/// ```
+pub fn vcg_auction_with_options<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
+    options: &SearchOptions<B::Value>,
+) -> Result<AuctionOutcome<'a, B>, AuctionError>
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    run_auction(
+        &ItemStock::new(items),
+        bid_set_refs(exclusive_bid_sets),
+        tiebreaker,
+        &SearchControl::new(options.clone()),
+    )
+}
+
+/// The highest-scoring bid sets and their value.
+pub(crate) type Highest<'a, B> = (Vec<Vec<&'a B>>, <B as Bid>::Value);
+
+/// The highest-scoring bid sets of a finished search, or the reason the
+/// search stopped and the highest-scoring bid sets found before then.
+pub(crate) type SearchResult<'a, B> =
+    Result<Highest<'a, B>, (Interrupt, Highest<'a, B>)>;
+
+/// Payments of the winning bidders, or the reason the search stopped.
+pub(crate) type PaymentsResult<'a, B> =
+    Result<Vec<(&'a <B as Bid>::Name, <B as Bid>::Value)>, Interrupt>;
+
+/// Shared implementation of the serial auction entry points.
+pub(crate) fn run_auction<'a, B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: Vec<Vec<&'a B>>,
+    tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
+    control: &SearchControl<B::Value>,
+) -> Result<AuctionOutcome<'a, B>, AuctionError> {
+    run_auction_with(
+        items,
+        exclusive_bid_sets,
+        tiebreaker,
+        control,
+        |bid_sets, phase| {
+            find_highest_value_bid_sets(items, bid_sets, control, phase)
+        },
+        |winning_bid_set, bid_sets| {
+            calculate_payments(winning_bid_set, items, bid_sets, control)
+        },
+    )
+}
+
+/// Run an auction with the given implementations of the search for the
+/// highest value bid sets, and of the payment calculation.
+pub(crate) fn run_auction_with<'a, B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: Vec<Vec<&'a B>>,
+    tiebreaker: impl FnOnce(&[Vec<&B>]) -> usize,
+    control: &SearchControl<B::Value>,
+    find: impl FnOnce(&[Vec<&'a B>], SearchPhase) -> SearchResult<'a, B>,
+    payments: impl FnOnce(&[&'a B], &[Vec<&'a B>]) -> PaymentsResult<'a, B>,
+) -> Result<AuctionOutcome<'a, B>, AuctionError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!(
+        "vcg_auction",
+        bid_sets = exclusive_bid_sets.len(),
+        bids = exclusive_bid_sets.iter().map(Vec::len).sum::<usize>(),
+    )
+    .entered();
+    control.options().check_size(&exclusive_bid_sets)?;
     // multiple sets of bids could be tied for the highest value
-    let (highest_bid_sets, _highest_value) =
-        find_highest_value_bid_sets(items, &exclusive_bid_sets);
-    let winning_bid_set = if highest_bid_sets.len() <= 1 {
-        highest_bid_sets.get(0)?
+    let (highest_bid_sets, highest_value) =
+        match find(&exclusive_bid_sets, SearchPhase::WinnerDetermination) {
+            Ok(highest) => highest,
+            Err((interrupt, (highest_bid_sets, highest_value))) => {
+                let best_so_far = if highest_bid_sets.is_empty() {
+                    vec![]
+                } else {
+                    break_tie(&highest_bid_sets, tiebreaker)
+                        .ok_or(AuctionError::InvalidTiebreak)?
+                        .to_vec()
+                };
+                let upper_bound =
+                    upper_bound::<B>(&annotate_max_values(&exclusive_bid_sets));
+                let gap = upper_bound.sub(&highest_value);
+                return interrupted(control, interrupt, best_so_far, gap);
+            }
+        };
+    let winning_bid_set = break_tie(&highest_bid_sets, tiebreaker)
+        .ok_or(AuctionError::InvalidTiebreak)?;
+    match payments(winning_bid_set, &exclusive_bid_sets) {
+        Ok(payments) => {
+            if control.options().verify_payments {
+                match verify_payments(
+                    winning_bid_set,
+                    items,
+                    &exclusive_bid_sets,
+                    &payments,
+                    control,
+                ) {
+                    Ok(true) => (),
+                    Ok(false) => return Err(AuctionError::PaymentMismatch),
+                    Err(interrupt) => {
+                        return interrupted(
+                            control,
+                            interrupt,
+                            winning_bid_set.to_vec(),
+                            B::Value::zero(),
+                        )
+                    }
+                }
+            }
+            if control.options().check_invariants {
+                check_invariants(
+                    items,
+                    winning_bid_set,
+                    &highest_value,
+                    &payments,
+                );
+            }
+            Ok(AuctionOutcome::Complete(AuctionResult {
+                winning_bids: winning_bid_set.to_vec(),
+                payments,
+            }))
+        }
+        Err(interrupt) => interrupted(
+            control,
+            interrupt,
+            winning_bid_set.to_vec(),
+            B::Value::zero(),
+        ),
+    }
+}
+
+/// The outcome of an auction that stopped before finishing.
+fn interrupted<'a, B: Bid>(
+    control: &SearchControl<B::Value>,
+    interrupt: Interrupt,
+    best_so_far: Vec<&'a B>,
+    gap: B::Value,
+) -> Result<AuctionOutcome<'a, B>, AuctionError> {
+    match (interrupt, control.on_timeout()) {
+        (Interrupt::TimedOut, OnTimeout::BestSoFar) => {
+            Ok(AuctionOutcome::TimedOut { best_so_far, gap })
+        }
+        _ => Err(interrupt.into_error()),
+    }
+}
+
+/// Select the winning bid set from those tied for the highest value. Returns
+/// `None` if there are none, or the tiebreaker returns an invalid index.
+pub(crate) fn break_tie<'h, 'a, B: Bid>(
+    highest_bid_sets: &'h [Vec<&'a B>],
+    tiebreaker: impl FnOnce(&[Vec<&'a B>]) -> usize,
+) -> Option<&'h Vec<&'a B>> {
+    if highest_bid_sets.len() <= 1 {
+        highest_bid_sets.first()
     } else {
-        highest_bid_sets.get(tiebreaker(&highest_bid_sets))?
-    };
-    let payments =
-        calculate_payments(winning_bid_set, items, &exclusive_bid_sets);
-    Some(AuctionResult {
-        winning_bids: winning_bid_set.to_vec(),
-        payments,
-    })
+        highest_bid_sets.get(tiebreaker(highest_bid_sets))
+    }
+}
+
+pub(crate) fn find_highest_value_bid_sets<'a, B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
+    control: &SearchControl<B::Value>,
+    phase: SearchPhase,
+) -> SearchResult<'a, B> {
+    find_highest_value_bid_sets_from(
+        items,
+        exclusive_bid_sets,
+        control,
+        phase,
+        &[],
+    )
+}
+
+/// The highest value bid sets without a winning bidder, for their payment.
+/// The other winners' bids still fit without them, so the search starts from
+/// their value, raised by a local search, rather than from zero, and prunes
+/// combinations that can't reach it from the start.
+pub(crate) fn find_counterfactual_bid_sets<'a, B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>], // bid sets without the bidder
+    control: &SearchControl<B::Value>,
+    other_winning_bids: &[&'a B],
+) -> SearchResult<'a, B> {
+    find_highest_value_bid_sets_from(
+        items,
+        exclusive_bid_sets,
+        control,
+        SearchPhase::Payments,
+        other_winning_bids,
+    )
 }
 
-fn find_highest_value_bid_sets<'a, B: Bid>(
-    items: &[(B::Item, B::Quantity)],
+/// Search for the highest value bid sets, needing at least the value of the
+/// combination of `known_bids` raised by a local search. Bid sets split into
+/// independent components are searched a component at a time.
+pub(crate) fn find_highest_value_bid_sets_from<'a, B: Bid>(
+    items: &ItemStock<B>,
     exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
-) -> (Vec<Vec<&'a B>>, B::Value) {
-    let mut highest_value_bid_sets: Vec<Vec<&'a B>> = vec![]; // empty
-    let mut highest_value = B::Value::zero();
-    // the items selected so far
-    let items_selected = items
+    control: &SearchControl<B::Value>,
+    phase: SearchPhase,
+    known_bids: &[&'a B],
+) -> SearchResult<'a, B> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("search", ?phase).entered();
+    ComponentSearches::new(
+        items,
+        exclusive_bid_sets,
+        control,
+        phase,
+        known_bids,
+    )
+    .run_to_end(items, control)
+}
+
+/// A search over all the bid sets, starting with no bids selected.
+pub(crate) fn start_search<'a, B: Bid>(
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
+    phase: SearchPhase,
+    control: &SearchControl<B::Value>,
+) -> Search<'a, B> {
+    let bid_sets = annotate_max_values(exclusive_bid_sets);
+    let upper_bound = upper_bound::<B>(&bid_sets);
+    let strategy = control.options().strategy;
+    let mut search = Search::new(items, phase, bid_sets, upper_bound, strategy);
+    let cliques = clique_cover(items, &search.bid_sets);
+    search = search.with_cliques(&cliques);
+    search.start(items.no_items_selected(), vec![], B::Value::zero());
+    search
+}
+
+/// Sum of the max values of the bid sets, which no combination of bids can
+/// exceed.
+pub(crate) fn upper_bound<B: Bid>(bid_sets: &[AnnotatedBidSet<B>]) -> B::Value {
+    bid_sets
         .iter()
-        .map(|(item, _)| (item, B::Quantity::zero()))
-        .collect::<Vec<_>>();
-    // annotate the max possible value of each bid set, used to quickly prune
-    // the solution space
-    let bid_sets_remaining = exclusive_bid_sets
+        .fold(B::Value::zero(), |sum, (_bs, max_bid_value)| {
+            sum.add(max_bid_value)
+        })
+}
+
+/// Highest value the bid sets from each depth on can add, when at most one
+/// bid of the bid sets in each clique can win. `cliques` has the clique of
+/// each bid set.
+pub(crate) fn remaining_bounds<B: Bid>(
+    bid_sets: &[AnnotatedBidSet<B>],
+    cliques: &[usize],
+) -> Vec<B::Value> {
+    let n_cliques = cliques.iter().max().map_or(0, |clique| clique + 1);
+    let mut highest: Vec<Option<&B::Value>> = vec![None; n_cliques];
+    let mut bounds = vec![B::Value::zero()];
+    for (&(_, max_bid_value), &clique) in bid_sets.iter().zip(cliques).rev() {
+        let later = bounds.last().expect("a bound is pushed");
+        // the clique's bound rises by how much this bid set's highest bid
+        // beats the clique's later bid sets
+        let bound = match highest[clique] {
+            None => later.add(max_bid_value),
+            Some(max) if max_bid_value > max => {
+                later.add(&max_bid_value.sub(max))
+            }
+            Some(_) => copy_value::<B>(later),
+        };
+        if highest[clique].map_or(true, |max| max_bid_value > max) {
+            highest[clique] = Some(max_bid_value);
+        }
+        bounds.push(bound);
+    }
+    bounds.reverse();
+    bounds
+}
+
+/// A bid set with the max value of its bids.
+pub(crate) type AnnotatedBidSet<'a, B> = (Vec<&'a B>, &'a <B as Bid>::Value);
+
+/// Annotate the max possible value of each bid set, used to quickly prune the
+/// solution space. Empty bid sets are dropped.
+pub(crate) fn annotate_max_values<'a, B: Bid>(
+    exclusive_bid_sets: &[Vec<&'a B>],
+) -> Vec<AnnotatedBidSet<'a, B>> {
+    exclusive_bid_sets
         .iter()
         .filter_map(|bs| {
-            bs.iter().map(|b| b.bid_value()).max().map(|max| (bs, max))
+            bs.iter()
+                .map(|b| b.bid_value())
+                .max()
+                .map(|max| (bs.clone(), max))
         })
-        .collect::<Vec<_>>();
-    find_highest_value_helper(
-        items,
-        &items_selected,
-        &bid_sets_remaining,
-        &[],
-        B::Value::zero(),
-        &mut highest_value_bid_sets,
-        &mut highest_value,
-    );
-    (highest_value_bid_sets, highest_value)
+        .collect::<Vec<_>>()
 }
 
-/// Finds valid combinations of bids using recursive backtracking to limit the
-/// exploration space where bid combinations are invalid.
-fn find_highest_value_helper<'a, B: Bid>(
-    item_stock: &[(B::Item, B::Quantity)], // max number of items available
-    items_selected: &[(&B::Item, B::Quantity)], // items in selected bids
-    bid_sets_remaining: &[(&Vec<&'a B>, &B::Value)], // bid sets to consider
-    bids_selected: &[&'a B],               // selected bids
+/// State of a single branch-and-bound search over bid combinations. The
+/// search is over an explicit frontier of nodes, so it can be run a bounded
+/// number of nodes at a time.
+pub(crate) struct Search<'a, B: Bid> {
+    phase: SearchPhase,
+    bid_sets: Vec<AnnotatedBidSet<'a, B>>, // bid sets to choose bids from
+    bid_items: Vec<Vec<IndexedItems<'a, B>>>, // items of each bid in stock
+    upper_bound: B::Value,                 // reported with progress
+    remaining: Vec<B::Value>, // highest value the bid sets from each depth add
+    cliques: Vec<usize>,      // clique of each bid set
+    frontier: Frontier<'a, B>, // nodes left to explore
+    levels: Levels<'a, B>,    // selections on a depth-first search's path
+    nodes: u64,               // nodes explored since the last checkpoint
+    interrupt: Option<Interrupt>,
+    highest_value_bid_sets: Vec<Vec<&'a B>>, // highest-scoring bid sets
+    highest_value: B::Value,                 // highest value found
+    stats: PhaseStats,
+    tree: Vec<SearchTreeNode<B::Value>>, // explored nodes, if recording
+    cuts: Vec<BoundCut<B::Value>>,       // bound cuts, if logging
+    #[cfg(feature = "lp")]
+    relaxation: Option<Box<dyn Relaxation<B> + 'a>>, // tighter bound, if any
+}
+
+/// A bound on what the bid sets left at a node can add, tighter than the sum
+/// of their highest bids but costlier to check.
+#[cfg(feature = "lp")]
+pub(crate) trait Relaxation<B: Bid> {
+    /// Whether no combination of bids from the bid sets from `depth` on,
+    /// within the stock left after `items_selected`, can raise
+    /// `selected_value` to `highest_value`.
+    fn prunes(
+        &self,
+        depth: usize,
+        items_selected: &[B::Quantity],
+        selected_value: &B::Value,
+        highest_value: &B::Value,
+    ) -> bool;
+}
+
+/// A combination of bids chosen from the bid sets before `depth`.
+struct Node<'a, B: Bid> {
+    items_selected: Vec<B::Quantity>, // items in selected bids
+    depth: usize,                     // number of bid sets considered
+    bids_selected: Vec<&'a B>,        // selected bids
     selected_value: B::Value,
-    highest_value_bid_sets: &mut Vec<Vec<&'a B>>, // highest-scoring bid sets
-    highest_value: &mut B::Value,                 // highest value found
-) {
-    // check that the allocated items is not greater than the stock
-    for i in 0..items_selected.len() {
-        if items_selected[i].1 > item_stock[i].1 {
-            // selected bids not valid -> return without further exploring
+    parent: Option<usize>, // recorded node this was reached from
+    bid: Option<usize>,    // index of the bid chosen from the last bid set
+    // choice at each depth, the bid's index or the bid set's length for no
+    // bid, ordering a best-first search
+    path: Vec<usize>,
+}
+
+impl<'a, B: Bid> Node<'a, B> {
+    fn view(&self) -> NodeView<'_, 'a, B> {
+        NodeView {
+            items_selected: &self.items_selected,
+            depth: self.depth,
+            bids_selected: &self.bids_selected,
+            selected_value: &self.selected_value,
+            parent: self.parent,
+            bid: self.bid,
+        }
+    }
+}
+
+/// A node being explored, borrowed from a [`Node`] or from the [`Levels`] of
+/// a depth-first search.
+struct NodeView<'n, 'a, B: Bid> {
+    items_selected: &'n [B::Quantity],
+    depth: usize,
+    bids_selected: &'n [&'a B],
+    selected_value: &'n B::Value,
+    parent: Option<usize>,
+    bid: Option<usize>,
+}
+
+/// A node of a depth-first search, whose selections are rebuilt from its
+/// parent's when it's explored rather than stored with it.
+struct Step {
+    depth: usize,
+    parent: Option<usize>,
+    bid: Option<usize>,
+}
+
+/// Selections of each node on the path of a depth-first search, from the
+/// root to the node being explored. A node overwrites the level of its depth
+/// in place, so exploring allocates nothing once the levels have grown to
+/// the depth of the search.
+struct Levels<'a, B: Bid> {
+    items_selected: Vec<Vec<B::Quantity>>,
+    selected_values: Vec<B::Value>,
+    bids_selected: Vec<&'a B>, // selected bids of the deepest level
+    bid_counts: Vec<usize>,    // number of bids selected at each level
+}
+
+// manual impl, since the derive would require `B: Default`
+impl<B: Bid> Default for Levels<'_, B> {
+    fn default() -> Self {
+        Self {
+            items_selected: vec![],
+            selected_values: vec![],
+            bids_selected: vec![],
+            bid_counts: vec![],
+        }
+    }
+}
+
+impl<'a, B: Bid> Levels<'a, B> {
+    /// Start from the selections of the root.
+    fn set_root(
+        &mut self,
+        items_selected: Vec<B::Quantity>,
+        bids_selected: Vec<&'a B>,
+        selected_value: B::Value,
+    ) {
+        self.bid_counts = vec![bids_selected.len()];
+        self.items_selected = vec![items_selected];
+        self.selected_values = vec![selected_value];
+        self.bids_selected = bids_selected;
+    }
+
+    /// Rebuild the selections of a step from those of its parent, the level
+    /// above. Levels below the step's are left stale.
+    fn enter(
+        &mut self,
+        step: &Step,
+        bid_sets: &[AnnotatedBidSet<'a, B>],
+        bid_items: &[Vec<IndexedItems<'a, B>>],
+    ) {
+        let depth = step.depth;
+        if depth == 0 {
             return;
         }
+        if self.items_selected.len() == depth {
+            self.items_selected.push(vec![]);
+            self.selected_values.push(B::Value::zero());
+            self.bid_counts.push(0);
+        }
+        let (above, below) = self.items_selected.split_at_mut(depth);
+        let items_selected = &mut below[0];
+        items_selected.clone_from(&above[depth - 1]);
+        self.bids_selected.truncate(self.bid_counts[depth - 1]);
+        let parent_value = &self.selected_values[depth - 1];
+        let selected_value = match step.bid {
+            Some(i) => {
+                let bid = bid_sets[depth - 1].0[i];
+                add_indexed_items::<B>(
+                    items_selected,
+                    &bid_items[depth - 1][i],
+                );
+                self.bids_selected.push(bid);
+                parent_value.add(bid.bid_value())
+            }
+            None => copy_value::<B>(parent_value),
+        };
+        self.selected_values[depth] = selected_value;
+        self.bid_counts[depth] = self.bids_selected.len();
+    }
+
+    /// Move the levels into the vectors of `buffers`, and the vectors they
+    /// were in into `buffers`. Levels of the buffers below the deepest level
+    /// so far are kept, to be overwritten rather than allocated.
+    fn swap_buffers(&mut self, buffers: &mut SearchBuffers<B>) {
+        let levels = self.items_selected.len();
+        buffers
+            .items_selected
+            .resize_with(buffers.items_selected.len().max(levels), Vec::new);
+        buffers
+            .selected_values
+            .resize_with(buffers.items_selected.len(), B::Value::zero);
+        buffers.bid_counts.resize(buffers.items_selected.len(), 0);
+        for depth in 0..levels {
+            buffers.items_selected[depth]
+                .clone_from(&self.items_selected[depth]);
+            buffers.selected_values[depth] =
+                copy_value::<B>(&self.selected_values[depth]);
+            buffers.bid_counts[depth] = self.bid_counts[depth];
+        }
+        std::mem::swap(&mut self.items_selected, &mut buffers.items_selected);
+        std::mem::swap(&mut self.selected_values, &mut buffers.selected_values);
+        std::mem::swap(&mut self.bid_counts, &mut buffers.bid_counts);
     }
 
-    // search reached full depth, check if selected bids are more valuable
-    if bid_sets_remaining.is_empty() {
-        match selected_value.cmp(highest_value) {
-            Ordering::Greater => {
-                *highest_value_bid_sets = vec![bids_selected.to_vec()];
-                *highest_value = selected_value;
+    /// The step, with the selections of its level.
+    fn view(&self, step: &Step) -> NodeView<'_, 'a, B> {
+        NodeView {
+            items_selected: &self.items_selected[step.depth],
+            depth: step.depth,
+            bids_selected: &self.bids_selected,
+            selected_value: &self.selected_values[step.depth],
+            parent: step.parent,
+            bid: step.bid,
+        }
+    }
+}
+
+/// Scratch memory of depth-first searches: the stack of nodes left to
+/// explore and the selections of each node on the current path. A
+/// [`Solver`](crate::Solver) given buffers searches with them rather than
+/// allocating its own for each search, and they can be taken back with
+/// [`Solver::into_buffers`](crate::Solver::into_buffers) to reuse in the
+/// next auction, so repeated auctions of a similar size allocate nothing for
+/// them once the buffers have grown to fit.
+///
+/// ```
+/// use std::task::Poll;
+/// use vcg_auction::{types::SimpleBid, SearchBuffers, Solver};
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 5, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+/// ];
+/// // enough for any auction of at most 1 item, 2 bid sets and 2 bids
+/// let mut buffers = SearchBuffers::with_capacity(1, 2, 2);
+/// for _ in 0..3 {
+///     let mut solver =
+///         Solver::new(&items, &bids, |_| 0).with_buffers(buffers);
+///     while solver.step(u64::MAX).is_pending() {}
+///     buffers = solver.into_buffers();
+/// }
+/// ```
+pub struct SearchBuffers<B: Bid> {
+    stack: Vec<Step>,
+    items_selected: Vec<Vec<B::Quantity>>,
+    selected_values: Vec<B::Value>,
+    bid_counts: Vec<usize>,
+}
+
+// manual impl, since the derive would require `B: Default`
+impl<B: Bid> Default for SearchBuffers<B> {
+    fn default() -> Self {
+        Self {
+            stack: vec![],
+            items_selected: vec![],
+            selected_values: vec![],
+            bid_counts: vec![],
+        }
+    }
+}
+
+impl<B: Bid> SearchBuffers<B> {
+    /// Empty buffers, which grow as they're searched with.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffers already large enough for a depth-first search of at most
+    /// `items` items in stock, `bid_sets` bid sets and `bids` bids in all, so
+    /// searching with them never grows them.
+    pub fn with_capacity(items: usize, bid_sets: usize, bids: usize) -> Self {
+        let levels = bid_sets + 1;
+        Self {
+            // each node on the path leaves at most its siblings on the stack
+            stack: Vec::with_capacity(bids + levels),
+            items_selected: (0..levels)
+                .map(|_| Vec::with_capacity(items))
+                .collect(),
+            selected_values: (0..levels).map(|_| B::Value::zero()).collect(),
+            bid_counts: vec![0; levels],
+        }
+    }
+}
+
+/// Nodes left to explore, in the order of the search strategy.
+enum Frontier<'a, B: Bid> {
+    /// Depth-first: the step pushed last is explored next.
+    Stack(Vec<Step>),
+    /// Best-first: the node with the highest bound is explored next.
+    Queue(BinaryHeap<Queued<'a, B>>),
+}
+
+impl<'a, B: Bid> Frontier<'a, B> {
+    fn new(strategy: SearchStrategy) -> Self {
+        match strategy {
+            SearchStrategy::DepthFirst => Frontier::Stack(vec![]),
+            SearchStrategy::BestFirst => Frontier::Queue(BinaryHeap::new()),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Frontier::Stack(stack) => stack.len(),
+            Frontier::Queue(heap) => heap.len(),
+        }
+    }
+}
+
+/// A node of a best-first search, ordered by its bound and then by the order
+/// a depth-first search would reach it in. Tied outcomes, which share the
+/// highest bound, are then found in the same order as depth-first.
+struct Queued<'a, B: Bid> {
+    bound: B::Value,
+    node: Node<'a, B>,
+}
+
+impl<B: Bid> Ord for Queued<'_, B> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // the heap pops its greatest node
+        self.bound
+            .cmp(&other.bound)
+            .then_with(|| other.node.path.cmp(&self.node.path))
+    }
+}
+
+impl<B: Bid> PartialOrd for Queued<'_, B> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<B: Bid> PartialEq for Queued<'_, B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<B: Bid> Eq for Queued<'_, B> {}
+
+impl<'a, B: Bid> Search<'a, B> {
+    pub(crate) fn new(
+        items: &ItemStock<B>,
+        phase: SearchPhase,
+        bid_sets: Vec<AnnotatedBidSet<'a, B>>,
+        upper_bound: B::Value,
+        strategy: SearchStrategy,
+    ) -> Self {
+        let alone = (0..bid_sets.len()).collect::<Vec<_>>();
+        // looked up once here rather than at every node
+        let bid_items = bid_sets
+            .iter()
+            .map(|(bid_set, _)| {
+                bid_set
+                    .iter()
+                    .map(|bid| items.index_bid_items(*bid))
+                    .collect()
+            })
+            .collect();
+        Self {
+            phase,
+            remaining: remaining_bounds::<B>(&bid_sets, &alone),
+            cliques: alone,
+            frontier: Frontier::new(strategy),
+            levels: Levels::default(),
+            bid_sets,
+            bid_items,
+            upper_bound,
+            nodes: 0,
+            interrupt: None,
+            highest_value_bid_sets: vec![], // empty
+            highest_value: B::Value::zero(),
+            stats: PhaseStats {
+                searches: 1,
+                ..PhaseStats::default()
+            },
+            tree: vec![],
+            cuts: vec![],
+            #[cfg(feature = "lp")]
+            relaxation: None,
+        }
+    }
+
+    /// Prune combinations that can't reach `value`, the value of a combination
+    /// known to fit. Combinations of that value are still recorded as they're
+    /// found, so the result is the same as without it.
+    pub(crate) fn need_at_least(&mut self, value: B::Value) {
+        self.highest_value = value;
+    }
+
+    /// Bound the search by the highest bid of each clique of bid sets, of
+    /// which at most one bid can win, rather than of each bid set. `cliques`
+    /// has the clique of each bid set. Call before starting the search.
+    pub(crate) fn with_cliques(mut self, cliques: &[usize]) -> Self {
+        self.remaining = remaining_bounds::<B>(&self.bid_sets, cliques);
+        self.cliques = cliques.to_vec();
+        self
+    }
+
+    /// Also prune nodes the relaxation bounds below the best value found.
+    #[cfg(feature = "lp")]
+    pub(crate) fn with_relaxation(
+        mut self,
+        relaxation: impl Relaxation<B> + 'a,
+    ) -> Self {
+        self.relaxation = Some(Box::new(relaxation));
+        self
+    }
+
+    /// Start the search from bids already selected, before any of the bid sets
+    /// of the search are considered.
+    pub(crate) fn start(
+        &mut self,
+        items_selected: Vec<B::Quantity>,
+        bids_selected: Vec<&'a B>,
+        selected_value: B::Value,
+    ) {
+        match &mut self.frontier {
+            Frontier::Stack(stack) => {
+                self.levels.set_root(
+                    items_selected,
+                    bids_selected,
+                    selected_value,
+                );
+                stack.push(Step {
+                    depth: 0,
+                    parent: None,
+                    bid: None,
+                });
+            }
+            Frontier::Queue(heap) => heap.push(Queued {
+                bound: selected_value.add(&self.remaining[0]),
+                node: Node {
+                    items_selected,
+                    depth: 0,
+                    bids_selected,
+                    selected_value,
+                    parent: None,
+                    bid: None,
+                    path: vec![],
+                },
+            }),
+        }
+    }
+
+    /// Continue a depth-first search in the memory of `buffers`, leaving the
+    /// memory it was in there until [`return_buffers`](Self::return_buffers)
+    /// gives the buffers back. A best-first search doesn't use them.
+    pub(crate) fn use_buffers(&mut self, buffers: &mut SearchBuffers<B>) {
+        if let Frontier::Stack(stack) = &mut self.frontier {
+            buffers.stack.clear();
+            buffers.stack.append(stack);
+            std::mem::swap(stack, &mut buffers.stack);
+            self.levels.swap_buffers(buffers);
+        }
+    }
+
+    /// Give back the buffers of [`use_buffers`](Self::use_buffers). The
+    /// search can't be continued after.
+    pub(crate) fn return_buffers(&mut self, buffers: &mut SearchBuffers<B>) {
+        if let Frontier::Stack(stack) = &mut self.frontier {
+            std::mem::swap(stack, &mut buffers.stack);
+            std::mem::swap(
+                &mut self.levels.items_selected,
+                &mut buffers.items_selected,
+            );
+            std::mem::swap(
+                &mut self.levels.selected_values,
+                &mut buffers.selected_values,
+            );
+            std::mem::swap(
+                &mut self.levels.bid_counts,
+                &mut buffers.bid_counts,
+            );
+            // nothing is left to explore with the vectors swapped in
+            stack.clear();
+        }
+    }
+
+    /// Whether every node has been explored, or the search was interrupted.
+    pub(crate) fn is_finished(&self) -> bool {
+        self.frontier.len() == 0 || self.interrupt.is_some()
+    }
+
+    /// The highest-scoring bid sets and their value.
+    pub(crate) fn into_highest(
+        self,
+        control: &SearchControl<B::Value>,
+    ) -> SearchResult<'a, B> {
+        control.add_nodes(self.nodes);
+        control.record(self.phase, &self.stats);
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            phase = ?self.phase,
+            nodes = self.stats.nodes,
+            improvements = self.stats.improvements,
+            prunes = self.stats.prunes,
+            interrupted = self.interrupt.is_some(),
+            "search finished"
+        );
+        if !self.tree.is_empty() {
+            control.add_tree_nodes(self.tree);
+        }
+        if let Some(pruning) = control.pruning() {
+            pruning.add_cuts(self.cuts);
+        }
+        let highest = (self.highest_value_bid_sets, self.highest_value);
+        match self.interrupt {
+            None => Ok(highest),
+            Some(interrupt) => Err((interrupt, highest)),
+        }
+    }
+
+    /// Explore up to `max_nodes` nodes, returning the number explored. Finds
+    /// valid combinations of bids using backtracking to limit the exploration
+    /// space where bid combinations are invalid.
+    pub(crate) fn run(
+        &mut self,
+        item_stock: &ItemStock<B>, // max number of items available
+        control: &SearchControl<B::Value>,
+        max_nodes: u64,
+    ) -> u64 {
+        let started = Instant::now();
+        // children bounded below the best value found are skipped before
+        // they're pushed, unless every cut must be logged
+        let prune_children = control.pruning().is_none();
+        let mut explored = 0;
+        while explored < max_nodes && !self.is_finished() {
+            if let Some(interrupt) = control.interrupt() {
+                self.interrupt = Some(interrupt);
+                break;
             }
-            Ordering::Equal => {
-                highest_value_bid_sets.push(bids_selected.to_vec());
+            if control.out_of_nodes(self.nodes) {
+                self.interrupt =
+                    Some(Interrupt::ResourceLimit(ResourceLimit::Nodes));
+                break;
             }
-            Ordering::Less => (),
+            match &mut self.frontier {
+                Frontier::Stack(stack) => {
+                    let step = stack.pop().expect("search isn't finished");
+                    // taken so the node can borrow it while exploring
+                    let mut levels = std::mem::take(&mut self.levels);
+                    levels.enter(&step, &self.bid_sets, &self.bid_items);
+                    let node = levels.view(&step);
+                    if let Some(parent) =
+                        self.explore(item_stock, control, &node)
+                    {
+                        self.push_steps(&node, parent, prune_children);
+                    }
+                    self.levels = levels;
+                }
+                Frontier::Queue(heap) => {
+                    let node = heap.pop().expect("search isn't finished").node;
+                    if let Some(parent) =
+                        self.explore(item_stock, control, &node.view())
+                    {
+                        self.push_nodes(
+                            item_stock,
+                            node,
+                            parent,
+                            prune_children,
+                        );
+                    }
+                }
+            }
+            self.stats.peak_stack_len =
+                self.stats.peak_stack_len.max(self.frontier.len());
+            explored += 1;
         }
-        return;
+        self.stats.search_time += started.elapsed();
+        self.stats.nodes += explored;
+        explored
     }
 
-    // check the possible value achievable with remaining bids
-    let max_remaining_value = bid_sets_remaining
-        .iter()
-        .fold(B::Value::zero(), |sum, (_bs, max_bid_value)| {
-            sum.add(max_bid_value)
-        });
-    let possible_value = selected_value.add(&max_remaining_value);
-    if possible_value < *highest_value {
-        // can't achieve a result with a higher value than we've already
-        // found -> return
-        return;
-    }
-
-    // recurse with next element
-    let (next_bid_set, _max_bid_value) = bid_sets_remaining[0];
-    for bid in next_bid_set {
-        let mut bids_selected_with_new_bid = bids_selected.to_vec();
-        bids_selected_with_new_bid.push(bid);
-        let mut items_selected_with_new_bid = items_selected
+    /// Explore a node. Returns whether its children should be explored, with
+    /// the index the node was recorded at if the tree is recorded.
+    fn explore(
+        &mut self,
+        item_stock: &ItemStock<B>,
+        control: &SearchControl<B::Value>,
+        node: &NodeView<'_, 'a, B>,
+    ) -> Option<Option<usize>> {
+        self.nodes += 1;
+        if self.nodes == CHECK_INTERVAL {
+            control.checkpoint(
+                self.nodes,
+                self.phase,
+                &self.highest_value,
+                &self.upper_bound,
+            );
+            self.nodes = 0;
+        }
+
+        let record = control.records_tree();
+
+        // check that the allocated items is not greater than the stock
+        if !within_stock(node.items_selected, item_stock.quantities()) {
+            // selected bids not valid -> return without further exploring
+            self.stats.prunes += 1;
+            if record {
+                self.record_node(node, None, NodeOutcome::OverAllocated);
+            }
+            return None;
+        }
+
+        // search reached full depth, check if selected bids are more valuable
+        if node.depth == self.bid_sets.len() {
+            let ordering = node.selected_value.cmp(&self.highest_value);
+            if record {
+                let outcome = match ordering {
+                    Ordering::Greater => NodeOutcome::Improved,
+                    Ordering::Equal => NodeOutcome::Tied,
+                    Ordering::Less => NodeOutcome::Worse,
+                };
+                self.record_node(node, None, outcome);
+            }
+            match ordering {
+                Ordering::Greater => {
+                    self.highest_value_bid_sets =
+                        vec![node.bids_selected.to_vec()];
+                    self.highest_value = copy_value::<B>(node.selected_value);
+                    self.stats.improvements += 1;
+                    #[cfg(feature = "tracing")]
+                    tracing::trace!(
+                        phase = ?self.phase,
+                        bids = self.highest_value_bid_sets[0].len(),
+                        "found a higher value"
+                    );
+                }
+                Ordering::Equal => {
+                    if !control
+                        .allows_ties(self.highest_value_bid_sets.len() + 1)
+                    {
+                        self.interrupt = Some(Interrupt::ResourceLimit(
+                            ResourceLimit::TiedOutcomes,
+                        ));
+                        return None;
+                    }
+                    self.highest_value_bid_sets
+                        .push(node.bids_selected.to_vec());
+                }
+                Ordering::Less => (),
+            }
+            self.stats.peak_tied_outcomes = self
+                .stats
+                .peak_tied_outcomes
+                .max(self.highest_value_bid_sets.len());
+            return None;
+        }
+
+        // check the possible value achievable with remaining bids
+        let possible_value =
+            node.selected_value.add(&self.remaining[node.depth]);
+        if possible_value < self.highest_value {
+            // can't achieve a result with a higher value than we've already
+            // found -> return
+            self.stats.prunes += 1;
+            if let Some(pruning) = control.pruning() {
+                let cut = self.bound_cut(pruning, node, &possible_value);
+                self.cuts.push(cut);
+            }
+            if record {
+                let bound = Some(possible_value);
+                self.record_node(node, bound, NodeOutcome::Bounded);
+            }
+            return None;
+        }
+        // searches with a relaxation don't record trees or log cuts, whose
+        // bounds are the sums of the highest bids of the cliques
+        #[cfg(feature = "lp")]
+        if let Some(relaxation) = &self.relaxation {
+            if relaxation.prunes(
+                node.depth,
+                node.items_selected,
+                node.selected_value,
+                &self.highest_value,
+            ) {
+                self.stats.prunes += 1;
+                return None;
+            }
+        }
+        Some(record.then(|| {
+            self.record_node(node, Some(possible_value), NodeOutcome::Expanded)
+        }))
+    }
+
+    /// Push the children of an expanded depth-first node: each bid of the
+    /// next bid set, then no bid from it. Steps are pushed in reverse so they
+    /// are explored in that order. With `prune_children`, bids bounded below
+    /// the best value found are pruned instead.
+    fn push_steps(
+        &mut self,
+        node: &NodeView<'_, 'a, B>,
+        parent: Option<usize>,
+        prune_children: bool,
+    ) {
+        let Frontier::Stack(stack) = &mut self.frontier else {
+            unreachable!("steps are only pushed to a depth-first search");
+        };
+        let depth = node.depth;
+        let remaining = &self.remaining[depth + 1];
+        let step = |bid| Step {
+            depth: depth + 1,
+            parent,
+            bid,
+        };
+        stack.push(step(None));
+        for (i, bid) in self.bid_sets[depth].0.iter().enumerate().rev() {
+            if prune_children {
+                let bound = node.selected_value.add(bid.bid_value());
+                if bound.add(remaining) < self.highest_value {
+                    self.stats.prunes += 1;
+                    continue;
+                }
+            }
+            stack.push(step(Some(i)));
+        }
+    }
+
+    /// Push the children of an expanded best-first node, with each bid of the
+    /// next bid set and without any bid from it. With `prune_children`, bids
+    /// bounded below the best value found are pruned instead.
+    fn push_nodes(
+        &mut self,
+        item_stock: &ItemStock<B>,
+        node: Node<'a, B>,
+        parent: Option<usize>,
+        prune_children: bool,
+    ) {
+        let Frontier::Queue(heap) = &mut self.frontier else {
+            unreachable!("nodes are only pushed to a best-first search");
+        };
+        let remaining = &self.remaining[node.depth + 1];
+        let (next_bid_set, _max_bid_value) = &self.bid_sets[node.depth];
+        let child_path = |choice| {
+            let mut child_path = node.path.clone();
+            child_path.push(choice);
+            child_path
+        };
+        for (i, (bid, bid_items)) in next_bid_set
             .iter()
-            .map(|(id, qty)| (*id, qty.clone()))
-            .collect::<Vec<_>>();
-        for (item, qty) in items_selected_with_new_bid.iter_mut() {
-            if let Some((_, bid_qty)) =
-                bid.bid_items().iter().find(|(id, _)| *id == **item)
-            {
-                *qty = qty.add(bid_qty)
-            }
-        }
-        find_highest_value_helper(
-            item_stock,
-            &items_selected_with_new_bid,
-            &bid_sets_remaining[1..],
-            &bids_selected_with_new_bid,
-            selected_value.add(bid.bid_value()),
-            highest_value_bid_sets,
-            highest_value,
-        );
+            .zip(&self.bid_items[node.depth])
+            .enumerate()
+        {
+            let selected_value = node.selected_value.add(bid.bid_value());
+            let bound = selected_value.add(remaining);
+            if prune_children && bound < self.highest_value {
+                self.stats.prunes += 1;
+                continue;
+            }
+            let mut bids_selected_with_new_bid = node.bids_selected.clone();
+            bids_selected_with_new_bid.push(*bid);
+            heap.push(Queued {
+                bound,
+                node: Node {
+                    items_selected: item_stock
+                        .select_indexed_items(&node.items_selected, bid_items),
+                    depth: node.depth + 1,
+                    bids_selected: bids_selected_with_new_bid,
+                    selected_value,
+                    parent,
+                    bid: Some(i),
+                    path: child_path(i),
+                },
+            });
+        }
+        let path = child_path(next_bid_set.len());
+        heap.push(Queued {
+            bound: node.selected_value.add(remaining),
+            node: Node {
+                depth: node.depth + 1,
+                parent,
+                bid: None,
+                path,
+                ..node
+            },
+        });
     }
-    // also recurse without using any bids from this bid set
-    find_highest_value_helper(
-        item_stock,
-        items_selected,
-        &bid_sets_remaining[1..],
-        bids_selected,
-        selected_value,
-        highest_value_bid_sets,
-        highest_value,
-    );
+
+    /// The cut of a node bounded below the best value found, as logged.
+    fn bound_cut(
+        &self,
+        pruning: &PruningRecorder<B::Value>,
+        node: &NodeView<'_, 'a, B>,
+        bound: &B::Value,
+    ) -> BoundCut<B::Value> {
+        let position = |depth: usize| {
+            let (bid_set, _) = &self.bid_sets[depth];
+            pruning.position(bid_set[0]).0
+        };
+        let remaining = node.depth..self.bid_sets.len();
+        // the remaining bid sets of each clique, in the order of their first
+        // bid set
+        let mut cliques: Vec<(usize, Vec<usize>)> = vec![];
+        for depth in remaining.clone() {
+            let clique = self.cliques[depth];
+            match cliques.iter_mut().find(|(c, _)| *c == clique) {
+                Some((_, bid_sets)) => bid_sets.push(position(depth)),
+                None => cliques.push((clique, vec![position(depth)])),
+            }
+        }
+        BoundCut {
+            phase: self.phase,
+            bids: node
+                .bids_selected
+                .iter()
+                .map(|bid| pruning.position(*bid))
+                .collect(),
+            remaining_bid_sets: remaining.map(position).collect(),
+            cliques: cliques
+                .into_iter()
+                .map(|(_, bid_sets)| bid_sets)
+                .collect(),
+            value: copy_value::<B>(node.selected_value),
+            bound: copy_value::<B>(bound),
+            best: copy_value::<B>(&self.highest_value),
+        }
+    }
+
+    /// Record an explored node, returning its index.
+    fn record_node(
+        &mut self,
+        node: &NodeView<'_, 'a, B>,
+        bound: Option<B::Value>,
+        outcome: NodeOutcome,
+    ) -> usize {
+        self.tree.push(SearchTreeNode {
+            parent: node.parent,
+            phase: self.phase,
+            depth: node.depth,
+            bid: node.bid,
+            value: copy_value::<B>(node.selected_value),
+            bound,
+            best: copy_value::<B>(&self.highest_value),
+            outcome,
+        });
+        self.tree.len() - 1
+    }
+}
+
+/// Copy of a bid value, which isn't required to be `Clone`.
+pub(crate) fn copy_value<B: Bid>(value: &B::Value) -> B::Value {
+    value.add(&B::Value::zero())
+}
+
+/// Derive the payment of each winning bidder given the winning bid set,
+/// keeping the counterfactual outcomes.
+pub(crate) fn derive_payments<'a, B: Bid>(
+    winning_bid_set: &[&'a B],
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
+    control: &SearchControl<B::Value>,
+) -> Result<Vec<PaymentDerivation<'a, B>>, Interrupt> {
+    winning_bidders(winning_bid_set)
+        .into_iter()
+        .map(|bidder_name| {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!("payment").entered();
+            let (highest_bid_sets, counterfactual_value) =
+                find_counterfactual_bid_sets(
+                    items,
+                    &bid_sets_without_bidder(exclusive_bid_sets, bidder_name),
+                    control,
+                    &other_winning_bids(winning_bid_set, bidder_name),
+                )
+                .map_err(|(interrupt, _)| interrupt)?;
+            let others_value =
+                value_of_other_bids(winning_bid_set, bidder_name);
+            let counterfactual_bids =
+                highest_bid_sets.into_iter().next().unwrap_or_default();
+            let displaced_bids = counterfactual_bids
+                .iter()
+                .filter(|bid| {
+                    !winning_bid_set.iter().any(|w| std::ptr::eq(*w, **bid))
+                })
+                .copied()
+                .collect();
+            Ok(PaymentDerivation {
+                bidder_name,
+                counterfactual_bids,
+                displaced_bids,
+                payment: counterfactual_value.sub(&others_value),
+                counterfactual_value,
+                others_value,
+            })
+        })
+        .collect()
 }
 
 /// Calculate the payments each winning bidder makes given the winning bid set.
-fn calculate_payments<'a, B: Bid>(
+pub(crate) fn calculate_payments<'a, B: Bid>(
     winning_bid_set: &[&'a B],
-    items: &[(B::Item, B::Quantity)],
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
+    control: &SearchControl<B::Value>,
+) -> PaymentsResult<'a, B> {
+    winning_bidders(winning_bid_set)
+        .into_iter()
+        .map(|bidder_name| {
+            let payment = payment_for_bidder(
+                winning_bid_set,
+                bidder_name,
+                exclusive_bid_sets,
+                |bid_sets| {
+                    find_counterfactual_bid_sets(
+                        items,
+                        bid_sets,
+                        control,
+                        &other_winning_bids(winning_bid_set, bidder_name),
+                    )
+                    .map(|(_, value)| value)
+                    .map_err(|(interrupt, _)| interrupt)
+                },
+            )?;
+            Ok((bidder_name, payment))
+        })
+        .collect()
+}
+
+/// Whether each payment matches the value of the auction without its winner
+/// found by another search, from [`verification_search`].
+fn verify_payments<'a, B: Bid>(
+    winning_bid_set: &[&'a B],
+    items: &ItemStock<B>,
+    exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
+    payments: &[(&'a B::Name, B::Value)],
+    control: &SearchControl<B::Value>,
+) -> Result<bool, Interrupt> {
+    for (bidder_name, payment) in payments {
+        let mut search = verification_search(
+            items,
+            exclusive_bid_sets,
+            bidder_name,
+            control,
+        );
+        search.run(items, control, u64::MAX);
+        let (_, value) = search
+            .into_highest(control)
+            .map_err(|(interrupt, _)| interrupt)?;
+        if payment_from_value(winning_bid_set, bidder_name, value) != *payment {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// A search for the value of the auction without a winning bidder, to check
+/// their payment, with the bid sets and bids in reverse order, the other
+/// strategy, and without propagation, cliques or a starting value.
+pub(crate) fn verification_search<'a, B: Bid>(
+    items: &ItemStock<B>,
     exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
-) -> Vec<(&'a B::Name, B::Value)> {
-    let mut payments = vec![];
+    bidder_name: &B::Name,
+    control: &SearchControl<B::Value>,
+) -> Search<'a, B> {
+    let strategy = match control.options().strategy {
+        SearchStrategy::DepthFirst => SearchStrategy::BestFirst,
+        SearchStrategy::BestFirst => SearchStrategy::DepthFirst,
+    };
+    let bid_sets = bid_sets_without_bidder(exclusive_bid_sets, bidder_name)
+        .into_iter()
+        .rev()
+        .map(|mut bid_set| {
+            bid_set.reverse();
+            bid_set
+        })
+        .collect::<Vec<_>>();
+    let bid_sets = annotate_max_values(&bid_sets);
+    let upper_bound = upper_bound::<B>(&bid_sets);
+    let mut search = Search::new(
+        items,
+        SearchPhase::Payments,
+        bid_sets,
+        upper_bound,
+        strategy,
+    );
+    search.start(items.no_items_selected(), vec![], B::Value::zero());
+    search
+}
+
+/// Names of the winning bidders, in the order of their first winning bid.
+pub(crate) fn winning_bidders<'a, B: Bid>(
+    winning_bid_set: &[&'a B],
+) -> Vec<&'a B::Name> {
+    let mut bidders: Vec<&B::Name> = vec![];
     for winning_bid in winning_bid_set {
         let bidder_name = winning_bid.bidder_name();
-        if payments.iter().any(|(name, _)| *name == bidder_name) {
-            // already calculated this bidder's payment
-            continue;
+        if !bidders.contains(&bidder_name) {
+            bidders.push(bidder_name);
         }
-        // find the auction value without this bidder
-        let bid_sets_without_bidder = exclusive_bid_sets
-            .iter()
-            .map(|bs| {
-                bs.iter()
-                    .filter(|b| *b.bidder_name() != *bidder_name)
-                    .copied()
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
-        let auction_value_without_bidder =
-            find_highest_value_bid_sets(items, &bid_sets_without_bidder).1;
-        // find the value of the bids placed by other bidders
-        let value_of_other_bids = winning_bid_set
-            .iter()
-            .filter(|b| *b.bidder_name() != *bidder_name)
-            .fold(B::Value::zero(), |acc, b| acc.add(b.bid_value()));
-        // invariant: this subtraction never underflows on unsigned types
-        let payment = auction_value_without_bidder.sub(&value_of_other_bids);
-        payments.push((winning_bid.bidder_name(), payment));
     }
-    payments
+    bidders
+}
+
+/// Calculate the payment of one winning bidder. `find_value` finds the
+/// highest auction value of the given bid sets.
+pub(crate) fn payment_for_bidder<'a, B: Bid>(
+    winning_bid_set: &[&'a B],
+    bidder_name: &B::Name,
+    exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
+    find_value: impl FnOnce(&[Vec<&'a B>]) -> Result<B::Value, Interrupt>,
+) -> Result<B::Value, Interrupt> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("payment").entered();
+    // find the auction value without this bidder
+    let auction_value_without_bidder =
+        find_value(&bid_sets_without_bidder(exclusive_bid_sets, bidder_name))?;
+    Ok(payment_from_value(
+        winning_bid_set,
+        bidder_name,
+        auction_value_without_bidder,
+    ))
+}
+
+/// The bid sets with the bids of one bidder removed.
+pub(crate) fn bid_sets_without_bidder<'a, B: Bid>(
+    exclusive_bid_sets: &[Vec<&'a B>], // sets of mutually-exclusive bids
+    bidder_name: &B::Name,
+) -> Vec<Vec<&'a B>> {
+    exclusive_bid_sets
+        .iter()
+        .map(|bs| {
+            bs.iter()
+                .filter(|b| *b.bidder_name() != *bidder_name)
+                .copied()
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>()
+}
+
+/// The payment of a winning bidder, given the highest auction value without
+/// them.
+pub(crate) fn payment_from_value<B: Bid>(
+    winning_bid_set: &[&B],
+    bidder_name: &B::Name,
+    auction_value_without_bidder: B::Value,
+) -> B::Value {
+    // find the value of the bids placed by other bidders
+    let value_of_other_bids = value_of_other_bids(winning_bid_set, bidder_name);
+    // invariant: this subtraction never underflows on unsigned types
+    auction_value_without_bidder.sub(&value_of_other_bids)
+}
+
+/// Total value of the winning bids placed by bidders other than one.
+fn value_of_other_bids<B: Bid>(
+    winning_bid_set: &[&B],
+    bidder_name: &B::Name,
+) -> B::Value {
+    total_value(
+        winning_bid_set
+            .iter()
+            .copied()
+            .filter(|b| *b.bidder_name() != *bidder_name),
+    )
+}
+
+/// Total value of some bids.
+pub(crate) fn total_value<'b, B: Bid + 'b>(
+    bids: impl IntoIterator<Item = &'b B>,
+) -> B::Value {
+    bids.into_iter()
+        .fold(B::Value::zero(), |acc, b| acc.add(b.bid_value()))
+}
+
+/// The winning bids placed by bidders other than one.
+pub(crate) fn other_winning_bids<'a, B: Bid>(
+    winning_bid_set: &[&'a B],
+    bidder_name: &B::Name,
+) -> Vec<&'a B> {
+    winning_bid_set
+        .iter()
+        .filter(|b| *b.bidder_name() != *bidder_name)
+        .copied()
+        .collect()
 }
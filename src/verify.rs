@@ -0,0 +1,161 @@
+//! Checking a claimed auction result against its inputs.
+//!
+//! Solving an auction can take exponential time, but checking that a result
+//! is consistent with the items and bids takes one pass over them. This lets
+//! a party that didn't run the auction reject results that couldn't have come
+//! from it.
+
+use std::collections::HashSet;
+use std::{fmt, mem};
+
+use crate::{
+    input::{add_indexed_items, ItemStock},
+    pricing::first_price_payments,
+    quantities::within_stock,
+    AuctionResult, Bid, StockEntry,
+};
+
+/// Ways an auction result can be inconsistent with its items and bids.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
+pub enum VerificationError {
+    /// A winning bid isn't one of the bids of the auction, or is listed more
+    /// than once.
+    UnknownBid,
+    /// Two winning bids are from the same bid set.
+    ExclusiveBids,
+    /// The winning bids use more of an item than is in stock.
+    OverAllocated,
+    /// The payments aren't listed once for each winning bidder, and for no
+    /// other bidder.
+    PaymentsMismatch,
+    /// A winner pays more than the value of their winning bids.
+    PaymentExceedsBid,
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::UnknownBid => {
+                write!(f, "winning bid isn't a bid of the auction")
+            }
+            VerificationError::ExclusiveBids => {
+                write!(f, "winning bids are mutually-exclusive")
+            }
+            VerificationError::OverAllocated => {
+                write!(f, "winning bids exceed the item stock")
+            }
+            VerificationError::PaymentsMismatch => {
+                write!(f, "payments don't match the winning bidders")
+            }
+            VerificationError::PaymentExceedsBid => {
+                write!(f, "payment exceeds the value of the winning bids")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerificationError {}
+
+/// Check that a result could have come from an auction of the given items
+/// and bid sets.
+///
+/// The bid sets are passed over once. Items only need to be comparable, so
+/// each winning bid's items are looked up in the stock once, taking time
+/// proportional to the stock for each item of a winning bid. Items listed
+/// more than once in the stock are merged, as auctions do by default.
+///
+/// The winning bids must be references into `exclusive_bid_sets`, as they
+/// are in the result of an auction run on them. Payments may be in any order,
+/// such as sorted by bidder. The result is checked for feasibility and for
+/// payments the winners can afford, not for optimality: a feasible but lower
+/// value combination of bids passes.
+///
+/// ```
+/// use vcg_auction::{
+///     types::SimpleBid, verify_result, vcg_auction_with_tiebreaker,
+///     VerificationError,
+/// };
+///
+/// let items = vec![("chair".to_string(), 1)];
+/// let bids = vec![
+///     vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+///     vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+/// ];
+/// let mut result =
+///     vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+/// assert_eq!(verify_result(&items, &bids, &result), Ok(()));
+///
+/// result.payments[0].1 = 11;
+/// assert_eq!(
+///     verify_result(&items, &bids, &result),
+///     Err(VerificationError::PaymentExceedsBid)
+/// );
+/// ```
+pub fn verify_result<'a, 'i, B, S>(
+    items: impl IntoIterator<Item = impl StockEntry<'i, B::Item, B::Quantity>>,
+    exclusive_bid_sets: impl IntoIterator<Item = &'a S>,
+    result: &AuctionResult<B>,
+) -> Result<(), VerificationError>
+where
+    B: Bid + 'i,
+    S: AsRef<[B]> + ?Sized + 'a,
+{
+    let items = ItemStock::<B>::new(items);
+    let winners = result
+        .winning_bids
+        .iter()
+        .map(|bid| *bid as *const B)
+        .collect::<HashSet<_>>();
+    if winners.len() != result.winning_bids.len() {
+        return Err(VerificationError::UnknownBid);
+    }
+
+    // every winner is found in a bid set, and no bid set has two
+    let mut found = 0;
+    for bid_set in exclusive_bid_sets {
+        let winners_in_set = bid_set
+            .as_ref()
+            .iter()
+            .filter(|bid| winners.contains(&(*bid as *const B)))
+            .count();
+        if winners_in_set > 1 {
+            return Err(VerificationError::ExclusiveBids);
+        }
+        found += winners_in_set;
+    }
+    if found != winners.len() {
+        return Err(VerificationError::UnknownBid);
+    }
+
+    let mut items_selected = items.no_items_selected();
+    for bid in &result.winning_bids {
+        add_indexed_items::<B>(
+            &mut items_selected,
+            &items.index_bid_items(bid),
+        );
+    }
+    if !within_stock(&items_selected, items.quantities()) {
+        return Err(VerificationError::OverAllocated);
+    }
+
+    // each winner pays once, at most the value of their winning bids
+    let bid_values = first_price_payments(&result.winning_bids);
+    if bid_values.len() != result.payments.len() {
+        return Err(VerificationError::PaymentsMismatch);
+    }
+    let mut paid = vec![false; bid_values.len()];
+    for (payment_bidder_name, payment) in &result.payments {
+        let i = bid_values
+            .iter()
+            .position(|(bidder_name, _)| bidder_name == payment_bidder_name)
+            .ok_or(VerificationError::PaymentsMismatch)?;
+        if mem::replace(&mut paid[i], true) {
+            return Err(VerificationError::PaymentsMismatch);
+        }
+        if *payment > bid_values[i].1 {
+            return Err(VerificationError::PaymentExceedsBid);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,108 @@
+//! Tests for affine maximizer auctions.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    affine_maximizer_auction, types::SimpleBid, vcg_auction_with_tiebreaker,
+    AffineMaximizer, Bid,
+};
+
+/// Bid with signed values, so payments can be subsidies.
+#[derive(Debug, PartialEq)]
+struct SignedBid {
+    name: String,
+    value: i64,
+    items: Vec<(String, u64)>,
+}
+
+impl SignedBid {
+    fn new(name: &str, value: i64, item: &str) -> Self {
+        Self {
+            name: name.into(),
+            value,
+            items: vec![(item.into(), 1)],
+        }
+    }
+}
+
+impl Bid for SignedBid {
+    type Name = String;
+    type Value = i64;
+    type Item = String;
+    type Quantity = u64;
+
+    fn bidder_name(&self) -> &String {
+        &self.name
+    }
+    fn bid_value(&self) -> &i64 {
+        &self.value
+    }
+    fn bid_items(&self) -> &[(String, u64)] {
+        &self.items
+    }
+}
+
+#[test]
+fn unweighted_maximizer_is_vcg() {
+    let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("table", 1)])],
+        vec![SimpleBid::new("Dave", 2, [("chair", 1), ("table", 1)])],
+    ];
+    let maximizer = AffineMaximizer::new();
+    assert_eq!(
+        affine_maximizer_auction(&items, &bids, |_| 0, &maximizer),
+        vcg_auction_with_tiebreaker(&items, &bids, |_| 0)
+    );
+}
+
+#[test]
+fn weights_scale_payments_back() {
+    let items = vec![("chair".to_string(), 2)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 9, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("chair", 1)])],
+    ];
+    // Carol's weighted value of 9 ties Alice and beats Bob
+    let weight = |name: &String| if name == "Carol" { 3 } else { 1 };
+    let maximizer = AffineMaximizer::new().weight(&weight);
+    let result =
+        affine_maximizer_auction(&items, &bids, |_| 0, &maximizer).unwrap();
+    assert_eq!(result.winning_bids, [&bids[0][0], &bids[2][0]]);
+    // Bob's 7 sets both prices, which is 7 / 3 of Carol's value
+    assert_eq!(
+        result.payments,
+        [(&"Alice".to_string(), 7), (&"Carol".to_string(), 7 / 3)]
+    );
+}
+
+#[test]
+fn offsets_can_subsidize_winners() {
+    let items = vec![("chair".to_string(), 1)];
+    let bids = vec![
+        vec![SignedBid::new("Alice", 10, "chair")],
+        vec![SignedBid::new("Entrant", 4, "chair")],
+    ];
+    let boost = |offset: i64| {
+        move |bid: &SignedBid| if bid.name == "Entrant" { offset } else { 0 }
+    };
+    // the entrant wins with a boost of 8, and pays what beats Alice's 10
+    let small = boost(8);
+    let maximizer = AffineMaximizer::new().offset(&small);
+    let result =
+        affine_maximizer_auction(&items, &bids, |_| 0, &maximizer).unwrap();
+    assert_eq!(result.winning_bids, [&bids[1][0]]);
+    assert_eq!(result.payments, [(&"Entrant".to_string(), 2)]);
+    // with a boost of 12 they'd win bidding -2, so they're paid 2
+    let large = boost(12);
+    let maximizer = AffineMaximizer::new().offset(&large);
+    let result =
+        affine_maximizer_auction(&items, &bids, |_| 0, &maximizer).unwrap();
+    assert_eq!(result.payments, [(&"Entrant".to_string(), -2)]);
+}
@@ -0,0 +1,106 @@
+//! Tests for allocations with payments calculated on demand.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    types::SimpleBid, vcg_allocation_with_tiebreaker,
+    vcg_auction_with_tiebreaker, PricingRule,
+};
+
+fn wikipedia_bids() -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = vec![("apple".into(), 2)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 5, [("apple", 1)])],
+        vec![SimpleBid::new("Bob", 2, [("apple", 1)])],
+        vec![SimpleBid::new("Carol", 6, [("apple", 2)])],
+    ];
+    (items, bids)
+}
+
+#[test]
+fn payments_on_demand() {
+    let (items, bids) = wikipedia_bids();
+    let allocation =
+        vcg_allocation_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    assert_eq!(allocation.winning_bids(), [&bids[0][0], &bids[1][0]]);
+    assert_eq!(allocation.payment_for(&"Bob".into()), Some(&1));
+    assert_eq!(allocation.payment_for(&"Alice".into()), Some(&4));
+    assert_eq!(allocation.payment_for(&"Carol".into()), None);
+    // cached payments are returned again
+    let first = allocation.payment_for(&"Bob".into()).unwrap();
+    let second = allocation.payment_for(&"Bob".into()).unwrap();
+    assert!(std::ptr::eq(first, second));
+}
+
+#[test]
+fn into_result_matches_auction() {
+    let (items, bids) = wikipedia_bids();
+    let allocation =
+        vcg_allocation_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    allocation.payment_for(&"Bob".into());
+    assert_eq!(
+        Some(allocation.into_result()),
+        vcg_auction_with_tiebreaker(&items, &bids, |_| 0)
+    );
+}
+
+#[test]
+fn invalid_tiebreak() {
+    let items = vec![("chair".into(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 5, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 5, [("chair", 1)])],
+    ];
+    assert!(vcg_allocation_with_tiebreaker(&items, &bids, |_| 2).is_none());
+}
+
+#[test]
+fn pricing_rules() {
+    let (items, bids) = wikipedia_bids();
+    let allocation =
+        vcg_allocation_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    let first_price = allocation.price(PricingRule::FirstPrice);
+    assert_eq!(first_price.winning_bids, [&bids[0][0], &bids[1][0]]);
+    assert_eq!(
+        first_price.payments,
+        [(&"Alice".into(), 5), (&"Bob".into(), 2)]
+    );
+    assert_eq!(
+        Some(allocation.price(PricingRule::Vcg)),
+        vcg_auction_with_tiebreaker(&items, &bids, |_| 0)
+    );
+}
+
+#[test]
+fn first_rejected_bid_pricing() {
+    // three units, bid for one at a time
+    let items = vec![("bond".into(), 3)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("bond", 1)])],
+        vec![SimpleBid::new("Alice", 9, [("bond", 1)])],
+        vec![SimpleBid::new("Bob", 8, [("bond", 1)])],
+        vec![SimpleBid::new("Carol", 7, [("bond", 1)])],
+        vec![SimpleBid::new("Dave", 6, [("bond", 1)])],
+    ];
+    let allocation =
+        vcg_allocation_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    // every unit goes at Carol's rejected bid of 7
+    let uniform = allocation.price(PricingRule::FirstRejectedBid);
+    assert_eq!(
+        uniform.payments,
+        [(&"Alice".into(), 14), (&"Bob".into(), 7)]
+    );
+    // under VCG Alice pays 6 for her second unit, Dave's bid
+    let vcg = allocation.price(PricingRule::Vcg);
+    assert_eq!(vcg.payments, [(&"Alice".into(), 13), (&"Bob".into(), 7)]);
+}
+
+#[test]
+fn first_rejected_bid_pricing_matches_bundles() {
+    let (items, bids) = wikipedia_bids();
+    let allocation =
+        vcg_allocation_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    // Carol's rejected bid is for both apples, so the winners pay nothing
+    let result = allocation.price(PricingRule::FirstRejectedBid);
+    assert_eq!(result.payments, [(&"Alice".into(), 0), (&"Bob".into(), 0)]);
+}
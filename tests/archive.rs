@@ -0,0 +1,67 @@
+//! Tests for the versioned serialization of auction results.
+
+#![cfg(feature = "serde")]
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_with_tiebreaker, ArchiveError,
+    ArchivedResult, OwnedAuctionResult, RESULT_SCHEMA,
+};
+
+fn result() -> OwnedAuctionResult<SimpleBid> {
+    let items = vec![("apple".to_string(), 2)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 5, [("apple", 1)])],
+        vec![SimpleBid::new("Bob", 2, [("apple", 1)])],
+        vec![SimpleBid::new("Carol", 6, [("apple", 2)])],
+    ];
+    vcg_auction_with_tiebreaker(&items, &bids, |_| 0)
+        .unwrap()
+        .into_owned()
+}
+
+#[test]
+fn archives_round_trip() {
+    let archived = ArchivedResult::from(result());
+    assert_eq!(archived.schema, RESULT_SCHEMA);
+    let json = serde_json::to_string(&archived).unwrap();
+    let read: ArchivedResult<SimpleBid> = serde_json::from_str(&json).unwrap();
+    assert_eq!(read, archived);
+    assert_eq!(read.into_result(), Ok(result()));
+}
+
+/// Archives written with schema 1 must stay readable in later versions.
+#[test]
+fn schema_1_archives_read() {
+    let json = serde_json::json!({
+        "schema": 1,
+        "winning_bids": [
+            {"name": "Alice", "value": 5, "items": [["apple", 1]]},
+            {"name": "Bob", "value": 2, "items": [["apple", 1]]},
+        ],
+        "payments": [["Alice", 4], ["Bob", 1]],
+    });
+    let read: ArchivedResult<SimpleBid> =
+        serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(read.into_result(), Ok(result()));
+    assert_eq!(
+        serde_json::to_value(ArchivedResult::new(result())).unwrap(),
+        json
+    );
+}
+
+#[test]
+fn unknown_schemas_are_rejected() {
+    let mut archived = ArchivedResult::new(result());
+    archived.schema = RESULT_SCHEMA + 1;
+    let error = archived.into_result().unwrap_err();
+    assert_eq!(error, ArchiveError::UnsupportedSchema(RESULT_SCHEMA + 1));
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "unknown result schema {}, expected at most {RESULT_SCHEMA}",
+            RESULT_SCHEMA + 1
+        )
+    );
+}
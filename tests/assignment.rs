@@ -0,0 +1,89 @@
+//! Tests that solving auctions of single units as assignment problems
+//! produces the same results as the branch-and-bound search, including the
+//! order of tied outcomes given to the tiebreaker.
+
+mod common;
+
+use std::cell::RefCell;
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_assignment, vcg_auction_with_tiebreaker,
+    SearchOptions,
+};
+
+use common::recording_tiebreaker;
+
+/// Run an auction with both the search and the assignment, checking that the
+/// results and the tied outcomes match.
+fn assert_matches_search(
+    items: &[(String, u64)],
+    bids: &[Vec<SimpleBid>],
+    pick: usize,
+) {
+    let search_ties = RefCell::new(vec![]);
+    let assignment_ties = RefCell::new(vec![]);
+    let search = vcg_auction_with_tiebreaker(
+        items,
+        bids,
+        recording_tiebreaker(&search_ties, pick),
+    );
+    let assignment = vcg_auction_assignment(
+        items,
+        bids,
+        recording_tiebreaker(&assignment_ties, pick),
+        &SearchOptions::default(),
+    )
+    .unwrap()
+    .complete();
+    assert_eq!(search, assignment);
+    assert_eq!(search_ties, assignment_ties);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn assignment_matches_search() {
+    use rand::{distributions::Distribution, rngs::StdRng, Rng, SeedableRng};
+    use vcg_auction::{AuctionInstance, InstanceShape};
+
+    // bids on single units
+    let shape = InstanceShape {
+        max_quantity: 1,
+        max_value: 4,
+        ..common::tied_shape()
+    };
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..300 {
+        let AuctionInstance {
+            items,
+            bid_sets: bids,
+        } = shape.sample(&mut rng);
+        assert_matches_search(&items, &bids, rng.gen());
+    }
+}
+
+#[test]
+fn assignment_of_several_units() {
+    let items = vec![("chair".to_string(), 3), ("table".to_string(), 1)];
+    let bids = (0..6)
+        .map(|bidder| {
+            vec![
+                SimpleBid::new(bidder.to_string(), 10 + bidder, [("chair", 1)]),
+                SimpleBid::new(bidder.to_string(), 20 - bidder, [("table", 1)]),
+            ]
+        })
+        .collect::<Vec<_>>();
+    assert_matches_search(&items, &bids, 0);
+}
+
+#[test]
+fn bundles_fall_back_to_the_search() {
+    let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 5, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 8, [("chair", 1), ("table", 1)])],
+        vec![SimpleBid::new("Carol", 6, [("chair", 2)])],
+    ];
+    assert_matches_search(&items, &bids, 0);
+}
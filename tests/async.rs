@@ -0,0 +1,38 @@
+//! Tests for the async wrappers running on tokio's blocking thread pool.
+
+#![cfg(feature = "tokio")]
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{types::SimpleBid, vcg_auction_with_tiebreaker_async};
+
+#[tokio::test]
+async fn simple_async_case() {
+    let items = vec![("chair".into(), 2)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+    ];
+    let expected_winners = vec![bids[0][0].clone(), bids[1][0].clone()];
+    let result = vcg_auction_with_tiebreaker_async(items, bids, |_| 0)
+        .await
+        .unwrap();
+    assert_eq!(result.winning_bids, expected_winners);
+    assert_eq!(result.payments, [("Alice".into(), 0), ("Bob".into(), 2)]);
+}
+
+#[cfg(feature = "rand")]
+#[tokio::test]
+async fn random_tiebreak_async() {
+    let items = vec![("chair".into(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 10, [("chair", 1)])],
+    ];
+    let result = vcg_auction::vcg_auction_async(items, bids).await.unwrap();
+    assert_eq!(result.winning_bids.len(), 1);
+    assert_eq!(result.payments[0].1, 10);
+}
@@ -0,0 +1,141 @@
+//! Tests for plugging winner determination backends into an auction.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_with_solver, vcg_auction_with_tiebreaker,
+    AuctionError, BranchAndBound, SearchOptions,
+};
+#[cfg(feature = "rand")]
+use vcg_auction::{HighestValueBids, SearchPhase, WinnerDeterminationSolver};
+
+/// Solver trying every combination of bids, each bid of a bid set then none,
+/// as the branch-and-bound search orders them.
+#[cfg(feature = "rand")]
+struct Exhaustive;
+
+#[cfg(feature = "rand")]
+impl WinnerDeterminationSolver<SimpleBid> for Exhaustive {
+    fn highest_value_bids<'a>(
+        &self,
+        items: &[(&String, &u64)],
+        exclusive_bid_sets: &[Vec<&'a SimpleBid>],
+        _phase: SearchPhase,
+    ) -> Result<HighestValueBids<'a, SimpleBid>, AuctionError> {
+        let mut combinations: Vec<Vec<&SimpleBid>> = vec![vec![]];
+        for bid_set in exclusive_bid_sets.iter().rev() {
+            combinations = bid_set
+                .iter()
+                .flat_map(|bid| {
+                    combinations
+                        .iter()
+                        .map(move |rest| [vec![*bid], rest.clone()].concat())
+                })
+                .chain(combinations.iter().cloned())
+                .collect();
+        }
+        let fits = |bids: &Vec<&SimpleBid>| {
+            items.iter().all(|(item, stock)| {
+                let used = bids
+                    .iter()
+                    .flat_map(|bid| &bid.items)
+                    .filter(|(bid_item, _)| bid_item == *item)
+                    .map(|(_, quantity)| quantity)
+                    .sum::<u64>();
+                used <= **stock
+            })
+        };
+        let value = |bids: &Vec<&SimpleBid>| {
+            bids.iter().map(|bid| bid.value).sum::<u64>()
+        };
+        let combinations =
+            combinations.into_iter().filter(fits).collect::<Vec<_>>();
+        let highest = combinations.iter().map(value).max().unwrap_or(0);
+        Ok(HighestValueBids {
+            tied: combinations
+                .into_iter()
+                .filter(|bids| value(bids) == highest)
+                .collect(),
+            value: highest,
+        })
+    }
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn exhaustive_matches_branch_and_bound() {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    let items = vec![("chair".into(), 2), ("table".into(), 1)];
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..200 {
+        let bids = (0..rng.gen_range(0..6))
+            .map(|bidder| {
+                (0..rng.gen_range(0..3))
+                    .map(|_| {
+                        let item = ["chair", "table"][rng.gen_range(0..2)];
+                        SimpleBid::new(
+                            bidder.to_string(),
+                            rng.gen_range(0..4),
+                            [(item, rng.gen_range(1..3))],
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let pick = rng.gen::<usize>();
+        let tiebreaker = |tied: &[Vec<&SimpleBid>]| pick % tied.len();
+        let branch_and_bound = BranchAndBound::default();
+        assert_eq!(
+            vcg_auction_with_solver(&items, &bids, tiebreaker, &Exhaustive),
+            vcg_auction_with_solver(
+                &items,
+                &bids,
+                tiebreaker,
+                &branch_and_bound
+            ),
+        );
+    }
+}
+
+#[test]
+fn branch_and_bound_matches_the_auction() {
+    let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("table", 1)])],
+    ];
+    let solver = BranchAndBound::default();
+    let result = vcg_auction_with_solver(&items, &bids, |_| 0, &solver);
+    assert_eq!(
+        result.ok(),
+        vcg_auction_with_tiebreaker(&items, &bids, |_| 0)
+    );
+    // winner determination, then a payment search for each winner, each
+    // searching the chairs and the table apart, except without Carol's table
+    let stats = solver.stats();
+    assert_eq!(stats.winner_determination.searches, 2);
+    assert_eq!(stats.payments.searches, 5);
+}
+
+#[test]
+fn branch_and_bound_errors() {
+    let items = vec![("chair".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+    ];
+    let options = SearchOptions {
+        max_nodes: Some(0),
+        ..Default::default()
+    };
+    let solver = BranchAndBound::new(&options);
+    assert!(matches!(
+        vcg_auction_with_solver(&items, &bids, |_| 0, &solver),
+        Err(AuctionError::ResourceLimit(_))
+    ));
+}
@@ -0,0 +1,76 @@
+//! Differential tests of the branch-and-bound search against the brute-force
+//! reference solver, on random small auctions.
+
+#![cfg(all(feature = "test-utils", feature = "rand"))]
+
+mod common;
+
+use std::cell::RefCell;
+
+use pretty_assertions::assert_eq;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_with_solver, vcg_auction_with_tiebreaker,
+    AuctionInstance, BruteForce, InstanceShape,
+};
+
+use common::{irregular_auction, recording_tiebreaker, tied_shape};
+
+/// Random auctions with bids on up to three items, some of them irregular.
+fn shape(bidders: usize) -> InstanceShape {
+    InstanceShape {
+        max_bidders: bidders,
+        max_bid_sets: 6,
+        max_items_per_bid: 3,
+        max_value: 4,
+        ..tied_shape()
+    }
+}
+
+#[test]
+fn search_matches_brute_force() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..300 {
+        let AuctionInstance {
+            items,
+            bid_sets: bids,
+        } = irregular_auction(&mut rng, &shape(8));
+        let pick = rng.gen::<usize>();
+        let search_ties = RefCell::new(vec![]);
+        let brute_force_ties = RefCell::new(vec![]);
+        let search = vcg_auction_with_tiebreaker(
+            &items,
+            &bids,
+            recording_tiebreaker(&search_ties, pick),
+        );
+        let brute_force = vcg_auction_with_solver(
+            &items,
+            &bids,
+            recording_tiebreaker(&brute_force_ties, pick),
+            &BruteForce,
+        )
+        .ok();
+        assert_eq!(search, brute_force);
+        assert_eq!(search_ties, brute_force_ties);
+    }
+}
+
+#[test]
+fn search_matches_brute_force_with_repeat_bidders() {
+    let mut rng = StdRng::seed_from_u64(1);
+    for _ in 0..300 {
+        // few bidders, so most have bids in several bid sets
+        let AuctionInstance {
+            items,
+            bid_sets: bids,
+        } = irregular_auction(&mut rng, &shape(2));
+        let pick = rng.gen::<usize>();
+        let tiebreaker = |tied: &[Vec<&SimpleBid>]| pick % tied.len();
+        assert_eq!(
+            vcg_auction_with_tiebreaker(&items, &bids, tiebreaker),
+            vcg_auction_with_solver(&items, &bids, tiebreaker, &BruteForce)
+                .ok(),
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! Tests for the catalog of attribute-tagged items.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    catalog::{Catalog, CatalogError, Pattern},
+    vcg_auction_with_tiebreaker,
+};
+
+fn furniture() -> Catalog {
+    let mut catalog = Catalog::new();
+    catalog
+        .item("oak-chair", 2, [("type", "chair"), ("color", "brown")])
+        .item("blue-chair", 1, [("type", "chair"), ("color", "blue")])
+        .item("navy-chair", 2, [("type", "chair"), ("color", "blue")])
+        .item("blue-table", 1, [("type", "table"), ("color", "blue")]);
+    catalog
+}
+
+fn items(bid: &vcg_auction::catalog::CatalogBid) -> Vec<(&str, u64)> {
+    bid.items
+        .iter()
+        .map(|(item, q)| (item.as_str(), *q))
+        .collect()
+}
+
+#[test]
+fn patterns_ground_to_every_way_of_taking_them() {
+    let catalog = furniture();
+    let blue_chairs = Pattern::new(2, [("type", "chair"), ("color", "blue")]);
+    assert_eq!(
+        catalog
+            .matching(&blue_chairs)
+            .map(|item| item.id.as_str())
+            .collect::<Vec<_>>(),
+        ["blue-chair", "navy-chair"]
+    );
+    let bid_set = catalog.bid_set("Alice", 30, [blue_chairs]).unwrap();
+    assert_eq!(
+        bid_set.iter().map(items).collect::<Vec<_>>(),
+        [
+            vec![("blue-chair", 1), ("navy-chair", 1)],
+            vec![("navy-chair", 2)],
+        ]
+    );
+    assert!(bid_set
+        .iter()
+        .all(|bid| bid.name == "Alice" && bid.value == 30));
+}
+
+#[test]
+fn overlapping_patterns_share_the_stock() {
+    let catalog = furniture();
+    let bid_set = catalog
+        .bid_set(
+            "Alice",
+            40,
+            [
+                Pattern::new(1, [("color", "blue")]),
+                Pattern::new(2, [("type", "chair")]),
+            ],
+        )
+        .unwrap();
+    // no alternative takes more of an item than is in stock, or repeats
+    for bid in &bid_set {
+        for (item, quantity) in &bid.items {
+            let entry = catalog.entries().iter().find(|e| e.id == *item);
+            assert!(*quantity <= entry.unwrap().quantity);
+        }
+        assert_eq!(bid_set.iter().filter(|b| *b == bid).count(), 1);
+    }
+    assert!(bid_set
+        .iter()
+        .any(|bid| items(bid) == [("oak-chair", 2), ("blue-table", 1)]));
+    assert!(bid_set
+        .iter()
+        .any(|bid| items(bid) == [("blue-chair", 1), ("navy-chair", 2)]));
+}
+
+#[test]
+fn grounded_bid_sets_run_as_exclusive_bids() {
+    let catalog = furniture();
+    let chair = |n| Pattern::new(n, [("type", "chair")]);
+    let bids = vec![
+        catalog.bid_set("Alice", 30, [chair(4)]).unwrap(),
+        catalog.bid_set("Bob", 12, [chair(1)]).unwrap(),
+        catalog.bid_set("Carol", 10, [chair(1)]).unwrap(),
+    ];
+    let items = catalog.items();
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    // five chairs fit Alice and Bob but not Carol, who sets both their prices
+    let winners = result
+        .winning_bids
+        .iter()
+        .map(|bid| bid.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(winners, ["Alice", "Bob"]);
+    assert_eq!(
+        result.payments,
+        [(&"Alice".to_string(), 10), (&"Bob".to_string(), 10)]
+    );
+}
+
+#[test]
+fn patterns_that_cant_be_taken_are_errors() {
+    let catalog = furniture();
+    assert_eq!(
+        catalog.bid_set("Alice", 5, [Pattern::new(2, [("color", "red")])]),
+        Err(CatalogError::NotEnoughStock {
+            pattern: 0,
+            requested: 2,
+            available: 0,
+        })
+    );
+    let table = Pattern::new(1, [("type", "table")]);
+    assert_eq!(
+        catalog.bid_set("Alice", 5, [table.clone(), table]),
+        Err(CatalogError::NoAlternatives)
+    );
+
+    let mut catalog = furniture();
+    catalog.max_alternatives(1);
+    assert_eq!(
+        catalog.bid_set("Alice", 5, [Pattern::new(2, [("color", "blue")])]),
+        Err(CatalogError::TooManyAlternatives { limit: 1 })
+    );
+}
@@ -0,0 +1,146 @@
+//! Tests for the ascending clinching auction.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    clinching_auction, types::SimpleBid, vcg_auction_with_tiebreaker,
+    ClinchingAuction, ClinchingError,
+};
+
+/// Units won and payment of each bidder of a sealed VCG auction, with each
+/// bidder's values for extra units as one bid set.
+fn sealed_outcome(
+    supply: u64,
+    bidders: &[(&str, Vec<u64>)],
+) -> Vec<(String, u64, u64)> {
+    let items = vec![("unit".to_string(), supply)];
+    let bid_sets = bidders
+        .iter()
+        .map(|(name, values)| {
+            (1..=values.len())
+                .map(|units| {
+                    let value = values[..units].iter().sum::<u64>();
+                    SimpleBid::new(*name, value, [("unit", units as u64)])
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let result = vcg_auction_with_tiebreaker(&items, &bid_sets, |_| 0).unwrap();
+    bidders
+        .iter()
+        .map(|(name, _)| {
+            let name = name.to_string();
+            let units = result
+                .winning_bids
+                .iter()
+                .filter(|bid| bid.name == name)
+                .map(|bid| bid.items[0].1)
+                .sum();
+            let payment = result.payment_for(&name).copied().unwrap_or(0);
+            (name, units, payment)
+        })
+        .collect()
+}
+
+fn clinching_outcome(
+    supply: u64,
+    bidders: &[(&str, Vec<u64>)],
+) -> Vec<(String, u64, u64)> {
+    clinching_auction(supply, bidders, 1)
+        .unwrap()
+        .bidders()
+        .iter()
+        .map(|b| (b.name.to_string(), b.clinched, b.payment))
+        .collect()
+}
+
+#[test]
+fn ausubel_example_matches_vcg() {
+    // A and C clinch units as the others drop out, until the demand fits at 85
+    let bidders = [
+        ("A", vec![123, 113, 103]),
+        ("B", vec![75, 5]),
+        ("C", vec![125, 125, 49]),
+        ("D", vec![85, 65]),
+        ("E", vec![45, 25]),
+    ];
+    let outcome = clinching_outcome(5, &bidders);
+    assert_eq!(outcome, sealed_outcome(5, &bidders));
+    let won = outcome
+        .iter()
+        .map(|(_, units, payment)| (*units, *payment))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        won,
+        [(3, 65 + 75 + 85), (0, 0), (2, 75 + 85), (0, 0), (0, 0)]
+    );
+}
+
+#[test]
+fn diminishing_values_match_vcg() {
+    // distinct values, so the sealed auction has no ties
+    let mut next = 7u64;
+    let mut value = || {
+        next = (next * 37 + 11) % 1009;
+        next
+    };
+    for bidder_count in 1..5 {
+        for supply in 1..6 {
+            let names = ["A", "B", "C", "D"];
+            let bidders = names[..bidder_count]
+                .iter()
+                .map(|name| {
+                    let mut values =
+                        (0..3).map(|_| value()).collect::<Vec<_>>();
+                    values.sort_unstable_by(|a, b| b.cmp(a));
+                    (*name, values)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                clinching_outcome(supply, &bidders),
+                sealed_outcome(supply, &bidders),
+                "{bidders:?} for {supply} units"
+            );
+        }
+    }
+}
+
+#[test]
+fn clinched_units_are_kept_when_demand_falls() {
+    let mut auction = ClinchingAuction::new(3, 0, ["Alice", "Bob"]);
+    auction.round(&[2, 2]).unwrap();
+    auction.raise_price(10).unwrap();
+    auction.round(&[0, 0]).unwrap();
+    assert!(!auction.is_open());
+    let bidders = auction.bidders();
+    assert_eq!((bidders[0].clinched, bidders[1].clinched), (1, 1));
+    assert_eq!(auction.unsold(), 1);
+    assert_eq!(auction.clinches().len(), 2);
+}
+
+#[test]
+fn rounds_follow_the_rules() {
+    let mut auction = ClinchingAuction::new(1, 0, ["Alice", "Bob"]);
+    assert_eq!(
+        auction.round(&[1]),
+        Err(ClinchingError::WrongBidderCount {
+            expected: 2,
+            found: 1
+        })
+    );
+    auction.round(&[1, 1]).unwrap();
+    assert_eq!(auction.round(&[1, 1]), Err(ClinchingError::PriceNotRaised));
+    assert_eq!(auction.raise_price(0), Err(ClinchingError::PriceNotRaised));
+    auction.raise_price(5).unwrap();
+    assert_eq!(
+        auction.round(&[2, 1]),
+        Err(ClinchingError::DemandIncreased { bidder: 0 })
+    );
+    auction.round(&[1, 0]).unwrap();
+    assert_eq!(auction.raise_price(6), Err(ClinchingError::Closed));
+    assert_eq!(auction.bidders()[0].payment, 5);
+    assert_eq!(
+        clinching_auction(1, &[("Alice", vec![1])], 0).map(|_| ()),
+        Err(ClinchingError::PriceNotRaised)
+    );
+}
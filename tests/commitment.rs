@@ -0,0 +1,107 @@
+//! Tests for the canonical encoding of auction inputs.
+
+#![cfg(feature = "commitment")]
+
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    commitment_hash, encode_instance, types::SimpleBid, AuctionConfig,
+    Objective, PricingRule, Tiebreak,
+};
+
+fn auction() -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = vec![("chair".into(), 2), ("table".into(), 1)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1), ("table", 1)]),
+            SimpleBid::new("Alice", 3, [("chair", 1)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("table", 1)])],
+    ];
+    (items, bids)
+}
+
+#[test]
+fn encoding_layout() {
+    let items = vec![("a".to_string(), 1u64)];
+    let bids = vec![vec![SimpleBid::new("b", 2, [("a", 1)])]];
+    let config = AuctionConfig::deterministic();
+    let mut expected = b"vcg-auction/instance/v1".to_vec();
+    // one item "a" with quantity 1
+    expected.extend([0, 0, 0, 0, 0, 0, 0, 1]);
+    expected.extend([0, 0, 0, 0, 0, 0, 0, 1, b'a', 0, 0, 0, 0, 0, 0, 0, 1]);
+    // one bid set of one bid from "b" with value 2 on item "a"
+    expected.extend([0, 0, 0, 0, 0, 0, 0, 1]);
+    expected.extend([0, 0, 0, 0, 0, 0, 0, 1]);
+    expected.extend([0, 0, 0, 0, 0, 0, 0, 1, b'b', 0, 0, 0, 0, 0, 0, 0, 2]);
+    expected.extend([0, 0, 0, 0, 0, 0, 0, 1]);
+    expected.extend([0, 0, 0, 0, 0, 0, 0, 1, b'a', 0, 0, 0, 0, 0, 0, 0, 1]);
+    // VCG pricing, first tiebreak, no limits, merged duplicate items, no
+    // objectives
+    expected.extend([0, 2, 0, 0, 0, 0, 0]);
+    expected.extend([0, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(encode_instance(&items, &bids, &config), expected);
+    // the SHA-256 of the encoding, as computed by other implementations
+    let hash = commitment_hash(&items, &bids, &config);
+    let hex = hash.iter().map(|b| format!("{b:02x}")).collect::<String>();
+    assert_eq!(
+        hex,
+        "b556ce7ea42848ff72363d87fb5ec3ac44b57a594a02a8f5c47876399aa3c3c7"
+    );
+}
+
+#[test]
+fn item_order_is_ignored() {
+    let (items, bids) = auction();
+    let config = AuctionConfig::deterministic();
+    let expected = commitment_hash(&items, &bids, &config);
+    let reversed = items.iter().rev().cloned().collect::<Vec<_>>();
+    let map = items.iter().cloned().collect::<HashMap<_, _>>();
+    assert_eq!(commitment_hash(&reversed, &bids, &config), expected);
+    assert_eq!(commitment_hash(&map, &bids, &config), expected);
+    let mut bids_reordered_items = bids.clone();
+    bids_reordered_items[0][0].items.reverse();
+    assert_eq!(
+        commitment_hash(&items, &bids_reordered_items, &config),
+        expected
+    );
+}
+
+#[test]
+fn outcome_changes_are_committed() {
+    let (items, bids) = auction();
+    let config = AuctionConfig::deterministic();
+    let expected = commitment_hash(&items, &bids, &config);
+
+    let mut changed_bids = bids.clone();
+    changed_bids[0].reverse();
+    assert_ne!(commitment_hash(&items, &changed_bids, &config), expected);
+    let mut changed_bids = bids.clone();
+    changed_bids[1][0].value += 1;
+    assert_ne!(commitment_hash(&items, &changed_bids, &config), expected);
+    // moving a bid between bid sets
+    let moved = vec![
+        vec![bids[0][0].clone()],
+        vec![bids[0][1].clone(), bids[1][0].clone()],
+    ];
+    assert_ne!(commitment_hash(&items, &moved, &config), expected);
+
+    let first_price = AuctionConfig::builder()
+        .tiebreak(Tiebreak::First)
+        .pricing(PricingRule::FirstPrice)
+        .build();
+    assert_ne!(commitment_hash(&items, &bids, &first_price), expected);
+    let limited = AuctionConfig::builder()
+        .tiebreak(Tiebreak::First)
+        .max_nodes(100)
+        .build();
+    assert_ne!(commitment_hash(&items, &bids, &limited), expected);
+    let objectives = [Objective::MinConcentration];
+    let with_objectives = AuctionConfig::builder()
+        .tiebreak(Tiebreak::First)
+        .objectives(&objectives)
+        .build();
+    assert_ne!(commitment_hash(&items, &bids, &with_objectives), expected);
+}
@@ -0,0 +1,105 @@
+//! Fixtures shared by the differential tests, which run random auctions two
+//! ways and compare the results, including the order of tied outcomes given
+//! to the tiebreaker.
+
+// each test crate uses only some of the fixtures
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+
+#[cfg(feature = "rand")]
+use rand::{distributions::Distribution, rngs::StdRng, Rng};
+
+use vcg_auction::{
+    run_with_stats, types::SimpleBid, AuctionConfig, AuctionConfigBuilder,
+    AuctionResult, Tiebreak,
+};
+#[cfg(feature = "rand")]
+use vcg_auction::{AuctionInstance, InstanceShape};
+
+/// Random auctions on up to three items, with a small range of values so
+/// that many outcomes are tied. Bids are on one item each, and some bidders
+/// have several bid sets.
+#[cfg(feature = "rand")]
+pub fn tied_shape() -> InstanceShape {
+    InstanceShape {
+        max_items: 3,
+        max_stock: 3,
+        max_bidders: 7,
+        max_bid_sets: 7,
+        max_bids_per_set: 3,
+        max_items_per_bid: 1,
+        max_quantity: 2,
+        max_value: 3,
+    }
+}
+
+/// A random auction of a shape, with some bids made irregular: on an item
+/// that isn't in stock, for none of an item, or listing an item twice.
+#[cfg(feature = "rand")]
+pub fn irregular_auction(
+    rng: &mut StdRng,
+    shape: &InstanceShape,
+) -> AuctionInstance {
+    let mut auction = shape.sample(rng);
+    for bid in auction.bid_sets.iter_mut().flatten() {
+        match rng.gen_range(0..8) {
+            0 => bid.items.push(("unstocked".into(), 1)),
+            1 => bid.items.push(("item0".into(), 0)),
+            2 => bid.items.extend(bid.items.first().cloned()),
+            _ => {}
+        }
+    }
+    auction
+}
+
+/// Tiebreaker that records the tied outcomes it was given, choosing the one
+/// at `pick` modulo their number.
+pub fn recording_tiebreaker(
+    ties: &RefCell<Vec<Vec<SimpleBid>>>,
+    pick: usize,
+) -> impl Fn(&[Vec<&SimpleBid>]) -> usize + '_ {
+    move |options| {
+        *ties.borrow_mut() = options
+            .iter()
+            .map(|bs| bs.iter().map(|b| (*b).clone()).collect())
+            .collect();
+        pick % options.len()
+    }
+}
+
+/// Winning bids and payments, owned so results of separate runs can be
+/// compared.
+pub type Owned = (Vec<SimpleBid>, Vec<(String, u64)>);
+
+/// Copy a result into an [`Owned`] one.
+pub fn owned(result: AuctionResult<SimpleBid>) -> Owned {
+    let winning_bids =
+        result.winning_bids.iter().map(|b| (*b).clone()).collect();
+    let payments = result
+        .payments
+        .iter()
+        .map(|(name, payment)| (name.to_string(), *payment))
+        .collect();
+    (winning_bids, payments)
+}
+
+/// Run an auction with a config, returning the result and the tied outcomes
+/// given to the tiebreaker, which chooses the one at `pick`.
+pub fn run_recording_ties(
+    items: &[(String, u64)],
+    bids: &[Vec<SimpleBid>],
+    pick: usize,
+    configure: impl for<'c> FnOnce(
+        AuctionConfigBuilder<'c, SimpleBid>,
+    ) -> AuctionConfigBuilder<'c, SimpleBid>,
+) -> (Option<Owned>, Vec<Vec<SimpleBid>>) {
+    let ties = RefCell::new(vec![]);
+    let tiebreaker = recording_tiebreaker(&ties, pick);
+    let builder =
+        AuctionConfig::builder().tiebreak(Tiebreak::Custom(&tiebreaker));
+    let config = configure(builder).build();
+    let (outcome, _) = run_with_stats(items, bids, &config);
+    let result = outcome.unwrap().complete().map(owned);
+    (result, ties.take())
+}
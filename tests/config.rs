@@ -0,0 +1,272 @@
+//! Tests for running auctions from an `AuctionConfig`.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_with_tiebreaker, AuctionConfig, AuctionError,
+    AuctionOutcome, DuplicateItems, InputError, PricingRule, ResultOrder,
+    SearchOptions, SearchStrategy, Tiebreak,
+};
+
+fn tied_bids() -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = vec![("chair".into(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 10, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 4, [("chair", 1)])],
+    ];
+    (items, bids)
+}
+
+#[test]
+fn default_config_is_vcg() {
+    let (items, bids) = tied_bids();
+    let config = AuctionConfig::builder().tiebreak(Tiebreak::First).build();
+    let outcome = vcg_auction::run(&items, &bids, &config).unwrap();
+    assert_eq!(
+        outcome.complete(),
+        vcg_auction_with_tiebreaker(&items, &bids, |_| 0)
+    );
+}
+
+#[test]
+fn custom_tiebreak() {
+    let (items, bids) = tied_bids();
+    let pick_last = |tied: &[Vec<&SimpleBid>]| tied.len() - 1;
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::Custom(&pick_last))
+        .build();
+    let result = vcg_auction::run(&items, &bids, &config)
+        .unwrap()
+        .complete()
+        .unwrap();
+    assert_eq!(result.winning_bids, [&bids[1][0]]);
+    assert_eq!(result.payments, [(&"Bob".into(), 10)]);
+}
+
+#[test]
+fn first_price_pricing() {
+    let (items, bids) = tied_bids();
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::First)
+        .pricing(PricingRule::FirstPrice)
+        .build();
+    let result = vcg_auction::run(&items, &bids, &config)
+        .unwrap()
+        .complete()
+        .unwrap();
+    assert_eq!(result.payments, [(&"Alice".into(), 10)]);
+}
+
+#[test]
+fn first_rejected_bid_pricing() {
+    let items = vec![("chair".into(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 4, [("chair", 2)])],
+    ];
+    let config = AuctionConfig::builder()
+        .pricing(PricingRule::FirstRejectedBid)
+        .build();
+    let result = vcg_auction::run(&items, &bids, &config)
+        .unwrap()
+        .complete()
+        .unwrap();
+    assert_eq!(result.payments, [(&"Alice".into(), 7)]);
+}
+
+#[test]
+fn duplicate_items_are_merged() {
+    let items = vec![
+        ("chair".into(), 1),
+        ("table".into(), 1),
+        ("chair".into(), 1),
+    ];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 2)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1), ("table", 1)])],
+    ];
+    // both chairs are for sale, so Alice wins them
+    let config = AuctionConfig::deterministic();
+    let result = vcg_auction::run(&items, &bids, &config)
+        .unwrap()
+        .complete()
+        .unwrap();
+    assert_eq!(result.winning_bids, [&bids[0][0]]);
+    assert_eq!(result.payments, [(&"Alice".into(), 7)]);
+
+    let config = AuctionConfig::builder()
+        .duplicate_items(DuplicateItems::Reject)
+        .build();
+    assert_eq!(
+        vcg_auction::run(&items, &bids, &config).err(),
+        Some(AuctionError::Input(InputError::DuplicateStockItem(2)))
+    );
+}
+
+#[test]
+fn results_in_input_order() {
+    let items = vec![("chair".into(), 2), ("table".into(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Carol", 2, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 3, [("chair", 1)])],
+        vec![SimpleBid::new("Alice", 9, [("table", 1)])],
+        vec![SimpleBid::new("Dave", 1, [("chair", 1)])],
+    ];
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::First)
+        .result_order(ResultOrder::Input)
+        .build();
+    let result = vcg_auction::run(&items, &bids, &config)
+        .unwrap()
+        .complete()
+        .unwrap();
+    assert_eq!(result.winning_bids, [&bids[0][0], &bids[1][0], &bids[2][0]]);
+    assert_eq!(
+        result.payments,
+        [
+            (&"Carol".into(), 1),
+            (&"Bob".into(), 1),
+            (&"Alice".into(), 0)
+        ]
+    );
+    let config = AuctionConfig::builder().tiebreak(Tiebreak::First).build();
+    let searched = vcg_auction::run(&items, &bids, &config)
+        .unwrap()
+        .complete()
+        .unwrap();
+    assert_eq!(searched.sorted(), result.sorted());
+}
+
+#[test]
+fn search_options() {
+    let (items, bids) = tied_bids();
+    let config = AuctionConfig::builder()
+        .search(SearchOptions {
+            max_duration: Some(Duration::from_secs(60)),
+            ..Default::default()
+        })
+        .tiebreak(Tiebreak::First)
+        .build();
+    assert!(matches!(
+        vcg_auction::run(&items, &bids, &config),
+        Ok(AuctionOutcome::Complete(_))
+    ));
+}
+
+#[test]
+fn checked_invariants_keep_result() {
+    let (items, bids) = tied_bids();
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::First)
+        .check_invariants()
+        .build();
+    let outcome = vcg_auction::run(&items, &bids, &config).unwrap();
+    assert_eq!(
+        outcome.complete(),
+        vcg_auction_with_tiebreaker(&items, &bids, |_| 0)
+    );
+}
+
+#[test]
+fn verified_payments_keep_result() {
+    let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 9, [("chair", 2), ("table", 1)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 6, [("table", 1)])],
+        vec![SimpleBid::new("Dave", 3, [("chair", 1), ("table", 1)])],
+    ];
+    for strategy in [SearchStrategy::DepthFirst, SearchStrategy::BestFirst] {
+        let config = AuctionConfig::builder()
+            .search(SearchOptions {
+                strategy,
+                ..Default::default()
+            })
+            .tiebreak(Tiebreak::First)
+            .verify_payments()
+            .build();
+        let outcome = vcg_auction::run(&items, &bids, &config).unwrap();
+        assert_eq!(
+            outcome.complete(),
+            vcg_auction_with_tiebreaker(&items, &bids, |_| 0)
+        );
+    }
+}
+
+#[test]
+fn deterministic_results_ignore_item_order() {
+    let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+    let reversed = items.iter().rev().cloned().collect::<Vec<_>>();
+    let map = items.iter().cloned().collect::<HashMap<_, _>>();
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 6, [("chair", 1), ("table", 1)])],
+        vec![SimpleBid::new("Bob", 6, [("table", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("chair", 2)])],
+    ];
+    let config = AuctionConfig::deterministic();
+    let expected = vcg_auction::run(&items, &bids, &config).unwrap();
+    assert_eq!(
+        vcg_auction::run(&reversed, &bids, &config),
+        Ok(expected.clone())
+    );
+    assert_eq!(vcg_auction::run(&map, &bids, &config), Ok(expected.clone()));
+    let result = expected.complete().unwrap();
+    assert_eq!(result.winning_bids, [&bids[1][0], &bids[2][0]]);
+}
+
+#[cfg(feature = "rng")]
+#[test]
+fn seeded_tiebreak() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let (items, bids) = tied_bids();
+    for seed in 0..10 {
+        let config = AuctionConfig::builder()
+            .tiebreak(Tiebreak::Seeded(seed))
+            .build();
+        let result =
+            vcg_auction::run(&items, &bids, &config).unwrap().complete();
+        let mut rng = StdRng::seed_from_u64(seed);
+        assert_eq!(
+            result,
+            vcg_auction::vcg_auction_with_rng(&items, &bids, &mut rng)
+        );
+    }
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn shared_rng_reproduces_runs() {
+    use vcg_auction::AuctionRng;
+
+    let (items, bids) = tied_bids();
+    let winners = |seed| {
+        let rng = AuctionRng::seed_from_u64(seed);
+        let config = AuctionConfig::builder()
+            .tiebreak(Tiebreak::Random)
+            .rng(&rng)
+            .build();
+        (0..20)
+            .map(|_| {
+                let result = vcg_auction::run(&items, &bids, &config)
+                    .unwrap()
+                    .complete()
+                    .unwrap();
+                result.winning_bids[0].name.clone()
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(winners(3), winners(3));
+    // both tied bidders win some runs
+    let winners = winners(3);
+    assert!(winners.contains(&"Alice".to_string()));
+    assert!(winners.contains(&"Bob".to_string()));
+}
@@ -0,0 +1,66 @@
+//! Tests that bounding the search by cliques of conflicting bid sets doesn't
+//! change the results, including the order of tied outcomes given to the
+//! tiebreaker.
+
+#[cfg(all(feature = "test-utils", feature = "rand"))]
+mod common;
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{run_with_stats, types::SimpleBid, AuctionConfig};
+
+#[cfg(all(feature = "test-utils", feature = "rand"))]
+#[test]
+fn cliques_match_brute_force() {
+    use std::cell::RefCell;
+
+    use rand::{distributions::Distribution, rngs::StdRng, Rng, SeedableRng};
+    use vcg_auction::{
+        vcg_auction_with_solver, AuctionInstance, BruteForce, InstanceShape,
+    };
+
+    use common::{owned, recording_tiebreaker, run_recording_ties, tied_shape};
+
+    let shape = InstanceShape {
+        max_items: 2,
+        max_bids_per_set: 2,
+        ..tied_shape()
+    };
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..300 {
+        let AuctionInstance {
+            items,
+            bid_sets: bids,
+        } = shape.sample(&mut rng);
+        let pick = rng.gen::<usize>();
+        // the brute-force solver tries every combination, unbounded
+        let ties = RefCell::new(vec![]);
+        let tiebreaker = recording_tiebreaker(&ties, pick);
+        let unbounded =
+            vcg_auction_with_solver(&items, &bids, tiebreaker, &BruteForce);
+        let unbounded = (unbounded.ok().map(owned), ties.take());
+        assert_eq!(run_recording_ties(&items, &bids, pick, |c| c), unbounded);
+    }
+}
+
+#[test]
+fn cliques_bound_competing_bids() {
+    // every bidder wants the only chair, so the bid sets form one clique
+    // that can add at most the highest bid
+    let items = vec![("chair".to_string(), 1)];
+    let bids = (0..12)
+        .map(|bidder| {
+            let value = 30 - bidder;
+            vec![SimpleBid::new(bidder.to_string(), value, [("chair", 1)])]
+        })
+        .collect::<Vec<_>>();
+    let (outcome, stats) =
+        run_with_stats(&items, &bids, &AuctionConfig::deterministic());
+    let result = outcome.unwrap().complete().unwrap();
+    assert_eq!(result.winning_bids, [&bids[0][0]]);
+    assert_eq!(result.payments, [(&"0".to_string(), 29)]);
+    // once the first bid is taken, every branch without it is cut by the
+    // clique's bound of 29. Bounded by the sum of the bids left instead, the
+    // search explores 155 nodes.
+    assert_eq!(stats.winner_determination.nodes, 25);
+}
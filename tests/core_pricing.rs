@@ -0,0 +1,379 @@
+//! Tests for minimum-revenue core payments.
+
+#![cfg(feature = "lp")]
+
+use std::{
+    cmp::Ordering,
+    ops::{Add, Sub},
+};
+
+use num_traits::{FromPrimitive, ToPrimitive, Zero};
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    core_auction, types::SimpleBid, vcg_auction_with_tiebreaker, Bid,
+    CoreResult, CoreSelection,
+};
+
+/// Type wrapper for a float to implement [`Ord`], and the conversions core
+/// payments are solved with.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct FloatValue(f64);
+
+impl Eq for FloatValue {}
+impl Ord for FloatValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+impl PartialOrd for FloatValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Zero for FloatValue {
+    fn zero() -> Self {
+        Self(0.0)
+    }
+    fn is_zero(&self) -> bool {
+        self.0 == 0.0
+    }
+}
+impl Add for FloatValue {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+impl Sub for FloatValue {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+impl ToPrimitive for FloatValue {
+    fn to_i64(&self) -> Option<i64> {
+        self.0.to_i64()
+    }
+    fn to_u64(&self) -> Option<u64> {
+        self.0.to_u64()
+    }
+    fn to_f64(&self) -> Option<f64> {
+        Some(self.0)
+    }
+}
+impl FromPrimitive for FloatValue {
+    fn from_i64(n: i64) -> Option<Self> {
+        n.to_f64().map(Self)
+    }
+    fn from_u64(n: u64) -> Option<Self> {
+        n.to_f64().map(Self)
+    }
+    fn from_f64(n: f64) -> Option<Self> {
+        Some(Self(n))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct FloatBid {
+    name: String,
+    value: FloatValue,
+    items: Vec<(String, u64)>,
+}
+
+impl Bid for FloatBid {
+    type Name = String;
+    type Value = FloatValue;
+    type Item = String;
+    type Quantity = u64;
+
+    fn bidder_name(&self) -> &String {
+        &self.name
+    }
+    fn bid_value(&self) -> &FloatValue {
+        &self.value
+    }
+    fn bid_items(&self) -> &[(String, u64)] {
+        &self.items
+    }
+}
+
+/// Core payments of an auction of bids from separate bidders with values in
+/// cents, made with float values and with whole cents, which have the same
+/// winners.
+fn float_and_cent_payments(
+    bids: &[(f64, &[&str])],
+    selection: CoreSelection,
+) -> (Vec<f64>, Vec<u64>) {
+    let items = ["a", "b", "c"].map(|item| (item.to_string(), 1)).to_vec();
+    let float_bids = bids
+        .iter()
+        .enumerate()
+        .map(|(i, (value, items))| {
+            vec![FloatBid {
+                name: format!("b{i}"),
+                value: FloatValue(*value),
+                items: items.iter().map(|item| (item.to_string(), 1)).collect(),
+            }]
+        })
+        .collect::<Vec<_>>();
+    let cent_bids = bids
+        .iter()
+        .enumerate()
+        .map(|(i, (value, items))| {
+            let cents = (value * 100.0).round() as u64;
+            let items = items.iter().map(|item| (*item, 1));
+            vec![SimpleBid::new(format!("b{i}"), cents, items)]
+        })
+        .collect::<Vec<_>>();
+    let float_core = core_auction(&items, &float_bids, |_| 0, selection)
+        .expect("float payments settle");
+    let cent_core = core_auction(&items, &cent_bids, |_| 0, selection).unwrap();
+    assert_float_in_core(&items, &float_bids, &float_core);
+    assert_in_core(&items, &cent_bids, &cent_core);
+    let float_winners = float_core.result.payments.iter().map(|(n, _)| *n);
+    let cent_winners = cent_core.result.payments.iter().map(|(n, _)| *n);
+    assert!(float_winners.eq(cent_winners));
+    (
+        float_core
+            .result
+            .payments
+            .iter()
+            .map(|(_, p)| p.0)
+            .collect(),
+        cent_core.result.payments.iter().map(|(_, p)| *p).collect(),
+    )
+}
+
+/// Check the payments are within their bounds, and that no coalition of
+/// bidders could offer the seller more than the winners pay.
+fn assert_in_core(
+    items: &[(String, u64)],
+    bid_sets: &[Vec<SimpleBid>],
+    core: &CoreResult<SimpleBid>,
+) {
+    let bid_of = |name: &String| -> u64 {
+        let bids = core.result.winning_bids.iter().filter(|b| b.name == *name);
+        bids.map(|b| b.value).sum()
+    };
+    for ((name, payment), (_, vcg)) in
+        core.result.payments.iter().zip(&core.vcg_payments)
+    {
+        assert!(vcg <= payment && *payment <= bid_of(name));
+    }
+    let bidders = bid_sets.iter().map(|set| &set[0].name).collect::<Vec<_>>();
+    for coalition in 0..1u32 << bidders.len() {
+        let member = |name: &String| {
+            let at = bidders.iter().position(|b| *b == name).unwrap();
+            coalition & 1 << at != 0
+        };
+        let sets = bid_sets.iter().filter(|set| member(&set[0].name));
+        let value = vcg_auction_with_tiebreaker(items, sets, |_| 0)
+            .unwrap()
+            .winning_bids
+            .iter()
+            .map(|b| b.value)
+            .sum::<u64>();
+        // winners in the coalition give up their winning bids
+        let (inside, outside): (Vec<_>, Vec<_>) = core
+            .result
+            .payments
+            .iter()
+            .partition(|(name, _)| member(name));
+        let given_up = inside.iter().map(|(name, _)| bid_of(name)).sum::<u64>();
+        let paid = outside.iter().map(|(_, payment)| payment).sum::<u64>();
+        assert!(
+            paid + given_up >= value,
+            "coalition {coalition:b} of {bid_sets:?} blocks {core:?}"
+        );
+    }
+}
+
+/// Check float payments are within their bounds, and that no coalition of
+/// bidders could offer the seller more than the winners pay, beyond rounding
+/// far finer than the solver's.
+fn assert_float_in_core(
+    items: &[(String, u64)],
+    bid_sets: &[Vec<FloatBid>],
+    core: &CoreResult<FloatBid>,
+) {
+    let bid_of = |name: &String| -> f64 {
+        let bids = core.result.winning_bids.iter().filter(|b| b.name == *name);
+        bids.map(|b| b.value.0).sum()
+    };
+    for ((name, payment), (_, vcg)) in
+        core.result.payments.iter().zip(&core.vcg_payments)
+    {
+        let tolerance = 1e-12 * bid_of(name);
+        assert!(vcg.0 - tolerance <= payment.0);
+        assert!(payment.0 <= bid_of(name) + tolerance);
+    }
+    let bidders = bid_sets.iter().map(|set| &set[0].name).collect::<Vec<_>>();
+    for coalition in 0..1u32 << bidders.len() {
+        let member = |name: &String| {
+            let at = bidders.iter().position(|b| *b == name).unwrap();
+            coalition & 1 << at != 0
+        };
+        let sets = bid_sets.iter().filter(|set| member(&set[0].name));
+        let value = vcg_auction_with_tiebreaker(items, sets, |_| 0)
+            .unwrap()
+            .winning_bids
+            .iter()
+            .map(|b| b.value.0)
+            .sum::<f64>();
+        let (inside, outside): (Vec<_>, Vec<_>) = core
+            .result
+            .payments
+            .iter()
+            .partition(|(name, _)| member(name));
+        let given_up = inside.iter().map(|(name, _)| bid_of(name)).sum::<f64>();
+        let paid = outside.iter().map(|(_, payment)| payment.0).sum::<f64>();
+        assert!(
+            paid + given_up >= value * (1.0 - 1e-12),
+            "coalition {coalition:b} of {bid_sets:?} blocks {core:?}"
+        );
+    }
+}
+
+#[test]
+fn vcg_payments_in_the_core_are_kept() {
+    let items = vec![("chair".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+    ];
+    let core =
+        core_auction(&items, &bids, |_| 0, CoreSelection::MinimumRevenue)
+            .unwrap();
+    assert_eq!(core.coalitions, 0);
+    assert_eq!(core.result.payments, core.vcg_payments);
+    assert_eq!(core.result.payments, [(&"Alice".to_string(), 7)]);
+}
+
+#[test]
+fn complements_raise_payments_to_the_core() {
+    let items = vec![
+        ("a".to_string(), 1),
+        ("b".to_string(), 1),
+        ("c".to_string(), 1),
+    ];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 8, [("a", 1)])],
+        vec![SimpleBid::new("Bob", 8, [("b", 1)])],
+        vec![SimpleBid::new("Carol", 8, [("c", 1)])],
+        vec![SimpleBid::new("Dave", 12, [("a", 1), ("b", 1)])],
+        vec![SimpleBid::new("Erin", 12, [("b", 1), ("c", 1)])],
+    ];
+    let core =
+        core_auction(&items, &bids, |_| 0, CoreSelection::MinimumRevenue)
+            .unwrap();
+    // each winner's VCG payment is 4, what Dave or Erin with the remaining
+    // winner add over the three winners without them
+    let vcg = core.vcg_payments.iter().map(|(_, p)| p).sum::<u64>();
+    assert_eq!(vcg, 12);
+    // Dave and Erin each offer 12 for two items, so Bob pays 8 and Alice and
+    // Carol pay 4
+    let payments = core
+        .result
+        .payments
+        .iter()
+        .map(|(name, payment)| (name.as_str(), *payment))
+        .collect::<Vec<_>>();
+    assert_eq!(payments, [("Alice", 4), ("Bob", 8), ("Carol", 4)]);
+    assert!(core.coalitions > 0);
+    assert_in_core(&items, &bids, &core);
+}
+
+#[test]
+fn nearest_vcg_payments_share_the_raise() {
+    let items = vec![("left".to_string(), 1), ("right".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 6, [("left", 1)])],
+        vec![SimpleBid::new("Bob", 4, [("right", 1)])],
+        vec![SimpleBid::new("Carol", 8, [("left", 1), ("right", 1)])],
+    ];
+    let payments = |selection| {
+        let core = core_auction(&items, &bids, |_| 0, selection).unwrap();
+        assert_in_core(&items, &bids, &core);
+        core.result
+            .payments
+            .iter()
+            .map(|(_, payment)| *payment)
+            .collect::<Vec<_>>()
+    };
+    // VCG charges 4 and 2, and Carol's 8 is raised evenly from there
+    assert_eq!(payments(CoreSelection::NearestVcg), [5, 3]);
+    assert_eq!(
+        payments(CoreSelection::MinimumRevenue).iter().sum::<u64>(),
+        8
+    );
+}
+
+#[cfg(feature = "rng")]
+#[test]
+fn random_auctions_have_core_payments() {
+    use rand::{distributions::Distribution, rngs::StdRng, SeedableRng};
+    use vcg_auction::InstanceShape;
+
+    let shape = InstanceShape {
+        max_items: 3,
+        max_stock: 2,
+        max_bidders: 5,
+        max_bid_sets: 6,
+        max_bids_per_set: 3,
+        max_items_per_bid: 3,
+        max_quantity: 2,
+        max_value: 20,
+    };
+    let mut rng = StdRng::seed_from_u64(3);
+    for auction in shape.sample_iter(&mut rng).take(100) {
+        // one bid set for each bidder
+        let mut bid_sets: Vec<Vec<SimpleBid>> = vec![];
+        for bid in auction.bid_sets.into_iter().flatten() {
+            match bid_sets.iter_mut().find(|set| set[0].name == bid.name) {
+                Some(set) => set.push(bid),
+                None => bid_sets.push(vec![bid]),
+            }
+        }
+        for selection in
+            [CoreSelection::NearestVcg, CoreSelection::MinimumRevenue]
+        {
+            let core =
+                core_auction(&auction.items, &bid_sets, |_| 0, selection)
+                    .unwrap();
+            assert_in_core(&auction.items, &bid_sets, &core);
+        }
+    }
+}
+
+#[test]
+fn float_minimum_revenue_payments_settle() {
+    // b2's VCG payment comes to an ulp under b1's bid of 5.34, so b1 blocked
+    // it by that ulp however often the constraint was added
+    let bids: [(f64, &[&str]); 3] =
+        [(5.7, &["a"]), (5.34, &["b", "c"]), (8.12, &["b"])];
+    let (float, cents) =
+        float_and_cent_payments(&bids, CoreSelection::MinimumRevenue);
+    let total = float.iter().sum::<f64>() * 100.0;
+    assert!((total - cents.iter().sum::<u64>() as f64).abs() < 1e-6);
+}
+
+#[test]
+fn float_nearest_vcg_payments_meet_the_core() {
+    // projecting onto the constraints left b1 and b4 paying about 1e-9 less
+    // than b2's bid for every item asks of them, so b2 still blocked them
+    let bids: [(f64, &[&str]); 5] = [
+        (3.06, &["c"]),
+        (1.91, &["a"]),
+        (4.45, &["a", "b", "c"]),
+        (3.51, &["c"]),
+        (7.02, &["b"]),
+    ];
+    let (float, cents) =
+        float_and_cent_payments(&bids, CoreSelection::NearestVcg);
+    // the payments in whole cents are the same ones rounded up
+    for (float, cents) in float.iter().zip(cents) {
+        let float = float * 100.0;
+        assert!(float <= cents as f64 + 1e-6 && cents as f64 - float < 1.0);
+    }
+}
@@ -0,0 +1,99 @@
+//! Tests for the course bidding preset.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    courses::{CourseError, CourseMarket, Meeting, Section},
+    types::SimpleBid,
+    vcg_auction_with_tiebreaker,
+};
+
+fn section(id: &str, course: &str, seats: u64, day: u8, start: u16) -> Section {
+    Section {
+        id: id.into(),
+        course: course.into(),
+        seats,
+        meetings: vec![Meeting {
+            day,
+            start,
+            end: start + 60,
+        }],
+    }
+}
+
+fn market() -> CourseMarket {
+    let mut market = CourseMarket::new([
+        section("math-a", "math", 1, 0, 540),
+        section("math-b", "math", 1, 1, 540),
+        section("art", "art", 2, 0, 570),
+        section("music", "music", 1, 2, 600),
+    ]);
+    market.student("Alice", 100).student("Bob", 100);
+    market
+}
+
+#[test]
+fn conflicts_share_a_bid_set() {
+    let market = market();
+    // math-a overlaps art, and is the same course as math-b
+    let bid_sets = market
+        .bid_sets(
+            "Alice",
+            [("math-a", 40), ("music", 10), ("math-b", 20), ("art", 30)],
+        )
+        .unwrap();
+    assert_eq!(
+        bid_sets,
+        [
+            vec![
+                SimpleBid::new("Alice", 50, [("math-b", 1), ("art", 1)]),
+                SimpleBid::new("Alice", 40, [("math-a", 1)]),
+                SimpleBid::new("Alice", 20, [("math-b", 1)]),
+                SimpleBid::new("Alice", 30, [("art", 1)]),
+            ],
+            vec![SimpleBid::new("Alice", 10, [("music", 1)])],
+        ]
+    );
+}
+
+#[test]
+fn points_are_checked() {
+    let market = market();
+    assert_eq!(
+        market.bid_sets("Carol", [("art", 10)]),
+        Err(CourseError::UnknownStudent("Carol".into()))
+    );
+    assert_eq!(
+        market.bid_sets("Alice", [("drama", 10)]),
+        Err(CourseError::UnknownSection("drama".into()))
+    );
+    assert_eq!(
+        market.bid_sets("Alice", [("art", 60), ("music", 30), ("art", 20)]),
+        Err(CourseError::OverBudget {
+            points: 110,
+            budget: 100,
+        })
+    );
+}
+
+#[test]
+fn students_win_sections_they_can_attend() {
+    let market = market();
+    let bids = [
+        market
+            .bid_sets("Alice", [("math-a", 60), ("art", 30)])
+            .unwrap(),
+        market
+            .bid_sets("Bob", [("math-a", 50), ("math-b", 30), ("music", 20)])
+            .unwrap(),
+    ]
+    .concat();
+    let result =
+        vcg_auction_with_tiebreaker(market.items(), &bids, |_| 0).unwrap();
+    // Alice takes math-a, so Bob takes math-b and music
+    assert_eq!(result.winning_bids, [&bids[0][0], &bids[1][1], &bids[2][0]]);
+    assert_eq!(
+        result.payments,
+        [(&"Alice".to_string(), 20), (&"Bob".to_string(), 0)]
+    );
+}
@@ -0,0 +1,131 @@
+//! Tests for auctions whose bids split into independent sub-auctions, which
+//! are searched apart.
+
+mod common;
+
+use std::cell::RefCell;
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    run_with_search_tree, run_with_stats, types::SimpleBid, AuctionConfig,
+    AuctionError, ResourceLimit, Tiebreak,
+};
+
+#[test]
+fn tied_outcomes_combine_in_input_order() {
+    let items = vec![("chair".to_string(), 1), ("table".to_string(), 1)];
+    // the chair's and the table's bid sets interleaved
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 5, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("table", 1)])],
+        vec![SimpleBid::new("Bob", 5, [("chair", 1)])],
+        vec![SimpleBid::new("Dave", 3, [("table", 1)])],
+    ];
+    let ties = RefCell::new(vec![]);
+    let tiebreaker = |tied: &[Vec<&SimpleBid>]| {
+        *ties.borrow_mut() = tied
+            .iter()
+            .map(|bids| bids.iter().map(|b| b.name.clone()).collect())
+            .collect::<Vec<Vec<_>>>();
+        2
+    };
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::Custom(&tiebreaker))
+        .build();
+    let (outcome, stats) = run_with_stats(&items, &bids, &config);
+    let result = outcome.unwrap().complete().unwrap();
+
+    assert_eq!(
+        ties.into_inner(),
+        [
+            ["Alice", "Carol"],
+            ["Alice", "Dave"],
+            ["Carol", "Bob"],
+            ["Bob", "Dave"],
+        ]
+    );
+    assert_eq!(result.winning_bids, [&bids[1][0], &bids[2][0]]);
+    assert_eq!(stats.winner_determination.searches, 2);
+}
+
+#[test]
+fn payments_of_a_bidder_in_several_sub_auctions() {
+    let items = vec![("chair".to_string(), 1), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+        vec![SimpleBid::new("Alice", 4, [("table", 1)])],
+        vec![SimpleBid::new("Carol", 6, [("table", 1)])],
+    ];
+    let config = AuctionConfig::deterministic();
+    let (outcome, _) = run_with_stats(&items, &bids, &config);
+    let result = outcome.unwrap().complete().unwrap();
+
+    assert_eq!(result.winning_bids, [&bids[0][0], &bids[3][0]]);
+    // without Alice, Bob and Carol win 13, of which Carol's 6 is still won;
+    // without Carol, Alice wins both for 14, of which her chair's 10 is
+    assert_eq!(
+        result.payments,
+        [(&"Alice".to_string(), 7), (&"Carol".to_string(), 4)]
+    );
+}
+
+#[test]
+fn tied_outcome_limit_counts_every_combination() {
+    let items = vec![("chair".to_string(), 1), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 5, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 5, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("table", 1)])],
+        vec![SimpleBid::new("Dave", 3, [("table", 1)])],
+    ];
+    let config = |max_tied_outcomes| {
+        AuctionConfig::builder()
+            .tiebreak(Tiebreak::First)
+            .max_tied_outcomes(max_tied_outcomes)
+            .build()
+    };
+    // two ties for the chair and two for the table, each within the limit,
+    // make four tied outcomes
+    let (outcome, stats) = run_with_stats(&items, &bids, &config(3));
+    assert_eq!(
+        outcome,
+        Err(AuctionError::ResourceLimit(ResourceLimit::TiedOutcomes))
+    );
+    assert_eq!(stats.winner_determination.searches, 2);
+    let (outcome, _) = run_with_search_tree(&items, &bids, &config(3));
+    assert_eq!(
+        outcome,
+        Err(AuctionError::ResourceLimit(ResourceLimit::TiedOutcomes))
+    );
+    let (outcome, _) = run_with_stats(&items, &bids, &config(4));
+    assert!(outcome.unwrap().complete().is_some());
+}
+
+#[cfg(all(feature = "test-utils", feature = "rand"))]
+#[test]
+fn decomposed_searches_match_the_whole_search() {
+    use rand::{distributions::Distribution, rngs::StdRng, Rng, SeedableRng};
+    use vcg_auction::{vcg_auction_with_solver, AuctionInstance, BruteForce};
+
+    use common::{owned, recording_tiebreaker, run_recording_ties, tied_shape};
+
+    // bids on one item each, so each item's bids are searched apart
+    let shape = tied_shape();
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..200 {
+        let AuctionInstance {
+            items,
+            bid_sets: bids,
+        } = shape.sample(&mut rng);
+        let pick = rng.gen::<usize>();
+        // the brute-force solver tries every combination of the whole auction
+        let ties = RefCell::new(vec![]);
+        let tiebreaker = recording_tiebreaker(&ties, pick);
+        let whole =
+            vcg_auction_with_solver(&items, &bids, tiebreaker, &BruteForce);
+        let whole = (whole.ok().map(owned), ties.take());
+        assert_eq!(run_recording_ties(&items, &bids, pick, |c| c), whole);
+    }
+}
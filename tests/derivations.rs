@@ -0,0 +1,100 @@
+//! Tests for the payment derivations of `run_with_payment_derivations`.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    run, run_with_payment_derivations, types::SimpleBid, AuctionConfig, Bid,
+    PricingRule, Tiebreak,
+};
+
+fn chair_bids() -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = vec![("chair".into(), 2), ("table".into(), 1)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("table", 1)])],
+        vec![SimpleBid::new("Dave", 2, [("chair", 1), ("table", 1)])],
+    ];
+    (items, bids)
+}
+
+#[test]
+fn derivations_match_the_payments() {
+    let (items, bids) = chair_bids();
+    let config = AuctionConfig::deterministic();
+    let (outcome, derivations) =
+        run_with_payment_derivations(&items, &bids, &config);
+    let result = outcome.unwrap().complete().unwrap();
+    assert_eq!(
+        result,
+        run(&items, &bids, &config).unwrap().complete().unwrap()
+    );
+
+    assert_eq!(derivations.len(), result.payments.len());
+    for (derivation, (bidder_name, payment)) in
+        derivations.iter().zip(&result.payments)
+    {
+        assert_eq!(derivation.bidder_name, *bidder_name);
+        assert_eq!(derivation.payment, *payment);
+        assert_eq!(
+            derivation.payment,
+            derivation.counterfactual_value - derivation.others_value
+        );
+        // the counterfactual leaves the bidder out
+        assert!(derivation
+            .counterfactual_bids
+            .iter()
+            .all(|bid| bid.bidder_name() != *bidder_name));
+        let counterfactual_value = derivation
+            .counterfactual_bids
+            .iter()
+            .map(|bid| bid.bid_value())
+            .sum::<u64>();
+        assert_eq!(derivation.counterfactual_value, counterfactual_value);
+    }
+}
+
+#[test]
+fn displaced_bids_lose() {
+    let (items, bids) = chair_bids();
+    let config = AuctionConfig::deterministic();
+    let (outcome, derivations) =
+        run_with_payment_derivations(&items, &bids, &config);
+    let result = outcome.unwrap().complete().unwrap();
+    for derivation in &derivations {
+        for bid in &derivation.displaced_bids {
+            assert!(derivation.counterfactual_bids.contains(bid));
+            assert!(!result.winning_bids.contains(bid));
+        }
+    }
+    // Alice and Carol displace no one, but without Bob, Alice would have won
+    // both chairs
+    let displaced = derivations
+        .iter()
+        .map(|d| (d.bidder_name.as_str(), d.displaced_bids.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        displaced,
+        [
+            ("Alice", vec![]),
+            ("Bob", vec![&bids[0][1]]),
+            ("Carol", vec![]),
+        ]
+    );
+}
+
+#[test]
+fn no_derivations_with_first_price() {
+    let (items, bids) = chair_bids();
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::First)
+        .pricing(PricingRule::FirstPrice)
+        .build();
+    let (outcome, derivations) =
+        run_with_payment_derivations(&items, &bids, &config);
+    assert!(outcome.is_ok());
+    assert!(derivations.is_empty());
+}
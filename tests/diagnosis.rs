@@ -0,0 +1,60 @@
+//! Tests for diagnosing auction outcomes with `run_with_diagnosis`.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    run, run_with_diagnosis, types::SimpleBid, AuctionConfig, ExcludedBid,
+    Exclusion,
+};
+
+#[test]
+fn losing_bids_are_explained() {
+    let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 9, [("chair", 3)]),
+            SimpleBid::new("Alice", 3, [("table", 1)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 2)])],
+        vec![SimpleBid::new("Carol", 6, [("table", 1)])],
+    ];
+    let config = AuctionConfig::deterministic();
+    let diagnosed = run_with_diagnosis(&items, &bids, &config).unwrap();
+    assert_eq!(diagnosed.outcome, run(&items, &bids, &config).unwrap());
+    assert_eq!(diagnosed.welfare, 11);
+    assert!(diagnosed.feasible);
+    let excluded = |bid_set, bid, reason| ExcludedBid {
+        bid_set,
+        bid,
+        reason,
+    };
+    // Alice wins a chair with her first bid, and there aren't three for her
+    // second; Bob wants both chairs, but Alice and Carol are worth more
+    assert_eq!(
+        diagnosed.excluded,
+        [
+            excluded(0, 1, Exclusion::OverStock),
+            excluded(0, 2, Exclusion::BidSetWon),
+            excluded(1, 0, Exclusion::Outbid),
+        ]
+    );
+}
+
+#[test]
+fn empty_auctions_are_feasible_or_not() {
+    let items = vec![("chair".to_string(), 1)];
+    let config = AuctionConfig::deterministic();
+
+    let no_bids: Vec<Vec<SimpleBid>> = vec![];
+    let diagnosed = run_with_diagnosis(&items, &no_bids, &config).unwrap();
+    assert_eq!(diagnosed.welfare, 0);
+    assert!(!diagnosed.feasible);
+    assert_eq!(diagnosed.excluded, []);
+
+    // a bid worth nothing could win, even if it doesn't
+    let bids = vec![vec![SimpleBid::new("Alice", 0, [("chair", 1)])]];
+    let diagnosed = run_with_diagnosis(&items, &bids, &config).unwrap();
+    assert_eq!(diagnosed.welfare, 0);
+    assert!(diagnosed.feasible);
+}
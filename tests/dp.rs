@@ -0,0 +1,92 @@
+//! Tests that the dynamic program over units of stock produces the same
+//! results as the branch-and-bound search, including the order of tied
+//! outcomes given to the tiebreaker.
+
+#![cfg(feature = "rand")]
+
+mod common;
+
+use std::cell::RefCell;
+
+use pretty_assertions::assert_eq;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_dp, vcg_auction_with_options,
+    vcg_auction_with_tiebreaker, AuctionError, AuctionInstance, InstanceShape,
+    ResourceLimit, SearchOptions,
+};
+
+use common::{irregular_auction, recording_tiebreaker, tied_shape};
+
+#[test]
+fn dp_matches_search() {
+    // bids on one or two items, some of them irregular
+    let shape = InstanceShape {
+        max_items_per_bid: 2,
+        ..tied_shape()
+    };
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..200 {
+        let AuctionInstance {
+            items,
+            bid_sets: bids,
+        } = irregular_auction(&mut rng, &shape);
+        let pick = rng.gen::<usize>();
+        let search_ties = RefCell::new(vec![]);
+        let dp_ties = RefCell::new(vec![]);
+        let search = vcg_auction_with_tiebreaker(
+            &items,
+            &bids,
+            recording_tiebreaker(&search_ties, pick),
+        );
+        let dp = vcg_auction_dp(
+            &items,
+            &bids,
+            recording_tiebreaker(&dp_ties, pick),
+            &SearchOptions::default(),
+        )
+        .unwrap()
+        .complete();
+        assert_eq!(search, dp);
+        assert_eq!(search_ties, dp_ties);
+    }
+}
+
+#[test]
+fn large_stock_falls_back_to_search() {
+    let items = vec![("chair".to_string(), u64::MAX / 2)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", u64::MAX / 2)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+    ];
+    let dp = vcg_auction_dp(&items, &bids, |_| 0, &SearchOptions::default())
+        .unwrap()
+        .complete();
+    assert_eq!(dp, vcg_auction_with_tiebreaker(&items, &bids, |_| 0));
+    assert_eq!(dp.unwrap().winning_bids, [&bids[0][0]]);
+}
+
+#[test]
+fn dp_limits_tied_outcomes() {
+    let items = vec![("chair".to_string(), 3)];
+    // every combination of the three bids is tied at zero
+    let bids = (0..3)
+        .map(|bidder| {
+            vec![SimpleBid::new(bidder.to_string(), 0, [("chair", 1)])]
+        })
+        .collect::<Vec<_>>();
+    let options = SearchOptions {
+        max_tied_outcomes: Some(4),
+        ..Default::default()
+    };
+    let error = AuctionError::ResourceLimit(ResourceLimit::TiedOutcomes);
+    assert_eq!(
+        vcg_auction_dp(&items, &bids, |_| 0, &options).err(),
+        Some(error)
+    );
+    assert_eq!(
+        vcg_auction_with_options(&items, &bids, |_| 0, &options).err(),
+        Some(error)
+    );
+}
@@ -0,0 +1,96 @@
+//! Tests for validating and estimating auctions with `vcg_auction_dry_run`.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    run_with_stats, types::SimpleBid, vcg_auction_dry_run, AuctionConfig,
+    DuplicateItems, InputError, ResourceLimit,
+};
+
+#[test]
+fn worst_case_bounds_the_search() {
+    let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("table", 1)])],
+        vec![SimpleBid::new("Dave", 2, [("chair", 1), ("table", 1)])],
+    ];
+    let config = AuctionConfig::deterministic();
+    let dry_run = vcg_auction_dry_run(&items, &bids, &config).unwrap();
+    assert_eq!(dry_run.bid_sets, 4);
+    assert_eq!(dry_run.bids, 5);
+    assert_eq!(dry_run.search_space, 3.0 * 2.0 * 2.0 * 2.0);
+    assert_eq!(
+        dry_run.winner_determination_nodes,
+        1.0 + 3.0 + 6.0 + 12.0 + 24.0
+    );
+    assert_eq!(dry_run.may_exceed, []);
+
+    let (_, stats) = run_with_stats(&items, &bids, &config);
+    assert!(
+        stats.winner_determination.nodes as f64
+            <= dry_run.winner_determination_nodes
+    );
+    assert!(stats.payments.nodes as f64 <= dry_run.payment_nodes);
+}
+
+#[test]
+fn limits_at_risk() {
+    let items = vec![("chair".to_string(), 1)];
+    let bids = ["Alice", "Bob", "Carol"]
+        .into_iter()
+        .map(|name| vec![SimpleBid::new(name, 5, [("chair", 1)])])
+        .collect::<Vec<_>>();
+    let config = AuctionConfig::builder()
+        .max_nodes(1000)
+        .max_tied_outcomes(2)
+        .build();
+    let dry_run = vcg_auction_dry_run(&items, &bids, &config).unwrap();
+    assert_eq!(dry_run.may_exceed, [ResourceLimit::TiedOutcomes]);
+}
+
+#[test]
+fn size_limits_exceeded() {
+    let items = vec![("chair".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 5, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 5, [("chair", 1)])],
+    ];
+    let config = AuctionConfig::builder().max_bid_sets(1).build();
+    let dry_run = vcg_auction_dry_run(&items, &bids, &config).unwrap();
+    assert_eq!(dry_run.may_exceed, [ResourceLimit::BidSets]);
+}
+
+#[test]
+fn invalid_inputs() {
+    let config = AuctionConfig::deterministic();
+    let items = vec![("chair".to_string(), 1), ("chair".to_string(), 1)];
+    let bids: Vec<Vec<SimpleBid>> = vec![];
+    // repeated items are merged unless the config rejects them
+    assert!(vcg_auction_dry_run(&items, &bids, &config).is_ok());
+    let rejecting = AuctionConfig::builder()
+        .duplicate_items(DuplicateItems::Reject)
+        .build();
+    assert_eq!(
+        vcg_auction_dry_run(&items, &bids, &rejecting),
+        Err(InputError::DuplicateStockItem(1))
+    );
+
+    let items = vec![("chair".to_string(), 2)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 5, [("chair", 1)])],
+        vec![
+            SimpleBid::new("Bob", 4, [("chair", 1)]),
+            SimpleBid::new("Bob", 6, [("chair", 1), ("chair", 1)]),
+        ],
+    ];
+    assert_eq!(
+        vcg_auction_dry_run(&items, &bids, &config),
+        Err(InputError::DuplicateBidItem { bid_set: 1, bid: 1 })
+    );
+}
@@ -0,0 +1,140 @@
+//! Tests that every auction function, and `verify_result`, treats an item
+//! listed more than once in the stock as one item with the quantities added
+//! up.
+
+use std::task::Poll;
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    run_with_pruning_log, run_with_search_tree, types::SimpleBid,
+    vcg_allocation_with_tiebreaker, vcg_auction_assignment, vcg_auction_dp,
+    vcg_auction_dry_run, vcg_auction_unique, vcg_auction_with_options,
+    vcg_auction_with_solver, vcg_auction_with_tiebreaker, verify_result,
+    AuctionConfig, AuctionOutcome, AuctionResult, BranchAndBound, InputError,
+    SearchOptions, Solver, UniqueItemError,
+};
+
+/// Two chairs listed apart, and a table.
+fn items() -> Vec<(String, u64)> {
+    vec![
+        ("chair".into(), 1),
+        ("table".into(), 1),
+        ("chair".into(), 1),
+    ]
+}
+
+fn bids() -> Vec<Vec<SimpleBid>> {
+    vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 2)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1), ("table", 1)])],
+    ]
+}
+
+/// Both chairs are for sale, so Alice wins them, and pays Bob's 7.
+fn assert_merged(
+    bids: &[Vec<SimpleBid>],
+    result: Option<AuctionResult<SimpleBid>>,
+) {
+    let result = result.expect("auction should complete");
+    assert_eq!(result.winning_bids, [&bids[0][0]]);
+    assert_eq!(result.payments, [(&"Alice".to_string(), 7)]);
+}
+
+fn complete(
+    outcome: Result<AuctionOutcome<SimpleBid>, vcg_auction::AuctionError>,
+) -> Option<AuctionResult<SimpleBid>> {
+    outcome.unwrap().complete()
+}
+
+#[test]
+fn every_auction_merges_duplicate_items() {
+    let (items, bids) = (items(), bids());
+    let options = SearchOptions::default();
+    let config = AuctionConfig::deterministic();
+
+    assert_merged(&bids, complete(vcg_auction::run(&items, &bids, &config)));
+    assert_merged(&bids, vcg_auction_with_tiebreaker(&items, &bids, |_| 0));
+    assert_merged(
+        &bids,
+        complete(vcg_auction_with_options(&items, &bids, |_| 0, &options)),
+    );
+    assert_merged(
+        &bids,
+        complete(run_with_search_tree(&items, &bids, &config).0),
+    );
+    assert_merged(
+        &bids,
+        complete(run_with_pruning_log(&items, &bids, &config).0),
+    );
+    assert_merged(
+        &bids,
+        complete(vcg_auction_dp(&items, &bids, |_| 0, &options)),
+    );
+    assert_merged(
+        &bids,
+        complete(vcg_auction_assignment(&items, &bids, |_| 0, &options)),
+    );
+    let solver = BranchAndBound::default();
+    assert_merged(
+        &bids,
+        vcg_auction_with_solver(&items, &bids, |_| 0, &solver).ok(),
+    );
+
+    let mut solver = Solver::new(&items, &bids, |_| 0);
+    let result = loop {
+        if let Poll::Ready(result) = solver.step(u64::MAX) {
+            break result;
+        }
+    };
+    assert_merged(&bids, result.ok());
+
+    let allocation =
+        vcg_allocation_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    assert_eq!(allocation.winning_bids(), [&bids[0][0]]);
+    assert_eq!(allocation.payment_for(&"Alice".to_string()), Some(&7));
+
+    assert!(vcg_auction_dry_run(&items, &bids, &config).is_ok());
+    // unique items must each be listed once
+    assert_eq!(
+        vcg_auction_unique(&items, &bids, |_| 0),
+        Err(UniqueItemError::Input(InputError::DuplicateStockItem(2)))
+    );
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn random_auctions_merge_duplicate_items() {
+    let (items, bids) = (items(), bids());
+    assert_merged(&bids, vcg_auction::vcg_auction(&items, &bids));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn parallel_auctions_merge_duplicate_items() {
+    let (items, bids) = (items(), bids());
+    assert_merged(
+        &bids,
+        vcg_auction::vcg_auction_parallel(&items, &bids, |_| 0),
+    );
+}
+
+#[cfg(feature = "lp")]
+#[test]
+fn lp_auctions_merge_duplicate_items() {
+    let (items, bids) = (items(), bids());
+    let options = SearchOptions::default();
+    assert_merged(
+        &bids,
+        complete(vcg_auction::vcg_auction_lp(&items, &bids, |_| 0, &options)),
+    );
+}
+
+#[test]
+fn merged_results_verify() {
+    let (items, bids) = (items(), bids());
+    let config = AuctionConfig::deterministic();
+    let result = complete(vcg_auction::run(&items, &bids, &config)).unwrap();
+    assert_eq!(result.winning_bids, [&bids[0][0]]);
+    assert_eq!(verify_result(&items, &bids, &result), Ok(()));
+}
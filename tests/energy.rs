@@ -0,0 +1,139 @@
+//! Tests for the energy market preset.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    energy::{EnergyError, EnergyMarket, SlotItem},
+    vcg_auction_with_tiebreaker,
+};
+
+#[test]
+fn supply_is_auctioned() {
+    let mut market = EnergyMarket::new(["power", "reserve"], 3);
+    market
+        .supply("power", 0, &[20, 20, 10])
+        .unwrap()
+        .supply("power", 2, &[5])
+        .unwrap();
+    let item = |resource: &str, slot| SlotItem {
+        resource: resource.into(),
+        slot,
+    };
+    let items = market.items();
+    assert_eq!(
+        items,
+        [
+            (item("power", 0), 20),
+            (item("power", 1), 20),
+            (item("power", 2), 15),
+            (item("reserve", 0), 0),
+            (item("reserve", 1), 0),
+            (item("reserve", 2), 0),
+        ]
+    );
+    let bids = vec![
+        vec![market
+            .block_bid("Alice", 50, "power", 0, &[10, 10, 10])
+            .unwrap()],
+        vec![market.block_bid("Bob", 30, "power", 1, &[10, 10]).unwrap()],
+        vec![market.block_bid("Carol", 20, "power", 0, &[15]).unwrap()],
+    ];
+    let cleared = market.clear(&bids, |_| 0).unwrap();
+    let expected = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    assert_eq!(cleared.winning_bids, expected.winning_bids);
+    assert_eq!(cleared.payments, expected.payments);
+    assert_eq!(cleared.sold, Vec::<&_>::new());
+    assert_eq!(cleared.receipts, []);
+}
+
+#[test]
+fn double_auction_pays_sellers() {
+    let mut market = EnergyMarket::new(["power"], 4);
+    market
+        .offer("Alice", 100, "power", 0, &[10, 10])
+        .unwrap()
+        .offer("Bob", 150, "power", 0, &[10, 10])
+        .unwrap();
+    let bids = vec![
+        vec![market
+            .block_bid("Carol", 300, "power", 0, &[10, 10])
+            .unwrap()],
+        vec![market
+            .block_bid("Dave", 120, "power", 0, &[10, 10])
+            .unwrap()],
+    ];
+    let cleared = market.clear(&bids, |_| 0).unwrap();
+    // Carol buys Alice's cheaper block, and Bob keeps his
+    assert_eq!(cleared.winning_bids, [&bids[0][0]]);
+    assert_eq!(cleared.sold, [&market.offers()[0]]);
+    // without Carol, Dave would buy Alice's block, worth 120 to Dave
+    assert_eq!(cleared.payments, [(&"Carol".to_string(), 120)]);
+    // Alice's ask, plus the 50 Carol would lose buying Bob's block instead
+    assert_eq!(cleared.receipts, [(&"Alice".to_string(), 150)]);
+}
+
+#[test]
+fn mcafee_drops_a_trade_to_balance_the_budget() {
+    let mut market = EnergyMarket::new(["power"], 4);
+    for (seller, ask) in [("Alice", 20), ("Bob", 40), ("Carol", 60)] {
+        market.offer(seller, ask, "power", 1, &[5, 5]).unwrap();
+    }
+    let bids = ["Dave", "Erin", "Frank"]
+        .iter()
+        .zip([100, 70, 10])
+        .map(|(buyer, value)| {
+            vec![market
+                .block_bid(*buyer, value, "power", 1, &[5, 5])
+                .unwrap()]
+        })
+        .collect::<Vec<_>>();
+    let cleared = market.clear_mcafee(&bids).unwrap();
+    // two trades are worth making, but the average of Frank's bid and
+    // Carol's ask is below Bob's ask, so Erin and Bob's trade is dropped
+    assert_eq!(cleared.winning_bids, [&bids[0][0]]);
+    assert_eq!(cleared.sold, [&market.offers()[0]]);
+    assert_eq!(cleared.payments, [(&"Dave".to_string(), 70)]);
+    assert_eq!(cleared.receipts, [(&"Alice".to_string(), 40)]);
+    assert_eq!(cleared.efficiency_loss, 30);
+    // VCG makes both trades, but pays out more than it takes
+    let vcg = market.clear(&bids, |_| 0).unwrap();
+    assert_eq!(vcg.winning_bids, [&bids[0][0], &bids[1][0]]);
+    assert_eq!(vcg.efficiency_loss, 0);
+    let paid = vcg.payments.iter().map(|(_, p)| p).sum::<u64>();
+    let received = vcg.receipts.iter().map(|(_, r)| r).sum::<u64>();
+    assert!(received > paid);
+}
+
+#[test]
+fn mcafee_needs_like_blocks() {
+    let mut market = EnergyMarket::new(["power"], 4);
+    market.offer("Alice", 20, "power", 0, &[5]).unwrap();
+    let bids =
+        vec![vec![market.block_bid("Bob", 30, "power", 1, &[5]).unwrap()]];
+    assert_eq!(market.clear_mcafee(&bids), Err(EnergyError::UnlikeBlocks));
+}
+
+#[test]
+fn blocks_are_checked() {
+    let mut market = EnergyMarket::new(["power"], 24);
+    market.offer("plant", 10, "power", 0, &[5]).unwrap();
+    assert_eq!(
+        market.block_bid("Alice", 10, "gas", 0, &[5]),
+        Err(EnergyError::UnknownResource("gas".into()))
+    );
+    assert_eq!(
+        market.block_bid("Alice", 10, "power", 22, &[5, 5, 5]),
+        Err(EnergyError::PastLastSlot {
+            last_slot: 24,
+            slots: 24,
+        })
+    );
+    assert_eq!(
+        market.block_bid("Alice", 10, "power", 0, &[]),
+        Err(EnergyError::EmptyBlock)
+    );
+    assert_eq!(
+        market.block_bid("plant", 10, "power", 0, &[5]),
+        Err(EnergyError::SellerBidding("plant".into()))
+    );
+}
@@ -0,0 +1,73 @@
+//! Tests for estimating the complexity of auctions.
+
+use std::time::Duration;
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    estimate_complexity, run_with_stats, types::SimpleBid, AuctionConfig,
+    Calibration, PhaseStats, SolveStats,
+};
+
+fn chair_bids() -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items =
+        vec![("chair".into(), 2), ("table".into(), 1), ("lamp".into(), 1)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("table", 1)])],
+        vec![SimpleBid::new("Dave", 2, [("chair", 1), ("table", 1)])],
+    ];
+    (items, bids)
+}
+
+#[test]
+fn report() {
+    let (items, bids) = chair_bids();
+    let report = estimate_complexity(&items, &bids);
+    assert_eq!(report.bid_sets, 4);
+    assert_eq!(report.bids, 5);
+    assert_eq!(report.largest_bid_set, 2);
+    assert_eq!(report.max_contention, 3);
+    assert_eq!(report.mean_contention, (3 + 2) as f64 / 3.0);
+    assert_eq!(report.search_space, 24.0);
+
+    let (_, stats) =
+        run_with_stats(&items, &bids, &AuctionConfig::deterministic());
+    assert!(stats.nodes() as f64 <= report.worst_case_nodes);
+}
+
+#[test]
+fn calibrated_estimates() {
+    let (items, bids) = chair_bids();
+    let report = estimate_complexity(&items, &bids);
+    let phase = |nodes, millis| PhaseStats {
+        searches: 1,
+        nodes,
+        search_time: Duration::from_millis(millis),
+        ..PhaseStats::default()
+    };
+    // a run exploring a quarter of the worst case, at 1000 nodes a second
+    let quarter = (report.worst_case_nodes / 4.0) as u64;
+    let stats = SolveStats {
+        winner_determination: phase(quarter, quarter),
+        payments: phase(0, 0),
+    };
+    let calibration = Calibration::from_runs([(&report, &stats)]).unwrap();
+    assert_eq!(calibration.nodes_per_second, 1000.0);
+    assert_eq!(
+        calibration.explored_fraction,
+        quarter as f64 / report.worst_case_nodes
+    );
+    assert_eq!(report.estimated_nodes(&calibration), quarter as f64);
+    assert_eq!(
+        report.estimated_duration(&calibration),
+        Duration::from_millis(quarter)
+    );
+
+    assert_eq!(Calibration::from_runs([]), None);
+}
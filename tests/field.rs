@@ -0,0 +1,109 @@
+//! Tests for bid values represented as prime field elements.
+
+#![cfg(feature = "rand")]
+
+use pretty_assertions::assert_eq;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_with_tiebreaker, AddSubSelf, Bid,
+    FieldElement, FieldValue,
+};
+
+/// Integers modulo a small prime, so that wrapping is easy to reach.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Fp(u32);
+
+const P: u32 = 101;
+
+impl FieldElement for Fp {
+    type Repr = u32;
+    fn zero() -> Self {
+        Fp(0)
+    }
+    fn add(&self, other: &Self) -> Self {
+        Fp((self.0 + other.0) % P)
+    }
+    fn sub(&self, other: &Self) -> Self {
+        Fp((self.0 + P - other.0) % P)
+    }
+    fn to_repr(&self) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct FieldBid {
+    name: String,
+    value: FieldValue<Fp>,
+    items: Vec<(String, u64)>,
+}
+
+impl Bid for FieldBid {
+    type Name = String;
+    type Value = FieldValue<Fp>;
+    type Item = String;
+    type Quantity = u64;
+
+    fn bidder_name(&self) -> &Self::Name {
+        &self.name
+    }
+    fn bid_value(&self) -> &Self::Value {
+        &self.value
+    }
+    fn bid_items(&self) -> &[(Self::Item, Self::Quantity)] {
+        &self.items
+    }
+}
+
+/// Random auctions give the same results with field values as with integers,
+/// while sums stay below the modulus.
+#[test]
+fn field_values_match_integers() {
+    let items = vec![("chair".into(), 2), ("table".into(), 1)];
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..50 {
+        let bids = (0..rng.gen_range(0..5))
+            .map(|bidder| {
+                (0..rng.gen_range(0..3))
+                    .map(|_| {
+                        let item = ["chair", "table"][rng.gen_range(0..2)];
+                        SimpleBid::new(
+                            bidder.to_string(),
+                            rng.gen_range(0..20),
+                            [(item, rng.gen_range(1..3))],
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let field_bids = bids
+            .iter()
+            .map(|bs| {
+                bs.iter()
+                    .map(|b| FieldBid {
+                        name: b.name.clone(),
+                        value: FieldValue(Fp(b.value as u32)),
+                        items: b.items.clone(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let expected =
+            vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+        let result =
+            vcg_auction_with_tiebreaker(&items, &field_bids, |_| 0).unwrap();
+        let payments = result
+            .payments
+            .iter()
+            .map(|(name, payment)| (*name, u64::from(payment.0 .0)))
+            .collect::<Vec<_>>();
+        assert_eq!(payments, expected.payments);
+    }
+}
+
+#[test]
+#[should_panic(expected = "sum of field values wrapped around the modulus")]
+fn wrapping_sums_panic() {
+    let _ = FieldValue(Fp(60)).add(&FieldValue(Fp(50)));
+}
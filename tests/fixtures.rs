@@ -0,0 +1,178 @@
+//! Regression tests loaded from the fixture files in `tests/fixtures`.
+//!
+//! Each fixture is a JSON file with the inputs of an auction of
+//! [`SimpleBid`]s and the result it's expected to have:
+//!
+//! ```json
+//! {
+//!     "description": "Bob's bid sets the price of the chair",
+//!     "items": [["chair", 1]],
+//!     "bid_sets": [
+//!         [{ "name": "Alice", "value": 10, "items": [["chair", 1]] }],
+//!         [{ "name": "Bob", "value": 7, "items": [["chair", 1]] }]
+//!     ],
+//!     "tiebreak": 0,
+//!     "winners": [[0, 0]],
+//!     "payments": [["Alice", 7]]
+//! }
+//! ```
+//!
+//! Winning bids are given as `[bid set, bid]` indices, in the order of their
+//! bid sets, and payments in the order of the winners. `tiebreak` is the
+//! index of the tied outcome to choose, and defaults to 0. To add a case,
+//! add a file ending in `.json`.
+
+use std::{fs, path::Path};
+
+use serde_json::Value;
+
+use vcg_auction::{types::SimpleBid, vcg_auction_with_tiebreaker};
+
+/// An auction read from a fixture, with its expected result.
+struct Fixture {
+    items: Vec<(String, u64)>,
+    bid_sets: Vec<Vec<SimpleBid>>,
+    tiebreak: usize,
+    winners: Vec<(usize, usize)>,
+    payments: Vec<(String, u64)>,
+}
+
+fn parse(json: &str) -> Result<Fixture, String> {
+    let fixture: Value =
+        serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let field = |name: &str| {
+        fixture
+            .get(name)
+            .ok_or_else(|| format!("missing field {name:?}"))
+    };
+    Ok(Fixture {
+        items: pairs(field("items")?, string, number)?,
+        bid_sets: array(field("bid_sets")?)?
+            .iter()
+            .map(|bid_set| array(bid_set)?.iter().map(bid).collect())
+            .collect::<Result<_, _>>()?,
+        tiebreak: match fixture.get("tiebreak") {
+            Some(tiebreak) => index(tiebreak)?,
+            None => 0,
+        },
+        winners: pairs(field("winners")?, index, index)?,
+        payments: pairs(field("payments")?, string, number)?,
+    })
+}
+
+fn bid(bid: &Value) -> Result<SimpleBid, String> {
+    let field = |name: &str| {
+        bid.get(name)
+            .ok_or_else(|| format!("bid is missing field {name:?}"))
+    };
+    Ok(SimpleBid {
+        name: string(field("name")?)?,
+        value: number(field("value")?)?,
+        items: pairs(field("items")?, string, number)?,
+    })
+}
+
+fn pairs<A, B>(
+    value: &Value,
+    first: impl Fn(&Value) -> Result<A, String>,
+    second: impl Fn(&Value) -> Result<B, String>,
+) -> Result<Vec<(A, B)>, String> {
+    array(value)?
+        .iter()
+        .map(|pair| match array(pair)?.as_slice() {
+            [a, b] => Ok((first(a)?, second(b)?)),
+            _ => Err(format!("expected a pair, found {pair}")),
+        })
+        .collect()
+}
+
+fn array(value: &Value) -> Result<&Vec<Value>, String> {
+    value
+        .as_array()
+        .ok_or_else(|| format!("expected an array, found {value}"))
+}
+
+fn string(value: &Value) -> Result<String, String> {
+    value
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| format!("expected a string, found {value}"))
+}
+
+fn number(value: &Value) -> Result<u64, String> {
+    value
+        .as_u64()
+        .ok_or_else(|| format!("expected a number, found {value}"))
+}
+
+fn index(value: &Value) -> Result<usize, String> {
+    number(value).map(|n| n as usize)
+}
+
+/// Run the fixture's auction, describing how the result differs from the
+/// expected one.
+fn check(fixture: &Fixture) -> Result<(), String> {
+    let result =
+        vcg_auction_with_tiebreaker(&fixture.items, &fixture.bid_sets, |_| {
+            fixture.tiebreak
+        })
+        .ok_or("tiebreak is not a tied outcome")?;
+    let winners = result
+        .winning_bids
+        .iter()
+        .map(|winner| {
+            fixture
+                .bid_sets
+                .iter()
+                .enumerate()
+                .find_map(|(i, bid_set)| {
+                    let j = bid_set
+                        .iter()
+                        .position(|b| std::ptr::eq(b, *winner))?;
+                    Some((i, j))
+                })
+                .expect("winning bid is from a bid set")
+        })
+        .collect::<Vec<_>>();
+    let payments = result
+        .payments
+        .iter()
+        .map(|(name, payment)| ((*name).clone(), *payment))
+        .collect::<Vec<_>>();
+    if winners != fixture.winners {
+        return Err(format!(
+            "expected winners {:?}, found {winners:?}",
+            fixture.winners
+        ));
+    }
+    if payments != fixture.payments {
+        return Err(format!(
+            "expected payments {:?}, found {payments:?}",
+            fixture.payments
+        ));
+    }
+    Ok(())
+}
+
+#[test]
+fn fixtures_have_their_expected_results() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut paths = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert!(!paths.is_empty(), "no fixtures in {}", dir.display());
+
+    let failures = paths
+        .iter()
+        .filter_map(|path| {
+            let json = fs::read_to_string(path).unwrap();
+            let error =
+                parse(&json).and_then(|fixture| check(&fixture)).err()?;
+            Some(format!("{}: {error}", path.display()))
+        })
+        .collect::<Vec<_>>();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,85 @@
+//! Tests for the freight procurement preset.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::freight::{Lane, LaneError, LaneMarket};
+
+fn lane(id: &str, volume: u64, reserve_rate: u64) -> Lane {
+    Lane {
+        id: id.into(),
+        volume,
+        reserve_rate,
+    }
+}
+
+#[test]
+fn capacity_limits_each_carrier() {
+    let mut market = LaneMarket::new([
+        lane("A", 10, 100),
+        lane("B", 10, 100),
+        lane("C", 10, 50),
+    ]);
+    market.capacity("Xpress", 10);
+    let bids = vec![
+        vec![market.package("Xpress", 600, [("A", 10)]).unwrap()],
+        vec![market.package("Xpress", 600, [("B", 10)]).unwrap()],
+        vec![market.package("Yard", 800, [("A", 10)]).unwrap()],
+    ];
+    let awards = market.award(&bids, |_| 0).unwrap();
+    // Xpress can only move one lane, so Yard takes A
+    let carriers = awards
+        .carriers
+        .iter()
+        .map(|award| {
+            (
+                award.carrier.as_str(),
+                award.packages.clone(),
+                award.cost,
+                award.compensation,
+            )
+        })
+        .collect::<Vec<_>>();
+    // without either carrier, the other moves one lane and the rest goes at
+    // the reserve rate, costing 1,600 rather than 1,400
+    assert_eq!(
+        carriers,
+        [
+            ("Xpress", vec![&bids[1][0]], 600, 1_000),
+            ("Yard", vec![&bids[2][0]], 800, 1_000),
+        ]
+    );
+    assert_eq!(awards.unawarded, [("C", 10)]);
+    assert_eq!(awards.total_cost, 1_000 + 1_000 + 500);
+}
+
+#[test]
+fn packages_are_checked() {
+    let mut market = LaneMarket::new([lane("A", 10, 100)]);
+    market.capacity("Xpress", 5);
+    assert_eq!(
+        market.package("Xpress", 100, [("B", 5)]),
+        Err(LaneError::UnknownLane("B".into()))
+    );
+    assert_eq!(
+        market.package("Xpress", 100, [("A", 0)]),
+        Err(LaneError::EmptyPackage)
+    );
+    assert_eq!(
+        market.package("Xpress", 100, [("A", 6), ("A", 6)]),
+        Err(LaneError::OverVolume {
+            lane: "A".into(),
+            requested: 12,
+            volume: 10,
+        })
+    );
+    assert_eq!(
+        market.package("Xpress", 500, [("A", 5)]),
+        Err(LaneError::AboveReserve {
+            cost: 500,
+            reserve: 500,
+        })
+    );
+    let package = market.package("Xpress", 300, [("A", 5)]).unwrap();
+    assert_eq!(package.savings, 200);
+    assert_eq!(package.lanes().collect::<Vec<_>>(), [("A", 5)]);
+}
@@ -0,0 +1,90 @@
+//! Tests for generating bids on demand with `generate_bid_sets`.
+
+#![cfg(feature = "lp")]
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    generate_bid_sets, types::SimpleBid, vcg_auction_with_tiebreaker,
+};
+
+const ITEMS: [&str; 8] = [
+    "chair", "table", "lamp", "sofa", "desk", "shelf", "stool", "rug",
+];
+
+/// Value of each item to each bidder, all different so there are no ties.
+fn values(bidder: u64) -> Vec<u64> {
+    (0..ITEMS.len() as u64)
+        .map(|item| 10 + (bidder * 7 + item * 5) % 13 + bidder * 20)
+        .collect()
+}
+
+/// Winning bidders and items, with payments, comparable across runs with
+/// different bid objects.
+type Summary = (Vec<(String, Vec<(String, u64)>)>, Vec<(String, u64)>);
+
+fn run(items: &[(String, u64)], bid_sets: &[Vec<SimpleBid>]) -> Summary {
+    let result = vcg_auction_with_tiebreaker(items, bid_sets, |_| 0).unwrap();
+    let winners = result
+        .winning_bids
+        .iter()
+        .map(|bid| (bid.name.clone(), bid.items.clone()))
+        .collect();
+    let payments = result
+        .payments
+        .iter()
+        .map(|(name, payment)| (name.to_string(), *payment))
+        .collect();
+    (winners, payments)
+}
+
+#[test]
+fn unit_demand_matches_every_bid() {
+    let items = ITEMS
+        .iter()
+        .map(|item| (item.to_string(), 1))
+        .collect::<Vec<_>>();
+    let bidders = (0..4)
+        .map(|bidder| {
+            // each bidder wants the item worth the most less its price
+            move |prices: &[(&String, f64)]| {
+                let surplus =
+                    |(price, value): &(f64, u64)| *value as f64 - price;
+                let (item, (_, value)) = prices
+                    .iter()
+                    .map(|(item, price)| (item, *price))
+                    .zip(values(bidder))
+                    .map(|((item, price), value)| (item, (price, value)))
+                    .max_by(|a, b| surplus(&a.1).total_cmp(&surplus(&b.1)))?;
+                Some(SimpleBid::new(
+                    bidder.to_string(),
+                    value,
+                    [(item.as_str(), 1)],
+                ))
+            }
+        })
+        .collect::<Vec<_>>();
+    let generated = generate_bid_sets(&items, &bidders);
+    let every_bid = (0..4)
+        .map(|bidder| {
+            ITEMS
+                .iter()
+                .zip(values(bidder))
+                .map(|(item, value)| {
+                    SimpleBid::new(bidder.to_string(), value, [(*item, 1)])
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(run(&items, &generated), run(&items, &every_bid));
+    let n_generated = generated.iter().map(Vec::len).sum::<usize>();
+    assert!(n_generated < 32, "generated {n_generated} bids");
+}
+
+#[test]
+fn bidders_without_bids() {
+    let items = vec![("chair".to_string(), 1)];
+    let bidders = [|_: &[(&String, f64)]| None::<SimpleBid>];
+    assert_eq!(generate_bid_sets(&items, &bidders), [vec![]]);
+}
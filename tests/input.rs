@@ -0,0 +1,80 @@
+//! Tests for the collections accepted as items and bid sets.
+
+use std::collections::{BTreeMap, HashMap};
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{types::SimpleBid, vcg_auction_with_tiebreaker};
+
+fn alice_bids() -> Vec<SimpleBid> {
+    vec![
+        SimpleBid::new("Alice", 5, [("chair", 1)]),
+        SimpleBid::new("Alice", 7, [("chair", 2)]),
+    ]
+}
+
+fn bob_bids() -> Vec<SimpleBid> {
+    vec![SimpleBid::new("Bob", 4, [("chair", 1)])]
+}
+
+#[test]
+fn items_from_maps() {
+    let bids = vec![alice_bids(), bob_bids()];
+    let expected =
+        vcg_auction_with_tiebreaker(&[("chair".to_string(), 2)], &bids, |_| 0)
+            .unwrap();
+    let hash_map = HashMap::from([("chair".to_string(), 2)]);
+    let btree_map = BTreeMap::from([("chair".to_string(), 2)]);
+    assert_eq!(
+        vcg_auction_with_tiebreaker(&hash_map, &bids, |_| 0).unwrap(),
+        expected
+    );
+    assert_eq!(
+        vcg_auction_with_tiebreaker(&btree_map, &bids, |_| 0).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn bid_sets_from_borrowed_slices() {
+    let items = vec![("chair".into(), 2)];
+    let alice = alice_bids();
+    let bob = bob_bids();
+    let bid_sets: [&[SimpleBid]; 2] = [&alice, &bob];
+    let result = vcg_auction_with_tiebreaker(&items, &bid_sets, |_| 0).unwrap();
+    assert_eq!(result.winning_bids, [&alice[0], &bob[0]]);
+    assert_eq!(result.payments, [(&"Alice".into(), 0), (&"Bob".into(), 2)]);
+}
+
+#[test]
+fn bid_sets_from_arrays() {
+    let items = vec![("chair".into(), 2)];
+    let bid_sets = [
+        [SimpleBid::new("Alice", 5, [("chair", 1)])],
+        [SimpleBid::new("Bob", 4, [("chair", 1)])],
+    ];
+    let result = vcg_auction_with_tiebreaker(&items, &bid_sets, |_| 0).unwrap();
+    assert_eq!(result.winning_bids, [&bid_sets[0][0], &bid_sets[1][0]]);
+}
+
+#[test]
+fn bid_sets_from_flat_storage() {
+    let items = vec![("chair".into(), 4)];
+    // every bidder places two mutually-exclusive bids
+    let bids = ["Alice", "Bob", "Carol"]
+        .into_iter()
+        .enumerate()
+        .flat_map(|(i, name)| {
+            let value = i as u64 + 1;
+            [
+                SimpleBid::new(name, value, [("chair", 1)]),
+                SimpleBid::new(name, value * 3, [("chair", 2)]),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let nested = bids.chunks(2).map(<[_]>::to_vec).collect::<Vec<_>>();
+    let result = vcg_auction_with_tiebreaker(&items, bids.chunks(2), |_| 0);
+    assert_eq!(result.as_ref().unwrap().winning_bids, [&bids[3], &bids[5]]);
+    // results compare the bids by value
+    assert_eq!(result, vcg_auction_with_tiebreaker(&items, &nested, |_| 0));
+}
@@ -0,0 +1,156 @@
+//! Tests for generating random auctions with `InstanceShape`.
+
+#![cfg(feature = "rng")]
+
+use pretty_assertions::assert_eq;
+use rand::{distributions::Distribution, rngs::StdRng, SeedableRng};
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_dry_run, AuctionConfig, AuctionOutcome,
+    InstanceShape, Tiebreak,
+};
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+use vcg_auction::{
+    vcg_auction_with_tiebreaker, verify_result, AuctionInstance,
+};
+
+/// Whether an auction is solved with a result that verifies.
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+fn solves(auction: &AuctionInstance) -> bool {
+    let (items, bid_sets) = (&auction.items, &auction.bid_sets);
+    vcg_auction_with_tiebreaker(items, bid_sets, |_| 0)
+        .is_some_and(|result| verify_result(items, bid_sets, &result).is_ok())
+}
+
+/// Number of bids in all of an auction's bid sets.
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+fn bids(auction: &AuctionInstance) -> usize {
+    auction.bid_sets.iter().map(Vec::len).sum()
+}
+
+#[test]
+fn instances_are_valid_and_within_their_shape() {
+    let shape = InstanceShape {
+        max_items: 3,
+        max_stock: 2,
+        max_bidders: 2,
+        max_bid_sets: 5,
+        max_bids_per_set: 2,
+        max_items_per_bid: 2,
+        max_quantity: 3,
+        max_value: 10,
+    };
+    let mut rng = StdRng::seed_from_u64(0);
+    for auction in shape.sample_iter(&mut rng).take(500) {
+        assert!(auction.items.len() <= 3);
+        assert!(auction.items.iter().all(|(_, q)| (1..=2).contains(q)));
+        assert!(auction.bid_sets.len() <= 5);
+        for bid_set in &auction.bid_sets {
+            assert!(bid_set.len() <= 2);
+            assert!(bid_set.iter().all(|b| b.name == bid_set[0].name));
+            for bid in bid_set {
+                assert!(bid.value <= 10);
+                assert!(bid.items.len() <= 2);
+                assert_eq!(bid.items.is_empty(), auction.items.is_empty());
+                assert!(bid.items.iter().all(|(_, q)| (1..=3).contains(q)));
+            }
+        }
+        let bidders = auction
+            .bid_sets
+            .iter()
+            .flatten()
+            .map(|b| b.name.as_str())
+            .filter(|name| !["bidder0", "bidder1"].contains(name))
+            .count();
+        assert_eq!(bidders, 0);
+        let config = AuctionConfig::<SimpleBid>::default();
+        assert!(vcg_auction_dry_run(
+            &auction.items,
+            &auction.bid_sets,
+            &config
+        )
+        .is_ok());
+    }
+}
+
+#[test]
+fn random_auctions_pass_checked_searches() {
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::First)
+        .check_invariants()
+        .verify_payments()
+        .build();
+    let mut rng = StdRng::seed_from_u64(1);
+    for auction in InstanceShape::default().sample_iter(&mut rng).take(300) {
+        let outcome =
+            vcg_auction::run(&auction.items, &auction.bid_sets, &config);
+        assert!(matches!(outcome, Ok(AuctionOutcome::Complete(_))));
+    }
+}
+
+#[test]
+fn instances_are_reproducible_from_a_seed() {
+    let shape = InstanceShape::default();
+    let sample = |seed| shape.sample(&mut StdRng::seed_from_u64(seed));
+    assert_eq!(sample(7), sample(7));
+    let stock = shape.stock(&mut StdRng::seed_from_u64(7));
+    assert_eq!(sample(7).items, stock);
+}
+
+#[cfg(feature = "proptest")]
+proptest::proptest! {
+    #[test]
+    fn proptest_auctions_are_solved(
+        auction in proptest::prelude::any::<AuctionInstance>(),
+    ) {
+        proptest::prop_assert!(solves(&auction));
+    }
+}
+
+#[cfg(feature = "proptest")]
+#[test]
+fn proptest_failures_shrink_to_fewer_bids() {
+    use proptest::{
+        prelude::*,
+        test_runner::{TestError, TestRunner},
+    };
+
+    // fails with two bids or more
+    let mut runner = TestRunner::deterministic();
+    let result = runner.run(&any::<AuctionInstance>(), |auction| {
+        prop_assert!(bids(&auction) < 2);
+        Ok(())
+    });
+    let Err(TestError::Fail(_, minimal)) = result else {
+        panic!("property should fail");
+    };
+    assert_eq!(bids(&minimal), 2);
+    let bid = any::<SimpleBid>().new_tree(&mut runner).unwrap().current();
+    assert!(bid.value <= InstanceShape::default().max_value);
+}
+
+#[cfg(feature = "quickcheck")]
+#[test]
+fn quickcheck_auctions_are_solved() {
+    quickcheck::quickcheck(
+        (|auction: AuctionInstance| solves(&auction)) as fn(_) -> bool,
+    );
+}
+
+#[cfg(feature = "quickcheck")]
+#[test]
+fn quickcheck_auctions_shrink_by_bid_sets_and_bids() {
+    use quickcheck::{Arbitrary, Gen};
+
+    let mut g = Gen::new(100);
+    for _ in 0..100 {
+        let auction = AuctionInstance::arbitrary(&mut g);
+        for smaller in auction.shrink() {
+            assert_eq!(smaller.items, auction.items);
+            let dropped_bid_set =
+                smaller.bid_sets.len() + 1 == auction.bid_sets.len();
+            assert!(dropped_bid_set || bids(&smaller) + 1 == bids(&auction));
+            assert!(solves(&smaller));
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Tests for the node and tied outcome limits of a search.
+
+use std::task::Poll;
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_with_options, AuctionError, AuctionOutcome,
+    ResourceLimit, SearchOptions, Solver,
+};
+
+/// Every bidder bids on every item independently, so the search explores a
+/// very large number of nodes.
+fn square_bids(n: u64) -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = (0..n).map(|i| (i.to_string(), 1)).collect();
+    let bids = (0..n)
+        .map(|j| {
+            (0..n)
+                .map(|i| {
+                    let value = (i * 7 + j * 3) % 10 + 1;
+                    SimpleBid::new(j.to_string(), value, [(i.to_string(), 1)])
+                })
+                .collect()
+        })
+        .collect();
+    (items, bids)
+}
+
+/// Four bidders tied for a single chair.
+fn tied_bids() -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = vec![("chair".into(), 1)];
+    let bids = ["Alice", "Bob", "Carol", "Dave"]
+        .into_iter()
+        .map(|name| vec![SimpleBid::new(name, 5, [("chair", 1)])])
+        .collect();
+    (items, bids)
+}
+
+#[test]
+fn node_limit() {
+    let (items, bids) = square_bids(12);
+    let options = SearchOptions {
+        max_nodes: Some(1000),
+        ..Default::default()
+    };
+    let result = vcg_auction_with_options(&items, &bids, |_| 0, &options);
+    assert_eq!(
+        result,
+        Err(AuctionError::ResourceLimit(ResourceLimit::Nodes))
+    );
+}
+
+#[test]
+fn node_limit_not_reached() {
+    let (items, bids) = square_bids(3);
+    let options = SearchOptions {
+        max_nodes: Some(100_000),
+        ..Default::default()
+    };
+    let result = vcg_auction_with_options(&items, &bids, |_| 0, &options);
+    let unlimited =
+        vcg_auction_with_options(&items, &bids, |_| 0, &Default::default());
+    assert!(matches!(result, Ok(AuctionOutcome::Complete(_))));
+    assert_eq!(result, unlimited);
+}
+
+#[test]
+fn tied_outcome_limit() {
+    let (items, bids) = tied_bids();
+    let options = |max_tied_outcomes| SearchOptions {
+        max_tied_outcomes: Some(max_tied_outcomes),
+        ..Default::default()
+    };
+    let result = vcg_auction_with_options(&items, &bids, |_| 0, &options(3));
+    assert_eq!(
+        result,
+        Err(AuctionError::ResourceLimit(ResourceLimit::TiedOutcomes))
+    );
+    let result = vcg_auction_with_options(&items, &bids, |_| 0, &options(4));
+    assert!(matches!(result, Ok(AuctionOutcome::Complete(_))));
+}
+
+#[test]
+fn size_limits() {
+    let (items, bids) = square_bids(4);
+    let options = |max_bid_sets, max_bids_per_set| SearchOptions {
+        max_bid_sets: Some(max_bid_sets),
+        max_bids_per_set: Some(max_bids_per_set),
+        ..Default::default()
+    };
+    let result = vcg_auction_with_options(&items, &bids, |_| 0, &options(3, 4));
+    assert_eq!(
+        result,
+        Err(AuctionError::ResourceLimit(ResourceLimit::BidSets))
+    );
+    let result = vcg_auction_with_options(&items, &bids, |_| 0, &options(4, 3));
+    assert_eq!(
+        result,
+        Err(AuctionError::ResourceLimit(ResourceLimit::BidsPerSet))
+    );
+    let result = vcg_auction_with_options(&items, &bids, |_| 0, &options(4, 4));
+    assert!(matches!(result, Ok(AuctionOutcome::Complete(_))));
+
+    // solvers fail on their first step, without searching
+    let mut solver = Solver::with_options(&items, &bids, |_| 0, &options(3, 4));
+    assert_eq!(
+        solver.step(u64::MAX),
+        Poll::Ready(Err(AuctionError::ResourceLimit(ResourceLimit::BidSets)))
+    );
+    assert_eq!(solver.nodes_explored(), 0);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn parallel_limits() {
+    use vcg_auction::vcg_auction_parallel_with_options;
+
+    let (items, bids) = square_bids(12);
+    let options = SearchOptions {
+        max_nodes: Some(1000),
+        ..Default::default()
+    };
+    let result =
+        vcg_auction_parallel_with_options(&items, &bids, |_| 0, &options);
+    assert_eq!(
+        result,
+        Err(AuctionError::ResourceLimit(ResourceLimit::Nodes))
+    );
+
+    let (items, bids) = tied_bids();
+    let options = SearchOptions {
+        max_tied_outcomes: Some(3),
+        ..Default::default()
+    };
+    let result =
+        vcg_auction_parallel_with_options(&items, &bids, |_| 0, &options);
+    assert_eq!(
+        result,
+        Err(AuctionError::ResourceLimit(ResourceLimit::TiedOutcomes))
+    );
+}
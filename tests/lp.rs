@@ -0,0 +1,90 @@
+//! Tests that pruning with the linear programming relaxation produces the
+//! same results as the plain search, including the order of tied outcomes
+//! given to the tiebreaker.
+
+#![cfg(all(feature = "lp", feature = "rand"))]
+
+mod common;
+
+use std::cell::RefCell;
+
+use pretty_assertions::assert_eq;
+use rand::{distributions::Distribution, rngs::StdRng, Rng, SeedableRng};
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_lp, vcg_auction_with_options,
+    vcg_auction_with_tiebreaker, AuctionError, AuctionInstance, InstanceShape,
+    ResourceLimit, SearchOptions,
+};
+
+use common::{recording_tiebreaker, tied_shape};
+
+#[test]
+fn lp_matches_search() {
+    // bids on one or two items
+    let shape = InstanceShape {
+        max_items_per_bid: 2,
+        ..tied_shape()
+    };
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..200 {
+        let AuctionInstance {
+            items,
+            bid_sets: bids,
+        } = shape.sample(&mut rng);
+        let pick = rng.gen::<usize>();
+        let search_ties = RefCell::new(vec![]);
+        let lp_ties = RefCell::new(vec![]);
+        let search = vcg_auction_with_tiebreaker(
+            &items,
+            &bids,
+            recording_tiebreaker(&search_ties, pick),
+        );
+        let lp = vcg_auction_lp(
+            &items,
+            &bids,
+            recording_tiebreaker(&lp_ties, pick),
+            &SearchOptions::default(),
+        )
+        .unwrap()
+        .complete();
+        assert_eq!(search, lp);
+        assert_eq!(search_ties, lp_ties);
+    }
+}
+
+/// Twice as many bidders as chairs, each wanting one, so only half the bids
+/// can win but the sum of the highest bids counts them all.
+fn congested_bids() -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = vec![("chair".to_string(), 12)];
+    let bids = (0..24)
+        .map(|bidder| {
+            let value = 100 + bidder;
+            vec![SimpleBid::new(bidder.to_string(), value, [("chair", 1)])]
+        })
+        .collect();
+    (items, bids)
+}
+
+#[test]
+fn lp_prunes_congested_searches() {
+    let (items, bids) = congested_bids();
+    let options = SearchOptions {
+        max_nodes: Some(200_000),
+        ..Default::default()
+    };
+    let result = vcg_auction_lp(&items, &bids, |_| 0, &options)
+        .unwrap()
+        .complete()
+        .unwrap();
+    assert_eq!(
+        result.winning_bids,
+        bids[12..].iter().map(|bs| &bs[0]).collect::<Vec<_>>()
+    );
+    // every winner pays the highest losing bid
+    assert!(result.payments.iter().all(|(_, payment)| *payment == 111));
+    assert_eq!(
+        vcg_auction_with_options(&items, &bids, |_| 0, &options).err(),
+        Some(AuctionError::ResourceLimit(ResourceLimit::Nodes))
+    );
+}
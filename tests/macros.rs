@@ -0,0 +1,47 @@
+//! Tests for the `bids!` and `auction!` macros.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    auction, bids, types::SimpleBid, vcg_auction_with_tiebreaker,
+};
+
+#[test]
+fn bids_macro() {
+    let bids = bids![
+        {
+            ("Alice", 5, [("chair", 1)]),
+            ("Alice", 10, [("chair", 1), ("table", 1)]),
+        },
+        {},
+        { ("Bob", 4, [("chair", 1)]) },
+    ];
+    assert_eq!(
+        bids,
+        [
+            vec![
+                SimpleBid::new("Alice", 5, [("chair", 1)]),
+                SimpleBid::new("Alice", 10, [("chair", 1), ("table", 1)]),
+            ],
+            vec![],
+            vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        ]
+    );
+    let empty: Vec<Vec<SimpleBid>> = bids![];
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn auction_macro() {
+    let (items, bids) = auction! {
+        items: [("apple", 2)],
+        bids: [
+            { ("Alice", 5, [("apple", 1)]) },
+            { ("Bob", 2, [("apple", 1)]) },
+            { ("Carol", 6, [("apple", 2)]) },
+        ],
+    };
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    assert_eq!(result.winning_bids, [&bids[0][0], &bids[1][0]]);
+    assert_eq!(result.payments, [(&"Alice".into(), 4), (&"Bob".into(), 1)]);
+}
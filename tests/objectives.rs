@@ -0,0 +1,87 @@
+//! Tests for choosing between tied outcomes by secondary objectives.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    types::SimpleBid, AuctionConfig, AuctionResult, Objective, PricingRule,
+    Tiebreak,
+};
+
+/// Alice's bid for both chairs ties with Bob's and Carol's for one each.
+fn tied_bids() -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = vec![("chair".into(), 2), ("table".into(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 2)])],
+        vec![
+            SimpleBid::new("Bob", 5, [("chair", 1)]),
+            SimpleBid::new("Bob", 5, [("chair", 1), ("table", 1)]),
+        ],
+        vec![SimpleBid::new("Carol", 5, [("chair", 1)])],
+        vec![SimpleBid::new("Dave", 2, [("chair", 1)])],
+    ];
+    (items, bids)
+}
+
+fn run<'a>(
+    items: &[(String, u64)],
+    bids: &'a [Vec<SimpleBid>],
+    pricing: PricingRule,
+    objectives: &[Objective],
+) -> AuctionResult<'a, SimpleBid> {
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::First)
+        .pricing(pricing)
+        .objectives(objectives)
+        .build();
+    vcg_auction::run(items, bids, &config)
+        .unwrap()
+        .complete()
+        .unwrap()
+}
+
+#[test]
+fn objectives_choose_between_tied_outcomes() {
+    let (items, bids) = tied_bids();
+    let vcg = PricingRule::Vcg;
+    // Bob's bid with the table gives him as much as Alice
+    let result = run(&items, &bids, vcg, &[Objective::MinConcentration]);
+    assert_eq!(result.winning_bids, [&bids[1][0], &bids[2][0]]);
+
+    let result = run(&items, &bids, vcg, &[Objective::MaxUtilization]);
+    assert_eq!(result.winning_bids, [&bids[1][1], &bids[2][0]]);
+
+    // objectives apply in order
+    let objectives = [Objective::MaxUtilization, Objective::MinConcentration];
+    let result = run(&items, &bids, vcg, &objectives);
+    assert_eq!(result.winning_bids, [&bids[1][1], &bids[2][0]]);
+}
+
+#[test]
+fn min_payments_uses_the_pricing_rule() {
+    let (items, bids) = tied_bids();
+    // no rejected bid is for both chairs, so Alice pays nothing, while Bob
+    // and Carol each pay Dave's 2
+    let pricing = PricingRule::FirstRejectedBid;
+    let result = run(&items, &bids, pricing, &[Objective::MinPayments]);
+    assert_eq!(result.winning_bids, [&bids[0][0]]);
+    assert_eq!(result.payments, [(&"Alice".into(), 0)]);
+}
+
+#[test]
+fn tiebreak_chooses_between_outcomes_left() {
+    let (items, bids) = tied_bids();
+    let chooser = |tied: &[Vec<&SimpleBid>]| {
+        assert_eq!(tied, [vec![&bids[1][0], &bids[2][0]]]);
+        0
+    };
+    let objectives = [Objective::MinConcentration];
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::Custom(&chooser))
+        .objectives(&objectives)
+        .build();
+    let outcome = vcg_auction::run(&items, &bids, &config).unwrap();
+    assert_eq!(
+        outcome.complete().unwrap().winning_bids,
+        [&bids[1][0], &bids[2][0]]
+    );
+}
@@ -0,0 +1,159 @@
+//! Tests for pricing OpenRTB-style bid responses.
+
+#![cfg(feature = "openrtb")]
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    openrtb::{
+        BidRequest, BidResponse, ClearingPrice, DroppedResponseBid, Imp,
+        ResponseBid, RtbAuction, SeatBid,
+    },
+    vcg_auction_with_tiebreaker, DropReason,
+};
+
+fn request(imps: &[(&str, f64)]) -> BidRequest {
+    BidRequest {
+        id: "request".into(),
+        imp: imps
+            .iter()
+            .map(|(id, bidfloor)| Imp {
+                id: id.to_string(),
+                bidfloor: *bidfloor,
+            })
+            .collect(),
+    }
+}
+
+fn response(seat: &str, group: u8, bids: &[(&str, f64)]) -> BidResponse {
+    BidResponse {
+        id: format!("{seat}-response"),
+        seatbid: vec![SeatBid {
+            seat: seat.into(),
+            group,
+            bid: bids
+                .iter()
+                .enumerate()
+                .map(|(i, (impid, price))| ResponseBid {
+                    id: format!("{seat}-{i}"),
+                    impid: impid.to_string(),
+                    price: *price,
+                })
+                .collect(),
+        }],
+    }
+}
+
+fn clearing_prices(
+    request: &BidRequest,
+    responses: &[BidResponse],
+) -> Vec<ClearingPrice> {
+    let auction = RtbAuction::new(request, responses);
+    let result =
+        vcg_auction_with_tiebreaker(auction.items(), auction.bid_sets(), |_| 0)
+            .unwrap();
+    auction.clearing_prices(&result)
+}
+
+fn price(seat: &str, bid: usize, impid: &str, price: f64) -> ClearingPrice {
+    ClearingPrice {
+        response_id: format!("{seat}-response"),
+        seat: seat.into(),
+        bid_id: format!("{seat}-{bid}"),
+        impid: impid.into(),
+        price,
+    }
+}
+
+#[test]
+fn winners_pay_the_second_price_or_the_floor() {
+    let request = request(&[("banner", 0.5), ("video", 3.0)]);
+    let responses = [
+        response("dsp-a", 0, &[("banner", 2.0), ("video", 4.0)]),
+        response("dsp-b", 0, &[("banner", 1.25)]),
+    ];
+    assert_eq!(
+        clearing_prices(&request, &responses),
+        [
+            price("dsp-a", 0, "banner", 1.25),
+            price("dsp-a", 1, "video", 3.0),
+        ]
+    );
+}
+
+#[test]
+fn group_payments_are_split_by_price() {
+    let request = request(&[("banner", 0.0), ("video", 0.0)]);
+    let responses = [
+        response("dsp-a", 1, &[("banner", 1.0), ("video", 3.0)]),
+        response("dsp-b", 0, &[("banner", 0.5)]),
+        response("dsp-c", 0, &[("video", 1.5)]),
+    ];
+    // the group displaces bids worth 2.0, paid a quarter on the banner
+    assert_eq!(
+        clearing_prices(&request, &responses),
+        [
+            price("dsp-a", 0, "banner", 0.5),
+            price("dsp-a", 1, "video", 1.5),
+        ]
+    );
+}
+
+#[test]
+fn invalid_bids_are_dropped() {
+    let request = request(&[("banner", 1.0)]);
+    let responses = [
+        response("below-floor", 0, &[("banner", 0.75)]),
+        response("unknown-imp", 0, &[("video", 5.0)]),
+        response("not-finite", 0, &[("banner", f64::NAN)]),
+        // a group with an invalid bid is dropped as a whole
+        response("group", 1, &[("banner", 4.0), ("video", 4.0)]),
+        response("repeats", 1, &[("banner", 2.0), ("banner", 2.0)]),
+        response("dsp", 0, &[("banner", 1.5)]),
+    ];
+    let auction = RtbAuction::new(&request, &responses);
+    // the floor and the one valid bid
+    assert_eq!(auction.bid_sets().len(), 2);
+    assert_eq!(
+        clearing_prices(&request, &responses),
+        [price("dsp", 0, "banner", 1.0)]
+    );
+}
+
+#[test]
+fn dropped_bids_are_reported() {
+    let request = request(&[("banner", 1.0)]);
+    let responses = [
+        response("below-floor", 0, &[("banner", 0.75)]),
+        response("unknown-imp", 0, &[("video", 5.0)]),
+        response("not-finite", 0, &[("banner", f64::NAN)]),
+        response("group", 1, &[("banner", 4.0), ("video", 4.0)]),
+        response("repeats", 1, &[("banner", 2.0), ("banner", 2.0)]),
+        response("dsp", 0, &[("banner", 1.5)]),
+    ];
+    let dropped =
+        |seat: &str, bid: usize, impid: &str, reason| DroppedResponseBid {
+            response_id: format!("{seat}-response"),
+            seat: seat.into(),
+            bid_id: format!("{seat}-{bid}"),
+            impid: impid.into(),
+            reason,
+        };
+    let auction = RtbAuction::new(&request, &responses);
+    assert_eq!(
+        auction.dropped_bids(),
+        [
+            dropped("below-floor", 0, "banner", DropReason::BelowReserve),
+            dropped("unknown-imp", 0, "video", DropReason::UnknownItem),
+            dropped("not-finite", 0, "banner", DropReason::InvalidValue),
+            dropped("group", 0, "banner", DropReason::PartDropped),
+            dropped("group", 1, "video", DropReason::UnknownItem),
+            dropped("repeats", 0, "banner", DropReason::PartDropped),
+            dropped("repeats", 1, "banner", DropReason::RepeatedItem),
+        ]
+    );
+    assert_eq!(
+        DropReason::BelowReserve.to_string(),
+        "value is below the reserve price"
+    );
+}
@@ -0,0 +1,127 @@
+//! Tests that searching the bid sets in a heuristic order produces the same
+//! results as searching them in the order given, including the order of tied
+//! outcomes given to the tiebreaker.
+
+#![cfg(feature = "rand")]
+
+mod common;
+
+use pretty_assertions::assert_eq;
+use rand::{distributions::Distribution, rngs::StdRng, Rng, SeedableRng};
+
+use std::cell::RefCell;
+use std::task::Poll;
+
+use vcg_auction::{
+    run_with_stats, types::SimpleBid, AuctionConfig, AuctionInstance,
+    BidSetOrder, SearchOptions, Solver, Tiebreak,
+};
+
+use common::{
+    owned, recording_tiebreaker, run_recording_ties, tied_shape, Owned,
+};
+
+/// Every bidder wants the same chair, with the highest bids last.
+fn chair_bids() -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = vec![("chair".to_string(), 1)];
+    let bids = (0..12)
+        .map(|bidder| {
+            vec![SimpleBid::new(bidder.to_string(), bidder, [("chair", 1)])]
+        })
+        .collect();
+    (items, bids)
+}
+
+/// Solve an auction with a solver searching in `order`, returning the
+/// result, the tied outcomes given to the tiebreaker, and the nodes explored.
+fn solve(
+    items: &[(String, u64)],
+    bids: &[Vec<SimpleBid>],
+    pick: usize,
+    order: BidSetOrder,
+) -> (Owned, Vec<Vec<SimpleBid>>, u64) {
+    let ties = RefCell::new(vec![]);
+    let options = SearchOptions {
+        bid_set_order: order,
+        ..Default::default()
+    };
+    let mut solver = Solver::with_options(
+        items,
+        bids,
+        recording_tiebreaker(&ties, pick),
+        &options,
+    );
+    let result = loop {
+        if let Poll::Ready(result) = solver.step(3) {
+            break owned(result.unwrap());
+        }
+    };
+    let nodes = solver.nodes_explored();
+    drop(solver);
+    (result, ties.into_inner(), nodes)
+}
+
+#[test]
+fn heuristic_orders_match_the_input_order() {
+    let shape = tied_shape();
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..200 {
+        let AuctionInstance {
+            items,
+            bid_sets: bids,
+        } = shape.sample(&mut rng);
+        let pick = rng.gen::<usize>();
+        let run = |order| {
+            run_recording_ties(&items, &bids, pick, |c| c.bid_set_order(order))
+        };
+        let input = run(BidSetOrder::Input);
+        for order in [BidSetOrder::MaxValue, BidSetOrder::Contention] {
+            assert_eq!(run(order), input);
+        }
+    }
+}
+
+#[test]
+fn max_value_order_finds_the_highest_value_first() {
+    let (items, bids) = chair_bids();
+    let config = AuctionConfig::deterministic();
+    let (input, input_stats) = run_with_stats(&items, &bids, &config);
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::First)
+        .bid_set_order(BidSetOrder::MaxValue)
+        .build();
+    let (max_value, max_value_stats) = run_with_stats(&items, &bids, &config);
+
+    assert_eq!(input, max_value);
+    assert_eq!(input_stats.winner_determination.improvements, 11);
+    assert_eq!(max_value_stats.winner_determination.improvements, 1);
+    assert!(max_value_stats.nodes() < input_stats.nodes());
+}
+
+#[test]
+fn solvers_search_in_the_heuristic_order() {
+    let shape = tied_shape();
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..100 {
+        let AuctionInstance {
+            items,
+            bid_sets: bids,
+        } = shape.sample(&mut rng);
+        let pick = rng.gen::<usize>();
+        let (input, input_ties, _) =
+            solve(&items, &bids, pick, BidSetOrder::Input);
+        for order in [BidSetOrder::MaxValue, BidSetOrder::Contention] {
+            let (result, ties, _) = solve(&items, &bids, pick, order);
+            assert_eq!(result, input);
+            assert_eq!(ties, input_ties);
+        }
+    }
+
+    // searching the highest bids first prunes the rest
+    let (items, bids) = chair_bids();
+    let (input, _, input_nodes) = solve(&items, &bids, 0, BidSetOrder::Input);
+    let (max_value, _, max_value_nodes) =
+        solve(&items, &bids, 0, BidSetOrder::MaxValue);
+    assert_eq!(max_value, input);
+    assert!(max_value_nodes < input_nodes);
+}
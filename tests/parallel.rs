@@ -0,0 +1,147 @@
+//! Tests that the parallel search produces the same results as the serial
+//! search, including the order of tied outcomes given to the tiebreaker, and
+//! runs on the configured thread pool.
+
+#![cfg(all(feature = "rayon", feature = "rand"))]
+
+mod common;
+
+use std::cell::RefCell;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use pretty_assertions::assert_eq;
+use rand::{distributions::Distribution, rngs::StdRng, Rng, SeedableRng};
+use rayon::ThreadPoolBuilder;
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_parallel, vcg_auction_parallel_with_options,
+    vcg_auction_with_tiebreaker, AuctionInstance, BidSetOrder, Progress,
+    SearchOptions,
+};
+
+use common::{recording_tiebreaker, tied_shape};
+
+#[test]
+fn parallel_matches_serial() {
+    let shape = tied_shape();
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..50 {
+        let AuctionInstance {
+            items,
+            bid_sets: bids,
+        } = shape.sample(&mut rng);
+        let pick = rng.gen::<usize>();
+        let serial_ties = RefCell::new(vec![]);
+        let parallel_ties = RefCell::new(vec![]);
+        let serial = vcg_auction_with_tiebreaker(
+            &items,
+            &bids,
+            recording_tiebreaker(&serial_ties, pick),
+        );
+        let parallel = vcg_auction_parallel(
+            &items,
+            &bids,
+            recording_tiebreaker(&parallel_ties, pick),
+        );
+        assert_eq!(serial, parallel);
+        assert_eq!(serial_ties, parallel_ties);
+    }
+}
+
+#[test]
+fn parallel_searches_in_the_heuristic_order() {
+    let shape = tied_shape();
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..50 {
+        let AuctionInstance {
+            items,
+            bid_sets: bids,
+        } = shape.sample(&mut rng);
+        let pick = rng.gen::<usize>();
+        let serial_ties = RefCell::new(vec![]);
+        let serial = vcg_auction_with_tiebreaker(
+            &items,
+            &bids,
+            recording_tiebreaker(&serial_ties, pick),
+        );
+        for order in [BidSetOrder::MaxValue, BidSetOrder::Contention] {
+            let options = SearchOptions {
+                bid_set_order: order,
+                ..Default::default()
+            };
+            let parallel_ties = RefCell::new(vec![]);
+            let parallel = vcg_auction_parallel_with_options(
+                &items,
+                &bids,
+                recording_tiebreaker(&parallel_ties, pick),
+                &options,
+            );
+            assert_eq!(parallel.unwrap().complete(), serial);
+            assert_eq!(parallel_ties, serial_ties);
+        }
+    }
+}
+
+/// Every bidder bids on every item independently, which takes enough search
+/// nodes to report progress from the search threads.
+fn square_bids(n: u64) -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = (0..n).map(|i| (i.to_string(), 1)).collect();
+    let bids = (0..n)
+        .map(|j| {
+            (0..n)
+                .map(|i| {
+                    let value = (i * 7 + j * 3) % 10;
+                    SimpleBid::new(j.to_string(), value, [(i.to_string(), 1)])
+                })
+                .collect()
+        })
+        .collect();
+    (items, bids)
+}
+
+/// Run a parallel auction, returning the thread counts of the pools the
+/// search ran on.
+fn search_pool_sizes(options: SearchOptions<u64>) -> Vec<usize> {
+    let (items, bids) = square_bids(8);
+    let pool_sizes = Mutex::new(vec![]);
+    let record = |_: &Progress<u64>| {
+        pool_sizes
+            .lock()
+            .unwrap()
+            .push(rayon::current_num_threads());
+    };
+    let options = SearchOptions {
+        progress: Some(&record),
+        progress_interval: Duration::ZERO,
+        ..options
+    };
+    let serial = vcg_auction_with_tiebreaker(&items, &bids, |_| 0);
+    let parallel =
+        vcg_auction_parallel_with_options(&items, &bids, |_| 0, &options)
+            .unwrap()
+            .complete();
+    assert_eq!(serial, parallel);
+    pool_sizes.into_inner().unwrap()
+}
+
+#[test]
+fn injected_thread_pool() {
+    let pool = ThreadPoolBuilder::new().num_threads(3).build().unwrap();
+    let pool_sizes = search_pool_sizes(SearchOptions {
+        thread_pool: Some(&pool),
+        ..Default::default()
+    });
+    assert!(!pool_sizes.is_empty());
+    assert!(pool_sizes.iter().all(|&n| n == 3));
+}
+
+#[test]
+fn configured_thread_count() {
+    let pool_sizes = search_pool_sizes(SearchOptions {
+        num_threads: Some(2),
+        ..Default::default()
+    });
+    assert!(!pool_sizes.is_empty());
+    assert!(pool_sizes.iter().all(|&n| n == 2));
+}
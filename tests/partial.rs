@@ -0,0 +1,79 @@
+//! Tests for running auctions without their malformed bid sets.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    run_skipping_malformed, types::SimpleBid, AuctionConfig, AuctionError,
+    InputError, ResourceLimit, SkippedBidSet, Tiebreak,
+};
+
+#[test]
+fn malformed_bid_sets_are_skipped() {
+    let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 4, [("chair", 1)])],
+        vec![
+            SimpleBid::new("Bob", 9, [("chair", 1), ("chair", 1)]),
+            SimpleBid::new("Bob", 8, [("lamp", 1)]),
+        ],
+        vec![
+            SimpleBid::new("Carol", 6, [("table", 1)]),
+            SimpleBid::new("Carol", 5, [("chair", 1)]),
+            SimpleBid::new("Carol", 7, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Dave", 3, [("chair", 1)])],
+    ];
+    let config = AuctionConfig::builder()
+        .max_bids_per_set(2)
+        .tiebreak(Tiebreak::First)
+        .build();
+    assert_eq!(
+        vcg_auction::run(&items, &bids, &config).err(),
+        Some(AuctionError::ResourceLimit(ResourceLimit::BidsPerSet))
+    );
+
+    let partial = run_skipping_malformed(&items, &bids, &config).unwrap();
+    assert_eq!(
+        partial.skipped,
+        [
+            SkippedBidSet {
+                bid_set: 1,
+                errors: vec![
+                    AuctionError::Input(InputError::DuplicateBidItem {
+                        bid_set: 1,
+                        bid: 0,
+                    }),
+                    AuctionError::Input(InputError::UnknownItem {
+                        bid_set: 1,
+                        bid: 1,
+                    }),
+                ],
+            },
+            SkippedBidSet {
+                bid_set: 2,
+                errors: vec![AuctionError::ResourceLimit(
+                    ResourceLimit::BidsPerSet
+                )],
+            },
+        ]
+    );
+    let result = partial.outcome.complete().unwrap();
+    assert_eq!(result.winning_bids, [&bids[0][0], &bids[3][0]]);
+    assert_eq!(result.payments, [(&"Alice".into(), 0), (&"Dave".into(), 0)]);
+}
+
+#[test]
+fn well_formed_auctions_skip_nothing() {
+    let items = vec![("chair".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+    ];
+    let config = AuctionConfig::deterministic();
+    let partial = run_skipping_malformed(&items, &bids, &config).unwrap();
+    assert_eq!(partial.skipped, []);
+    assert_eq!(
+        partial.outcome.complete(),
+        vcg_auction::run(&items, &bids, &config).unwrap().complete()
+    );
+}
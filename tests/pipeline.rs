@@ -0,0 +1,80 @@
+//! Tests for auctions run in qualification rounds and a final round.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    run, run_pipeline, types::SimpleBid, AuctionConfig, Qualification,
+    RoundReport,
+};
+
+fn bids() -> Vec<Vec<SimpleBid>> {
+    vec![
+        vec![
+            SimpleBid::new("Alice", 4, [("chair", 1)]),
+            SimpleBid::new("Alice", 12, [("chair", 1), ("table", 1)]),
+        ],
+        vec![SimpleBid::new("Bob", 9, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 5, [("table", 1)])],
+    ]
+}
+
+#[test]
+fn without_rounds_the_pipeline_is_a_run() {
+    let items = vec![("chair".to_string(), 1), ("table".to_string(), 1)];
+    let bids = bids();
+    let config = AuctionConfig::deterministic();
+    let report = run_pipeline(&items, &bids, &[], &config).unwrap();
+    assert!(report.rounds.is_empty());
+    assert_eq!(report.outcome, run(&items, &bids, &config).unwrap());
+}
+
+#[test]
+fn rounds_screen_what_passed_the_rounds_before() {
+    let items = vec![("chair".to_string(), 1), ("table".to_string(), 1)];
+    let bids = bids();
+    let no_bundles = |bid: &SimpleBid| bid.items.len() == 1;
+    let rounds = [
+        Qualification::MinValue(5),
+        Qualification::Custom(&no_bundles),
+    ];
+    let config = AuctionConfig::deterministic();
+    let report = run_pipeline(&items, &bids, &rounds, &config).unwrap();
+    assert_eq!(
+        report.rounds,
+        [
+            RoundReport {
+                entered: 4,
+                disqualified: vec![(0, 0)],
+            },
+            RoundReport {
+                entered: 3,
+                disqualified: vec![(0, 1)],
+            },
+        ]
+    );
+    // Alice has no bids left, so Bob and Carol win uncontested
+    let result = report.outcome.complete().unwrap();
+    assert_eq!(result.winning_bids, [&bids[1][0], &bids[2][0]]);
+    assert_eq!(
+        result.payments,
+        [(&"Bob".to_string(), 0), (&"Carol".to_string(), 0)]
+    );
+}
+
+#[test]
+fn qualified_bids_of_a_bid_set_stay_exclusive() {
+    let items = vec![("chair".to_string(), 1), ("table".to_string(), 1)];
+    let mut bids = bids();
+    bids[0].push(SimpleBid::new("Alice", 8, [("table", 1)]));
+    let rounds = [Qualification::MinValue(6)];
+    let config = AuctionConfig::deterministic();
+    let report = run_pipeline(&items, &bids, &rounds, &config).unwrap();
+    assert_eq!(report.rounds[0].disqualified, [(0, 0), (2, 0)]);
+    // Alice wins her table, not her bundle as well, beside Bob's chair
+    let result = report.outcome.complete().unwrap();
+    assert_eq!(result.winning_bids, [&bids[0][2], &bids[1][0]]);
+    assert_eq!(
+        result.payments,
+        [(&"Alice".to_string(), 0), (&"Bob".to_string(), 4)]
+    );
+}
@@ -0,0 +1,98 @@
+//! Tests for the points market preset.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    points::{PointsError, PointsItem, PointsMarket},
+    vcg_auction_with_tiebreaker,
+};
+
+#[test]
+fn balances_bound_what_is_won() {
+    let mut market = PointsMarket::new([("gpu-hour", 4)]);
+    market
+        .balance("Alice", 30)
+        .balance("Bob", 25)
+        .balance("Carol", 5);
+    assert_eq!(
+        market.items(),
+        [
+            (PointsItem::Good("gpu-hour".into()), 4),
+            (PointsItem::Balance("Alice".into()), 30),
+            (PointsItem::Balance("Bob".into()), 25),
+            (PointsItem::Balance("Carol".into()), 5),
+        ]
+    );
+    let bids = vec![
+        vec![market.bid("Alice", 20, [("gpu-hour", 2)]).unwrap()],
+        vec![market.bid("Alice", 15, [("gpu-hour", 1)]).unwrap()],
+        vec![market.bid("Bob", 12, [("gpu-hour", 1)]).unwrap()],
+        vec![market.bid("Carol", 0, [("gpu-hour", 1)]).unwrap()],
+    ];
+    let cleared = market.clear(&bids, |_| 0).unwrap();
+    // Alice's 35 points of bids are over her balance, so she wins one
+    assert_eq!(
+        cleared.winning_bids,
+        [&bids[0][0], &bids[2][0], &bids[3][0]]
+    );
+    let result =
+        vcg_auction_with_tiebreaker(&market.items(), &bids, |_| 0).unwrap();
+    assert_eq!(cleared.payments, result.payments);
+    // Alice's balance keeps her from taking any more hours, so nobody pays
+    assert_eq!(
+        cleared.payments,
+        [
+            (&"Alice".to_string(), 0),
+            (&"Bob".to_string(), 0),
+            (&"Carol".to_string(), 0),
+        ]
+    );
+    assert_eq!(
+        cleared.balances,
+        [
+            (&"Alice".to_string(), 30),
+            (&"Bob".to_string(), 25),
+            (&"Carol".to_string(), 5),
+        ]
+    );
+}
+
+#[test]
+fn payments_are_taken_from_balances() {
+    let mut market = PointsMarket::new([("desk", 1)]);
+    market
+        .balance("Alice", 100)
+        .balance("Bob", 40)
+        .balance("Alice", 90);
+    let bids = vec![
+        vec![market.bid("Alice", 90, [("desk", 1)]).unwrap()],
+        vec![market.bid("Bob", 40, [("desk", 1)]).unwrap()],
+    ];
+    let cleared = market.clear(&bids, |_| 0).unwrap();
+    assert_eq!(cleared.payments, [(&"Alice".to_string(), 40)]);
+    assert_eq!(
+        cleared.balances,
+        [(&"Alice".to_string(), 50), (&"Bob".to_string(), 40)]
+    );
+}
+
+#[test]
+fn bids_are_checked() {
+    let mut market = PointsMarket::new([("desk", 1)]);
+    market.balance("Alice", 10);
+    assert_eq!(
+        market.bid("Bob", 5, [("desk", 1)]),
+        Err(PointsError::NoBalance("Bob".into()))
+    );
+    assert_eq!(
+        market.bid("Alice", 11, [("desk", 1)]),
+        Err(PointsError::OverBalance {
+            value: 11,
+            balance: 10
+        })
+    );
+    assert_eq!(
+        market.bid("Alice", 5, [("chair", 1)]),
+        Err(PointsError::UnknownGood("chair".into()))
+    );
+}
@@ -0,0 +1,119 @@
+//! Tests for the preset configurations run by name.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    presets::{self, Preset, PresetError},
+    types::SimpleBid,
+    AuctionError, InputError, PricingRule, Tiebreak,
+};
+
+#[test]
+fn presets_are_found_by_name() {
+    for preset in Preset::ALL {
+        assert_eq!(preset.name().parse::<Preset>(), Ok(preset));
+        assert_eq!(preset.to_string(), preset.name());
+    }
+    assert_eq!(
+        "english".parse::<Preset>(),
+        Err(PresetError::UnknownPreset("english".into()))
+    );
+    let config = Preset::MultiUnitUniform.config::<SimpleBid>();
+    assert_eq!(config.pricing, PricingRule::FirstRejectedBid);
+    assert!(matches!(config.tiebreak, Tiebreak::First));
+}
+
+#[test]
+fn multi_unit_uniform_charges_the_highest_rejected_bid() {
+    let items = vec![("bond".to_string(), 2)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("bond", 1)])],
+        vec![SimpleBid::new("Alice", 8, [("bond", 1)])],
+        vec![SimpleBid::new("Bob", 9, [("bond", 1)])],
+        vec![SimpleBid::new("Carol", 6, [("bond", 1)])],
+    ];
+    let result = presets::run("multi-unit-uniform", &items, &bids)
+        .unwrap()
+        .complete()
+        .unwrap();
+    assert_eq!(result.winning_bids, [&bids[0][0], &bids[2][0]]);
+    assert_eq!(
+        result.payments,
+        [(&"Alice".to_string(), 8), (&"Bob".to_string(), 8)]
+    );
+}
+
+#[test]
+fn combinatorial_vcg_runs_packages() {
+    let items = vec![("chair".to_string(), 1), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 15, [("chair", 1), ("table", 1)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 6, [("table", 1)])],
+    ];
+    let result = Preset::CombinatorialVcg
+        .run(&items, &bids)
+        .unwrap()
+        .complete()
+        .unwrap();
+    assert_eq!(result.winning_bids, [&bids[0][0]]);
+    assert_eq!(result.payments, [(&"Alice".to_string(), 13)]);
+}
+
+#[test]
+fn procurement_spreads_tied_awards() {
+    let items = vec![("lane-a".to_string(), 1), ("lane-b".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Acme", 10, [("lane-a", 1), ("lane-b", 1)])],
+        vec![SimpleBid::new("Bolt", 5, [("lane-a", 1)])],
+        vec![SimpleBid::new("Core", 5, [("lane-b", 1)])],
+    ];
+    let result = presets::run("procurement", &items, &bids)
+        .unwrap()
+        .complete()
+        .unwrap();
+    // Acme's package saves as much as Bolt's and Core's together
+    assert_eq!(result.winning_bids, [&bids[1][0], &bids[2][0]]);
+}
+
+#[test]
+fn input_a_preset_isnt_for_is_rejected() {
+    let items = vec![("painting".to_string(), 1), ("vase".to_string(), 1)];
+    let bids = vec![vec![SimpleBid::new("Alice", 10, [("painting", 1)])]];
+    assert_eq!(
+        Preset::SingleItemVickrey.validate(&items, &bids),
+        Err(PresetError::UnsupportedStock(Preset::SingleItemVickrey))
+    );
+
+    let items = vec![("bond".to_string(), 4)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("bond", 1)])],
+        vec![SimpleBid::new("Bob", 18, [("bond", 2)])],
+    ];
+    assert_eq!(
+        presets::run("multi-unit-uniform", &items, &bids).unwrap_err(),
+        PresetError::UnsupportedBid {
+            preset: Preset::MultiUnitUniform,
+            bid_set: 1,
+            bid: 0,
+        }
+    );
+
+    let bids = vec![vec![SimpleBid::new("Alice", 0, [("bond", 1)])]];
+    assert_eq!(
+        Preset::Procurement.validate(&items, &bids),
+        Err(PresetError::UnsupportedBid {
+            preset: Preset::Procurement,
+            bid_set: 0,
+            bid: 0,
+        })
+    );
+
+    let bids = vec![vec![SimpleBid::new("Alice", 5, [("sofa", 1)])]];
+    assert_eq!(
+        Preset::CombinatorialVcg.validate(&items, &bids),
+        Err(PresetError::Auction(AuctionError::Input(
+            InputError::UnknownItem { bid_set: 0, bid: 0 }
+        )))
+    );
+}
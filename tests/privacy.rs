@@ -0,0 +1,67 @@
+//! Tests for the noisy aggregates of auction results.
+#![cfg(feature = "rng")]
+
+use pretty_assertions::assert_eq;
+use rand::{rngs::StdRng, SeedableRng};
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_with_tiebreaker, AggregateNoise, Aggregates,
+};
+
+#[test]
+fn exact_aggregates_sum_the_result() {
+    let items = vec![
+        ("chair".to_string(), 3),
+        ("table".to_string(), 1),
+        ("sofa".to_string(), 1),
+    ];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 2), ("table", 1)])],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("chair", 2)])],
+    ];
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    let aggregates = Aggregates::of(&items, &result);
+    assert_eq!(aggregates.total_revenue, 3.0);
+    assert_eq!(
+        aggregates.allocated,
+        [
+            (&"chair".to_string(), 3.0),
+            (&"table".to_string(), 1.0),
+            (&"sofa".to_string(), 0.0),
+        ]
+    );
+}
+
+#[test]
+fn noise_is_reproducible_and_calibrated() {
+    let item = "chair".to_string();
+    let exact = Aggregates {
+        total_revenue: 100.0,
+        allocated: vec![(&item, 5.0)],
+    };
+    // two statistics, so each gets an epsilon of 1
+    let noise = AggregateNoise {
+        epsilon: 2.0,
+        revenue_sensitivity: 4.0,
+        quantity_sensitivity: 1.0,
+    };
+    let mut rng = StdRng::seed_from_u64(1);
+    assert_eq!(
+        exact.noisy(&noise, &mut rng),
+        exact.noisy(&noise, &mut StdRng::seed_from_u64(1))
+    );
+
+    // the mean absolute noise of a Laplace distribution is its scale
+    let draws = 20_000;
+    let (mut revenue, mut quantity) = (0.0, 0.0);
+    for _ in 0..draws {
+        let published = exact.noisy(&noise, &mut rng);
+        revenue += (published.total_revenue - 100.0).abs();
+        quantity += (published.allocated[0].1 - 5.0).abs();
+    }
+    let revenue = revenue / draws as f64;
+    let quantity = quantity / draws as f64;
+    assert!((3.8..4.2).contains(&revenue), "{revenue}");
+    assert!((0.95..1.05).contains(&quantity), "{quantity}");
+}
@@ -0,0 +1,67 @@
+//! Tests for progress reporting during long searches.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_with_options, Progress, SearchOptions,
+    SearchPhase,
+};
+
+/// Every bidder bids on every item independently, which takes enough search
+/// nodes to trigger progress reports.
+fn square_bids(n: u64) -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = (0..n).map(|i| (i.to_string(), 1)).collect();
+    let bids = (0..n)
+        .map(|j| {
+            (0..n)
+                .map(|i| {
+                    SimpleBid::new(
+                        j.to_string(),
+                        (i * 7 + j * 3) % 10,
+                        [(i.to_string(), 1)],
+                    )
+                })
+                .collect()
+        })
+        .collect();
+    (items, bids)
+}
+
+#[test]
+fn progress_reports() {
+    let (items, bids) = square_bids(6);
+    let reports = Mutex::new(vec![]);
+    let record = |progress: &Progress<u64>| {
+        assert!(progress.best_value <= progress.upper_bound);
+        reports
+            .lock()
+            .unwrap()
+            .push((progress.phase, progress.nodes));
+    };
+    let options = SearchOptions {
+        progress: Some(&record),
+        progress_interval: Duration::ZERO,
+        ..Default::default()
+    };
+    vcg_auction_with_options(&items, &bids, |_| 0, &options).unwrap();
+    let reports = reports.into_inner().unwrap();
+    assert!(!reports.is_empty());
+    assert_eq!(reports[0].0, SearchPhase::WinnerDetermination);
+    // node counts keep increasing across phases
+    assert!(reports.windows(2).all(|w| w[0].1 < w[1].1));
+}
+
+#[test]
+fn no_reports_within_interval() {
+    let (items, bids) = square_bids(6);
+    let reports = Mutex::new(0);
+    let record = |_: &Progress<u64>| *reports.lock().unwrap() += 1;
+    let options = SearchOptions {
+        progress: Some(&record),
+        progress_interval: Duration::from_secs(3600),
+        ..Default::default()
+    };
+    vcg_auction_with_options(&items, &bids, |_| 0, &options).unwrap();
+    assert_eq!(reports.into_inner().unwrap(), 0);
+}
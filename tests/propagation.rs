@@ -0,0 +1,131 @@
+//! Tests that dropping bids that can't win before searching doesn't change
+//! the results, including the order of tied outcomes given to the tiebreaker.
+
+#[cfg(all(feature = "test-utils", feature = "rand"))]
+mod common;
+
+#[cfg(all(feature = "test-utils", feature = "rand"))]
+use std::cell::RefCell;
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    run_with_pruning_log, run_with_stats, types::SimpleBid, AuctionConfig,
+    DropReason, DroppedBid, SearchPhase,
+};
+#[cfg(all(feature = "test-utils", feature = "rand"))]
+use vcg_auction::{
+    vcg_auction_with_solver, vcg_auction_with_tiebreaker, BruteForce,
+};
+
+#[cfg(all(feature = "test-utils", feature = "rand"))]
+use common::recording_tiebreaker;
+
+#[cfg(all(feature = "test-utils", feature = "rand"))]
+#[test]
+fn propagation_matches_search_of_all_bids() {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    let items =
+        vec![("chair".into(), 2), ("table".into(), 1), ("lamp".into(), 3)];
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..300 {
+        // some bids are worth far more than others, are alone in their bid
+        // set, or need more than is in stock
+        let bids = (0..rng.gen_range(0..8))
+            .map(|bidder| {
+                (0..rng.gen_range(0..3))
+                    .map(|_| {
+                        let items = (0..rng.gen_range(1..3))
+                            .map(|_| {
+                                let item = ["chair", "table", "lamp", "sofa"]
+                                    [rng.gen_range(0..4)];
+                                (item, rng.gen_range(1..4))
+                            })
+                            .collect::<Vec<_>>();
+                        let value = [0, 1, 2, 3, 20][rng.gen_range(0..5)];
+                        SimpleBid::new(bidder.to_string(), value, items)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let pick = rng.gen::<usize>();
+        let propagated_ties = RefCell::new(vec![]);
+        let propagated = vcg_auction_with_tiebreaker(
+            &items,
+            &bids,
+            recording_tiebreaker(&propagated_ties, pick),
+        );
+        // the brute-force solver tries every combination of every bid
+        let all_ties = RefCell::new(vec![]);
+        let all = vcg_auction_with_solver(
+            &items,
+            &bids,
+            recording_tiebreaker(&all_ties, pick),
+            &BruteForce,
+        );
+        assert_eq!(propagated, all.ok());
+        assert_eq!(propagated_ties, all_ties);
+    }
+}
+
+#[test]
+fn unbeatable_bid_drops_the_bids_it_excludes() {
+    let items = vec![("chair".to_string(), 1)];
+    // Alice's bid is worth more than all the others together
+    let bids =
+        std::iter::once(vec![SimpleBid::new("Alice", 100, [("chair", 1)])])
+            .chain((1..=10).map(|value| {
+                vec![SimpleBid::new(value.to_string(), value, [("chair", 1)])]
+            }))
+            .collect::<Vec<_>>();
+    let config = AuctionConfig::deterministic();
+    let (outcome, stats) = run_with_stats(&items, &bids, &config);
+    let result = outcome.unwrap().complete().unwrap();
+    assert_eq!(result.winning_bids, [&bids[0][0]]);
+    assert_eq!(result.payments, [(&"Alice".to_string(), 10)]);
+
+    // only Alice's bid set is left to search: with and without her bid
+    assert_eq!(stats.winner_determination.nodes, 3);
+    let (_, log) = run_with_pruning_log(&items, &bids, &config);
+    let dropped = log
+        .dropped_bids
+        .iter()
+        .filter(|(phase, _)| *phase == SearchPhase::WinnerDetermination)
+        .map(|(_, dropped)| *dropped)
+        .collect::<Vec<_>>();
+    let excluded = (1..=10)
+        .map(|bid_set| DroppedBid {
+            bid_set,
+            bid: 0,
+            reason: DropReason::Excluded,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(dropped, excluded);
+}
+
+#[test]
+fn dominated_bids_are_dropped() {
+    let items = vec![("chair".to_string(), 2)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 2)]),
+            SimpleBid::new("Alice", 7, [("chair", 1)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("chair", 1)])],
+    ];
+    let config = AuctionConfig::deterministic();
+    let (outcome, log) = run_with_pruning_log(&items, &bids, &config);
+    let result = outcome.unwrap().complete().unwrap();
+    assert_eq!(result.winning_bids, [&bids[0][1], &bids[1][0]]);
+    // Alice's bid for two chairs is worth less than her bid for one
+    let dominated = DroppedBid {
+        bid_set: 0,
+        bid: 0,
+        reason: DropReason::Dominated,
+    };
+    assert!(log
+        .dropped_bids
+        .contains(&(SearchPhase::WinnerDetermination, dominated)));
+}
@@ -0,0 +1,193 @@
+//! Tests for the pruning log of `run_with_pruning_log`.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    run_with_pruning_log, types::SimpleBid, AuctionConfig, Bid, BoundCut,
+    DropReason, DroppedBid, PruningLog, SearchPhase,
+};
+
+#[test]
+fn logs_cuts_by_the_bound() {
+    let items = vec![("chair".to_string(), 1), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 1), ("table", 1)])],
+        vec![],
+        vec![SimpleBid::new("Bob", 6, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 5, [("table", 1)])],
+        vec![SimpleBid::new("Dave", 1, [("chair", 1)])],
+    ];
+    let config = AuctionConfig::deterministic();
+    let (outcome, log) = run_with_pruning_log(&items, &bids, &config);
+    assert!(outcome.is_ok());
+
+    assert_eq!(log.empty_bid_sets, [1]);
+    // with Bob's bid and without Carol's, Dave's can reach at most 7
+    assert_eq!(
+        log.bound_cuts[0],
+        BoundCut {
+            phase: SearchPhase::WinnerDetermination,
+            bids: vec![(2, 0)],
+            remaining_bid_sets: vec![4],
+            cliques: vec![vec![4]],
+            value: 6,
+            bound: 7,
+            best: 11,
+        }
+    );
+}
+
+#[test]
+fn logs_bids_dropped_before_searching() {
+    let items = vec![("chair".to_string(), 2)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 2)]),
+            SimpleBid::new("Alice", 9, [("chair", 1)]),
+        ],
+        vec![SimpleBid::new("Bob", 9, [("chair", 3)])],
+        vec![SimpleBid::new("Carol", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Dave", 3, [("chair", 2)])],
+    ];
+    let config = AuctionConfig::deterministic();
+    let (outcome, log) = run_with_pruning_log(&items, &bids, &config);
+    let result = outcome.unwrap().complete().unwrap();
+    assert_eq!(result.winning_bids, [&bids[0][1], &bids[2][0]]);
+
+    let winner_drops = log
+        .dropped_bids
+        .iter()
+        .filter(|(phase, _)| *phase == SearchPhase::WinnerDetermination)
+        .map(|(_, dropped)| *dropped)
+        .collect::<Vec<_>>();
+    let dropped = |bid_set, bid, reason| DroppedBid {
+        bid_set,
+        bid,
+        reason,
+    };
+    // Alice's second bid must win, so Dave's two chairs don't fit beside it
+    assert_eq!(
+        winner_drops,
+        [
+            dropped(0, 0, DropReason::Dominated),
+            dropped(1, 0, DropReason::OverStock),
+            dropped(3, 0, DropReason::Excluded),
+        ]
+    );
+}
+
+/// Check the winner determination cuts of a log against the bids, returning
+/// how many there are.
+fn check_winner_cuts(
+    items: &[(String, u64)],
+    bids: &[Vec<SimpleBid>],
+    log: &PruningLog<u64>,
+) -> usize {
+    let dropped = log
+        .dropped_bids
+        .iter()
+        .filter(|(phase, _)| *phase == SearchPhase::WinnerDetermination)
+        .map(|(_, dropped)| (dropped.bid_set, dropped.bid))
+        .collect::<Vec<_>>();
+    let dropped = &dropped;
+    // bids dropped before the search are left out of the cliques
+    let kept = |bid_set: usize| {
+        bids[bid_set]
+            .iter()
+            .enumerate()
+            .filter(move |(bid, _)| !dropped.contains(&(bid_set, *bid)))
+            .map(|(_, bid)| bid)
+    };
+    let conflict = |a: &SimpleBid, b: &SimpleBid| {
+        items.iter().any(|(item, stock)| {
+            let quantity = |bid: &SimpleBid| {
+                bid.items
+                    .iter()
+                    .find(|(i, _)| i == item)
+                    .map_or(0, |(_, quantity)| *quantity)
+            };
+            quantity(a) + quantity(b) > *stock
+        })
+    };
+    let winner_cuts = log
+        .bound_cuts
+        .iter()
+        .filter(|cut| cut.phase == SearchPhase::WinnerDetermination)
+        .collect::<Vec<_>>();
+    for cut in &winner_cuts {
+        let value = cut
+            .bids
+            .iter()
+            .map(|&(bid_set, bid)| bids[bid_set][bid].bid_value())
+            .sum::<u64>();
+        let mut grouped = cut.cliques.concat();
+        grouped.sort_unstable();
+        assert_eq!(grouped, cut.remaining_bid_sets);
+        for clique in &cut.cliques {
+            for (i, &a) in clique.iter().enumerate() {
+                for &b in &clique[i + 1..] {
+                    assert!(kept(a).all(|x| kept(b).all(|y| conflict(x, y))));
+                }
+            }
+        }
+        let remaining = cut
+            .cliques
+            .iter()
+            .map(|clique| {
+                clique
+                    .iter()
+                    .flat_map(|&bid_set| kept(bid_set))
+                    .map(|bid| bid.bid_value())
+                    .max()
+                    .unwrap()
+            })
+            .sum::<u64>();
+        assert_eq!(cut.value, value);
+        assert_eq!(cut.bound, value + remaining);
+        assert!(cut.bound < cut.best);
+    }
+    winner_cuts.len()
+}
+
+#[test]
+fn cuts_can_be_checked_against_the_bids() {
+    let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("table", 1)])],
+        vec![SimpleBid::new("Dave", 2, [("chair", 1), ("table", 1)])],
+    ];
+    let config = AuctionConfig::deterministic();
+    let (_, log) = run_with_pruning_log(&items, &bids, &config);
+    assert!(check_winner_cuts(&items, &bids, &log) > 0);
+}
+
+#[test]
+fn logs_cuts_by_clique_bounds() {
+    // every bidder wants the only chair, so the bid sets form one clique
+    // that can add at most the highest bid
+    let items = vec![("chair".to_string(), 1)];
+    let bids = (0..12)
+        .map(|bidder| {
+            let value = 30 - bidder;
+            vec![SimpleBid::new(bidder.to_string(), value, [("chair", 1)])]
+        })
+        .collect::<Vec<_>>();
+    let config = AuctionConfig::deterministic();
+    let (_, log) = run_with_pruning_log(&items, &bids, &config);
+    assert!(check_winner_cuts(&items, &bids, &log) > 0);
+    // without the first bid, the others can add at most the second's 29
+    let cut = log
+        .bound_cuts
+        .iter()
+        .find(|cut| {
+            cut.phase == SearchPhase::WinnerDetermination && cut.bids.is_empty()
+        })
+        .unwrap();
+    assert_eq!(cut.cliques, [(1..12).collect::<Vec<_>>()]);
+    assert_eq!((cut.bound, cut.best), (29, 30));
+}
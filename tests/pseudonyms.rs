@@ -0,0 +1,72 @@
+//! Tests for running auctions with bidder names replaced by pseudonyms.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_with_tiebreaker, AuctionConfig,
+    AuctionOutcome, Bid, Pseudonyms,
+};
+
+fn bids() -> Vec<Vec<SimpleBid>> {
+    vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 8, [("chair", 1), ("table", 1)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Alice", 3, [("table", 1)])],
+    ]
+}
+
+#[test]
+fn bidders_get_one_pseudonym_each() {
+    let bids = bids();
+    let pseudonyms = Pseudonyms::new(&bids);
+    let bid_sets = pseudonyms.bid_sets();
+    let alice = *bid_sets[0][0].bidder_name();
+    let bob = *bid_sets[1][0].bidder_name();
+    assert_eq!(*bid_sets[0][1].bidder_name(), alice);
+    assert_eq!(*bid_sets[2][0].bidder_name(), alice);
+    assert!(alice != bob);
+    assert_eq!(alice.to_string(), "bidder-0");
+    assert_eq!(pseudonyms.name(alice), Some(&"Alice".to_string()));
+    assert_eq!(pseudonyms.name(bob), Some(&"Bob".to_string()));
+    assert_eq!(bid_sets[1][0].reveal(), &bids[1][0]);
+    assert_eq!(*bid_sets[0][1].bid_value(), 8);
+    assert!(!format!("{bid_sets:?}").contains("Alice"));
+}
+
+#[test]
+fn revealed_results_match_named_auctions() {
+    let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+    let bids = bids();
+    let pseudonyms = Pseudonyms::new(&bids);
+    let config = AuctionConfig::deterministic();
+    let outcome =
+        vcg_auction::run(&items, pseudonyms.bid_sets(), &config).unwrap();
+    let expected = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    assert_eq!(
+        pseudonyms.reveal(outcome),
+        AuctionOutcome::Complete(expected)
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn reports_leave_out_names() {
+    let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+    let bids = bids();
+    let pseudonyms = Pseudonyms::new(&bids);
+    let (_, report) = vcg_auction::run_with_report(
+        &items,
+        pseudonyms.bid_sets(),
+        &AuctionConfig::deterministic(),
+    );
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(
+        json["bid_sets"][1][0],
+        serde_json::json!({"name": 1, "value": 4, "items": [["chair", 1]]})
+    );
+    let json = json.to_string();
+    assert!(!json.contains("Alice") && !json.contains("Bob"));
+}
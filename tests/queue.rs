@@ -0,0 +1,118 @@
+//! Tests for running auctions on an `AuctionQueue`.
+
+use std::sync::{mpsc, Arc, Mutex};
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{types::SimpleBid, AuctionError, AuctionQueue, JobHandle};
+
+fn chair_auction(value: u64) -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = vec![("chair".into(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", value, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", value, [("chair", 1)])],
+    ];
+    (items, bids)
+}
+
+/// Submit a job that occupies the queue's only worker until `release` is sent
+/// to. Returns once the job is running.
+fn block_worker(
+    queue: &AuctionQueue<SimpleBid>,
+) -> (JobHandle<SimpleBid>, mpsc::Sender<()>) {
+    let (release, blocked) = mpsc::channel();
+    let (running, started) = mpsc::channel();
+    let (items, bids) = chair_auction(1);
+    let handle = queue.submit(
+        items,
+        bids,
+        move |_| {
+            running.send(()).unwrap();
+            blocked.recv().unwrap();
+            0
+        },
+        0,
+    );
+    started.recv().unwrap();
+    (handle, release)
+}
+
+#[test]
+fn jobs_match_serial_results() {
+    let queue = AuctionQueue::new(3).unwrap();
+    let handles = (1..=10)
+        .map(|value| {
+            let (items, bids) = chair_auction(value);
+            queue.submit(items, bids, |_| 1, 0)
+        })
+        .collect::<Vec<_>>();
+    for (value, handle) in (1..=10).zip(handles) {
+        let (items, bids) = chair_auction(value);
+        let expected =
+            vcg_auction::vcg_auction_with_tiebreaker(&items, &bids, |_| 1)
+                .unwrap()
+                .into_owned();
+        assert_eq!(handle.wait(), Ok(expected));
+    }
+}
+
+#[test]
+fn higher_priority_jobs_start_first() {
+    let queue = AuctionQueue::new(1).unwrap();
+    let (blocker, release) = block_worker(&queue);
+    let started = Arc::new(Mutex::new(vec![]));
+    let handles = [(1, 0), (2, 5), (3, 0), (4, 5)]
+        .into_iter()
+        .map(|(value, priority)| {
+            let (items, bids) = chair_auction(value);
+            let started = Arc::clone(&started);
+            queue.submit(
+                items,
+                bids,
+                move |_| {
+                    started.lock().unwrap().push(value);
+                    0
+                },
+                priority,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(queue.pending(), 4);
+    release.send(()).unwrap();
+    blocker.wait().unwrap();
+    for handle in handles {
+        handle.wait().unwrap();
+    }
+    assert_eq!(*started.lock().unwrap(), [2, 4, 1, 3]);
+}
+
+#[test]
+fn cancelled_job_is_not_started() {
+    let queue = AuctionQueue::new(1).unwrap();
+    let (blocker, release) = block_worker(&queue);
+    let (items, bids) = chair_auction(2);
+    let handle = queue.submit(items, bids, |_| panic!("job was started"), 0);
+    handle.cancel();
+    release.send(()).unwrap();
+    assert!(blocker.wait().is_ok());
+    assert_eq!(handle.wait(), Err(AuctionError::Cancelled));
+}
+
+#[test]
+#[should_panic(expected = "tiebreaker panicked")]
+fn job_panics_reach_the_handle() {
+    let queue = AuctionQueue::new(1).unwrap();
+    let (items, bids) = chair_auction(1);
+    let handle =
+        queue.submit(items, bids, |_| panic!("tiebreaker panicked"), 0);
+    let _ = handle.wait();
+}
+
+#[test]
+fn queue_finishes_jobs_after_drop() {
+    let queue = AuctionQueue::new(1).unwrap();
+    let (items, bids) = chair_auction(1);
+    let handle = queue.submit(items, bids, |_| 0, 0);
+    drop(queue);
+    assert!(handle.wait().is_ok());
+}
@@ -0,0 +1,76 @@
+//! Tests for redistributing VCG payments with Cavallo's mechanism.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    cavallo_redistribution, types::SimpleBid, vcg_auction_with_tiebreaker,
+};
+
+fn rebates<'a>(rebates: &[(&'a String, u64)]) -> Vec<(&'a str, u64)> {
+    rebates
+        .iter()
+        .map(|(name, rebate)| (name.as_str(), *rebate))
+        .collect()
+}
+
+#[test]
+fn rebates_split_the_revenue_without_each_bidder() {
+    let items = vec![("chair".to_string(), 2)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 12, [("chair", 1)]),
+            SimpleBid::new("Alice", 20, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Bob", 9, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 6, [("chair", 1)])],
+        vec![SimpleBid::new("Dave", 3, [("chair", 1)])],
+    ];
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    // Alice and Bob each win a chair, and pay 6 and 8
+    let redistribution =
+        cavallo_redistribution(&items, &bids, &result).unwrap();
+    assert_eq!(redistribution.revenue, 14);
+    // the revenue is 6 without Alice, when Bob and Carol pay 3 each, 9 without
+    // Bob, when Alice wins both chairs, 11 without Carol and 14 without Dave
+    assert_eq!(
+        rebates(&redistribution.rebates),
+        [
+            ("Alice", 6 / 4),
+            ("Bob", 9 / 4),
+            ("Carol", 11 / 4),
+            ("Dave", 14 / 4)
+        ]
+    );
+    assert_eq!(redistribution.retained(), Some(14 - 1 - 2 - 2 - 3));
+}
+
+#[test]
+fn rebates_can_exceed_revenue_with_complements() {
+    let items = vec![("left".to_string(), 1), ("right".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 6, [("left", 1), ("right", 1)])],
+        vec![SimpleBid::new("Bob", 6, [("left", 1)])],
+        vec![SimpleBid::new("Carol", 6, [("right", 1)])],
+    ];
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    let redistribution =
+        cavallo_redistribution(&items, &bids, &result).unwrap();
+    // Bob and Carol win and pay nothing, but alone against Alice they'd pay
+    assert_eq!(redistribution.revenue, 0);
+    assert_eq!(
+        rebates(&redistribution.rebates),
+        [("Alice", 0), ("Bob", 2), ("Carol", 2)]
+    );
+    assert_eq!(redistribution.retained(), None);
+}
+
+#[test]
+fn no_bidders_no_rebates() {
+    let items = vec![("chair".to_string(), 1)];
+    let bids: Vec<Vec<SimpleBid>> = vec![vec![]];
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    let redistribution =
+        cavallo_redistribution(&items, &bids, &result).unwrap();
+    assert_eq!(redistribution.rebates, []);
+    assert_eq!(redistribution.retained(), Some(0));
+}
@@ -0,0 +1,144 @@
+//! Tests for the run reports of `run_with_report`.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    run_with_report, types::SimpleBid, AuctionConfig, AuctionError, DropReason,
+    DroppedBid, InputError, InputSummary, PricingRule, ReportedResult,
+    ResourceLimit, TieReport, Tiebreak, TiebreakKind,
+};
+
+#[test]
+fn report_of_a_tied_auction() {
+    let items = vec![("chair".to_string(), 1), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+        vec![],
+        vec![SimpleBid::new("Bob", 10, [("chair", 1), ("lamp", 1)])],
+        vec![SimpleBid::new("Carol", 4, [("table", 1)])],
+    ];
+    let pick_second = |_: &[Vec<&SimpleBid>]| 1;
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::Custom(&pick_second))
+        .build();
+    let (outcome, report) = run_with_report(&items, &bids, &config);
+    let result = outcome.unwrap().complete().unwrap();
+
+    assert_eq!(report.items, [(&items[0].0, &1), (&items[1].0, &1)]);
+    assert_eq!(report.bid_sets[2], [&bids[2][0]]);
+    assert_eq!(
+        report.summary,
+        InputSummary {
+            items: 2,
+            bid_sets: 4,
+            empty_bid_sets: 1,
+            bids: 3,
+        }
+    );
+    assert_eq!(report.config.tiebreak, TiebreakKind::Custom);
+    assert_eq!(report.config.pricing, PricingRule::Vcg);
+    assert_eq!(
+        report.tie,
+        Some(TieReport {
+            tied_outcomes: 2,
+            chosen: 1,
+            passed_over: vec![(0, 0)],
+        })
+    );
+    // Alice bid as much as Bob, and lost the tiebreak
+    assert_eq!(report.passed_over_bidders(), [&"Alice".to_string()]);
+    // Bob's lamp isn't in stock
+    assert_eq!(
+        report.warnings,
+        [InputError::UnknownItem { bid_set: 2, bid: 0 }]
+    );
+    // the lamp is ignored rather than Bob's bid dropped
+    assert!(report.dropped_bids.is_empty());
+    // the chair and the table are searched apart
+    assert_eq!(report.stats.winner_determination.searches, 2);
+
+    let ReportedResult::Complete {
+        winning_bids,
+        payments,
+    } = report.result
+    else {
+        panic!("expected a complete result");
+    };
+    let winners = winning_bids
+        .iter()
+        .map(|&(i, j)| &bids[i][j])
+        .collect::<Vec<_>>();
+    assert_eq!(winners, result.winning_bids);
+    assert_eq!(payments, result.payments);
+}
+
+#[test]
+fn report_without_a_tie() {
+    let items = vec![("chair".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+    ];
+    let (_, report) =
+        run_with_report(&items, &bids, &AuctionConfig::deterministic());
+    assert_eq!(report.tie, None);
+    assert!(report.passed_over_bidders().is_empty());
+    assert_eq!(report.config.tiebreak, TiebreakKind::First);
+    assert!(report.warnings.is_empty());
+    // Alice's bid is worth more than Bob's, so his is left out
+    assert_eq!(
+        report.dropped_bids,
+        [DroppedBid {
+            bid_set: 1,
+            bid: 0,
+            reason: DropReason::Excluded,
+        }]
+    );
+    assert_eq!(
+        DropReason::Excluded.to_string(),
+        "doesn't fit beside bids that must win"
+    );
+}
+
+#[test]
+fn report_of_a_failed_auction() {
+    let items = vec![("chair".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+    ];
+    let config = AuctionConfig::builder().max_bid_sets(1).build();
+    let (outcome, report) = run_with_report(&items, &bids, &config);
+    let error = AuctionError::ResourceLimit(ResourceLimit::BidSets);
+    assert_eq!(outcome.err(), Some(error));
+    assert_eq!(report.result, ReportedResult::Failed(error));
+    assert_eq!(report.config.max_bid_sets, Some(1));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn report_serializes() {
+    let items = vec![("chair".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+    ];
+    let (_, report) =
+        run_with_report(&items, &bids, &AuctionConfig::deterministic());
+    let json = serde_json::to_value(&report).unwrap();
+
+    assert_eq!(json["items"], serde_json::json!([["chair", 1]]));
+    assert_eq!(json["bid_sets"][1][0]["name"], "Bob");
+    assert_eq!(json["config"]["tiebreak"], "First");
+    assert_eq!(
+        json["result"],
+        serde_json::json!({
+            "Complete": {
+                "winning_bids": [[0, 0]],
+                "payments": [["Alice", 7]],
+            }
+        })
+    );
+    assert_eq!(json["stats"]["payments"]["searches"], 1);
+    assert_eq!(json["tie"], serde_json::Value::Null);
+}
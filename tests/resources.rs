@@ -0,0 +1,125 @@
+//! Tests for auctions of machine resources over several dimensions.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    types::{
+        ResourceBid, ResourceDimension, ResourceError, ResourceItem,
+        ResourcePool, Resources,
+    },
+    vcg_auction_with_tiebreaker, Bid,
+};
+
+fn resources(vcpus: u64, memory_mib: u64, gpus: u64) -> Resources {
+    Resources {
+        vcpus,
+        memory_mib,
+        gpus,
+    }
+}
+
+#[test]
+fn every_dimension_is_an_item() {
+    let pool = ResourcePool::new([
+        ("small", resources(8, 16_384, 0)),
+        ("gpu", resources(32, 65_536, 2)),
+        ("small", resources(8, 16_384, 0)),
+    ]);
+    let item = |machine_type: &str, dimension| ResourceItem {
+        machine_type: machine_type.into(),
+        dimension,
+    };
+    assert_eq!(
+        pool.items(),
+        [
+            (item("small", ResourceDimension::Vcpus), 16),
+            (item("small", ResourceDimension::MemoryMib), 32_768),
+            (item("small", ResourceDimension::Gpus), 0),
+            (item("gpu", ResourceDimension::Vcpus), 32),
+            (item("gpu", ResourceDimension::MemoryMib), 65_536),
+            (item("gpu", ResourceDimension::Gpus), 2),
+        ]
+    );
+
+    // requests of the same machine type are merged, and zeros left out
+    let bid = ResourceBid::new(
+        "Alice",
+        10,
+        [
+            ("gpu", resources(4, 8_192, 0)),
+            ("small", resources(2, 0, 0)),
+            ("gpu", resources(4, 8_192, 1)),
+        ],
+    );
+    assert_eq!(
+        bid.requests(),
+        [
+            ("gpu".to_string(), resources(8, 16_384, 1)),
+            ("small".to_string(), resources(2, 0, 0)),
+        ]
+    );
+    assert_eq!(bid.bid_items().len(), 4);
+}
+
+#[test]
+fn bids_are_checked_against_capacity() {
+    let pool = ResourcePool::new([("gpu", resources(32, 65_536, 2))]);
+    assert_eq!(
+        pool.check(&ResourceBid::new(
+            "Alice",
+            10,
+            [("gpu", resources(32, 0, 2))]
+        )),
+        Ok(())
+    );
+    assert_eq!(
+        pool.check(&ResourceBid::new("Bob", 10, [("tpu", resources(1, 0, 0))])),
+        Err(ResourceError::UnknownMachineType("tpu".into()))
+    );
+    assert_eq!(
+        pool.check(&ResourceBid::new(
+            "Carol",
+            10,
+            [("gpu", resources(16, 131_072, 4))]
+        )),
+        Err(ResourceError::OverCapacity {
+            machine_type: "gpu".into(),
+            dimension: ResourceDimension::MemoryMib,
+            requested: 131_072,
+            capacity: 65_536,
+        })
+    );
+    assert!(resources(16, 1_024, 2).fits_within(&resources(32, 65_536, 2)));
+    assert!(!resources(16, 1_024, 3).fits_within(&resources(32, 65_536, 2)));
+}
+
+#[test]
+fn bids_win_only_if_every_dimension_fits() {
+    let pool = ResourcePool::new([("gpu", resources(32, 65_536, 2))]);
+    let bids = vec![
+        vec![ResourceBid::new(
+            "Alice",
+            50,
+            [("gpu", resources(8, 8_192, 2))],
+        )],
+        // fits the vCPUs and memory left by Alice, but not the GPUs
+        vec![ResourceBid::new(
+            "Bob",
+            40,
+            [("gpu", resources(8, 8_192, 1))],
+        )],
+        vec![ResourceBid::new(
+            "Carol",
+            15,
+            [("gpu", resources(24, 0, 0))],
+        )],
+    ];
+    let result =
+        vcg_auction_with_tiebreaker(pool.items(), &bids, |_| 0).unwrap();
+    assert_eq!(result.winning_bids, [&bids[0][0], &bids[2][0]]);
+    // Alice keeps Bob from winning, and Carol doesn't keep anyone out
+    assert_eq!(
+        result.payments,
+        [(&"Alice".to_string(), 40), (&"Carol".to_string(), 0)]
+    );
+}
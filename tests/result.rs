@@ -0,0 +1,153 @@
+//! Tests for the accessors of auction results.
+
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_with_tiebreaker, OwnedAuctionResult,
+};
+
+fn wikipedia_bids() -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = vec![("apple".into(), 2)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 5, [("apple", 1)])],
+        vec![SimpleBid::new("Bob", 2, [("apple", 1)])],
+        vec![SimpleBid::new("Carol", 6, [("apple", 2)])],
+    ];
+    (items, bids)
+}
+
+#[test]
+fn payment_lookup() {
+    let (items, bids) = wikipedia_bids();
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    assert_eq!(result.payment_for(&"Alice".into()), Some(&4));
+    assert_eq!(result.payment_for(&"Bob".into()), Some(&1));
+    assert_eq!(result.payment_for(&"Carol".into()), None);
+    let owned = result.clone().into_owned();
+    assert_eq!(owned.payment_for(&"Bob".into()), Some(&1));
+    assert_eq!(owned.payment_for(&"Carol".into()), None);
+}
+
+#[test]
+fn payments_map() {
+    let (items, bids) = wikipedia_bids();
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    let (alice, bob) = ("Alice".to_string(), "Bob".to_string());
+    let expected = HashMap::from([(&alice, &4), (&bob, &1)]);
+    assert_eq!(result.payments_map(), expected);
+    assert_eq!(result.into_owned().payments_map(), expected);
+}
+
+#[test]
+fn winners_grouped_by_bidder() {
+    let items = vec![("chair".into(), 3), ("table".into(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 5, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Alice", 6, [("chair", 1), ("table", 1)])],
+    ];
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    let winners = result.winners_by_bidder();
+    assert_eq!(winners.len(), 2);
+    assert_eq!(winners[0].bidder_name, "Alice");
+    assert_eq!(winners[0].bids, [&bids[0][0], &bids[2][0]]);
+    assert_eq!(
+        winners[0].items,
+        [(&"chair".to_string(), 2), (&"table".to_string(), 1)]
+    );
+    assert_eq!(winners[1].bidder_name, "Bob");
+    assert_eq!(winners[1].items, [(&"chair".to_string(), 1)]);
+    let owned = result.clone().into_owned();
+    assert_eq!(owned.winners_by_bidder(), winners);
+}
+
+#[test]
+fn winning_positions_index_the_input() {
+    let items = vec![("chair".into(), 2), ("table".into(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 3, [("chair", 1)])],
+        vec![],
+        vec![
+            SimpleBid::new("Bob", 2, [("chair", 1)]),
+            SimpleBid::new("Bob", 8, [("chair", 1), ("table", 1)]),
+        ],
+    ];
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    let positions = result.winning_positions(&bids);
+    assert_eq!(positions, [(0, 0), (2, 1)]);
+    for ((i, j), bid) in positions.into_iter().zip(&result.winning_bids) {
+        assert!(std::ptr::eq(&bids[i][j], *bid));
+    }
+}
+
+#[test]
+fn borrowed_and_owned_conversions() {
+    let (items, bids) = wikipedia_bids();
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    let owned: OwnedAuctionResult<SimpleBid> = result.clone().into();
+    assert_eq!(owned, result.to_owned());
+    assert_eq!(owned.as_borrowed(), result);
+}
+
+#[test]
+fn rows_join_bids_and_payments() {
+    let (items, bids) = wikipedia_bids();
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    let rows = result
+        .rows()
+        .map(|row| (row.bidder_name.as_str(), row.bid, row.items, *row.payment))
+        .collect::<Vec<_>>();
+    let apple = [("apple".to_string(), 1)];
+    assert_eq!(
+        rows,
+        [
+            ("Alice", &bids[0][0], &apple[..], 4),
+            ("Bob", &bids[1][0], &apple[..], 1),
+        ]
+    );
+    let owned = result.clone().into_owned();
+    assert!(owned.rows().eq(result.rows()));
+}
+
+#[test]
+fn sorted_results_ignore_input_order() {
+    let items = vec![("chair".to_string(), 3), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Carol", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Alice", 2, [("table", 1), ("chair", 1)])],
+        vec![SimpleBid::new("Alice", 3, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 1, [("chair", 1)])],
+    ];
+    let mut reversed = bids.clone();
+    reversed.reverse();
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    let sorted = result.sorted();
+    assert_eq!(sorted.winning_bids, [&bids[1][0], &bids[2][0], &bids[0][0]]);
+    assert_eq!(
+        sorted.payments,
+        [(&"Alice".into(), 1), (&"Carol".into(), 1)]
+    );
+    let reversed =
+        vcg_auction_with_tiebreaker(&items, &reversed, |_| 0).unwrap();
+    assert_eq!(reversed.sorted(), sorted);
+    assert_eq!(result.clone().into_owned().sorted(), sorted);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn sorted_results_serialize() {
+    let (items, bids) = wikipedia_bids();
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    assert_eq!(
+        serde_json::to_value(result.sorted()).unwrap(),
+        serde_json::json!({
+            "winning_bids": [
+                {"name": "Alice", "value": 5, "items": [["apple", 1]]},
+                {"name": "Bob", "value": 2, "items": [["apple", 1]]},
+            ],
+            "payments": [["Alice", 4], ["Bob", 1]],
+        })
+    );
+}
@@ -0,0 +1,76 @@
+//! Tests for the JSON schemas of the serializable types.
+#![cfg(feature = "schemars")]
+
+use pretty_assertions::assert_eq;
+use schemars::{schema::RootSchema, schema_for};
+use serde_json::Value;
+
+use vcg_auction::{
+    run_with_report, types::SimpleBid, vcg_auction_with_tiebreaker,
+    ArchivedResult, AuctionConfig, AuctionError, ConfigSummary,
+};
+
+/// Names of the properties of an object schema, sorted.
+fn properties(schema: &RootSchema) -> Vec<String> {
+    let object = schema.schema.object.as_ref().expect("an object schema");
+    object.properties.keys().cloned().collect()
+}
+
+/// Keys of a serialized object, sorted.
+fn keys(value: &impl serde::Serialize) -> Vec<String> {
+    match serde_json::to_value(value).unwrap() {
+        Value::Object(object) => object.keys().cloned().collect(),
+        other => panic!("{other} isn't an object"),
+    }
+}
+
+#[test]
+fn schemas_describe_the_serialized_fields() {
+    let items = vec![("chair".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+    ];
+    assert_eq!(properties(&schema_for!(SimpleBid)), keys(&bids[0][0]));
+
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    let archived = ArchivedResult::new(result.to_owned());
+    let schema = schema_for!(ArchivedResult<SimpleBid>);
+    assert_eq!(properties(&schema), keys(&archived));
+    assert!(schema.definitions.contains_key("SimpleBid"));
+
+    let config = AuctionConfig::deterministic();
+    let (_, report) = run_with_report(&items, &bids, &config);
+    assert_eq!(
+        properties(&schema_for!(ConfigSummary)),
+        keys(&report.config)
+    );
+}
+
+#[test]
+fn schemas_of_enums_list_their_variants() {
+    let schema = serde_json::to_value(schema_for!(AuctionError)).unwrap();
+    // unit variants are strings, others objects keyed by the variant
+    let variants = schema["oneOf"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|variant| match variant["enum"].as_array() {
+            Some(names) => names.clone(),
+            None => variant["required"].as_array().unwrap().clone(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        variants,
+        [
+            "InvalidTiebreak",
+            "TimedOut",
+            "Cancelled",
+            "ThreadPool",
+            "ResourceLimit",
+            "PaymentMismatch",
+            "Input",
+            "UnsupportedOption",
+        ]
+    );
+}
@@ -0,0 +1,72 @@
+//! Tests for the commit-reveal bookkeeping of sealed bids.
+
+#![cfg(feature = "commitment")]
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    commit_bid_set, types::SimpleBid, vcg_auction_with_tiebreaker,
+    SealedBidError, SealedBids,
+};
+
+#[test]
+fn commitments_bind_bids_and_salt() {
+    let bids = vec![SimpleBid::new("Alice", 10, [("chair", 1)])];
+    let commitment = commit_bid_set(&bids, b"salt");
+    assert_eq!(commit_bid_set(&bids, b"salt"), commitment);
+    assert_ne!(commit_bid_set(&bids, b"other salt"), commitment);
+    let raised = vec![SimpleBid::new("Alice", 11, [("chair", 1)])];
+    assert_ne!(commit_bid_set(&raised, b"salt"), commitment);
+}
+
+#[test]
+fn reveal_errors() {
+    let alice = vec![SimpleBid::new("Alice", 10, [("chair", 1)])];
+    let mut sealed = SealedBids::new();
+    let commitment = commit_bid_set(&alice, b"salt");
+    let id = sealed.commit(commitment).unwrap();
+    assert_eq!(
+        sealed.commit(commitment),
+        Err(SealedBidError::DuplicateCommitment)
+    );
+    let changed = vec![SimpleBid::new("Alice", 9, [("chair", 1)])];
+    assert_eq!(
+        sealed.reveal(id, changed, b"salt"),
+        Err(SealedBidError::RevealMismatch)
+    );
+    assert_eq!(sealed.reveal(id, alice.clone(), b"salt"), Ok(()));
+    assert_eq!(
+        sealed.reveal(id, alice.clone(), b"salt"),
+        Err(SealedBidError::AlreadyRevealed)
+    );
+
+    let mut other = SealedBids::<SimpleBid>::new();
+    assert_eq!(
+        other.reveal(id, alice, b"salt"),
+        Err(SealedBidError::UnknownCommitment)
+    );
+}
+
+#[test]
+fn unrevealed_bids_are_left_out() {
+    let bid_sets = [
+        vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 4, [("chair", 1)])],
+    ];
+    let mut sealed = SealedBids::new();
+    let ids = bid_sets
+        .iter()
+        .map(|bid_set| sealed.commit(commit_bid_set(bid_set, b"salt")).unwrap())
+        .collect::<Vec<_>>();
+    // Bob doesn't reveal
+    sealed.reveal(ids[2], bid_sets[2].clone(), b"salt").unwrap();
+    sealed.reveal(ids[0], bid_sets[0].clone(), b"salt").unwrap();
+    assert_eq!(sealed.unrevealed().collect::<Vec<_>>(), [ids[1]]);
+
+    let revealed = sealed.into_bid_sets();
+    assert_eq!(revealed, [bid_sets[0].clone(), bid_sets[2].clone()]);
+    let items = vec![("chair".to_string(), 1)];
+    let result = vcg_auction_with_tiebreaker(&items, &revealed, |_| 0).unwrap();
+    assert_eq!(result.payments, [(&"Alice".to_string(), 4)]);
+}
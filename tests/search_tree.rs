@@ -0,0 +1,149 @@
+//! Tests for the search tree recorded by `run_with_search_tree`.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    run_with_search_tree, run_with_stats, types::SimpleBid, AuctionConfig,
+    NodeOutcome, SearchPhase, SearchTree, SearchTreeNode, Tiebreak,
+};
+
+#[test]
+fn records_every_explored_node() {
+    let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("table", 1)])],
+    ];
+    let config = AuctionConfig::deterministic();
+    let (outcome, tree) = run_with_search_tree(&items, &bids, &config);
+    let result = outcome.unwrap().complete().unwrap();
+    let (_, stats) = run_with_stats(&items, &bids, &config);
+
+    // the tree is of the searches the stats count, with a root for each:
+    // the chairs and the table are searched apart
+    assert_eq!(tree.nodes.len() as u64, stats.nodes());
+    let roots = tree.nodes.iter().filter(|node| node.parent.is_none());
+    let searches =
+        stats.winner_determination.searches + stats.payments.searches;
+    assert_eq!(roots.count(), searches as usize);
+    assert!(searches as usize > 1 + result.payments.len());
+    for (i, node) in tree.nodes.iter().enumerate() {
+        if let Some(parent) = node.parent {
+            assert!(parent < i);
+            assert_eq!(tree.nodes[parent].outcome, NodeOutcome::Expanded);
+            assert_eq!(tree.nodes[parent].depth + 1, node.depth);
+            assert_eq!(tree.nodes[parent].phase, node.phase);
+        }
+    }
+    // Alice's second bid with Bob's needs three chairs
+    assert!(tree
+        .nodes
+        .iter()
+        .any(|node| node.outcome == NodeOutcome::OverAllocated));
+    // the best values found by the searches for the winners, the chairs'
+    // and the table's, add up to what the winners are worth
+    let mut best = vec![];
+    for node in &tree.nodes {
+        if node.phase != SearchPhase::WinnerDetermination {
+            continue;
+        }
+        if node.parent.is_none() {
+            best.push(0);
+        }
+        if node.outcome == NodeOutcome::Improved {
+            *best.last_mut().unwrap() = node.value;
+        }
+    }
+    assert_eq!(best, [9, 3]);
+    let value = result.winning_bids.iter().map(|bid| bid.value);
+    assert_eq!(value.sum::<u64>(), 12);
+}
+
+#[test]
+fn bounded_nodes_are_below_the_best_value() {
+    let items = vec![("chair".to_string(), 1), ("table".to_string(), 1)];
+    // Alice's bid is worth less than Bob's and Carol's, so neither side can
+    // be dropped before searching
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 1), ("table", 1)])],
+        vec![SimpleBid::new("Bob", 6, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 5, [("table", 1)])],
+        vec![SimpleBid::new("Dave", 1, [("chair", 1)])],
+    ];
+    let config = AuctionConfig::builder().tiebreak(Tiebreak::First).build();
+    let (_, tree) = run_with_search_tree(&items, &bids, &config);
+    let bounded = tree
+        .nodes
+        .iter()
+        .filter(|node| node.outcome == NodeOutcome::Bounded)
+        .collect::<Vec<_>>();
+    assert!(!bounded.is_empty());
+    for node in bounded {
+        assert!(node.bound.unwrap() < node.best);
+    }
+}
+
+#[test]
+fn records_nothing_past_the_node_limit() {
+    let items = vec![("chair".to_string(), 1)];
+    let bids = vec![vec![SimpleBid::new("Alice", 10, [("chair", 1)])]];
+    let config = AuctionConfig::builder().max_nodes(0).build();
+    let (_, tree) = run_with_search_tree(&items, &bids, &config);
+    assert_eq!(tree, SearchTree::default());
+}
+
+#[test]
+fn exports() {
+    let tree = SearchTree {
+        nodes: vec![
+            SearchTreeNode {
+                parent: None,
+                phase: SearchPhase::WinnerDetermination,
+                depth: 0,
+                bid: None,
+                value: 0,
+                bound: Some(10),
+                best: 0,
+                outcome: NodeOutcome::Expanded,
+            },
+            SearchTreeNode {
+                parent: Some(0),
+                phase: SearchPhase::WinnerDetermination,
+                depth: 1,
+                bid: Some(0),
+                value: 10,
+                bound: None,
+                best: 0,
+                outcome: NodeOutcome::Improved,
+            },
+        ],
+    };
+    assert_eq!(
+        tree.to_json(),
+        concat!(
+            r#"{"nodes":["#,
+            r#"{"parent":null,"phase":"winner_determination","depth":0,"#,
+            r#""bid":null,"value":"0","bound":"10","best":"0","#,
+            r#""outcome":"expanded"},"#,
+            r#"{"parent":0,"phase":"winner_determination","depth":1,"#,
+            r#""bid":0,"value":"10","bound":null,"best":"0","#,
+            r#""outcome":"improved"}]}"#,
+        )
+    );
+    assert_eq!(
+        tree.to_dot(),
+        concat!(
+            "digraph search_tree {\n",
+            "  n0 [label=\"winner_determination depth 0\\n",
+            "value 0 bound 10 best 0\\nexpanded\"];\n",
+            "  n1 [label=\"winner_determination depth 1\\n",
+            "value 10 best 0\\nimproved\"];\n",
+            "  n0 -> n1 [label=\"bid 0\"];\n",
+            "}\n",
+        )
+    );
+}
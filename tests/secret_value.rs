@@ -0,0 +1,72 @@
+//! Test of `SecretValue`, the `secrecy` feature's bid value wrapper, which
+//! does for any bid type what the wrapper in the `secrecy` test does by hand:
+//! every value, sum and payment is kept in a Secret, zeroed on drop and
+//! redacted from debug output.
+
+#![cfg(feature = "secrecy")]
+
+use vcg_auction::{vcg_auction_with_tiebreaker, SecretValue};
+
+#[derive(Debug)]
+struct SecretBid {
+    name: String,
+    value: SecretValue<u64>,
+    items: Vec<(String, u64)>,
+}
+
+impl SecretBid {
+    fn new(
+        name: impl Into<String>,
+        value: u64,
+        items: Vec<(String, u64)>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            value: SecretValue::new(value),
+            items,
+        }
+    }
+}
+
+impl vcg_auction::Bid for SecretBid {
+    type Name = String;
+    type Value = SecretValue<u64>;
+    type Item = String;
+    type Quantity = u64;
+
+    fn bidder_name(&self) -> &Self::Name {
+        &self.name
+    }
+    fn bid_value(&self) -> &Self::Value {
+        &self.value
+    }
+    fn bid_items(&self) -> &[(Self::Item, Self::Quantity)] {
+        &self.items
+    }
+}
+
+#[test]
+fn simple_secret_case() {
+    let items = vec![("chair".into(), 2)];
+    let bids = vec![
+        vec![
+            SecretBid::new("Alice", 5, vec![("chair".into(), 1)]),
+            SecretBid::new("Alice", 7, vec![("chair".into(), 2)]),
+        ],
+        vec![SecretBid::new("Bob", 4, vec![("chair".into(), 1)])],
+    ];
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    assert_eq!(
+        result.payments,
+        [
+            (&"Alice".into(), SecretValue::new(0)),
+            (&"Bob".into(), SecretValue::new(2))
+        ]
+    );
+
+    // bid values and payments are redacted from debug logging
+    let logged = format!("{bids:?} {result:?}");
+    assert!(logged.contains("Alice"));
+    assert!(!logged.contains("value: 5"));
+    assert_eq!(logged.matches("[REDACTED]").count(), 3 + 2 + 2);
+}
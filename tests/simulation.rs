@@ -0,0 +1,138 @@
+//! Tests for replaying auctions under policies with `simulate_policies`.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    simulate_policies, sweep_reserve_prices, types::SimpleBid,
+    vcg_auction_with_tiebreaker, Change, FeePolicy, PolicyChange, PolicyTotals,
+    PricingRule, RecordedAuction,
+};
+
+fn corpus() -> Vec<RecordedAuction<SimpleBid>> {
+    vec![
+        RecordedAuction {
+            items: vec![("chair".to_string(), 2), ("table".to_string(), 1)],
+            bid_sets: vec![
+                vec![
+                    SimpleBid::new("Alice", 5, [("chair", 1)]),
+                    SimpleBid::new("Alice", 9, [("chair", 1), ("table", 1)]),
+                ],
+                vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+                vec![SimpleBid::new("Carol", 6, [("table", 1)])],
+            ],
+        },
+        RecordedAuction {
+            items: vec![("lamp".to_string(), 1)],
+            bid_sets: vec![
+                vec![SimpleBid::new("Dave", 3, [("lamp", 1)])],
+                vec![SimpleBid::new("Erin", 2, [("lamp", 1)])],
+            ],
+        },
+    ]
+}
+
+#[test]
+fn vcg_totals_match_the_auctions() {
+    let corpus = corpus();
+    let totals =
+        simulate_policies(&corpus, &[FeePolicy::new(PricingRule::Vcg)]);
+    let mut revenue = 0;
+    let mut welfare = 0;
+    for auction in &corpus {
+        let result = vcg_auction_with_tiebreaker(
+            &auction.items,
+            &auction.bid_sets,
+            |_| 0,
+        )
+        .unwrap();
+        revenue += result.payments.iter().map(|(_, p)| p).sum::<u64>();
+        welfare += result.winning_bids.iter().map(|b| b.value).sum::<u64>();
+    }
+    assert_eq!(
+        totals,
+        [PolicyTotals {
+            revenue,
+            fees: 0,
+            welfare,
+            surplus: welfare - revenue,
+        }]
+    );
+}
+
+#[test]
+fn policies_are_compared() {
+    let corpus = corpus();
+    let fee = |payment: &u64| (payment + 3) / 4;
+    let policies = [
+        FeePolicy::new(PricingRule::Vcg),
+        FeePolicy::new(PricingRule::Vcg).fee(&fee),
+        FeePolicy::new(PricingRule::FirstPrice).min_bid(5),
+    ];
+    let totals = simulate_policies(&corpus, &policies);
+    // Carol pays 4 for the table and Dave 2 for the lamp, while the chairs
+    // are free
+    assert_eq!(
+        totals[0],
+        PolicyTotals {
+            revenue: 6,
+            fees: 0,
+            welfare: 18,
+            surplus: 12,
+        }
+    );
+    // fees come out of the payments, so bidders pay the same
+    assert_eq!(
+        totals[1].change_from(&totals[0]),
+        PolicyChange {
+            revenue: Change::Unchanged,
+            fees: Change::Increase(2),
+            welfare: Change::Unchanged,
+            surplus: Change::Unchanged,
+        }
+    );
+    // only Alice and Carol bid enough, paying their bids
+    assert_eq!(
+        totals[2],
+        PolicyTotals {
+            revenue: 11,
+            fees: 0,
+            welfare: 11,
+            surplus: 0,
+        }
+    );
+    assert_eq!(
+        totals[2].change_from(&totals[0]),
+        PolicyChange {
+            revenue: Change::Increase(5),
+            fees: Change::Unchanged,
+            welfare: Change::Decrease(7),
+            surplus: Change::Decrease(12),
+        }
+    );
+}
+
+#[test]
+fn reserves_trade_welfare_for_revenue() {
+    let corpus = corpus();
+    let points = sweep_reserve_prices(&corpus, PricingRule::Vcg, [0, 3, 5]);
+    let curve = points
+        .iter()
+        .map(|point| {
+            let totals = &point.totals;
+            (point.reserve, totals.revenue, totals.welfare)
+        })
+        .collect::<Vec<_>>();
+    // a reserve of 3 leaves out Erin, so Dave pays 3, and Alice and Bob pay
+    // 3 for their chairs; a reserve of 5 leaves out Bob, Dave and Erin, and
+    // Alice and Carol pay 5 each
+    assert_eq!(curve, [(0, 6, 18), (3, 13, 18), (5, 10, 11)]);
+    assert_eq!(
+        points[0].totals,
+        simulate_policies(&corpus, &[FeePolicy::new(PricingRule::Vcg)])[0]
+    );
+    // a minimum bid above the reserve leaves out more bids, but payments
+    // are only raised to the reserve, so Carol pays her VCG payment of 4
+    let policies = [FeePolicy::new(PricingRule::Vcg).reserve(3).min_bid(5)];
+    let totals = &simulate_policies(&corpus, &policies)[0];
+    assert_eq!((totals.revenue, totals.welfare), (3 + 4, 11));
+}
@@ -0,0 +1,238 @@
+//! Tests that a `Solver` stepped a few nodes at a time gives the same results
+//! as a blocking auction.
+
+#![cfg(feature = "rand")]
+
+mod common;
+
+use std::task::Poll;
+
+use pretty_assertions::assert_eq;
+use rand::{distributions::Distribution, rngs::StdRng, Rng, SeedableRng};
+
+use vcg_auction::{
+    run_with_stats, types::SimpleBid, vcg_auction_with_tiebreaker,
+    AuctionConfig, AuctionError, AuctionInstance, OnTimeout, ResourceLimit,
+    SearchBuffers, SearchOptions, Solver, Tiebreak,
+};
+
+use common::tied_shape;
+
+#[test]
+fn stepped_solver_matches_blocking_auction() {
+    let shape = tied_shape();
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..50 {
+        let AuctionInstance {
+            items,
+            bid_sets: bids,
+        } = shape.sample(&mut rng);
+        let pick = rng.gen::<usize>();
+        let tiebreaker = |options: &[Vec<&SimpleBid>]| pick % options.len();
+        let expected = vcg_auction_with_tiebreaker(&items, &bids, tiebreaker);
+        for max_nodes in [1, 7, u64::MAX] {
+            for strict in [false, true] {
+                let mut solver = Solver::new(&items, &bids, tiebreaker);
+                if strict {
+                    solver = solver.strict_budget();
+                }
+                let result = loop {
+                    if let Poll::Ready(result) = solver.step(max_nodes) {
+                        break result;
+                    }
+                };
+                assert_eq!(result.ok(), expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn steps_are_bounded() {
+    let items = vec![("chair".into(), 2)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+    ];
+    let mut solver = Solver::new(&items, &bids, |_| 0);
+    let mut steps = 0;
+    let result = loop {
+        let before = solver.nodes_explored();
+        let poll = solver.step(1);
+        assert!(solver.nodes_explored() - before <= 1);
+        steps += 1;
+        if let Poll::Ready(result) = poll {
+            break result.unwrap();
+        }
+    };
+    assert!(steps > 1);
+    assert_eq!(result.winning_bids, [&bids[0][0], &bids[1][0]]);
+    assert_eq!(result.payments, [(&"Alice".into(), 0), (&"Bob".into(), 2)]);
+}
+
+#[test]
+fn strict_steps_count_moves_between_searches() {
+    let items = vec![("chair".into(), 2)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+    ];
+    let mut solver = Solver::new(&items, &bids, |_| 0).strict_budget();
+    let mut steps = 0;
+    let result = loop {
+        steps += 1;
+        if let Poll::Ready(result) = solver.step(1) {
+            break result.unwrap();
+        }
+    };
+    // each step explores a node or moves on from one of the three searches
+    assert_eq!(steps, solver.nodes_explored() + 3);
+    assert_eq!(result.payments, [(&"Alice".into(), 0), (&"Bob".into(), 2)]);
+}
+
+#[test]
+fn strict_steps_count_moves_between_sub_auctions() {
+    let items = vec![("chair".to_string(), 1), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 5, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 6, [("table", 1)])],
+    ];
+    let (_, stats) =
+        run_with_stats(&items, &bids, &AuctionConfig::deterministic());
+    let searches =
+        stats.winner_determination.searches + stats.payments.searches;
+    let mut solver = Solver::new(&items, &bids, |_| 0).strict_budget();
+    let mut steps = 0;
+    while solver.step(1).is_pending() {
+        steps += 1;
+    }
+    // the chair and the table are searched apart, each move taking a step
+    assert_eq!(steps + 1, solver.nodes_explored() + searches as u64);
+}
+
+#[test]
+#[should_panic(expected = "solver stepped after finishing")]
+fn step_after_finishing_panics() {
+    let items = vec![("chair".into(), 1)];
+    let bids = vec![vec![SimpleBid::new("Alice", 5, [("chair", 1)])]];
+    let mut solver = Solver::new(&items, &bids, |_| 0);
+    assert!(solver.step(u64::MAX).is_ready());
+    let _ = solver.step(1);
+}
+
+#[test]
+fn buffers_reused_across_solvers() {
+    let shape = tied_shape();
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut buffers = SearchBuffers::new();
+    for _ in 0..50 {
+        let AuctionInstance {
+            items,
+            bid_sets: bids,
+        } = shape.sample(&mut rng);
+        let pick = rng.gen::<usize>();
+        let tiebreaker = |options: &[Vec<&SimpleBid>]| pick % options.len();
+        let expected = vcg_auction_with_tiebreaker(&items, &bids, tiebreaker);
+        // a solver abandoned partway leaves the buffers reusable
+        let mut solver =
+            Solver::new(&items, &bids, tiebreaker).with_buffers(buffers);
+        let _ = solver.step(rng.gen_range(0..10));
+        buffers = solver.into_buffers();
+
+        let mut solver =
+            Solver::new(&items, &bids, tiebreaker).with_buffers(buffers);
+        let result = loop {
+            if let Poll::Ready(result) = solver.step(3) {
+                break result;
+            }
+        };
+        assert_eq!(result.ok(), expected);
+        buffers = solver.into_buffers();
+    }
+}
+
+#[test]
+fn node_limit_caps_stepping() {
+    let items = vec![("chair".into(), 2)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+    ];
+    let options = SearchOptions {
+        max_nodes: Some(5),
+        ..Default::default()
+    };
+    let mut solver = Solver::with_options(&items, &bids, |_| 0, &options);
+    let result = loop {
+        if let Poll::Ready(result) = solver.step(1) {
+            break result;
+        }
+    };
+    assert_eq!(solver.nodes_explored(), 5);
+    assert_eq!(
+        result,
+        Err(AuctionError::ResourceLimit(ResourceLimit::Nodes))
+    );
+}
+
+#[test]
+fn payments_are_verified_step_by_step() {
+    let items = vec![("chair".to_string(), 2), ("table".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 5, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 6, [("table", 1)])],
+        vec![SimpleBid::new("Dave", 3, [("chair", 1), ("table", 1)])],
+    ];
+    let options = SearchOptions {
+        verify_payments: true,
+        ..Default::default()
+    };
+    let mut solver = Solver::with_options(&items, &bids, |_| 0, &options);
+    let result = loop {
+        if let Poll::Ready(result) = solver.step(1) {
+            break result.unwrap();
+        }
+    };
+    assert_eq!(
+        Some(result),
+        vcg_auction_with_tiebreaker(&items, &bids, |_| 0)
+    );
+
+    // the checking searches are explored as a blocking auction explores them
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::First)
+        .verify_payments()
+        .build();
+    let (_, stats) = run_with_stats(&items, &bids, &config);
+    assert_eq!(solver.nodes_explored(), stats.nodes());
+    let (_, unverified) =
+        run_with_stats(&items, &bids, &AuctionConfig::deterministic());
+    assert!(unverified.nodes() < stats.nodes());
+}
+
+#[test]
+fn best_so_far_is_rejected() {
+    let items = vec![("chair".to_string(), 1)];
+    let bids = vec![vec![SimpleBid::new("Alice", 5, [("chair", 1)])]];
+    let options = SearchOptions {
+        on_timeout: OnTimeout::BestSoFar,
+        ..Default::default()
+    };
+    let mut solver = Solver::with_options(&items, &bids, |_| 0, &options);
+    assert_eq!(
+        solver.step(u64::MAX),
+        Poll::Ready(Err(AuctionError::UnsupportedOption))
+    );
+    assert_eq!(solver.nodes_explored(), 0);
+}
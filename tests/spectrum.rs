@@ -0,0 +1,125 @@
+//! Tests for the spectrum auction preset.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    spectrum::{License, SpectrumCap, SpectrumError, SpectrumMarket},
+    vcg_auction_with_tiebreaker,
+};
+
+fn market() -> SpectrumMarket {
+    let mut market = SpectrumMarket::new(
+        [("600MHz", 2), ("3.5GHz", 4)],
+        ["north", "central", "south"],
+    );
+    market
+        .adjacent("north", "central")
+        .adjacent("central", "south")
+        .adjacent("south", "central")
+        .adjacent("north", "east");
+    market
+}
+
+#[test]
+fn adjacency_and_footprints() {
+    let market = market();
+    assert_eq!(market.licenses().len(), 6);
+    assert!(market.is_adjacent("central", "north"));
+    assert!(!market.is_adjacent("north", "south"));
+    assert_eq!(
+        market.neighbours("central").collect::<Vec<_>>(),
+        ["north", "south"]
+    );
+    assert_eq!(
+        market.footprints("600MHz", 1),
+        [
+            vec![("600MHz", "north", 1), ("600MHz", "central", 1)],
+            vec![
+                ("600MHz", "central", 1),
+                ("600MHz", "north", 1),
+                ("600MHz", "south", 1),
+            ],
+            vec![("600MHz", "south", 1), ("600MHz", "central", 1)],
+        ]
+    );
+    assert_eq!(
+        market.national("3.5GHz", 2),
+        [
+            ("3.5GHz", "north", 2),
+            ("3.5GHz", "central", 2),
+            ("3.5GHz", "south", 2),
+        ]
+    );
+}
+
+#[test]
+fn bids_are_checked_against_the_licenses() {
+    let market = market();
+    let license = |band: &str, region: &str| License {
+        band: band.into(),
+        region: region.into(),
+    };
+    assert_eq!(
+        market.bid("Alice", 5, [("600MHz", "east", 1)]),
+        Err(SpectrumError::UnknownLicense(license("600MHz", "east")))
+    );
+    // blocks of the same license are added up
+    assert_eq!(
+        market.bid(
+            "Alice",
+            5,
+            [("600MHz", "north", 1), ("600MHz", "north", 2)]
+        ),
+        Err(SpectrumError::TooManyBlocks {
+            license: license("600MHz", "north"),
+            requested: 3,
+            offered: 2,
+        })
+    );
+    let bid = market
+        .bid("Alice", 5, [("600MHz", "north", 1), ("600MHz", "north", 1)])
+        .unwrap();
+    assert_eq!(
+        bid.licenses().collect::<Vec<_>>(),
+        [(&license("600MHz", "north"), 2)]
+    );
+}
+
+#[test]
+fn caps_limit_each_bidder() {
+    let mut market = market();
+    // at most 4 blocks of 3.5GHz in the north and central regions
+    market.cap(SpectrumCap {
+        name: "3.5GHz urban".into(),
+        bands: vec!["3.5GHz".into()],
+        regions: vec!["north".into(), "central".into()],
+        blocks: 4,
+    });
+    let bids = vec![
+        vec![
+            market
+                .bid("Alice", 12, market.national("3.5GHz", 2))
+                .unwrap(),
+            market
+                .bid(
+                    "Alice",
+                    7,
+                    [("3.5GHz", "north", 2), ("3.5GHz", "south", 4)],
+                )
+                .unwrap(),
+        ],
+        vec![market.bid("Alice", 4, [("3.5GHz", "central", 1)]).unwrap()],
+        vec![market.bid("Bob", 3, [("3.5GHz", "central", 2)]).unwrap()],
+    ];
+    let items = market.items(&bids);
+    // the licenses, then a cap for each bidder
+    assert_eq!(items.len(), 8);
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    // Alice's national bid and her central bid would take 5 blocks under the
+    // cap, so the national bid wins with Bob's
+    assert_eq!(result.winning_bids, [&bids[0][0], &bids[2][0]]);
+    assert_eq!(
+        result.payments,
+        [(&"Alice".to_string(), 0), (&"Bob".to_string(), 0)]
+    );
+}
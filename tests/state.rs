@@ -0,0 +1,232 @@
+//! Tests for live auctions kept in a `BidStore`.
+
+use std::collections::BTreeMap;
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_with_tiebreaker, AuctionConfig, AuctionState,
+    BidSetId, BidStore, MemoryStore, OwnedAuctionResult, StateError,
+    StoredBidSets, SubmissionLimit, SubmissionLimits, SubmissionRejection,
+};
+
+/// A store of bid sets by key, like a key-value database, which fails while
+/// disconnected.
+#[derive(Default)]
+struct KeyValueStore {
+    items: Vec<(String, u64)>,
+    bid_sets: BTreeMap<u64, Vec<SimpleBid>>,
+    result: Option<OwnedAuctionResult<SimpleBid>>,
+    disconnected: bool,
+}
+
+impl KeyValueStore {
+    fn connected(&self) -> Result<(), String> {
+        match self.disconnected {
+            true => Err("disconnected".into()),
+            false => Ok(()),
+        }
+    }
+}
+
+impl BidStore<SimpleBid> for KeyValueStore {
+    type Error = String;
+
+    fn save_items(&mut self, items: Vec<(String, u64)>) -> Result<(), String> {
+        self.connected()?;
+        self.items = items;
+        Ok(())
+    }
+
+    fn load_items(&self) -> Result<Vec<(String, u64)>, String> {
+        self.connected()?;
+        Ok(self.items.clone())
+    }
+
+    fn insert_bid_set(
+        &mut self,
+        bid_set: Vec<SimpleBid>,
+    ) -> Result<BidSetId, String> {
+        self.connected()?;
+        let key = self.bid_sets.keys().last().map_or(0, |key| key + 1);
+        self.bid_sets.insert(key, bid_set);
+        Ok(BidSetId(key))
+    }
+
+    fn remove_bid_set(
+        &mut self,
+        id: BidSetId,
+    ) -> Result<Option<Vec<SimpleBid>>, String> {
+        self.connected()?;
+        Ok(self.bid_sets.remove(&id.0))
+    }
+
+    fn load_bid_sets(&self) -> Result<StoredBidSets<SimpleBid>, String> {
+        self.connected()?;
+        let bid_sets = self.bid_sets.iter();
+        Ok(bid_sets
+            .map(|(&key, b)| (BidSetId(key), b.clone()))
+            .collect())
+    }
+
+    fn save_result(
+        &mut self,
+        result: Option<&OwnedAuctionResult<SimpleBid>>,
+    ) -> Result<(), String> {
+        self.connected()?;
+        self.result = result.cloned();
+        Ok(())
+    }
+
+    fn load_result(
+        &self,
+    ) -> Result<Option<OwnedAuctionResult<SimpleBid>>, String> {
+        self.connected()?;
+        Ok(self.result.clone())
+    }
+}
+
+fn chairs() -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = vec![("chair".to_string(), 2)];
+    let bid_sets = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("chair", 1)])],
+    ];
+    (items, bid_sets)
+}
+
+#[test]
+fn closing_runs_the_stored_auction() {
+    let (items, bid_sets) = chairs();
+    let mut state = AuctionState::new(MemoryStore::new());
+    state.set_stock(items.clone()).unwrap();
+    let ids = bid_sets
+        .iter()
+        .map(|bid_set| state.submit(bid_set.clone()).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        state.bid_sets().unwrap(),
+        ids.into_iter().zip(bid_sets.clone()).collect::<Vec<_>>()
+    );
+    let result = state.close(&AuctionConfig::deterministic()).unwrap();
+    let expected = vcg_auction_with_tiebreaker(&items, &bid_sets, |_| 0)
+        .unwrap()
+        .into_owned();
+    assert_eq!(result, expected);
+    assert_eq!(state.result().unwrap(), Some(expected));
+}
+
+#[test]
+fn auctions_resume_from_their_store() {
+    let (items, bid_sets) = chairs();
+    let mut state = AuctionState::new(KeyValueStore::default());
+    state.set_stock(items).unwrap();
+    state.submit(bid_sets[0].clone()).unwrap();
+    let bob = state.submit(bid_sets[1].clone()).unwrap();
+    // a restarted service picks up the stored bids
+    let mut state = AuctionState::new(state.into_store());
+    state.submit(bid_sets[2].clone()).unwrap();
+    assert_eq!(state.withdraw(bob).unwrap(), bid_sets[1]);
+    let result = state.close(&AuctionConfig::deterministic()).unwrap();
+    // without Bob, Alice and Carol each win a chair, and Carol pays 2 for
+    // Alice's second chair
+    let payments = result
+        .payments
+        .iter()
+        .map(|(name, payment)| (name.as_str(), *payment))
+        .collect::<Vec<_>>();
+    assert_eq!(payments, [("Alice", 0), ("Carol", 2)]);
+}
+
+#[test]
+fn changes_clear_the_result() {
+    let (items, bid_sets) = chairs();
+    let mut state = AuctionState::new(MemoryStore::new());
+    state.set_stock(items.clone()).unwrap();
+    let alice = state.submit(bid_sets[0].clone()).unwrap();
+    let config = AuctionConfig::deterministic();
+    state.close(&config).unwrap();
+    state.submit(bid_sets[1].clone()).unwrap();
+    assert_eq!(state.result().unwrap(), None);
+    state.close(&config).unwrap();
+    state.withdraw(alice).unwrap();
+    assert_eq!(state.result().unwrap(), None);
+    state.close(&config).unwrap();
+    state.set_stock(items).unwrap();
+    assert_eq!(state.result().unwrap(), None);
+}
+
+#[test]
+fn failures_are_reported() {
+    let (items, bid_sets) = chairs();
+    let mut state = AuctionState::new(KeyValueStore::default());
+    state.set_stock(items).unwrap();
+    let alice = state.submit(bid_sets[0].clone()).unwrap();
+    state.withdraw(alice).unwrap();
+    assert_eq!(state.withdraw(alice), Err(StateError::UnknownBidSet(alice)));
+    let mut store = state.into_store();
+    store.disconnected = true;
+    let mut state = AuctionState::new(store);
+    assert_eq!(
+        state.submit(bid_sets[1].clone()),
+        Err(StateError::Store("disconnected".to_string()))
+    );
+    assert_eq!(
+        state
+            .close(&AuctionConfig::deterministic())
+            .unwrap_err()
+            .to_string(),
+        "store failed: disconnected"
+    );
+}
+
+#[test]
+fn submissions_over_a_limit_are_rejected() {
+    let (items, bid_sets) = chairs();
+    let limits = SubmissionLimits {
+        max_bids: Some(2),
+        max_bundle_size: Some(1),
+        ..Default::default()
+    };
+    let mut state =
+        AuctionState::new(KeyValueStore::default()).with_limits(limits);
+    state.set_stock(items).unwrap();
+    let alice = state.submit(bid_sets[0].clone()).unwrap();
+    let rejected = |bid, limit| {
+        Err(StateError::Rejected(SubmissionRejection { bid, limit }))
+    };
+    // Alice already has two bids
+    let more = vec![SimpleBid::new("Alice", 9, [("chair", 1)])];
+    assert_eq!(
+        state.submit(more.clone()),
+        rejected(0, SubmissionLimit::Bids)
+    );
+    let bundle = vec![
+        SimpleBid::new("Bob", 4, [("chair", 1)]),
+        SimpleBid::new("Bob", 6, [("chair", 1), ("table", 1)]),
+    ];
+    assert_eq!(
+        state.submit(bundle),
+        rejected(1, SubmissionLimit::BundleSize)
+    );
+    // rejected bid sets aren't stored, and a withdrawn one no longer counts
+    assert_eq!(state.bid_sets().unwrap(), [(alice, bid_sets[0].clone())]);
+    state.withdraw(alice).unwrap();
+    state.submit(more).unwrap();
+
+    // limits aren't stored with the auction, so Alice's bids go over them
+    // until they're set again
+    let mut state = AuctionState::new(state.into_store());
+    state.submit(bid_sets[0].clone()).unwrap();
+    let mut state = state.with_limits(limits);
+    state.submit(bid_sets[2].clone()).unwrap();
+    let last = vec![SimpleBid::new("Alice", 2, [("chair", 1)])];
+    assert_eq!(
+        state.submit(last).unwrap_err().to_string(),
+        "bid set rejected: bid 0 is over its bidder's limit of bids"
+    );
+}
@@ -0,0 +1,145 @@
+//! Tests for the search statistics of `run_with_stats`.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    run_with_search_tree, run_with_stats, types::SimpleBid, AuctionConfig,
+    AuctionError, PricingRule, SearchPhase, Solver, Tiebreak,
+};
+
+fn chair_bids() -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = vec![("chair".into(), 2), ("table".into(), 1)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("table", 1)])],
+        vec![SimpleBid::new("Dave", 2, [("chair", 1), ("table", 1)])],
+    ];
+    (items, bids)
+}
+
+#[test]
+fn stats_of_each_phase() {
+    let (items, bids) = chair_bids();
+    let config = AuctionConfig::deterministic();
+    let (outcome, stats) = run_with_stats(&items, &bids, &config);
+    let result = outcome.unwrap().complete().unwrap();
+
+    // Dave's bid can't win, leaving the chairs and the table to be searched
+    // apart
+    let winners = &stats.winner_determination;
+    assert_eq!(winners.searches, 2);
+    assert!(winners.improvements >= 1);
+    assert!(winners.peak_stack_len >= 1);
+    assert_eq!(winners.peak_tied_outcomes, 1);
+    // a payment search for each winner, split the same way except without
+    // Carol, when there's nothing left for the table
+    assert_eq!(result.payments.len(), 3);
+    assert_eq!(stats.payments.searches, 5);
+    assert_eq!(stats.phase(SearchPhase::Payments), &stats.payments);
+
+    // a solver splits its searches the same way, so explores the same nodes
+    let mut solver = Solver::new(&items, &bids, |_| 0);
+    while solver.step(u64::MAX).is_pending() {}
+    assert_eq!(solver.nodes_explored(), stats.nodes());
+    // stepped a node at a time, it still explores the same nodes
+    let mut solver = Solver::new(&items, &bids, |_| 0);
+    while solver.step(1).is_pending() {}
+    assert_eq!(solver.nodes_explored(), stats.nodes());
+}
+
+#[test]
+fn first_price_runs_no_payment_searches() {
+    let (items, bids) = chair_bids();
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::First)
+        .pricing(PricingRule::FirstPrice)
+        .build();
+    let (_, stats) = run_with_stats(&items, &bids, &config);
+    // the chairs and the table are searched apart
+    assert_eq!(stats.winner_determination.searches, 2);
+    assert_eq!(stats.payments.searches, 0);
+}
+
+#[test]
+fn stats_of_interrupted_auctions() {
+    let (items, bids) = chair_bids();
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::First)
+        .max_nodes(3)
+        .build();
+    let (outcome, stats) = run_with_stats(&items, &bids, &config);
+    assert!(matches!(outcome, Err(AuctionError::ResourceLimit(_))));
+    assert_eq!(stats.nodes(), 3);
+}
+
+#[test]
+fn payment_searches_start_from_the_other_winners() {
+    // every bidder bids on every item, so the other winners' items are still
+    // a highest value combination without each winner
+    let items = (0..6).map(|i| (i.to_string(), 1)).collect::<Vec<_>>();
+    let bids = (0..6)
+        .map(|j| {
+            (0..6)
+                .map(|i| {
+                    let value = (i * 7 + j * 3) % 10 + 1;
+                    SimpleBid::new(j.to_string(), value, [(i.to_string(), 1)])
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let config = AuctionConfig::deterministic();
+    let (outcome, stats) = run_with_stats(&items, &bids, &config);
+    let result = outcome.unwrap().complete().unwrap();
+    assert!(result.payments.iter().all(|(_, payment)| *payment == 0));
+    assert_eq!(stats.payments.searches, 6);
+    // searching each payment from a value of zero explores 1591 nodes
+    assert_eq!(stats.payments.nodes, 66);
+}
+
+#[test]
+fn bids_that_cant_beat_the_best_value_arent_explored() {
+    let items = vec![
+        ("chair".to_string(), 1),
+        ("sofa".to_string(), 1),
+        ("table".to_string(), 1),
+        ("lamp".to_string(), 1),
+    ];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 10, [("chair", 1)]),
+            SimpleBid::new("Alice", 10, [("sofa", 1)]),
+        ],
+        vec![
+            SimpleBid::new("Bob", 6, [("table", 1)]),
+            SimpleBid::new("Bob", 1, [("lamp", 1)]),
+        ],
+    ];
+    let config = AuctionConfig::deterministic();
+    let (outcome, stats) = run_with_stats(&items, &bids, &config);
+    let result = outcome.unwrap().complete().unwrap();
+    assert_eq!(result.winning_bids, [&bids[0][0], &bids[1][0]]);
+    // the bid sets share no items, so each is searched apart: a root, and a
+    // child for each bid and for none
+    assert_eq!(stats.winner_determination.nodes, 8);
+    // the recorded tree is of the same searches
+    let (_, tree) = run_with_search_tree(&items, &bids, &config);
+    let nodes = tree
+        .nodes
+        .iter()
+        .filter(|node| node.phase == SearchPhase::WinnerDetermination);
+    assert_eq!(nodes.count() as u64, stats.winner_determination.nodes);
+
+    // Bob's lamp also asks for the chair, so the bid sets are searched
+    // together; with Alice's sofa, it can't reach the 16 found with her chair,
+    // so it's pruned without being explored: the root, Alice's chair and its
+    // three children, her sofa and two children, and no bid from Alice
+    let mut bids = bids;
+    bids[1][1] = SimpleBid::new("Bob", 1, [("chair", 1), ("lamp", 1)]);
+    let (_, stats) = run_with_stats(&items, &bids, &config);
+    assert_eq!(stats.winner_determination.searches, 1);
+    assert_eq!(stats.winner_determination.nodes, 9);
+}
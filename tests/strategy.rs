@@ -0,0 +1,84 @@
+//! Tests that the best-first search produces the same results as the
+//! depth-first search, including the order of tied outcomes given to the
+//! tiebreaker.
+
+#![cfg(feature = "rand")]
+
+mod common;
+
+use pretty_assertions::assert_eq;
+use rand::{distributions::Distribution, rngs::StdRng, Rng, SeedableRng};
+
+use vcg_auction::{
+    run_with_stats, types::SimpleBid, AuctionConfig, AuctionInstance,
+    SearchOptions, SearchStrategy, Solver, Tiebreak,
+};
+
+use common::{run_recording_ties, tied_shape};
+
+#[test]
+fn best_first_matches_depth_first() {
+    let shape = tied_shape();
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..200 {
+        let AuctionInstance {
+            items,
+            bid_sets: bids,
+        } = shape.sample(&mut rng);
+        let pick = rng.gen::<usize>();
+        let run = |strategy| {
+            run_recording_ties(&items, &bids, pick, |c| c.strategy(strategy))
+        };
+        assert_eq!(
+            run(SearchStrategy::DepthFirst),
+            run(SearchStrategy::BestFirst)
+        );
+    }
+}
+
+/// Every bidder wants the same chair, with the highest bids last so the
+/// depth-first search finds them late.
+fn congested_bids() -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = vec![("chair".to_string(), 1)];
+    let bids = (0..12)
+        .map(|bidder| {
+            vec![SimpleBid::new(bidder.to_string(), bidder, [("chair", 1)])]
+        })
+        .collect();
+    (items, bids)
+}
+
+#[test]
+fn best_first_finds_the_highest_value_first() {
+    let (items, bids) = congested_bids();
+    let config = AuctionConfig::deterministic();
+    let (depth_first, depth_first_stats) =
+        run_with_stats(&items, &bids, &config);
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::First)
+        .strategy(SearchStrategy::BestFirst)
+        .build();
+    let (best_first, best_first_stats) = run_with_stats(&items, &bids, &config);
+
+    assert_eq!(depth_first, best_first);
+    // depth-first improves on each higher bid in turn
+    assert_eq!(depth_first_stats.winner_determination.improvements, 11);
+    assert_eq!(best_first_stats.winner_determination.improvements, 1);
+}
+
+#[test]
+fn solver_uses_the_strategy() {
+    let (items, bids) = congested_bids();
+    let options = SearchOptions {
+        strategy: SearchStrategy::BestFirst,
+        ..Default::default()
+    };
+    let mut solver = Solver::with_options(&items, &bids, |_| 0, &options);
+    let result = loop {
+        if let std::task::Poll::Ready(result) = solver.step(10) {
+            break result.unwrap();
+        }
+    };
+    assert_eq!(result.winning_bids, [&bids[11][0]]);
+    assert_eq!(result.payments, [(&"11".to_string(), 10)]);
+}
@@ -0,0 +1,79 @@
+//! Tests for the per-bidder submission limits.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    types::SimpleBid, SubmissionLimit, SubmissionLimits, SubmissionRejection,
+};
+
+fn rejection(bid: usize, limit: SubmissionLimit) -> SubmissionRejection {
+    SubmissionRejection { bid, limit }
+}
+
+#[test]
+fn no_limits_admit_everything() {
+    let accepted = vec![vec![SimpleBid::new("Alice", 10, [("chair", 1)]); 50]];
+    let bid_set = vec![SimpleBid::new("Alice", 5, [("table", 1)]); 50];
+    assert_eq!(
+        SubmissionLimits::default().admit(&accepted, &bid_set),
+        Ok(())
+    );
+}
+
+#[test]
+fn bid_sets_are_counted_per_bidder() {
+    let limits = SubmissionLimits {
+        max_bid_sets: Some(1),
+        ..Default::default()
+    };
+    let accepted = vec![vec![
+        SimpleBid::new("Alice", 10, [("chair", 1)]),
+        SimpleBid::new("Alice", 12, [("table", 1)]),
+    ]];
+    let bob = vec![SimpleBid::new("Bob", 7, [("chair", 1)])];
+    assert_eq!(limits.admit(&accepted, &bob), Ok(()));
+    let alice = vec![
+        SimpleBid::new("Bob", 8, [("table", 1)]),
+        SimpleBid::new("Alice", 15, [("sofa", 1)]),
+    ];
+    assert_eq!(
+        limits.admit(&accepted, &alice),
+        Err(rejection(1, SubmissionLimit::BidSets))
+    );
+}
+
+#[test]
+fn bids_are_counted_across_bid_sets() {
+    let limits = SubmissionLimits {
+        max_bids: Some(3),
+        ..Default::default()
+    };
+    let accepted = vec![vec![SimpleBid::new("Alice", 10, [("chair", 1)])]];
+    let two = vec![
+        SimpleBid::new("Alice", 12, [("table", 1)]),
+        SimpleBid::new("Alice", 15, [("sofa", 1)]),
+    ];
+    assert_eq!(limits.admit(&accepted, &two), Ok(()));
+    let mut three = two.clone();
+    three.push(SimpleBid::new("Alice", 20, [("desk", 1)]));
+    assert_eq!(
+        limits.admit(&accepted, &three),
+        Err(rejection(2, SubmissionLimit::Bids))
+    );
+}
+
+#[test]
+fn bundles_are_limited_per_bid() {
+    let limits = SubmissionLimits {
+        max_bundle_size: Some(2),
+        ..Default::default()
+    };
+    let accepted: Vec<Vec<SimpleBid>> = vec![];
+    let bid_set = vec![
+        SimpleBid::new("Alice", 10, [("chair", 1), ("table", 1)]),
+        SimpleBid::new("Alice", 15, [("chair", 1), ("table", 1), ("sofa", 1)]),
+    ];
+    let rejected = limits.admit(&accepted, &bid_set).unwrap_err();
+    assert_eq!(rejected, rejection(1, SubmissionLimit::BundleSize));
+    assert_eq!(rejected.to_string(), "bid 1 lists more items than allowed");
+}
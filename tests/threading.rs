@@ -0,0 +1,65 @@
+//! Compile-time checks that public types are `Send` and `Sync` when the bid
+//! type allows, and a check that auctions can share bids across threads.
+
+use std::thread;
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_with_tiebreaker, AuctionResult,
+    OwnedAuctionResult, SearchBuffers,
+};
+
+fn assert_send<T: Send>() {}
+fn assert_sync<T: Sync>() {}
+
+#[test]
+fn result_types_are_send_and_sync() {
+    assert_send::<AuctionResult<'static, SimpleBid>>();
+    assert_sync::<AuctionResult<'static, SimpleBid>>();
+    assert_send::<OwnedAuctionResult<SimpleBid>>();
+    assert_sync::<OwnedAuctionResult<SimpleBid>>();
+    assert_send::<SearchBuffers<SimpleBid>>();
+    assert_sync::<SearchBuffers<SimpleBid>>();
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn async_futures_are_send() {
+    fn assert_send_val<T: Send>(_: &T) {}
+    let future = vcg_auction::vcg_auction_with_tiebreaker_async::<SimpleBid>(
+        vec![],
+        vec![],
+        |_| 0,
+    );
+    assert_send_val(&future);
+}
+
+#[test]
+fn shared_bids_across_threads() {
+    let items = vec![("chair".into(), 2)];
+    let bids = [
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+    ];
+    let results = thread::scope(|s| {
+        let handles = (0..4)
+            .map(|_| {
+                s.spawn(|| {
+                    vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    for result in results {
+        assert_eq!(result.winning_bids, [&bids[0][0], &bids[1][0]]);
+        assert_eq!(result.payments, [(&"Alice".into(), 0), (&"Bob".into(), 2)]);
+    }
+}
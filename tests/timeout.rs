@@ -0,0 +1,110 @@
+//! Tests for stopping long searches at a maximum duration.
+
+use std::time::{Duration, Instant};
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_with_options, AuctionError, AuctionOutcome,
+    OnTimeout, SearchOptions,
+};
+
+/// Every bidder bids on every item independently. Big enough instances take
+/// far longer than the timeouts used here.
+fn square_bids(n: u64) -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = (0..n).map(|i| (i.to_string(), 1)).collect();
+    let bids = (0..n)
+        .map(|j| {
+            (0..n)
+                .map(|i| {
+                    let value = (i * 7 + j * 3) % 10 + 1;
+                    SimpleBid::new(j.to_string(), value, [(i.to_string(), 1)])
+                })
+                .collect()
+        })
+        .collect();
+    (items, bids)
+}
+
+#[test]
+fn timeout_error() {
+    let (items, bids) = square_bids(12);
+    let options = SearchOptions {
+        max_duration: Some(Duration::from_millis(50)),
+        ..Default::default()
+    };
+    let start = Instant::now();
+    let result = vcg_auction_with_options(&items, &bids, |_| 0, &options);
+    assert_eq!(result, Err(AuctionError::TimedOut));
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn timeout_best_so_far() {
+    let (items, bids) = square_bids(12);
+    let options = SearchOptions {
+        max_duration: Some(Duration::from_millis(50)),
+        on_timeout: OnTimeout::BestSoFar,
+        ..Default::default()
+    };
+    let result = vcg_auction_with_options(&items, &bids, |_| 0, &options);
+    let Ok(AuctionOutcome::TimedOut { best_so_far, gap }) = result else {
+        panic!("expected a timeout, got {result:?}");
+    };
+    let best_value: u64 = best_so_far.iter().map(|b| b.value).sum();
+    // the upper bound is the highest bid of every bidder
+    assert_eq!(best_value + gap, 12 * 10);
+}
+
+#[test]
+fn no_timeout_on_small_auction() {
+    let (items, bids) = square_bids(3);
+    let options = SearchOptions {
+        max_duration: Some(Duration::from_secs(60)),
+        on_timeout: OnTimeout::BestSoFar,
+        ..Default::default()
+    };
+    let result = vcg_auction_with_options(&items, &bids, |_| 0, &options);
+    assert!(matches!(result, Ok(AuctionOutcome::Complete(_))));
+}
+
+#[test]
+fn timeout_best_so_far_is_improved() {
+    // every bidder values the same items most, listed last, so the search has
+    // barely improved on its first combination of the cheapest items when it
+    // times out
+    let items = (0..30).map(|i| (i.to_string(), 1)).collect::<Vec<_>>();
+    let value = |bidder: u64, item: u64| 10 * item + (item * 7 + bidder) % 5;
+    let bids = (0..10)
+        .map(|bidder| {
+            (0..30)
+                .map(|item| {
+                    SimpleBid::new(
+                        bidder.to_string(),
+                        value(bidder, item),
+                        [(item.to_string(), 1)],
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let options = SearchOptions {
+        max_duration: Some(Duration::ZERO),
+        on_timeout: OnTimeout::BestSoFar,
+        ..Default::default()
+    };
+    let result = vcg_auction_with_options(&items, &bids, |_| 0, &options);
+    let Ok(AuctionOutcome::TimedOut { best_so_far, gap }) = result else {
+        panic!("expected a timeout, got {result:?}");
+    };
+    let best_value: u64 = best_so_far.iter().map(|b| b.value).sum();
+    let upper_bound: u64 = (0..10).map(|bidder| value(bidder, 29)).sum();
+    assert_eq!(best_value + gap, upper_bound);
+    // improved until no winner would rather have an item nobody won
+    assert_eq!(best_so_far.len(), 10);
+    let won = |item: &String| best_so_far.iter().any(|b| &b.items[0].0 == item);
+    for bid in &best_so_far {
+        let bidder = bid.name.parse().unwrap();
+        for (item, _) in items.iter().filter(|(item, _)| !won(item)) {
+            assert!(value(bidder, item.parse().unwrap()) < bid.value);
+        }
+    }
+}
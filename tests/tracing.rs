@@ -0,0 +1,75 @@
+//! Tests for the spans and events of the `tracing` feature.
+#![cfg(feature = "tracing")]
+
+use std::fmt;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
+
+use pretty_assertions::assert_eq;
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+use vcg_auction::{types::SimpleBid, vcg_auction_with_tiebreaker};
+
+/// Records the names of spans and the messages of events.
+#[derive(Default)]
+struct Recorder {
+    next_id: AtomicU64,
+    spans: Mutex<Vec<&'static str>>,
+    events: Mutex<Vec<String>>,
+}
+
+struct Message(String);
+
+impl Visit for Message {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{value:?}");
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        self.spans.lock().unwrap().push(span.metadata().name());
+        span::Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+    fn event(&self, event: &Event<'_>) {
+        let mut message = Message(String::new());
+        event.record(&mut message);
+        self.events.lock().unwrap().push(message.0);
+    }
+    fn enter(&self, _span: &span::Id) {}
+    fn exit(&self, _span: &span::Id) {}
+}
+
+#[test]
+fn spans_and_events() {
+    let items = vec![("chair".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("chair", 1)])],
+        vec![SimpleBid::new("Bob", 7, [("chair", 1)])],
+    ];
+    let recorder = Arc::new(Recorder::default());
+    tracing::subscriber::with_default(recorder.clone(), || {
+        vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    });
+
+    assert_eq!(
+        *recorder.spans.lock().unwrap(),
+        ["vcg_auction", "search", "payment", "search"]
+    );
+    let events = recorder.events.lock().unwrap();
+    let finished = events.iter().filter(|e| *e == "search finished").count();
+    assert_eq!(finished, 2);
+    assert!(events.iter().any(|e| e == "found a higher value"));
+}
@@ -0,0 +1,88 @@
+//! Tests for auctions of unique items with `vcg_auction_unique`.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_unique, vcg_auction_with_tiebreaker,
+    InputError, UniqueItemError,
+};
+
+#[test]
+fn sales_match_the_auction() {
+    let items = vec![
+        ("a".to_string(), 1),
+        ("b".to_string(), 1),
+        ("c".to_string(), 1),
+    ];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 10, [("a", 1)]),
+            SimpleBid::new("Alice", 6, [("b", 1)]),
+        ],
+        vec![SimpleBid::new("Bob", 8, [("a", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("c", 1)])],
+    ];
+    let sales = vcg_auction_unique(&items, &bids, |_| 0).unwrap();
+    let sold = sales
+        .iter()
+        .map(|sale| (sale.item.as_str(), sale.winner.as_str(), sale.price))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sold,
+        [("b", "Alice", 0), ("a", "Bob", 4), ("c", "Carol", 0)]
+    );
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    for (sale, (name, payment)) in sales.iter().zip(&result.payments) {
+        assert_eq!((sale.winner, &sale.price), (*name, payment));
+    }
+}
+
+#[test]
+fn auctions_are_checked() {
+    let items = vec![("a".to_string(), 1), ("b".to_string(), 2)];
+    let bids = vec![vec![SimpleBid::new("Alice", 10, [("a", 1)])]];
+    assert_eq!(
+        vcg_auction_unique(&items, &bids, |_| 0),
+        Err(UniqueItemError::NotOneInStock(1))
+    );
+
+    let items = vec![("a".to_string(), 1), ("b".to_string(), 1)];
+    let bids = vec![vec![SimpleBid::new("Alice", 10, [("c", 1)])]];
+    assert_eq!(
+        vcg_auction_unique(&items, &bids, |_| 0),
+        Err(UniqueItemError::Input(InputError::UnknownItem {
+            bid_set: 0,
+            bid: 0
+        }))
+    );
+    let bids = vec![vec![
+        SimpleBid::new("Alice", 10, [("a", 1)]),
+        SimpleBid::new("Alice", 12, [("a", 1), ("b", 1)]),
+    ]];
+    assert_eq!(
+        vcg_auction_unique(&items, &bids, |_| 0),
+        Err(UniqueItemError::NotOneUnit { bid_set: 0, bid: 1 })
+    );
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 10, [("a", 1)])],
+        vec![SimpleBid::new("Bob", 8, [("a", 1)])],
+        vec![SimpleBid::new("Alice", 6, [("b", 1)])],
+    ];
+    assert_eq!(
+        vcg_auction_unique(&items, &bids, |_| 0),
+        Err(UniqueItemError::SeveralBidSets { bid_set: 2, bid: 0 })
+    );
+}
+
+#[test]
+fn invalid_tiebreak_is_an_error() {
+    let items = vec![("a".to_string(), 1)];
+    let bids = vec![
+        vec![SimpleBid::new("Alice", 5, [("a", 1)])],
+        vec![SimpleBid::new("Bob", 5, [("a", 1)])],
+    ];
+    assert_eq!(
+        vcg_auction_unique(&items, &bids, |_| 2),
+        Err(UniqueItemError::InvalidTiebreak)
+    );
+}
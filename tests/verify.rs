@@ -0,0 +1,117 @@
+//! Tests for verifying auction results against their inputs.
+
+use pretty_assertions::assert_eq;
+
+use vcg_auction::{
+    types::SimpleBid, vcg_auction_with_tiebreaker, verify_result,
+    AuctionConfig, AuctionResult, ResultOrder, Tiebreak, VerificationError,
+};
+
+fn chair_bids() -> (Vec<(String, u64)>, Vec<Vec<SimpleBid>>) {
+    let items = vec![("chair".into(), 2)];
+    let bids = vec![
+        vec![
+            SimpleBid::new("Alice", 5, [("chair", 1)]),
+            SimpleBid::new("Alice", 7, [("chair", 2)]),
+        ],
+        vec![SimpleBid::new("Bob", 4, [("chair", 1)])],
+        vec![SimpleBid::new("Carol", 3, [("chair", 1)])],
+    ];
+    (items, bids)
+}
+
+#[test]
+fn auction_results_verify() {
+    let (items, bids) = chair_bids();
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    assert_eq!(verify_result(&items, &bids, &result), Ok(()));
+}
+
+#[test]
+fn reordered_results_verify() {
+    let (items, bids) = chair_bids();
+    let config = AuctionConfig::builder()
+        .tiebreak(Tiebreak::First)
+        .result_order(ResultOrder::Input)
+        .build();
+    let mut result = vcg_auction::run(&items, &bids, &config)
+        .unwrap()
+        .complete()
+        .unwrap();
+    assert_eq!(verify_result(&items, &bids, &result), Ok(()));
+    // payments sorted by bidder, last first
+    result.payments.sort_by(|a, b| b.0.cmp(a.0));
+    assert_eq!(result.payments[0].0, "Bob");
+    assert_eq!(verify_result(&items, &bids, &result), Ok(()));
+    result.winning_bids.reverse();
+    assert_eq!(verify_result(&items, &bids, &result), Ok(()));
+}
+
+#[test]
+fn results_with_duplicate_items_verify() {
+    // the chairs listed apart
+    let items = vec![("chair".into(), 1), ("chair".into(), 1)];
+    let (_, bids) = chair_bids();
+    let result =
+        vcg_auction::run(&items, &bids, &AuctionConfig::deterministic())
+            .unwrap()
+            .complete()
+            .unwrap();
+    // both chairs are sold
+    assert_eq!(result.winning_bids, [&bids[0][0], &bids[1][0]]);
+    assert_eq!(verify_result(&items, &bids, &result), Ok(()));
+}
+
+#[test]
+fn invalid_results() {
+    let (items, bids) = chair_bids();
+    let other = SimpleBid::new("Bob", 4, [("chair", 1)]);
+    let alice = &"Alice".to_string();
+    let bob = &"Bob".to_string();
+    let carol = &"Carol".to_string();
+    let cases = [
+        (vec![&other], vec![(bob, 0)], VerificationError::UnknownBid),
+        (
+            vec![&bids[1][0], &bids[1][0]],
+            vec![(bob, 0)],
+            VerificationError::UnknownBid,
+        ),
+        (
+            vec![&bids[0][0], &bids[0][1]],
+            vec![(alice, 0)],
+            VerificationError::ExclusiveBids,
+        ),
+        (
+            vec![&bids[0][1], &bids[1][0]],
+            vec![(alice, 0), (bob, 0)],
+            VerificationError::OverAllocated,
+        ),
+        (
+            vec![&bids[0][0], &bids[1][0]],
+            vec![(alice, 0), (carol, 0)],
+            VerificationError::PaymentsMismatch,
+        ),
+        (
+            vec![&bids[0][0], &bids[1][0]],
+            vec![(alice, 0), (alice, 0)],
+            VerificationError::PaymentsMismatch,
+        ),
+        (
+            vec![&bids[0][0], &bids[1][0]],
+            vec![(alice, 0)],
+            VerificationError::PaymentsMismatch,
+        ),
+        (
+            vec![&bids[0][0], &bids[1][0]],
+            vec![(alice, 0), (bob, 5)],
+            VerificationError::PaymentExceedsBid,
+        ),
+    ];
+    for (winning_bids, payments, error) in cases {
+        let result = AuctionResult {
+            winning_bids,
+            payments,
+        };
+        assert_eq!(verify_result(&items, &bids, &result), Err(error));
+    }
+}
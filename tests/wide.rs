@@ -0,0 +1,87 @@
+//! Tests for `u128` bid values whose sums overflow `u64`.
+
+#![cfg(feature = "rand")]
+
+use pretty_assertions::assert_eq;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use vcg_auction::{
+    types::{SimpleBid, WideBid},
+    vcg_auction_with_tiebreaker,
+};
+
+const TOKEN: u128 = 10u128.pow(18);
+
+#[test]
+fn sums_beyond_u64() {
+    let items = (0..4).map(|i| (i.to_string(), 1)).collect::<Vec<_>>();
+    let value = u128::from(u64::MAX);
+    let mut bids = (0..4)
+        .map(|i| vec![WideBid::new(i.to_string(), value, [(i.to_string(), 1)])])
+        .collect::<Vec<_>>();
+    bids.push(vec![WideBid::new("Eve", value - 1, [("0", 1)])]);
+    let result = vcg_auction_with_tiebreaker(&items, &bids, |_| 0).unwrap();
+    let winning_bids = bids[..4].iter().map(|bs| &bs[0]).collect::<Vec<_>>();
+    assert_eq!(result.winning_bids, winning_bids);
+    // bidder 0 keeps Eve from winning item 0
+    assert_eq!(result.payment_for(&"0".into()), Some(&(value - 1)));
+    assert_eq!(result.payment_for(&"1".into()), Some(&0));
+}
+
+/// Random auctions with token-denominated values give the same result as the
+/// same auctions in whole tokens.
+#[test]
+fn scaled_values_match() {
+    let items = vec![("chair".into(), 2), ("table".into(), 1)];
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..50 {
+        let bids = (0..rng.gen_range(0..6))
+            .map(|bidder| {
+                (0..rng.gen_range(0..3))
+                    .map(|_| {
+                        let item = ["chair", "table"][rng.gen_range(0..2)];
+                        SimpleBid::new(
+                            bidder.to_string(),
+                            rng.gen_range(0..20),
+                            [(item, rng.gen_range(1..3))],
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let wide_bids = bids
+            .iter()
+            .map(|bs| {
+                bs.iter()
+                    .map(|b| {
+                        let value = u128::from(b.value) * TOKEN;
+                        WideBid::new(b.name.clone(), value, b.items.clone())
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let expected = vcg_auction_with_tiebreaker(&items, &bids, |_| 0);
+        let result = vcg_auction_with_tiebreaker(&items, &wide_bids, |_| 0);
+        let (Some(expected), Some(result)) = (expected, result) else {
+            panic!("auctions without a tiebreak can't fail");
+        };
+        let scaled_payments = expected
+            .payments
+            .iter()
+            .map(|(name, payment)| (*name, u128::from(*payment) * TOKEN))
+            .collect::<Vec<_>>();
+        assert_eq!(result.payments, scaled_payments);
+        assert_eq!(
+            result
+                .winning_bids
+                .iter()
+                .map(|b| &b.name)
+                .collect::<Vec<_>>(),
+            expected
+                .winning_bids
+                .iter()
+                .map(|b| &b.name)
+                .collect::<Vec<_>>()
+        );
+    }
+}